@@ -244,6 +244,12 @@ pub enum SyncCommands {
         about = "Sync all projects and configs in the workspace."
     )]
     Projects,
+
+    #[command(
+        name = "prototools",
+        about = "Pin the toolchain versions to a `.prototools` file."
+    )]
+    Prototools,
 }
 
 #[derive(Debug, Subcommand)]
@@ -556,6 +562,9 @@ pub enum Commands {
         about = "Clean the workspace and delete any stale or invalid artifacts."
     )]
     Clean {
+        #[arg(long, help = "Compact state files into a single bundle file")]
+        compact: bool,
+
         #[arg(long, default_value = "7 days", help = "Lifetime of cached artifacts")]
         lifetime: String,
     },
@@ -4,6 +4,7 @@ use starbase::AppResult;
 
 pub struct CleanOptions {
     pub cache_lifetime: String,
+    pub compact: bool,
 }
 
 pub async fn clean(options: CleanOptions) -> AppResult {
@@ -22,5 +23,13 @@ pub async fn clean(options: CleanOptions) -> AppResult {
         true,
     );
 
+    if options.compact {
+        let done = create_progress_bar("Compacting cache state files");
+
+        let files_compacted = workspace.cache.compact_states()?;
+
+        done(format!("Compacted {files_compacted} state files"), true);
+    }
+
     Ok(())
 }
@@ -8,6 +8,7 @@ use moon_project_graph::ProjectGraph;
 use moon_rust_lang::{CARGO, RUST};
 use moon_rust_tool::RustTool;
 use moon_terminal::safe_exit;
+use moon_tool::is_install_dry_run;
 use rustc_hash::FxHashSet;
 use starbase::AppResult;
 use starbase_utils::fs;
@@ -43,7 +44,7 @@ pub async fn prune_node(
 
     // Install production only dependencies for focused projects
     node.get_package_manager()
-        .install_focused_dependencies(node, &package_names, true)
+        .install_focused_dependencies(node, &package_names, true, is_install_dry_run())
         .await?;
 
     Ok(())
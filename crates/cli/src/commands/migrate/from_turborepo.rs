@@ -4,7 +4,7 @@ use moon_common::{consts, Id};
 use moon_config::{
     InputPath, OutputPath, PartialInheritedTasksConfig, PartialProjectConfig,
     PartialTaskCommandArgs, PartialTaskConfig, PartialTaskOptionsConfig, PlatformType,
-    ProjectConfig,
+    ProjectConfig, TaskDependency,
 };
 use moon_logger::{info, warn};
 use moon_target::Target;
@@ -105,7 +105,7 @@ pub fn convert_task(name: Id, task: TurboTask) -> AppResult<PartialTaskConfig> {
         }
 
         if !deps.is_empty() {
-            config.deps = Some(deps);
+            config.deps = Some(deps.into_iter().map(TaskDependency::Target).collect());
         }
     }
 
@@ -344,9 +344,9 @@ mod tests {
             assert_eq!(
                 config.deps,
                 Some(vec![
-                    Target::new_self("normal").unwrap(),
-                    Target::parse("^:parent").unwrap(),
-                    Target::parse("project:normal").unwrap(),
+                    TaskDependency::Target(Target::new_self("normal").unwrap()),
+                    TaskDependency::Target(Target::parse("^:parent").unwrap()),
+                    TaskDependency::Target(Target::parse("project:normal").unwrap()),
                 ])
             );
             assert_eq!(
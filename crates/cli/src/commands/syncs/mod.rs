@@ -1,3 +1,4 @@
 pub mod codeowners;
 pub mod hooks;
 pub mod projects;
+pub mod prototools;
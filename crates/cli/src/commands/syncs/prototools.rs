@@ -0,0 +1,32 @@
+use crate::helpers::create_progress_bar;
+use moon::load_workspace;
+use proto::TOOLS_CONFIG_NAME;
+use starbase::AppResult;
+use starbase_styles::color;
+
+pub async fn sync() -> AppResult {
+    let workspace = load_workspace().await?;
+
+    let done = create_progress_bar("Syncing toolchain versions...");
+
+    let written = workspace
+        .toolchain_config
+        .write_proto_tools(&workspace.root)?;
+
+    done(
+        if written {
+            format!(
+                "Successfully pinned versions to {}",
+                color::path(TOOLS_CONFIG_NAME)
+            )
+        } else {
+            format!(
+                "Versions in {} are up to date",
+                color::path(TOOLS_CONFIG_NAME)
+            )
+        },
+        true,
+    );
+
+    Ok(())
+}
@@ -140,9 +140,10 @@ pub async fn run_cli() -> AppResult {
             )
             .await
         }
-        Commands::Clean { lifetime } => {
+        Commands::Clean { compact, lifetime } => {
             clean(CleanOptions {
                 cache_lifetime: lifetime.to_owned(),
+                compact,
             })
             .await
         }
@@ -325,6 +326,7 @@ pub async fn run_cli() -> AppResult {
                 syncs::hooks::sync(SyncHooksOptions { clean, force }).await
             }
             Some(SyncCommands::Projects) => syncs::projects::sync().await,
+            Some(SyncCommands::Prototools) => syncs::prototools::sync().await,
             None => sync().await,
         },
         Commands::Task { target, json } => task(target, json).await,
@@ -7,7 +7,7 @@ use moon_test_utils::{
 };
 use moon_utils::string_vec;
 use rustc_hash::FxHashMap;
-use std::fs::read_to_string;
+use std::fs::{self, read_to_string};
 
 fn node_sandbox() -> Sandbox {
     let (workspace_config, toolchain_config, tasks_config) = get_node_fixture_configs();
@@ -458,6 +458,61 @@ mod install_deps {
         assert!(sandbox.path().join("bar/package-lock.json").exists());
         assert!(sandbox.path().join("baz/package-lock.json").exists());
     }
+
+    // Dedupe never runs in CI, so remove the variable from the parent environment
+    fn run_without_ci(sandbox: &Sandbox) -> String {
+        sandbox
+            .run_moon(|cmd| {
+                cmd.arg("run").arg("node:standard").env_remove("CI");
+            })
+            .output()
+    }
+
+    // Bump the lockfile's modified time so that the next run installs again,
+    // but without changing its contents
+    fn touch_lockfile(sandbox: &Sandbox) {
+        let lockfile = sandbox.path().join("package-lock.json");
+
+        fs::write(&lockfile, fs::read(&lockfile).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn dedupes_when_install_changes_lockfile() {
+        let sandbox = node_sandbox();
+
+        let output = run_without_ci(&sandbox);
+
+        assert!(predicate::str::contains("npm install").eval(&output));
+        assert!(predicate::str::contains("npm dedupe").eval(&output));
+    }
+
+    #[test]
+    fn skips_dedupe_when_install_doesnt_change_lockfile() {
+        let sandbox = node_sandbox();
+
+        run_without_ci(&sandbox);
+        touch_lockfile(&sandbox);
+
+        let output = run_without_ci(&sandbox);
+
+        assert!(predicate::str::contains("npm install").eval(&output));
+        assert!(!predicate::str::contains("npm dedupe").eval(&output));
+    }
+
+    #[test]
+    fn always_dedupes_when_forced() {
+        let sandbox = node_sandbox_with_config(|cfg| {
+            cfg.dedupe_always = Some(true);
+        });
+
+        run_without_ci(&sandbox);
+        touch_lockfile(&sandbox);
+
+        let output = run_without_ci(&sandbox);
+
+        assert!(predicate::str::contains("npm install").eval(&output));
+        assert!(predicate::str::contains("npm dedupe").eval(&output));
+    }
 }
 
 mod engines {
@@ -1,3 +1,4 @@
+use moon_cache::CacheEngine;
 use moon_config::{PartialInheritedTasksConfig, PartialWorkspaceConfig, PartialWorkspaceProjects};
 use moon_test_utils::{
     assert_snapshot, create_sandbox_with_config, predicates::prelude::*, Sandbox,
@@ -100,6 +101,36 @@ mod unix {
         assert.code(1);
     }
 
+    #[test]
+    fn doesnt_cache_failed_runs() {
+        let sandbox = system_sandbox();
+
+        sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("unix:exitNonZeroWithOutputs");
+        });
+
+        let hash = CacheEngine::load(sandbox.path())
+            .unwrap()
+            .cache_run_target_state("unix:exitNonZeroWithOutputs")
+            .unwrap()
+            .hash;
+
+        assert!(sandbox.path().join("unix/out/file.txt").exists());
+        assert!(!sandbox
+            .path()
+            .join(format!(".moon/cache/outputs/{hash}.tar.gz"))
+            .exists());
+
+        // Runs again instead of reusing the failed run
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("unix:exitNonZeroWithOutputs");
+        });
+
+        assert!(!predicate::str::contains("cached").eval(&assert.output()));
+
+        assert.code(1);
+    }
+
     #[test]
     fn passes_args_through() {
         let sandbox = system_sandbox();
@@ -1035,6 +1035,102 @@ mod outputs {
     mod archiving {
         use super::*;
 
+        #[test]
+        fn doesnt_archive_identical_outputs() {
+            let sandbox = cases_sandbox();
+            sandbox.enable_git();
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateFixed");
+            });
+
+            let hash1 = extract_hash_from_run(sandbox.path(), "outputs:generateFixed");
+
+            // Change an input so that the task runs again
+            sandbox.create_file("outputs/other.js", "");
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateFixed");
+            });
+
+            let hash2 = extract_hash_from_run(sandbox.path(), "outputs:generateFixed");
+
+            assert_ne!(hash1, hash2);
+
+            // The previous archive is reused for the new hash
+            let archive1 = fs::read(
+                sandbox
+                    .path()
+                    .join(format!(".moon/cache/outputs/{hash1}.tar.gz")),
+            )
+            .unwrap();
+            let archive2 = fs::read(
+                sandbox
+                    .path()
+                    .join(format!(".moon/cache/outputs/{hash2}.tar.gz")),
+            )
+            .unwrap();
+
+            assert_eq!(archive1, archive2);
+        }
+
+        #[test]
+        fn archives_changed_outputs_with_the_same_size() {
+            let sandbox = cases_sandbox();
+            sandbox.enable_git();
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateFile");
+            });
+
+            let hash1 = extract_hash_from_run(sandbox.path(), "outputs:generateFile");
+            let contents1 =
+                fs::read_to_string(sandbox.path().join("outputs/single-file/one.js")).unwrap();
+
+            // Change an input so that the task runs again, which writes a new timestamp
+            sandbox.create_file("outputs/other.js", "");
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateFile");
+            });
+
+            let hash2 = extract_hash_from_run(sandbox.path(), "outputs:generateFile");
+            let contents2 =
+                fs::read_to_string(sandbox.path().join("outputs/single-file/one.js")).unwrap();
+
+            assert_ne!(hash1, hash2);
+            assert_ne!(contents1, contents2);
+            assert_eq!(contents1.len(), contents2.len());
+
+            // A new archive is created with the changed contents
+            let archive1 = fs::read(
+                sandbox
+                    .path()
+                    .join(format!(".moon/cache/outputs/{hash1}.tar.gz")),
+            )
+            .unwrap();
+            let archive2 = fs::read(
+                sandbox
+                    .path()
+                    .join(format!(".moon/cache/outputs/{hash2}.tar.gz")),
+            )
+            .unwrap();
+
+            assert_ne!(archive1, archive2);
+
+            // And restoring it hydrates the changed contents
+            fs::remove_dir_all(sandbox.path().join("outputs/single-file")).unwrap();
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateFile");
+            });
+
+            assert_eq!(
+                fs::read_to_string(sandbox.path().join("outputs/single-file/one.js")).unwrap(),
+                contents2
+            );
+        }
+
         #[test]
         fn doesnt_archive_non_build_tasks() {
             let sandbox = cases_sandbox();
@@ -5,6 +5,7 @@ use moon_hasher::HashSet;
 use moon_logger::{debug, warn};
 use moon_platform::Runtime;
 use moon_project::Project;
+use moon_tool::is_install_dry_run;
 use moon_utils::time;
 use moon_workspace::Workspace;
 use starbase_styles::color;
@@ -142,15 +143,22 @@ pub async fn install_deps(
             color::path(working_dir)
         );
 
-        workspace.cache.create_hash_manifest(&hash, &hashset)?;
+        // A dry run doesn't install anything, so don't mark the install as complete
+        let is_dry_run = is_install_dry_run();
+
+        if !is_dry_run {
+            workspace.cache.create_hash_manifest(&hash, &hashset)?;
+        }
 
         platform
             .install_deps(&context, runtime, working_dir)
             .await?;
 
-        cache.last_hash = hash;
-        cache.last_install_time = time::now_millis();
-        cache.save()?;
+        if !is_dry_run {
+            cache.last_hash = hash;
+            cache.last_install_time = time::now_millis();
+            cache.save()?;
+        }
 
         env::remove_var("MOON_INSTALLING_DEPS");
 
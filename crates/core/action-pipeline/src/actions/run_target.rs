@@ -118,8 +118,9 @@ pub async fn run_target(
                 ActionStatus::Failed
             };
 
-            // If successful, cache the task outputs
-            if is_cache_enabled {
+            // If successful, cache the task outputs. Failed runs may have
+            // produced partial outputs, so they must never be cached!
+            if is_cache_enabled && matches!(status, ActionStatus::Passed) {
                 runner.archive_outputs().await?;
             }
 
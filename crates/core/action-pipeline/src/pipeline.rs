@@ -12,6 +12,7 @@ use moon_emitter::{Emitter, Event};
 use moon_logger::{debug, error, trace};
 use moon_notifier::WebhooksSubscriber;
 use moon_project_graph::ProjectGraph;
+use moon_runner::output_record::reset_output_records;
 use moon_terminal::{label_checkpoint, label_to_the_moon, Checkpoint, ExtendedTerm};
 use moon_utils::{is_ci, is_test_env, time};
 use moon_workspace::Workspace;
@@ -95,6 +96,8 @@ impl Pipeline {
             "Running {} actions across {} batches", total_actions_count, batches_count
         );
 
+        reset_output_records(&workspace.read().await.cache.dir)?;
+
         local_emitter
             .emit(Event::PipelineStarted {
                 actions_count: total_actions_count,
@@ -1,11 +1,12 @@
+use moon_archive::ArchiveMetadata;
 use moon_cache::get_cache_mode;
 use moon_emitter::{Event, EventFlow, Subscriber};
 use moon_utils::{async_trait, path};
 use moon_workspace::Workspace;
 
 /// The local cache subscriber is in charge of managing archives
-/// (task output's archived as tarballs), by reading and writing them
-/// to the `.moon/cache/{outputs,hashes}` directories.
+/// (task output's archived as tarballs) and the content store, by reading
+/// and writing them to the `.moon/cache/{outputs,hashes,blobs}` directories.
 ///
 /// This is the last subscriber amongst all subscribers, as local
 /// cache is the last line of defense. However, other subscribers
@@ -51,7 +52,32 @@ impl Subscriber for LocalCacheSubscriber {
                     .filter_map(|o| o.to_workspace_relative(&project.source))
                     .collect::<Vec<_>>();
 
-                if cache.archive_outputs(&archive_path, &workspace.root, &output_paths)? {
+                // Stamp the archive with provenance, so it can be inspected later
+                let mut metadata = ArchiveMetadata::new();
+                metadata.git_sha = workspace
+                    .vcs
+                    .get_local_branch_revision()
+                    .await
+                    .ok()
+                    .map(|sha| sha.to_owned());
+
+                let archived = cache.archive_outputs(
+                    &archive_path,
+                    &workspace.root,
+                    &output_paths,
+                    metadata,
+                )?;
+
+                // Also store the outputs in the content store, so that identical files
+                // across targets and runs are deduplicated, and can be hardlinked on hydrate
+                cache.store_outputs(
+                    &workspace.cache.get_content_store(),
+                    &workspace.cache.get_hash_content_manifest_path(hash),
+                    &workspace.root,
+                    &output_paths,
+                )?;
+
+                if archived {
                     return Ok(EventFlow::Return(path::to_string(archive_path)?));
                 }
             }
@@ -71,6 +97,18 @@ impl Subscriber for LocalCacheSubscriber {
                     .filter_map(|o| o.to_workspace_relative(&project.source))
                     .collect::<Vec<_>>();
 
+                // Prefer the content store, and fall back to the archive
+                // if any of the stored blobs are missing or corrupt
+                let manifest_path = workspace.cache.get_hash_content_manifest_path(hash);
+
+                if cache.hydrate_outputs_from_store(
+                    &workspace.cache.get_content_store(),
+                    &manifest_path,
+                    &workspace.root,
+                )? {
+                    return Ok(EventFlow::Return(path::to_string(manifest_path)?));
+                }
+
                 if cache.hydrate_outputs(&archive_path, &workspace.root, &output_paths)? {
                     return Ok(EventFlow::Return(path::to_string(archive_path)?));
                 }
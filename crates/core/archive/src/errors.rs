@@ -0,0 +1,24 @@
+use miette::Diagnostic;
+use starbase_styles::{Style, Stylize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum ArchiveError {
+    #[diagnostic(code(archive::unsupported_format))]
+    #[error(
+        "Unable to unpack {}, as {} compressed archives are not supported. Enable the {} feature to support them.",
+        .path.style(Style::Path),
+        .format,
+        .format.style(Style::Symbol),
+    )]
+    UnsupportedFormat { path: PathBuf, format: String },
+
+    #[diagnostic(code(archive::symlink_outside_output))]
+    #[error(
+        "Unable to restore symlink {}, as its target {} is outside of the output directory.",
+        .path.style(Style::Path),
+        .target.style(Style::File),
+    )]
+    SymlinkOutsideOutput { path: PathBuf, target: String },
+}
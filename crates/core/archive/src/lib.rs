@@ -1,8 +1,12 @@
+mod errors;
 mod helpers;
+mod metadata;
 mod tar;
 mod tree_differ;
 mod zip;
 
+pub use crate::errors::*;
+pub use crate::metadata::*;
 pub use crate::tar::*;
 pub use crate::zip::*;
-pub use tree_differ::TreeDiffer;
+pub use tree_differ::{TreeDiffer, TreeManifestDiff};
@@ -0,0 +1,43 @@
+use miette::IntoDiagnostic;
+use moon_utils::time;
+use serde::{Deserialize, Serialize};
+use starbase_utils::json;
+use std::env;
+
+/// Name of the reserved entry that metadata is written to within tar archives.
+pub const ARCHIVE_METADATA_ENTRY: &str = ".moon-meta.json";
+
+/// Provenance information stamped into an archive when packed,
+/// which can be read back without extracting the archive.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveMetadata {
+    /// Version of moon that created the archive.
+    pub moon_version: String,
+
+    /// Time the archive was created, in milliseconds since the epoch.
+    pub created_at: u128,
+
+    /// Git commit that was checked out when the archive was created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+}
+
+impl ArchiveMetadata {
+    /// Create metadata for the current moon process and time.
+    pub fn new() -> Self {
+        ArchiveMetadata {
+            moon_version: env::var("MOON_VERSION").unwrap_or_default(),
+            created_at: time::now_millis(),
+            git_sha: None,
+        }
+    }
+
+    pub fn from_json(data: &str) -> miette::Result<Self> {
+        json::from_str(data).into_diagnostic()
+    }
+
+    pub fn to_json(&self) -> miette::Result<String> {
+        json::to_string(self).into_diagnostic()
+    }
+}
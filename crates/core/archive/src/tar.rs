@@ -1,4 +1,5 @@
 use crate::helpers::prepend_name;
+use crate::metadata::{ArchiveMetadata, ARCHIVE_METADATA_ENTRY};
 use crate::tree_differ::TreeDiffer;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
@@ -8,12 +9,38 @@ use moon_logger::{debug, trace};
 use moon_utils::path;
 use rustc_hash::FxHashMap;
 use starbase_styles::color;
+use starbase_utils::glob::GlobSet;
 use starbase_utils::{fs, glob};
+use std::cell::Cell;
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use tar::{Archive, Builder};
+use std::rc::Rc;
+use tar::{Archive, Builder, Header, HeaderMode};
 
 const LOG_TARGET: &str = "moon:archive:tar";
 
+const BZIP2_MAGIC: [u8; 3] = [b'B', b'Z', b'h'];
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Compression to apply when packing a tar archive. When unpacking,
+/// the compression is detected from the magic bytes of the file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TarCompression {
+    #[default]
+    Gzip,
+    None,
+    Zstd(i32),
+}
+
+impl TarCompression {
+    pub fn zstd() -> Self {
+        TarCompression::Zstd(DEFAULT_ZSTD_LEVEL)
+    }
+}
+
 pub struct TarArchiver<'l> {
     input_root: &'l Path,
 
@@ -25,6 +52,16 @@ pub struct TarArchiver<'l> {
     sources: FxHashMap<String, PathBuf>,
 
     source_globs: FxHashMap<String, String>,
+
+    metadata: Option<ArchiveMetadata>,
+
+    compression: TarCompression,
+
+    // Globs of archive-relative paths to not pack
+    ignores: Vec<String>,
+
+    // Normalize ownership, permissions, and ordering of entries
+    normalize: bool,
 }
 
 impl<'l> TarArchiver<'l> {
@@ -35,6 +72,10 @@ impl<'l> TarArchiver<'l> {
             prefix: "",
             sources: FxHashMap::default(),
             source_globs: FxHashMap::default(),
+            compression: TarCompression::default(),
+            ignores: vec![],
+            metadata: None,
+            normalize: false,
         }
     }
 
@@ -56,6 +97,35 @@ impl<'l> TarArchiver<'l> {
         self
     }
 
+    /// Ignore files whose path within the archive (excluding the prefix) matches
+    /// the pattern. Like gitignore, a pattern without a slash (`.DS_Store`, `*.tmp`)
+    /// matches at any depth, and a pattern that matches a directory also ignores
+    /// everything within it.
+    pub fn add_ignore(&mut self, pattern: &str) -> &mut Self {
+        self.ignores.push(pattern.to_owned());
+        self
+    }
+
+    pub fn set_compression(&mut self, compression: TarCompression) -> &mut Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn set_metadata(&mut self, metadata: ArchiveMetadata) -> &mut Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// When enabled, zero the uid/gid of every entry, set the modification time
+    /// to a fixed (non-zero) timestamp, reduce the mode to 0o755 (executable)
+    /// or 0o644, and pack entries in a stable order, so that archives of the
+    /// same tree are byte-for-byte identical regardless of which user produced
+    /// them. Empty directories are not packed in this mode.
+    pub fn set_normalize(&mut self, normalize: bool) -> &mut Self {
+        self.normalize = normalize;
+        self
+    }
+
     pub fn set_prefix(&mut self, prefix: &'l str) -> &mut Self {
         self.prefix = prefix;
         self
@@ -72,13 +142,86 @@ impl<'l> TarArchiver<'l> {
         // Create .tar
         let tar = fs::create_file(self.output_file)?;
 
-        // Compress to .tar.gz
-        let tar_gz = GzEncoder::new(tar, Compression::fast());
+        // Compress to .tar.gz, .tar.zst, or leave as-is
+        match self.compression {
+            TarCompression::Gzip => {
+                self.pack_into(GzEncoder::new(tar, Compression::fast()))?
+                    .finish()
+                    .into_diagnostic()?;
+            }
+            TarCompression::None => {
+                self.pack_into(tar)?.flush().into_diagnostic()?;
+            }
+            TarCompression::Zstd(level) => {
+                self.pack_into(zstd::Encoder::new(tar, level).into_diagnostic()?)?
+                    .finish()
+                    .into_diagnostic()?;
+            }
+        };
+
+        Ok(())
+    }
+
+    fn create_ignore_patterns(&self) -> Vec<String> {
+        let mut patterns = vec![];
 
-        // Add the files to the archive
-        let mut archive = Builder::new(tar_gz);
+        for ignore in &self.ignores {
+            let ignore = ignore.trim_start_matches('/').trim_end_matches('/');
+
+            if ignore.contains('/') {
+                patterns.push(ignore.to_owned());
+                patterns.push(format!("{ignore}/**/*"));
+            } else {
+                patterns.push(format!("**/{ignore}"));
+                patterns.push(format!("**/{ignore}/**/*"));
+            }
+        }
+
+        patterns
+    }
+
+    fn pack_into<W: Write>(&self, writer: W) -> miette::Result<W> {
+        let ignore_patterns = self.create_ignore_patterns();
+        let ignore = GlobSet::new(&ignore_patterns)?;
+        let is_ignored = |name: &str| {
+            if !ignore_patterns.is_empty() && ignore.matches(name) {
+                trace!(target: LOG_TARGET, "Ignoring {}", color::file(name));
+
+                return true;
+            }
+
+            false
+        };
 
-        for (file, source) in &self.sources {
+        // Add the files to the archive. When normalizing,
+        // only the executable bit of each file's mode is preserved.
+        let mut archive = Builder::new(writer);
+
+        if self.normalize {
+            archive.mode(HeaderMode::Deterministic);
+        }
+
+        // Add metadata as the first entry, so that reading it
+        // only requires scanning the start of the archive
+        if let Some(metadata) = &self.metadata {
+            let data = metadata.to_json()?;
+
+            trace!(target: LOG_TARGET, "Packing metadata {}", data);
+
+            let mut header = Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+
+            archive
+                .append_data(&mut header, ARCHIVE_METADATA_ENTRY, data.as_bytes())
+                .into_diagnostic()?;
+        }
+
+        let mut sources = self.sources.iter().collect::<Vec<_>>();
+        sources.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (file, source) in sources {
             if !source.exists() {
                 trace!(
                     target: LOG_TARGET,
@@ -89,6 +232,10 @@ impl<'l> TarArchiver<'l> {
                 continue;
             }
 
+            if is_ignored(file) {
+                continue;
+            }
+
             if source.is_file() {
                 trace!(target: LOG_TARGET, "Packing file {}", color::path(source));
 
@@ -97,6 +244,35 @@ impl<'l> TarArchiver<'l> {
                 archive
                     .append_file(prepend_name(file, self.prefix), &mut fh)
                     .into_diagnostic()?;
+            } else if self.normalize || !ignore_patterns.is_empty() {
+                trace!(
+                    target: LOG_TARGET,
+                    "Packing directory {} (file by file)",
+                    color::path(source)
+                );
+
+                // Directory walking order is not guaranteed, so pack
+                // each file individually in a sorted order, which also
+                // allows ignored files to be filtered
+                let mut files = glob::walk_files(source, &["**/*"])?;
+                files.sort();
+
+                for dir_file in files {
+                    let file_name = prepend_name(
+                        &path::to_string(dir_file.strip_prefix(source).unwrap())?,
+                        file,
+                    );
+
+                    if is_ignored(&file_name) {
+                        continue;
+                    }
+
+                    let mut fh = fs::open_file(&dir_file)?;
+
+                    archive
+                        .append_file(prepend_name(&file_name, self.prefix), &mut fh)
+                        .into_diagnostic()?;
+                }
             } else {
                 trace!(
                     target: LOG_TARGET,
@@ -110,29 +286,38 @@ impl<'l> TarArchiver<'l> {
             }
         }
 
-        for (file_prefix, glob) in &self.source_globs {
+        let mut source_globs = self.source_globs.iter().collect::<Vec<_>>();
+        source_globs.sort_by(|a, b| a.0.cmp(b.0).then(a.1.cmp(b.1)));
+
+        for (file_prefix, glob) in source_globs {
             trace!(
                 target: LOG_TARGET,
                 "Packing glob {}",
                 color::path(self.input_root.join(glob))
             );
 
-            for file in glob::walk_files(self.input_root, &[glob])? {
+            let mut files = glob::walk_files(self.input_root, &[glob])?;
+            files.sort();
+
+            for file in files {
+                let file_name = prepend_name(
+                    &path::to_string(file.strip_prefix(self.input_root).unwrap())?,
+                    file_prefix,
+                );
+
+                if is_ignored(&file_name) {
+                    continue;
+                }
+
                 let mut fh = fs::open_file(&file)?;
-                let file_name = path::to_string(file.strip_prefix(self.input_root).unwrap())?;
 
                 archive
-                    .append_file(
-                        prepend_name(&prepend_name(&file_name, file_prefix), self.prefix),
-                        &mut fh,
-                    )
+                    .append_file(prepend_name(&file_name, self.prefix), &mut fh)
                     .into_diagnostic()?;
             }
         }
 
-        archive.finish().into_diagnostic()?;
-
-        Ok(())
+        archive.into_inner().into_diagnostic()
     }
 }
 
@@ -163,15 +348,124 @@ pub fn tar<I: AsRef<Path>, O: AsRef<Path>>(
     Ok(())
 }
 
+/// Open a tar archive and decompress it based on its magic bytes,
+/// so that gzip, zstd, and uncompressed archives are all supported.
+/// Bzip2 archives can only be read when the `bzip2` feature is enabled.
+fn open_tar(input_file: &Path) -> miette::Result<Box<dyn Read>> {
+    open_tar_reader(input_file, fs::open_file(input_file)?)
+}
+
+/// Like [`open_tar`], but also counts the bytes read from the file, which
+/// are the compressed bytes, so that progress can be reported in a single pass.
+fn open_tar_with_counter(input_file: &Path) -> miette::Result<(Box<dyn Read>, Rc<Cell<u64>>)> {
+    let counter = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        inner: fs::open_file(input_file)?,
+        count: Rc::clone(&counter),
+    };
+
+    Ok((open_tar_reader(input_file, reader)?, counter))
+}
+
+fn open_tar_reader<R: Read + 'static>(
+    input_file: &Path,
+    reader: R,
+) -> miette::Result<Box<dyn Read>> {
+    let mut reader = BufReader::new(reader);
+    let magic = reader.fill_buf().into_diagnostic()?;
+
+    Ok(if magic.starts_with(&GZIP_MAGIC) {
+        Box::new(GzDecoder::new(reader))
+    } else if magic.starts_with(&BZIP2_MAGIC) {
+        #[cfg(feature = "bzip2")]
+        {
+            Box::new(bzip2::read::BzDecoder::new(reader))
+        }
+
+        #[cfg(not(feature = "bzip2"))]
+        {
+            return Err(crate::errors::ArchiveError::UnsupportedFormat {
+                path: input_file.to_path_buf(),
+                format: "bzip2".into(),
+            }
+            .into());
+        }
+    } else if magic.starts_with(&ZSTD_MAGIC) {
+        Box::new(zstd::Decoder::with_buffer(reader).into_diagnostic()?)
+    } else {
+        Box::new(reader)
+    })
+}
+
+struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.inner.read(buf)?;
+        self.count.set(self.count.get() + size as u64);
+
+        Ok(size)
+    }
+}
+
+/// Reports the number of bytes read from the archive file so far, and the total
+/// size of the archive file (when it could be determined). These are compressed
+/// bytes for compressed archives. Called at least once per entry.
+pub type UntarProgress<'p> = &'p mut dyn FnMut(u64, Option<u64>);
+
+fn get_tar_size(input_file: &Path) -> Option<u64> {
+    input_file.metadata().ok().map(|meta| meta.len())
+}
+
+// The trailing blocks and compression footer may not have been read when the
+// last entry is reported, so report the archive as completely read afterwards
+fn report_tar_completed(on_progress: &mut Option<UntarProgress>, read: u64, total: Option<u64>) {
+    if let (Some(on_progress), Some(total)) = (on_progress, total) {
+        if read < total {
+            on_progress(total, Some(total));
+        }
+    }
+}
+
 #[track_caller]
 pub fn untar<I: AsRef<Path>, O: AsRef<Path>>(
     input_file: I,
     output_dir: O,
     remove_prefix: Option<&str>,
 ) -> miette::Result<()> {
-    let input_file = input_file.as_ref();
-    let output_dir = output_dir.as_ref();
+    untar_internal(
+        input_file.as_ref(),
+        output_dir.as_ref(),
+        remove_prefix,
+        None,
+    )
+}
 
+/// Like [`untar`], but reports extraction progress to the provided callback.
+#[track_caller]
+pub fn untar_with_progress<I: AsRef<Path>, O: AsRef<Path>>(
+    input_file: I,
+    output_dir: O,
+    remove_prefix: Option<&str>,
+    on_progress: UntarProgress,
+) -> miette::Result<()> {
+    untar_internal(
+        input_file.as_ref(),
+        output_dir.as_ref(),
+        remove_prefix,
+        Some(on_progress),
+    )
+}
+
+fn untar_internal(
+    input_file: &Path,
+    output_dir: &Path,
+    remove_prefix: Option<&str>,
+    mut on_progress: Option<UntarProgress>,
+) -> miette::Result<()> {
     debug!(
         target: LOG_TARGET,
         "Unpacking tar archive {} to {}",
@@ -181,11 +475,10 @@ pub fn untar<I: AsRef<Path>, O: AsRef<Path>>(
 
     fs::create_dir_all(output_dir)?;
 
-    // Open .tar.gz file
-    let tar_gz = fs::open_file(input_file)?;
+    let total = on_progress.as_ref().and_then(|_| get_tar_size(input_file));
 
-    // Decompress to .tar
-    let tar = GzDecoder::new(tar_gz);
+    // Open and decompress to .tar
+    let (tar, read) = open_tar_with_counter(input_file)?;
 
     // Unpack the archive into the output dir
     let mut archive = Archive::new(tar);
@@ -194,6 +487,11 @@ pub fn untar<I: AsRef<Path>, O: AsRef<Path>>(
         let mut entry = entry_result.into_diagnostic()?;
         let mut path: PathBuf = entry.path().into_diagnostic()?.into_owned();
 
+        // Metadata is not an output, so never unpack it
+        if path == Path::new(ARCHIVE_METADATA_ENTRY) {
+            continue;
+        }
+
         // Remove the prefix
         if let Some(prefix) = remove_prefix {
             if path.starts_with(prefix) {
@@ -209,8 +507,42 @@ pub fn untar<I: AsRef<Path>, O: AsRef<Path>>(
         }
 
         entry.unpack(&output_path).into_diagnostic()?;
+
+        if let Some(on_progress) = &mut on_progress {
+            on_progress(read.get(), total);
+        }
     }
 
+    report_tar_completed(&mut on_progress, read.get(), total);
+
+    Ok(())
+}
+
+// Return the permission bits (rwx) that unpacking the entry would apply,
+// or `None` on Windows, where the executable bit does not exist.
+#[cfg(unix)]
+fn get_entry_mode<R: Read>(entry: &tar::Entry<'_, R>) -> miette::Result<Option<u32>> {
+    Ok(Some(entry.header().mode().into_diagnostic()? & 0o777))
+}
+
+#[cfg(windows)]
+fn get_entry_mode<R: Read>(_entry: &tar::Entry<'_, R>) -> miette::Result<Option<u32>> {
+    Ok(None)
+}
+
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: Option<u32>) -> miette::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(mode) = mode {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn set_file_mode(_path: &Path, _mode: Option<u32>) -> miette::Result<()> {
     Ok(())
 }
 
@@ -221,9 +553,40 @@ pub fn untar_with_diff<I: AsRef<Path>, O: AsRef<Path>>(
     output_dir: O,
     remove_prefix: Option<&str>,
 ) -> miette::Result<()> {
-    let input_file = input_file.as_ref();
-    let output_dir = output_dir.as_ref();
+    untar_with_diff_internal(
+        differ,
+        input_file.as_ref(),
+        output_dir.as_ref(),
+        remove_prefix,
+        None,
+    )
+}
+
+/// Like [`untar_with_diff`], but reports extraction progress to the provided callback.
+#[track_caller]
+pub fn untar_with_diff_and_progress<I: AsRef<Path>, O: AsRef<Path>>(
+    differ: &mut TreeDiffer,
+    input_file: I,
+    output_dir: O,
+    remove_prefix: Option<&str>,
+    on_progress: UntarProgress,
+) -> miette::Result<()> {
+    untar_with_diff_internal(
+        differ,
+        input_file.as_ref(),
+        output_dir.as_ref(),
+        remove_prefix,
+        Some(on_progress),
+    )
+}
 
+fn untar_with_diff_internal(
+    differ: &mut TreeDiffer,
+    input_file: &Path,
+    output_dir: &Path,
+    remove_prefix: Option<&str>,
+    mut on_progress: Option<UntarProgress>,
+) -> miette::Result<()> {
     debug!(
         target: LOG_TARGET,
         "Unpacking tar archive {} to {}",
@@ -233,11 +596,14 @@ pub fn untar_with_diff<I: AsRef<Path>, O: AsRef<Path>>(
 
     fs::create_dir_all(output_dir)?;
 
-    // Open .tar.gz file
-    let tar_gz = fs::open_file(input_file)?;
+    // Entries are streamed and can't be read in parallel, so fingerprint
+    // the existing destination files upfront, in parallel, instead
+    differ.fingerprint_tracked_files()?;
+
+    let total = on_progress.as_ref().and_then(|_| get_tar_size(input_file));
 
-    // Decompress to .tar
-    let tar = GzDecoder::new(tar_gz);
+    // Open and decompress to .tar
+    let (tar, read) = open_tar_with_counter(input_file)?;
 
     // Unpack the archive into the output dir
     let mut archive = Archive::new(tar);
@@ -247,6 +613,11 @@ pub fn untar_with_diff<I: AsRef<Path>, O: AsRef<Path>>(
         let mut entry = entry_result.into_diagnostic()?;
         let mut path: PathBuf = entry.path().into_diagnostic()?.into_owned();
 
+        // Metadata is not an output, so never unpack it
+        if path == Path::new(ARCHIVE_METADATA_ENTRY) {
+            continue;
+        }
+
         // Remove the prefix
         if let Some(prefix) = remove_prefix {
             if path.starts_with(prefix) {
@@ -261,15 +632,96 @@ pub fn untar_with_diff<I: AsRef<Path>, O: AsRef<Path>>(
             fs::create_dir_all(parent_dir)?;
         }
 
-        // Unpack the file if different than destination
-        if differ.should_write_source(entry.size(), &mut entry, &output_path)? {
+        // Unpack the file if different than destination. When comparing contents,
+        // the entry is consumed, so buffer it to write it ourselves if different
+        if entry.header().entry_type().is_file()
+            && differ.can_compare_source(entry.size(), &output_path)
+        {
+            let mode = get_entry_mode(&entry)?;
+            let mut contents = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut contents).into_diagnostic()?;
+
+            if differ.should_write_source(entry.size(), &mut contents.as_slice(), &output_path)?
+                || differ.modes.get(&output_path) != mode.as_ref()
+            {
+                // Remove first, as the destination may be a hard link
+                fs::remove_file(&output_path)?;
+                fs::write_file(&output_path, &contents)?;
+                set_file_mode(&output_path, mode)?;
+            }
+        } else if differ.should_write_source(entry.size(), &mut entry, &output_path)? {
             entry.unpack(&output_path).into_diagnostic()?;
         }
 
         differ.untrack_file(&output_path);
+
+        if let Some(on_progress) = &mut on_progress {
+            on_progress(read.get(), total);
+        }
     }
 
+    report_tar_completed(&mut on_progress, read.get(), total);
+
     differ.remove_stale_tracked_files();
 
     Ok(())
 }
+
+/// Read the contents of multiple entries by name, without extracting the archive.
+/// Tar archives have no index, so *every* entry must be scanned, but this is done
+/// in a single pass regardless of how many names are requested.
+#[track_caller]
+pub fn read_tar_entries<I: AsRef<Path>>(
+    input_file: I,
+    names: &[&str],
+) -> miette::Result<FxHashMap<String, Vec<u8>>> {
+    let input_file = input_file.as_ref();
+    let mut entries = FxHashMap::default();
+
+    trace!(
+        target: LOG_TARGET,
+        "Scanning tar archive {} for entries {}",
+        color::path(input_file),
+        names.join(", "),
+    );
+
+    let mut archive = Archive::new(open_tar(input_file)?);
+
+    for entry_result in archive.entries().into_diagnostic()? {
+        let mut entry = entry_result.into_diagnostic()?;
+        let path = path::to_virtual_string(entry.path().into_diagnostic()?)?;
+
+        if !names.contains(&path.as_str()) {
+            continue;
+        }
+
+        let mut contents = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut contents).into_diagnostic()?;
+
+        entries.insert(path, contents);
+
+        if entries.len() == names.len() {
+            break;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Read the contents of a single entry by name. This requires a scan of the
+/// archive, so prefer [`read_tar_entries`] when reading many entries.
+#[track_caller]
+pub fn read_tar_entry<I: AsRef<Path>>(
+    input_file: I,
+    name: &str,
+) -> miette::Result<Option<Vec<u8>>> {
+    Ok(read_tar_entries(input_file, &[name])?.remove(name))
+}
+
+/// Read the metadata that was stamped into the archive when packed, if any.
+#[track_caller]
+pub fn read_tar_metadata<I: AsRef<Path>>(input_file: I) -> miette::Result<Option<ArchiveMetadata>> {
+    read_tar_entry(input_file, ARCHIVE_METADATA_ENTRY)?
+        .map(|data| ArchiveMetadata::from_json(&String::from_utf8_lossy(&data)))
+        .transpose()
+}
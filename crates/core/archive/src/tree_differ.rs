@@ -1,12 +1,85 @@
-use rustc_hash::FxHashMap;
+use miette::IntoDiagnostic;
+use moon_utils::path;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use rustc_hash::{FxHashMap, FxHashSet};
+use sha2::{Digest, Sha256};
 use starbase_utils::{fs, glob};
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Files larger than this size (in bytes) are not read when fingerprinting.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Differences between a tree and a manifest of file fingerprints.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct TreeManifestDiff {
+    /// Files in the tree that are not in the manifest.
+    pub added: Vec<PathBuf>,
+
+    /// Files in both, but with a different fingerprint.
+    pub changed: Vec<PathBuf>,
+
+    /// Files in both, with the same fingerprint, but different permissions.
+    /// This is always empty on Windows.
+    pub mode_changed: Vec<PathBuf>,
+
+    /// Files in the manifest that are not in the tree.
+    pub removed: Vec<PathBuf>,
+}
+
+impl TreeManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.changed.is_empty()
+            && self.mode_changed.is_empty()
+            && self.removed.is_empty()
+    }
+}
+
+/// Return the permission bits of the file, or `None` on Windows,
+/// where the executable bit does not exist.
+#[cfg(unix)]
+fn get_file_mode(meta: &std::fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+
+    Some(meta.permissions().mode() & 0o7777)
+}
+
+#[cfg(windows)]
+fn get_file_mode(_meta: &std::fs::Metadata) -> Option<u32> {
+    None
+}
 
 pub struct TreeDiffer {
     /// A mapping of all files in the destination directory
     /// to their current file sizes.
     pub files: FxHashMap<PathBuf, u64>,
+
+    /// A mapping of all files in the destination directory
+    /// to their current permission bits. Empty on Windows.
+    pub modes: FxHashMap<PathBuf, u32>,
+
+    /// A mapping of all files in the destination directory
+    /// to their last modified time, if supported by the platform.
+    pub modified_times: FxHashMap<PathBuf, SystemTime>,
+
+    /// A mapping of files in the destination directory to their content
+    /// fingerprint. Only populated by [`TreeDiffer::fingerprint_tracked_files`].
+    pub fingerprints: FxHashMap<PathBuf, String>,
+
+    /// Files larger than this size (in bytes) will be fingerprinted
+    /// by their size and modified time, instead of their contents.
+    pub max_file_size: u64,
+
+    /// The number of threads to fingerprint files with.
+    /// Defaults to the number of logical CPUs.
+    pub concurrency: usize,
+
+    // Only created when a custom concurrency is configured,
+    // otherwise rayon's global pool is used.
+    pool: Option<ThreadPool>,
 }
 
 impl TreeDiffer {
@@ -15,11 +88,23 @@ impl TreeDiffer {
     /// recursively scan all files and create an internal manifest to track diffing.
     pub fn load(dest_root: &Path, paths: &[String]) -> miette::Result<Self> {
         let mut files = FxHashMap::default();
+        let mut modes = FxHashMap::default();
+        let mut modified_times = FxHashMap::default();
 
         let mut track = |file: PathBuf| {
             if file.exists() {
                 let size = match std::fs::metadata(&file) {
-                    Ok(meta) => meta.len(),
+                    Ok(meta) => {
+                        if let Some(mode) = get_file_mode(&meta) {
+                            modes.insert(file.clone(), mode);
+                        }
+
+                        if let Ok(modified) = meta.modified() {
+                            modified_times.insert(file.clone(), modified);
+                        }
+
+                        meta.len()
+                    }
                     Err(_) => 0,
                 };
 
@@ -45,7 +130,152 @@ impl TreeDiffer {
             }
         }
 
-        Ok(TreeDiffer { files })
+        Ok(TreeDiffer {
+            files,
+            modes,
+            modified_times,
+            fingerprints: FxHashMap::default(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            concurrency: num_cpus::get(),
+            pool: None,
+        })
+    }
+
+    /// Set the number of threads to fingerprint files with. A value of 1
+    /// (or 0) will fingerprint files sequentially on the current thread.
+    pub fn set_concurrency(&mut self, concurrency: usize) -> &mut Self {
+        self.concurrency = concurrency;
+
+        // Build the pool once, instead of for every fingerprint batch
+        self.pool = if concurrency <= 1 || concurrency == rayon::current_num_threads() {
+            None
+        } else {
+            ThreadPoolBuilder::new()
+                .num_threads(concurrency)
+                .build()
+                .ok()
+        };
+
+        self
+    }
+
+    /// Set the file size threshold in which files are no longer read when fingerprinting.
+    pub fn set_max_file_size(&mut self, size: u64) -> &mut Self {
+        self.max_file_size = size;
+        self
+    }
+
+    /// Create a fingerprint for the file at the defined path, by hashing the provided
+    /// contents. However, if the file is larger than the max file size, the contents
+    /// are *not read*, and the fingerprint is derived from the file's size and last
+    /// modified time instead. This is a tradeoff of speed over accuracy, as large files
+    /// (media, binaries, etc) are expensive to read, but a change that retains the
+    /// same size and modified time will not be detected.
+    pub fn fingerprint_file<T: Read>(
+        &self,
+        path: &Path,
+        contents: &mut T,
+    ) -> miette::Result<String> {
+        let meta = fs::metadata(path)?;
+        let size = meta.len();
+
+        if size > self.max_file_size {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos())
+                .unwrap_or_default();
+
+            return Ok(format!("{size}:{modified}"));
+        }
+
+        let mut sha = Sha256::new();
+        let mut buffer = [0; 8192];
+
+        loop {
+            let read = contents.read(&mut buffer).into_diagnostic()?;
+
+            if read == 0 {
+                break;
+            }
+
+            sha.update(&buffer[0..read]);
+        }
+
+        Ok(format!("{:x}", sha.finalize()))
+    }
+
+    /// Fingerprint many files across the configured number of threads.
+    /// The returned fingerprints are in the same order as the provided files,
+    /// regardless of which thread completed first.
+    pub fn fingerprint_files(&self, files: &[&PathBuf]) -> miette::Result<Vec<String>> {
+        let fingerprint = |file: &&PathBuf| {
+            let mut contents = BufReader::new(fs::open_file(file)?);
+
+            self.fingerprint_file(file, &mut contents)
+        };
+
+        if self.concurrency <= 1 || files.len() <= 1 {
+            return files.iter().map(fingerprint).collect();
+        }
+
+        match &self.pool {
+            Some(pool) => pool.install(|| files.par_iter().map(fingerprint).collect()),
+            None => files.par_iter().map(fingerprint).collect(),
+        }
+    }
+
+    /// Fingerprint all tracked files that are not larger than the max file size,
+    /// across the configured number of threads. This allows sources to be compared
+    /// against the destination by [`TreeDiffer::should_write_source`].
+    pub fn fingerprint_tracked_files(&mut self) -> miette::Result<()> {
+        let files = self
+            .files
+            .iter()
+            .filter(|(_, size)| **size <= self.max_file_size)
+            .map(|(file, _)| file)
+            .collect::<Vec<_>>();
+
+        let fingerprints = self.fingerprint_files(&files)?;
+        let fingerprints = files
+            .into_iter()
+            .cloned()
+            .zip(fingerprints)
+            .collect::<FxHashMap<_, _>>();
+
+        self.fingerprints = fingerprints;
+
+        Ok(())
+    }
+
+    /// Return true if the source, with the provided size, can be compared against
+    /// the destination by contents, as the destination has been fingerprinted.
+    pub fn can_compare_source(&self, source_size: u64, dest_path: &Path) -> bool {
+        self.fingerprints.contains_key(dest_path)
+            && self
+                .files
+                .get(dest_path)
+                .is_some_and(|size| *size == source_size)
+    }
+
+    /// Create a single fingerprint for all tracked files, derived from each file's
+    /// path (relative to the destination root) and its individual fingerprint.
+    pub fn fingerprint_tree(&self, dest_root: &Path) -> miette::Result<String> {
+        let mut files = self.files.keys().collect::<Vec<_>>();
+        files.sort();
+
+        let fingerprints = self.fingerprint_files(&files)?;
+        let mut sha = Sha256::new();
+
+        for (file, fingerprint) in files.into_iter().zip(fingerprints) {
+            let rel_file = file.strip_prefix(dest_root).unwrap_or(file);
+
+            sha.update(rel_file.to_string_lossy().as_bytes());
+            sha.update(fingerprint.as_bytes());
+        }
+
+        Ok(format!("{:x}", sha.finalize()))
     }
 
     /// Compare 2 files byte by byte and return true if both files are equal.
@@ -74,6 +304,119 @@ impl TreeDiffer {
         Ok(false)
     }
 
+    /// Compare against a previous snapshot of the same tree, and return a list
+    /// of files that have been created, or modified (different size or last
+    /// modified time), since.
+    pub fn diff(&self, previous: &TreeDiffer) -> Vec<PathBuf> {
+        let mut changed = self
+            .files
+            .iter()
+            .filter_map(|(file, size)| match previous.files.get(file) {
+                Some(prev_size)
+                    if prev_size == size
+                        && previous.modified_times.get(file) == self.modified_times.get(file) =>
+                {
+                    None
+                }
+                _ => Some(file.to_owned()),
+            })
+            .collect::<Vec<_>>();
+
+        changed.sort();
+        changed
+    }
+
+    /// Create a manifest of the permission bits of all tracked files, keyed by
+    /// file path relative to the destination root (with forward slashes), to be
+    /// passed to [`TreeDiffer::diff_against_manifest_with_modes`] on a later build.
+    pub fn get_modes_manifest(&self, dest_root: &Path) -> miette::Result<FxHashMap<String, u32>> {
+        let mut manifest = FxHashMap::default();
+
+        for (file, mode) in &self.modes {
+            manifest.insert(
+                path::to_virtual_string(file.strip_prefix(dest_root).unwrap_or(file))?,
+                *mode,
+            );
+        }
+
+        Ok(manifest)
+    }
+
+    /// Compare against a manifest of file fingerprints from a previous build,
+    /// keyed by file path relative to the destination root (with forward slashes).
+    /// Unlike [`TreeDiffer::diff`], the previous files do not need to exist on disk,
+    /// and files are compared by their fingerprint, not their size.
+    pub fn diff_against_manifest(
+        &self,
+        dest_root: &Path,
+        manifest: &FxHashMap<String, String>,
+    ) -> miette::Result<TreeManifestDiff> {
+        self.diff_against_manifest_with_modes(dest_root, manifest, &FxHashMap::default())
+    }
+
+    /// Like [`TreeDiffer::diff_against_manifest`], but also compare against a manifest
+    /// of permission bits from a previous build. Files with matching contents but
+    /// different permissions (an executable bit flip) are reported as mode changed.
+    /// Files missing from the modes manifest are not compared.
+    pub fn diff_against_manifest_with_modes(
+        &self,
+        dest_root: &Path,
+        manifest: &FxHashMap<String, String>,
+        modes: &FxHashMap<String, u32>,
+    ) -> miette::Result<TreeManifestDiff> {
+        let mut diff = TreeManifestDiff::default();
+        let mut seen = FxHashSet::default();
+        let mut candidates = vec![];
+
+        for file in self.files.keys() {
+            let rel_file = path::to_virtual_string(file.strip_prefix(dest_root).unwrap_or(file))?;
+
+            match manifest.get(&rel_file) {
+                Some(prev_hash) => {
+                    candidates.push((file, rel_file.clone(), prev_hash));
+                }
+                None => {
+                    diff.added.push(file.to_owned());
+                }
+            };
+
+            seen.insert(rel_file);
+        }
+
+        // Only files in both need to be read, so fingerprint them all at once
+        let fingerprints = self.fingerprint_files(
+            &candidates
+                .iter()
+                .map(|(file, _, _)| *file)
+                .collect::<Vec<_>>(),
+        )?;
+
+        for ((file, rel_file, prev_hash), fingerprint) in candidates.into_iter().zip(fingerprints) {
+            if fingerprint != *prev_hash {
+                diff.changed.push(file.to_owned());
+            } else if let (Some(mode), Some(prev_mode)) =
+                (self.modes.get(file), modes.get(&rel_file))
+            {
+                if mode != prev_mode {
+                    diff.mode_changed.push(file.to_owned());
+                }
+            }
+        }
+
+        for rel_file in manifest.keys() {
+            if !seen.contains(rel_file) {
+                diff.removed.push(dest_root.join(rel_file));
+            }
+        }
+
+        diff.added.sort();
+        diff.changed.sort();
+        diff.mode_changed.sort();
+        diff.removed.sort();
+
+        Ok(diff)
+    }
+
     /// Remove all files in the destination directory that have not been
     /// overwritten with a source file, or are the same size as a source file.
     /// We can assume these are stale artifacts that should no longer exist!
@@ -81,6 +424,10 @@ impl TreeDiffer {
         for (file, _) in self.files.drain() {
             let _ = std::fs::remove_file(file);
         }
+
+        self.modes.clear();
+        self.modified_times.clear();
+        self.fingerprints.clear();
     }
 
     /// Determine whether the source should be written to the destination.
@@ -89,7 +436,7 @@ impl TreeDiffer {
     pub fn should_write_source<T: Read>(
         &self,
         source_size: u64,
-        _source: &mut T,
+        source: &mut T,
         dest_path: &Path,
     ) -> miette::Result<bool> {
         // If the destination doesn't exist, always use the source
@@ -106,23 +453,21 @@ impl TreeDiffer {
             return Ok(true);
         }
 
-        // NOTE: gzip doesn't support seeking, so we can't use the following util then!
-
-        // If the file sizes are the same, compare byte ranges to determine a difference
-        // let mut dest = fs::open_file(dest_path)?;
-
-        // if self.are_files_equal(source, &mut dest)? {
-        //     return Ok(true);
-        // }
-
-        // // Reset read pointer to the start of the buffer
-        // source.seek(std::io::SeekFrom::Start(0));
+        // If the file sizes are the same, compare the source contents against the
+        // destination's fingerprint. This *consumes* the source, so it must be
+        // buffered by the caller if it will need to be written.
+        let Some(dest_fingerprint) = self.fingerprints.get(dest_path) else {
+            return Ok(true);
+        };
 
-        Ok(true)
+        Ok(self.fingerprint_file(dest_path, source)? != *dest_fingerprint)
     }
 
     /// Untrack a destination file from the internal registry.
     pub fn untrack_file(&mut self, dest: &Path) {
         self.files.remove(dest);
+        self.modes.remove(dest);
+        self.modified_times.remove(dest);
+        self.fingerprints.remove(dest);
     }
 }
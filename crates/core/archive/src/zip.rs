@@ -1,37 +1,99 @@
+use crate::errors::ArchiveError;
 use crate::helpers::prepend_name;
+use crate::metadata::ArchiveMetadata;
 use miette::IntoDiagnostic;
-use moon_logger::{debug, map_list, trace};
+use moon_logger::{debug, map_list, trace, warn};
 use moon_utils::path::to_string;
 use starbase_styles::color;
 use starbase_utils::fs;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use zip::write::FileOptions;
 use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
 const LOG_TARGET: &str = "moon:archive:zip";
 
+// Files smaller than this are stored as-is, as compressing them
+// costs more time than is saved in size
+pub const ZIP_COMPRESSION_THRESHOLD: u64 = 4096;
+
+// Unix file type bits stored in the upper half of the external attributes
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+pub struct ZipOptions<'l> {
+    /// When true, symlinks are resolved and the target contents are stored.
+    /// When false, symlinks are stored as symlink entries.
+    pub follow_symlinks: bool,
+
+    /// Metadata to stamp as the archive comment.
+    pub metadata: Option<&'l ArchiveMetadata>,
+
+    /// Files smaller than this size (in bytes) are stored uncompressed,
+    /// while larger files are deflated.
+    pub compression_threshold: u64,
+}
+
+impl<'l> Default for ZipOptions<'l> {
+    fn default() -> Self {
+        ZipOptions {
+            follow_symlinks: true,
+            metadata: None,
+            compression_threshold: ZIP_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UnzipOptions {
+    /// When true, symlink entries are restored as symlinks (or copies of their
+    /// target on Windows). When false, symlink entries are skipped.
+    pub restore_symlinks: bool,
+}
+
 fn zip_contents<P: AsRef<str>>(
     archive: &mut ZipWriter<File>,
     path: &Path,
     root: &Path,
     prefix: P,
+    zip_options: &ZipOptions,
 ) -> miette::Result<()> {
     let prefix = prefix.as_ref();
     let name = to_string(path.strip_prefix(root).unwrap())?;
 
-    #[allow(unused_mut)] // windows
     let mut options = FileOptions::default().compression_method(CompressionMethod::Stored);
 
+    if !zip_options.follow_symlinks && path.is_symlink() {
+        let target = to_string(std::fs::read_link(path).into_diagnostic()?)?;
+
+        trace!(
+            target: LOG_TARGET,
+            "Zipping symlink {} (to {})",
+            color::path(path),
+            color::path(&target),
+        );
+
+        archive
+            .add_symlink(prepend_name(&name, prefix), target, options)
+            .into_diagnostic()?;
+
+        return Ok(());
+    }
+
     if path.is_file() {
+        let metadata = path.metadata().into_diagnostic()?;
+
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
 
-            options =
-                options.unix_permissions(path.metadata().into_diagnostic()?.permissions().mode());
+            options = options.unix_permissions(metadata.permissions().mode());
+        }
+
+        if metadata.len() >= zip_options.compression_threshold {
+            options = options.compression_method(CompressionMethod::Deflated);
         }
 
         trace!(target: LOG_TARGET, "Zipping file {}", color::path(path));
@@ -52,7 +114,7 @@ fn zip_contents<P: AsRef<str>>(
         for entry in fs::read_dir(path)? {
             let path = entry.path();
 
-            zip_contents(archive, &path, root, prefix)?;
+            zip_contents(archive, &path, root, prefix, zip_options)?;
         }
 
         return Ok(());
@@ -67,6 +129,41 @@ pub fn zip<I: AsRef<Path>, O: AsRef<Path>>(
     files: &[String],
     output_file: O,
     base_prefix: Option<&str>,
+) -> miette::Result<()> {
+    zip_with_metadata(input_root, files, output_file, base_prefix, None)
+}
+
+/// Zip the files and stamp the provided metadata as the archive comment,
+/// which is stored in the central directory and can be read without extraction.
+#[track_caller]
+pub fn zip_with_metadata<I: AsRef<Path>, O: AsRef<Path>>(
+    input_root: I,
+    files: &[String],
+    output_file: O,
+    base_prefix: Option<&str>,
+    metadata: Option<&ArchiveMetadata>,
+) -> miette::Result<()> {
+    zip_with_options(
+        input_root,
+        files,
+        output_file,
+        base_prefix,
+        ZipOptions {
+            metadata,
+            ..ZipOptions::default()
+        },
+    )
+}
+
+/// Zip the files with the provided options, such as preserving symlinks
+/// instead of storing the contents of their targets.
+#[track_caller]
+pub fn zip_with_options<I: AsRef<Path>, O: AsRef<Path>>(
+    input_root: I,
+    files: &[String],
+    output_file: O,
+    base_prefix: Option<&str>,
+    options: ZipOptions,
 ) -> miette::Result<()> {
     let input_root = input_root.as_ref();
     let output_file = output_file.as_ref();
@@ -89,7 +186,11 @@ pub fn zip<I: AsRef<Path>, O: AsRef<Path>>(
     for file in files {
         let input_src = input_root.join(file);
 
-        zip_contents(&mut archive, &input_src, input_root, prefix)?;
+        zip_contents(&mut archive, &input_src, input_root, prefix, &options)?;
+    }
+
+    if let Some(metadata) = options.metadata {
+        archive.set_comment(metadata.to_json()?);
     }
 
     archive.finish().into_diagnostic()?;
@@ -102,6 +203,22 @@ pub fn unzip<I: AsRef<Path>, O: AsRef<Path>>(
     input_file: I,
     output_dir: O,
     remove_prefix: Option<&str>,
+) -> miette::Result<()> {
+    unzip_with_options(
+        input_file,
+        output_dir,
+        remove_prefix,
+        UnzipOptions::default(),
+    )
+}
+
+/// Unzip the archive with the provided options, such as restoring symlink entries.
+#[track_caller]
+pub fn unzip_with_options<I: AsRef<Path>, O: AsRef<Path>>(
+    input_file: I,
+    output_dir: O,
+    remove_prefix: Option<&str>,
+    options: UnzipOptions,
 ) -> miette::Result<()> {
     let input_file = input_file.as_ref();
     let output_dir = output_dir.as_ref();
@@ -120,6 +237,7 @@ pub fn unzip<I: AsRef<Path>, O: AsRef<Path>>(
 
     // Unpack the archive into the output dir
     let mut archive = ZipArchive::new(zip).into_diagnostic()?;
+    let mut symlinks = vec![];
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i).into_diagnostic()?;
@@ -143,6 +261,40 @@ pub fn unzip<I: AsRef<Path>, O: AsRef<Path>>(
             fs::create_dir_all(parent_dir)?;
         }
 
+        // If a symlink, the contents are the link target. Links are created
+        // after all other entries, as the target may not have been unpacked yet.
+        if file
+            .unix_mode()
+            .is_some_and(|mode| mode & S_IFMT == S_IFLNK)
+        {
+            if !options.restore_symlinks {
+                warn!(
+                    target: LOG_TARGET,
+                    "Skipping symlink {}, as restoring symlinks is not enabled",
+                    color::path(&output_path),
+                );
+
+                continue;
+            }
+
+            let mut target = String::new();
+            file.read_to_string(&mut target).into_diagnostic()?;
+
+            // Links must not point outside of the output directory, otherwise
+            // an archive could read or overwrite arbitrary files
+            if !is_link_target_within(output_dir, &output_path, &target) {
+                return Err(ArchiveError::SymlinkOutsideOutput {
+                    path: output_path,
+                    target,
+                }
+                .into());
+            }
+
+            symlinks.push((output_path, target));
+
+            continue;
+        }
+
         // If a folder, create the dir
         if file.is_dir() {
             fs::create_dir_all(&output_path)?;
@@ -153,13 +305,136 @@ pub fn unzip<I: AsRef<Path>, O: AsRef<Path>>(
             let mut out = fs::create_file(&output_path)?;
 
             io::copy(&mut file, &mut out).into_diagnostic()?;
-            fs::update_perms(&output_path, file.unix_mode())?;
+
+            // Restore mode bits (executables) when recorded. Archives created on
+            // Windows have no mode, so keep the default permissions instead.
+            // This is a no-op on Windows.
+            if let Some(mode) = file.unix_mode() {
+                fs::update_perms(&output_path, Some(mode))?;
+            }
+        }
+    }
+
+    for (output_path, target) in symlinks {
+        unzip_symlink(&output_path, &target)?;
+    }
+
+    Ok(())
+}
+
+// Resolve the target relative to the link's parent directory, without touching
+// the file system (the target may not exist yet), and verify it's within the dir.
+fn is_link_target_within(dir: &Path, link: &Path, target: &str) -> bool {
+    let mut resolved = link.parent().map(PathBuf::from).unwrap_or_default();
+
+    for component in Path::new(target).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return false;
+                }
+            }
+            // Absolute paths and drive prefixes
+            Component::RootDir | Component::Prefix(_) => return false,
         }
     }
 
+    resolved.starts_with(dir)
+}
+
+#[cfg(unix)]
+fn unzip_symlink(output_path: &Path, target: &str) -> miette::Result<()> {
+    trace!(
+        target: LOG_TARGET,
+        "Creating symlink {} (to {})",
+        color::path(output_path),
+        color::path(target),
+    );
+
+    if output_path.symlink_metadata().is_ok() {
+        fs::remove(output_path)?;
+    }
+
+    std::os::unix::fs::symlink(target, output_path).into_diagnostic()?;
+
+    Ok(())
+}
+
+// Symlinks require elevated privileges on Windows,
+// so copy the target to the link location instead.
+#[cfg(windows)]
+fn unzip_symlink(output_path: &Path, target: &str) -> miette::Result<()> {
+    let source = output_path
+        .parent()
+        .map(|parent| parent.join(target))
+        .unwrap_or_else(|| target.into());
+
+    trace!(
+        target: LOG_TARGET,
+        "Copying symlink target {} to {}",
+        color::path(&source),
+        color::path(output_path),
+    );
+
+    if source.is_dir() {
+        fs::copy_dir_all(&source, &source, output_path)?;
+    } else if source.is_file() {
+        fs::copy_file(&source, output_path)?;
+    }
+
     Ok(())
 }
 
+/// Read the contents of a single entry by name, without extracting the archive.
+/// Zip archives contain a central directory, so the entry is seeked to directly,
+/// and other entries are not read.
+#[track_caller]
+pub fn read_zip_entry<I: AsRef<Path>>(
+    input_file: I,
+    name: &str,
+) -> miette::Result<Option<Vec<u8>>> {
+    let input_file = input_file.as_ref();
+
+    trace!(
+        target: LOG_TARGET,
+        "Reading entry {} from archive {}",
+        color::file(name),
+        color::path(input_file),
+    );
+
+    let zip = fs::open_file(input_file)?;
+    let mut archive = ZipArchive::new(zip).into_diagnostic()?;
+
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(error).into_diagnostic(),
+    };
+
+    let mut contents = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut contents).into_diagnostic()?;
+
+    Ok(Some(contents))
+}
+
+/// Read the metadata that was stamped into the archive comment when zipped, if any.
+#[track_caller]
+pub fn read_zip_metadata<I: AsRef<Path>>(input_file: I) -> miette::Result<Option<ArchiveMetadata>> {
+    let zip = fs::open_file(input_file.as_ref())?;
+    let archive = ZipArchive::new(zip).into_diagnostic()?;
+    let comment = archive.comment();
+
+    if comment.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ArchiveMetadata::from_json(&String::from_utf8_lossy(
+        comment,
+    ))?))
+}
+
 // Uncomment when needed!
 // #[track_caller]
 // pub fn unzip_with_diff<I: AsRef<Path>, O: AsRef<Path>>(
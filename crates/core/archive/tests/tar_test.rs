@@ -1,4 +1,7 @@
-use moon_archive::{tar, untar};
+use moon_archive::{
+    read_tar_entries, read_tar_entry, read_tar_metadata, tar, untar, ArchiveMetadata, TarArchiver,
+    ARCHIVE_METADATA_ENTRY,
+};
 use moon_test_utils::create_sandbox;
 use moon_utils::string_vec;
 use std::fs;
@@ -404,3 +407,610 @@ fn tars_dir_with_prefix_thats_removed() {
         &output.join("folder/nested/other.js")
     ));
 }
+
+mod read_entry {
+    use super::*;
+
+    #[test]
+    fn reads_single_entry() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(
+            sandbox.path(),
+            &string_vec!["file.txt", "folder"],
+            &archive,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_tar_entry(&archive, "folder/file.js").unwrap().unwrap(),
+            fs::read(sandbox.path().join("folder/file.js")).unwrap()
+        );
+        assert_eq!(read_tar_entry(&archive, "unknown.txt").unwrap(), None);
+    }
+
+    #[test]
+    fn reads_many_entries_in_one_pass() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(
+            sandbox.path(),
+            &string_vec!["file.txt", "folder"],
+            &archive,
+            None,
+        )
+        .unwrap();
+
+        let entries = read_tar_entries(&archive, &["file.txt", "folder/nested/other.js"]).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries.get("file.txt").unwrap(),
+            &fs::read(sandbox.path().join("file.txt")).unwrap()
+        );
+    }
+}
+
+mod metadata {
+    use super::*;
+
+    fn create_metadata() -> ArchiveMetadata {
+        ArchiveMetadata {
+            moon_version: "1.11.0".into(),
+            created_at: 1690000000000,
+            git_sha: Some("0123456789abcdef0123456789abcdef01234567".into()),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_pack_and_read() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        let mut tar = TarArchiver::new(sandbox.path(), &archive);
+        tar.add_source(sandbox.path().join("file.txt"), Some("file.txt"));
+        tar.set_metadata(create_metadata());
+        tar.pack().unwrap();
+
+        assert_eq!(
+            read_tar_metadata(&archive).unwrap(),
+            Some(create_metadata())
+        );
+    }
+
+    #[test]
+    fn returns_none_when_not_stamped() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(sandbox.path(), &string_vec!["file.txt"], &archive, None).unwrap();
+
+        assert_eq!(read_tar_metadata(&archive).unwrap(), None);
+    }
+
+    #[test]
+    fn doesnt_unpack_metadata_entry() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+        let output = sandbox.path().join("out");
+
+        let mut tar = TarArchiver::new(sandbox.path(), &archive);
+        tar.add_source(sandbox.path().join("file.txt"), Some("file.txt"));
+        tar.set_metadata(create_metadata());
+        tar.pack().unwrap();
+
+        untar(&archive, &output, None).unwrap();
+
+        assert!(output.join("file.txt").exists());
+        assert!(!output.join(ARCHIVE_METADATA_ENTRY).exists());
+    }
+}
+
+#[cfg(unix)]
+mod permissions {
+    use super::*;
+    use moon_archive::{untar_with_diff, TreeDiffer};
+    use std::os::unix::fs::PermissionsExt;
+
+    fn get_mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn restores_executable_bit() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/script.sh", "#!/bin/sh\necho hi");
+
+        let script = sandbox.path().join("bin/script.sh");
+        fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let archive = sandbox.path().join("out.tar.gz");
+        let output = sandbox.path().join("out");
+
+        tar(
+            sandbox.path(),
+            &string_vec!["bin/script.sh", "file.txt"],
+            &archive,
+            None,
+        )
+        .unwrap();
+        untar(&archive, &output, None).unwrap();
+
+        assert_eq!(get_mode(&output.join("bin/script.sh")), 0o755);
+        assert_eq!(
+            get_mode(&output.join("file.txt")),
+            get_mode(&sandbox.path().join("file.txt"))
+        );
+    }
+
+    #[test]
+    fn restores_executable_bit_when_overwriting() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/script.sh", "#!/bin/sh\necho hi");
+
+        let script = sandbox.path().join("bin/script.sh");
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(sandbox.path(), &string_vec!["bin"], &archive, None).unwrap();
+
+        // Simulate a stale output that lost its executable bit
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["bin"]).unwrap();
+
+        untar_with_diff(&mut differ, &archive, sandbox.path(), None).unwrap();
+
+        assert_eq!(get_mode(&script), 0o755);
+    }
+}
+
+#[cfg(unix)]
+mod normalize {
+    use super::*;
+    use moon_test_utils::Sandbox;
+    use std::os::unix::fs::PermissionsExt;
+    use tar::Archive;
+
+    fn create_tree(mode: u32, exec_mode: u32) -> Sandbox {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/script.sh", "#!/bin/sh\necho hi");
+
+        let root = sandbox.path();
+
+        fs::set_permissions(
+            root.join("bin/script.sh"),
+            fs::Permissions::from_mode(exec_mode),
+        )
+        .unwrap();
+
+        for file in ["file.txt", "folder/file.js", "folder/nested/other.js"] {
+            fs::set_permissions(root.join(file), fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        sandbox
+    }
+
+    fn pack(root: &Path) -> Vec<u8> {
+        let archive = root.join("out.tar.gz");
+
+        let mut tar = TarArchiver::new(root, &archive);
+        tar.set_normalize(true);
+        tar.add_source(root.join("bin"), Some("bin"));
+        tar.add_source(root.join("folder"), Some("folder"));
+        tar.add_source_glob("file.*", None);
+        tar.pack().unwrap();
+
+        fs::read(archive).unwrap()
+    }
+
+    #[test]
+    fn produces_identical_archives_across_owners() {
+        // Simulate trees created by users with different umasks
+        let a = create_tree(0o600, 0o700);
+        let b = create_tree(0o664, 0o775);
+
+        assert_eq!(pack(a.path()), pack(b.path()));
+    }
+
+    #[test]
+    fn zeroes_ownership_and_keeps_executable_bit() {
+        let sandbox = create_tree(0o640, 0o750);
+        let data = pack(sandbox.path());
+
+        let mut archive = Archive::new(flate2::read::GzDecoder::new(data.as_slice()));
+
+        for entry in archive.entries().unwrap() {
+            let entry = entry.unwrap();
+            let header = entry.header();
+            let path = entry.path().unwrap().into_owned();
+
+            assert_eq!(header.uid().unwrap(), 0);
+            assert_eq!(header.gid().unwrap(), 0);
+            assert_eq!(
+                header.mode().unwrap(),
+                if path.ends_with("script.sh") {
+                    0o755
+                } else {
+                    0o644
+                }
+            );
+        }
+    }
+}
+
+mod compression {
+    use super::*;
+    use moon_archive::TarCompression;
+
+    fn pack_with(input: &Path, archive: &Path, compression: TarCompression) {
+        let mut tar = TarArchiver::new(input, archive);
+        tar.set_compression(compression);
+        tar.add_source(input.join("file.txt"), Some("file.txt"));
+        tar.add_source(input.join("folder"), Some("folder"));
+        tar.pack().unwrap();
+    }
+
+    fn read_magic(archive: &Path) -> Vec<u8> {
+        fs::read(archive).unwrap()[0..4].to_vec()
+    }
+
+    #[test]
+    fn defaults_to_gzip() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        pack_with(sandbox.path(), &archive, TarCompression::default());
+
+        assert_eq!(read_magic(&archive)[0..2], [0x1f, 0x8b]);
+    }
+
+    #[test]
+    fn round_trips_zstd() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.zst");
+        let output = sandbox.path().join("out");
+
+        pack_with(sandbox.path(), &archive, TarCompression::zstd());
+
+        assert_eq!(read_magic(&archive), [0x28, 0xb5, 0x2f, 0xfd]);
+
+        untar(&archive, &output, None).unwrap();
+
+        assert!(file_contents_match(
+            &sandbox.path().join("file.txt"),
+            &output.join("file.txt")
+        ));
+        assert!(file_contents_match(
+            &sandbox.path().join("folder/nested/other.js"),
+            &output.join("folder/nested/other.js")
+        ));
+    }
+
+    #[test]
+    fn supports_custom_zstd_level() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.zst");
+        let output = sandbox.path().join("out");
+
+        pack_with(sandbox.path(), &archive, TarCompression::Zstd(19));
+        untar(&archive, &output, None).unwrap();
+
+        assert!(output.join("folder/file.js").exists());
+    }
+
+    #[test]
+    fn round_trips_uncompressed() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar");
+        let output = sandbox.path().join("out");
+
+        pack_with(sandbox.path(), &archive, TarCompression::None);
+        untar(&archive, &output, None).unwrap();
+
+        assert!(file_contents_match(
+            &sandbox.path().join("file.txt"),
+            &output.join("file.txt")
+        ));
+    }
+
+    #[test]
+    fn reads_entries_from_zstd() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.zst");
+
+        pack_with(sandbox.path(), &archive, TarCompression::zstd());
+
+        assert_eq!(
+            read_tar_entry(&archive, "file.txt").unwrap().unwrap(),
+            fs::read(sandbox.path().join("file.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn round_trips_empty_archives() {
+        for compression in [
+            TarCompression::Gzip,
+            TarCompression::None,
+            TarCompression::zstd(),
+        ] {
+            let sandbox = create_sandbox("archives");
+            let archive = sandbox.path().join("empty.tar");
+            let output = sandbox.path().join("out");
+
+            let mut tar = TarArchiver::new(sandbox.path(), &archive);
+            tar.set_compression(compression);
+            tar.pack().unwrap();
+
+            untar(&archive, &output, None).unwrap();
+
+            assert!(output.exists());
+            assert_eq!(fs::read_dir(&output).unwrap().count(), 0);
+        }
+    }
+}
+
+mod bzip2_compression {
+    use super::*;
+
+    #[cfg(feature = "bzip2")]
+    #[test]
+    fn unpacks_bzip2() {
+        use std::io::Write;
+
+        let sandbox = create_sandbox("archives");
+        let uncompressed = sandbox.path().join("out.tar");
+        let archive = sandbox.path().join("out.tar.bz2");
+        let output = sandbox.path().join("out");
+
+        let mut tar = TarArchiver::new(sandbox.path(), &uncompressed);
+        tar.set_compression(moon_archive::TarCompression::None);
+        tar.add_source(sandbox.path().join("file.txt"), Some("file.txt"));
+        tar.pack().unwrap();
+
+        let mut encoder = bzip2::write::BzEncoder::new(
+            fs::File::create(&archive).unwrap(),
+            bzip2::Compression::fast(),
+        );
+        encoder.write_all(&fs::read(uncompressed).unwrap()).unwrap();
+        encoder.finish().unwrap();
+
+        untar(&archive, &output, None).unwrap();
+
+        assert!(file_contents_match(
+            &sandbox.path().join("file.txt"),
+            &output.join("file.txt")
+        ));
+    }
+
+    #[cfg(not(feature = "bzip2"))]
+    #[test]
+    #[should_panic(expected = "bzip2 compressed archives are not supported")]
+    fn errors_when_feature_disabled() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.bz2");
+
+        fs::write(&archive, b"BZh91AY&SY").unwrap();
+
+        untar(&archive, sandbox.path().join("out"), None).unwrap();
+    }
+}
+
+mod progress {
+    use super::*;
+    use moon_archive::{untar_with_diff_and_progress, untar_with_progress, TreeDiffer};
+
+    #[test]
+    fn reports_progress_per_entry() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+        let output = sandbox.path().join("out");
+
+        tar(
+            sandbox.path(),
+            &string_vec!["file.txt", "folder/file.js", "folder/nested/other.js"],
+            &archive,
+            None,
+        )
+        .unwrap();
+
+        let mut calls = vec![];
+
+        untar_with_progress(&archive, &output, None, &mut |extracted, total| {
+            calls.push((extracted, total));
+        })
+        .unwrap();
+
+        let total = fs::metadata(&archive).unwrap().len();
+
+        assert!(calls.len() >= 3);
+        assert!(calls.iter().all(|call| call.1 == Some(total)));
+        assert!(calls.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_eq!(calls.last().unwrap().0, total);
+    }
+
+    #[test]
+    fn ignores_metadata_entry() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+        let output = sandbox.path().join("out");
+
+        let mut tar = TarArchiver::new(sandbox.path(), &archive);
+        tar.add_source(sandbox.path().join("file.txt"), Some("file.txt"));
+        tar.set_metadata(ArchiveMetadata::default());
+        tar.pack().unwrap();
+
+        let mut calls = vec![];
+
+        untar_with_progress(&archive, &output, None, &mut |extracted, total| {
+            calls.push((extracted, total));
+        })
+        .unwrap();
+
+        let size = fs::metadata(&archive).unwrap().len();
+
+        assert_eq!(calls, vec![(size, Some(size))]);
+    }
+
+    #[test]
+    fn reports_progress_when_diffing() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(sandbox.path(), &string_vec!["folder"], &archive, None).unwrap();
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["folder"]).unwrap();
+        let mut calls = 0;
+
+        untar_with_diff_and_progress(
+            &mut differ,
+            &archive,
+            sandbox.path(),
+            None,
+            &mut |_, total| {
+                calls += 1;
+                assert!(total.is_some());
+            },
+        )
+        .unwrap();
+
+        assert!(calls >= 2);
+    }
+}
+
+mod ignore {
+    use super::*;
+
+    fn create_junk() -> moon_test_utils::Sandbox {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file(".DS_Store", "junk");
+        sandbox.create_file("folder/.DS_Store", "junk");
+        sandbox.create_file("folder/temp.tmp", "junk");
+        sandbox.create_file("folder/nested/temp.tmp", "junk");
+        sandbox.create_file("folder/cache/data.json", "{}");
+        sandbox
+    }
+
+    fn assert_ignored(archive: &Path) {
+        let output = archive.parent().unwrap().join("out");
+
+        untar(archive, &output, None).unwrap();
+
+        assert!(output.join("file.txt").exists());
+        assert!(output.join("folder/file.js").exists());
+        assert!(output.join("folder/nested/other.js").exists());
+        assert!(!output.join(".DS_Store").exists());
+        assert!(!output.join("folder/.DS_Store").exists());
+        assert!(!output.join("folder/temp.tmp").exists());
+        assert!(!output.join("folder/nested/temp.tmp").exists());
+        assert!(!output.join("folder/cache").exists());
+    }
+
+    #[test]
+    fn skips_ignored_sources_and_dir_files() {
+        let sandbox = create_junk();
+        let archive = sandbox.path().join("out.tar.gz");
+
+        TarArchiver::new(sandbox.path(), &archive)
+            .add_source(sandbox.path().join("file.txt"), None)
+            .add_source(sandbox.path().join(".DS_Store"), None)
+            .add_source(sandbox.path().join("folder"), None)
+            .add_ignore(".DS_Store")
+            .add_ignore("*.tmp")
+            .add_ignore("folder/cache")
+            .pack()
+            .unwrap();
+
+        assert_ignored(&archive);
+    }
+
+    #[test]
+    fn skips_ignored_glob_files() {
+        let sandbox = create_junk();
+        // Write outside of the sandbox, so the archive doesn't match the glob
+        let temp = moon_test_utils::create_temp_dir();
+        let archive = temp.path().join("out.tar.gz");
+
+        TarArchiver::new(sandbox.path(), &archive)
+            .add_source_glob("**/*", None)
+            .add_ignore(".DS_Store")
+            .add_ignore("*.tmp")
+            .add_ignore("folder/cache")
+            .pack()
+            .unwrap();
+
+        assert_ignored(&archive);
+    }
+
+    #[test]
+    fn matches_without_prefix() {
+        let sandbox = create_junk();
+        let archive = sandbox.path().join("out.tar.gz");
+
+        TarArchiver::new(sandbox.path(), &archive)
+            .add_source(sandbox.path().join("folder"), None)
+            .add_ignore("folder/*.tmp")
+            .set_prefix("prefix")
+            .pack()
+            .unwrap();
+
+        assert!(read_tar_entry(&archive, "prefix/folder/file.js")
+            .unwrap()
+            .is_some());
+        assert!(read_tar_entry(&archive, "prefix/folder/temp.tmp")
+            .unwrap()
+            .is_none());
+        assert!(read_tar_entry(&archive, "prefix/folder/nested/temp.tmp")
+            .unwrap()
+            .is_some());
+    }
+}
+
+mod diff {
+    use super::*;
+    use moon_archive::{untar_with_diff, TreeDiffer};
+
+    #[test]
+    fn restores_changed_files_of_the_same_size() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(sandbox.path(), &string_vec!["folder"], &archive, None).unwrap();
+
+        let file = sandbox.path().join("folder/file.js");
+        let original = fs::read_to_string(&file).unwrap();
+
+        fs::write(&file, original.to_uppercase()).unwrap();
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["folder"]).unwrap();
+
+        untar_with_diff(&mut differ, &archive, sandbox.path(), None).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+    }
+
+    #[test]
+    fn keeps_unchanged_files_and_removes_stale_files() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.tar.gz");
+
+        tar(sandbox.path(), &string_vec!["folder"], &archive, None).unwrap();
+
+        let file = sandbox.path().join("folder/file.js");
+        let original = fs::read_to_string(&file).unwrap();
+
+        sandbox.create_file("folder/stale.js", "stale");
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["folder"]).unwrap();
+
+        untar_with_diff(&mut differ, &archive, sandbox.path(), None).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+        assert!(sandbox.path().join("folder/nested/other.js").exists());
+        assert!(!sandbox.path().join("folder/stale.js").exists());
+    }
+}
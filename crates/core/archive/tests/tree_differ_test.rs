@@ -1,7 +1,10 @@
-use moon_archive::TreeDiffer;
+use filetime::FileTime;
+use moon_archive::{TreeDiffer, TreeManifestDiff};
 use moon_test_utils::create_sandbox;
 use moon_utils::string_vec;
+use rustc_hash::FxHashMap;
 use std::fs::{self, File};
+use std::io::Read;
 
 #[test]
 fn loads_all_files() {
@@ -32,6 +35,352 @@ fn removes_stale_files() {
     assert_eq!(differ.files.len(), 0);
 }
 
+mod diff {
+    use super::*;
+
+    #[test]
+    fn returns_nothing_if_unchanged() {
+        let sandbox = create_sandbox("generator");
+        let before = TreeDiffer::load(sandbox.path(), &string_vec!["templates"]).unwrap();
+        let after = TreeDiffer::load(sandbox.path(), &string_vec!["templates"]).unwrap();
+
+        assert!(after.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn captures_created_and_modified_files() {
+        let sandbox = create_sandbox("generator");
+        let before = TreeDiffer::load(sandbox.path(), &string_vec!["templates"]).unwrap();
+
+        let created = sandbox.path().join("templates/standard/undeclared.txt");
+        fs::write(&created, "generated").unwrap();
+
+        let modified = sandbox.path().join("templates/standard/file.txt");
+        fs::write(&modified, "modified content").unwrap();
+
+        let after = TreeDiffer::load(sandbox.path(), &string_vec!["templates"]).unwrap();
+
+        assert_eq!(after.diff(&before), vec![modified, created]);
+    }
+
+    #[test]
+    fn captures_modified_files_of_the_same_size() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "before");
+
+        let file = sandbox.path().join("out/a.txt");
+        filetime::set_file_mtime(&file, FileTime::from_unix_time(1_000_000, 0)).unwrap();
+
+        let before = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        fs::write(&file, "after!").unwrap();
+        filetime::set_file_mtime(&file, FileTime::from_unix_time(2_000_000, 0)).unwrap();
+
+        let after = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        assert_eq!(after.diff(&before), vec![file]);
+    }
+}
+
+mod diff_against_manifest {
+    use super::*;
+
+    #[test]
+    fn returns_nothing_if_matching() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "a");
+
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+        let path = sandbox.path().join("out/a.txt");
+        let hash = differ
+            .fingerprint_file(&path, &mut File::open(&path).unwrap())
+            .unwrap();
+
+        let diff = differ
+            .diff_against_manifest(
+                sandbox.path(),
+                &FxHashMap::from_iter([("out/a.txt".to_owned(), hash)]),
+            )
+            .unwrap();
+
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn captures_added_changed_and_removed_files() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "a");
+        sandbox.create_file("out/b.txt", "b");
+        sandbox.create_file("out/nested/d.txt", "d");
+
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+        let path = sandbox.path().join("out/a.txt");
+        let hash = differ
+            .fingerprint_file(&path, &mut File::open(&path).unwrap())
+            .unwrap();
+
+        // The files from the previous build do not exist on disk
+        let manifest = FxHashMap::from_iter([
+            ("out/a.txt".to_owned(), hash),
+            ("out/b.txt".to_owned(), "previous".to_owned()),
+            ("out/c.txt".to_owned(), "previous".to_owned()),
+        ]);
+
+        assert_eq!(
+            differ
+                .diff_against_manifest(sandbox.path(), &manifest)
+                .unwrap(),
+            TreeManifestDiff {
+                added: vec![sandbox.path().join("out/nested/d.txt")],
+                changed: vec![sandbox.path().join("out/b.txt")],
+                mode_changed: vec![],
+                removed: vec![sandbox.path().join("out/c.txt")],
+            }
+        );
+    }
+}
+
+#[cfg(unix)]
+mod diff_modes {
+    use super::*;
+    use moon_test_utils::Sandbox;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    fn set_mode(path: &Path, mode: u32) {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    fn create_manifests(sandbox: &Sandbox) -> (FxHashMap<String, String>, FxHashMap<String, u32>) {
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+        let mut manifest = FxHashMap::default();
+
+        for file in differ.files.keys() {
+            let rel_file = file
+                .strip_prefix(sandbox.path())
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+
+            manifest.insert(
+                rel_file,
+                differ
+                    .fingerprint_file(file, &mut File::open(file).unwrap())
+                    .unwrap(),
+            );
+        }
+
+        (manifest, differ.get_modes_manifest(sandbox.path()).unwrap())
+    }
+
+    #[test]
+    fn returns_nothing_if_modes_match() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/bin", "binary");
+        set_mode(&sandbox.path().join("out/bin"), 0o755);
+
+        let (manifest, modes) = create_manifests(&sandbox);
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        assert!(differ
+            .diff_against_manifest_with_modes(sandbox.path(), &manifest, &modes)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn captures_executable_bit_changes() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/bin", "binary");
+        sandbox.create_file("out/file.txt", "text");
+        set_mode(&sandbox.path().join("out/bin"), 0o644);
+        set_mode(&sandbox.path().join("out/file.txt"), 0o644);
+
+        let (manifest, modes) = create_manifests(&sandbox);
+
+        set_mode(&sandbox.path().join("out/bin"), 0o755);
+
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        assert_eq!(
+            differ
+                .diff_against_manifest_with_modes(sandbox.path(), &manifest, &modes)
+                .unwrap(),
+            TreeManifestDiff {
+                mode_changed: vec![sandbox.path().join("out/bin")],
+                ..TreeManifestDiff::default()
+            }
+        );
+    }
+
+    #[test]
+    fn content_changes_take_precedence() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/bin", "binary");
+        set_mode(&sandbox.path().join("out/bin"), 0o644);
+
+        let (manifest, modes) = create_manifests(&sandbox);
+
+        sandbox.create_file("out/bin", "new binary");
+        set_mode(&sandbox.path().join("out/bin"), 0o755);
+
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        assert_eq!(
+            differ
+                .diff_against_manifest_with_modes(sandbox.path(), &manifest, &modes)
+                .unwrap(),
+            TreeManifestDiff {
+                changed: vec![sandbox.path().join("out/bin")],
+                ..TreeManifestDiff::default()
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_modes_without_manifest() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/bin", "binary");
+
+        let (manifest, _) = create_manifests(&sandbox);
+
+        set_mode(&sandbox.path().join("out/bin"), 0o700);
+
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        assert!(differ
+            .diff_against_manifest(sandbox.path(), &manifest)
+            .unwrap()
+            .is_empty());
+    }
+}
+
+mod concurrency {
+    use super::*;
+
+    fn create_tree() -> moon_test_utils::Sandbox {
+        let sandbox = create_sandbox("generator");
+
+        for i in 0..100 {
+            sandbox.create_file(&format!("out/{}/file-{i}.txt", i % 7), i.to_string());
+        }
+
+        sandbox
+    }
+
+    #[test]
+    fn fingerprints_tree_the_same_regardless_of_threads() {
+        let sandbox = create_tree();
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        differ.set_concurrency(1);
+        let sequential = differ.fingerprint_tree(sandbox.path()).unwrap();
+
+        differ.set_concurrency(8);
+        let parallel = differ.fingerprint_tree(sandbox.path()).unwrap();
+
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn diffs_manifest_the_same_regardless_of_threads() {
+        let sandbox = create_tree();
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        // Every even file has changed since the previous build
+        let manifest = FxHashMap::from_iter(differ.files.keys().map(|file| {
+            let rel_file = file
+                .strip_prefix(sandbox.path())
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            let hash = differ
+                .fingerprint_file(file, &mut File::open(file).unwrap())
+                .unwrap();
+            let index = fs::read_to_string(file).unwrap().parse::<usize>().unwrap();
+
+            (
+                rel_file,
+                if index % 2 == 0 {
+                    "previous".to_owned()
+                } else {
+                    hash
+                },
+            )
+        }));
+
+        differ.set_concurrency(1);
+        let sequential = differ
+            .diff_against_manifest(sandbox.path(), &manifest)
+            .unwrap();
+
+        differ.set_concurrency(8);
+        let parallel = differ
+            .diff_against_manifest(sandbox.path(), &manifest)
+            .unwrap();
+
+        assert_eq!(sequential.changed.len(), 50);
+        assert_eq!(sequential, parallel);
+    }
+}
+
+mod fingerprint {
+    use super::*;
+
+    struct CountingReader<T: Read> {
+        inner: T,
+        count: usize,
+    }
+
+    impl<T: Read> Read for CountingReader<T> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.inner.read(buf)?;
+            self.count += read;
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn hashes_contents_under_threshold() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("file.txt", "content");
+
+        let path = sandbox.path().join("file.txt");
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["templates"]).unwrap();
+        let mut reader = CountingReader {
+            inner: File::open(&path).unwrap(),
+            count: 0,
+        };
+
+        assert_eq!(
+            differ.fingerprint_file(&path, &mut reader).unwrap(),
+            "ed7002b439e9ac845f22357d822bac1444730fbdb6016d3ec9432297b9ec9f73"
+        );
+        assert_eq!(reader.count, 7);
+    }
+
+    #[test]
+    fn doesnt_read_contents_over_threshold() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("file.txt", "content");
+
+        let path = sandbox.path().join("file.txt");
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["templates"]).unwrap();
+        differ.set_max_file_size(5);
+
+        let mut reader = CountingReader {
+            inner: File::open(&path).unwrap(),
+            count: 0,
+        };
+
+        assert!(differ
+            .fingerprint_file(&path, &mut reader)
+            .unwrap()
+            .starts_with("7:"));
+        assert_eq!(reader.count, 0);
+    }
+}
+
 mod equal_check {
     use super::*;
 
@@ -83,3 +432,67 @@ mod equal_check {
         assert!(!differ.are_files_equal(&mut source, &mut dest).unwrap());
     }
 }
+
+mod should_write_source {
+    use super::*;
+
+    #[test]
+    fn writes_when_not_fingerprinted() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "content");
+
+        let differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+
+        assert!(differ
+            .should_write_source(
+                7,
+                &mut "content".as_bytes(),
+                &sandbox.path().join("out/a.txt")
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn doesnt_write_when_contents_match() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "content");
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+        differ.fingerprint_tracked_files().unwrap();
+
+        let dest = sandbox.path().join("out/a.txt");
+
+        assert!(differ.can_compare_source(7, &dest));
+        assert!(!differ
+            .should_write_source(7, &mut "content".as_bytes(), &dest)
+            .unwrap());
+    }
+
+    #[test]
+    fn writes_when_same_size_contents_differ() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "content");
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+        differ.fingerprint_tracked_files().unwrap();
+
+        assert!(differ
+            .should_write_source(
+                7,
+                &mut "CONTENT".as_bytes(),
+                &sandbox.path().join("out/a.txt")
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn cant_compare_when_size_differs() {
+        let sandbox = create_sandbox("generator");
+        sandbox.create_file("out/a.txt", "content");
+
+        let mut differ = TreeDiffer::load(sandbox.path(), &string_vec!["out"]).unwrap();
+        differ.fingerprint_tracked_files().unwrap();
+
+        assert!(!differ.can_compare_source(3, &sandbox.path().join("out/a.txt")));
+    }
+}
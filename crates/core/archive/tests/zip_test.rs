@@ -1,8 +1,12 @@
-use moon_archive::{unzip, zip};
-use moon_test_utils::create_sandbox;
+use moon_archive::{
+    read_zip_entry, read_zip_metadata, unzip, unzip_with_options, zip, zip_with_metadata,
+    zip_with_options, ArchiveMetadata, UnzipOptions, ZipOptions,
+};
+use moon_test_utils::{create_sandbox, create_temp_dir};
 use moon_utils::string_vec;
 use std::fs;
 use std::path::Path;
+use zip::{CompressionMethod, ZipArchive};
 
 fn file_contents_match(a: &Path, b: &Path) -> bool {
     fs::read_to_string(a).unwrap() == fs::read_to_string(b).unwrap()
@@ -276,3 +280,340 @@ fn zips_dir_with_prefix_thats_removed() {
         &output.join("folder/nested/other.js")
     ));
 }
+
+mod read_entry {
+    use super::*;
+
+    #[test]
+    fn reads_single_entry_without_extracting() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.zip");
+
+        zip(
+            sandbox.path(),
+            &string_vec!["file.txt", "folder"],
+            &archive,
+            Some("prefix"),
+        )
+        .unwrap();
+
+        let contents = read_zip_entry(&archive, "prefix/folder/nested/other.js").unwrap();
+
+        assert_eq!(
+            contents.unwrap(),
+            fs::read(sandbox.path().join("folder/nested/other.js")).unwrap()
+        );
+        assert!(!sandbox.path().join("prefix").exists());
+    }
+
+    #[test]
+    fn returns_none_for_missing_entry() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.zip");
+
+        zip(sandbox.path(), &string_vec!["file.txt"], &archive, None).unwrap();
+
+        assert_eq!(read_zip_entry(&archive, "unknown.txt").unwrap(), None);
+    }
+}
+
+mod metadata {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_zip_and_read() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.zip");
+        let metadata = ArchiveMetadata {
+            moon_version: "1.11.0".into(),
+            created_at: 1690000000000,
+            git_sha: None,
+        };
+
+        zip_with_metadata(
+            sandbox.path(),
+            &string_vec!["file.txt"],
+            &archive,
+            None,
+            Some(&metadata),
+        )
+        .unwrap();
+
+        assert_eq!(read_zip_metadata(&archive).unwrap(), Some(metadata));
+    }
+
+    #[test]
+    fn returns_none_when_not_stamped() {
+        let sandbox = create_sandbox("archives");
+        let archive = sandbox.path().join("out.zip");
+
+        zip(sandbox.path(), &string_vec!["file.txt"], &archive, None).unwrap();
+
+        assert_eq!(read_zip_metadata(&archive).unwrap(), None);
+    }
+}
+
+#[cfg(unix)]
+mod permissions {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn get_mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn restores_executable_bit() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/script.sh", "#!/bin/sh\necho hi");
+
+        let script = sandbox.path().join("bin/script.sh");
+        fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(
+            sandbox.path().join("file.txt"),
+            fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+
+        let archive = sandbox.path().join("out.zip");
+        let output = sandbox.path().join("out");
+
+        zip(
+            sandbox.path(),
+            &string_vec!["bin", "file.txt"],
+            &archive,
+            None,
+        )
+        .unwrap();
+        unzip(&archive, &output, None).unwrap();
+
+        assert_eq!(get_mode(&output.join("bin/script.sh")), 0o755);
+        assert_eq!(get_mode(&output.join("file.txt")), 0o644);
+    }
+}
+
+#[cfg(unix)]
+mod symlinks {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn follows_symlinks_by_default() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/real.sh", "#!/bin/sh\necho hi");
+
+        symlink("real.sh", sandbox.path().join("bin/link.sh")).unwrap();
+
+        let archive = sandbox.path().join("out.zip");
+        let output = create_temp_dir();
+
+        zip(sandbox.path(), &string_vec!["bin"], &archive, None).unwrap();
+        unzip(&archive, output.path(), None).unwrap();
+
+        let link = output.path().join("bin/link.sh");
+
+        assert!(!link.is_symlink());
+        assert_eq!(fs::read_to_string(link).unwrap(), "#!/bin/sh\necho hi");
+    }
+
+    #[test]
+    fn round_trips_symlinks_when_not_following() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/real.sh", "#!/bin/sh\necho hi");
+
+        symlink("real.sh", sandbox.path().join("bin/link.sh")).unwrap();
+        symlink("../folder", sandbox.path().join("bin/folder")).unwrap();
+
+        let archive = sandbox.path().join("out.zip");
+        let output = create_temp_dir();
+
+        zip_with_options(
+            sandbox.path(),
+            &string_vec!["bin", "folder"],
+            &archive,
+            None,
+            ZipOptions {
+                follow_symlinks: false,
+                ..ZipOptions::default()
+            },
+        )
+        .unwrap();
+        unzip_with_options(
+            &archive,
+            output.path(),
+            None,
+            UnzipOptions {
+                restore_symlinks: true,
+            },
+        )
+        .unwrap();
+
+        let link = output.path().join("bin/link.sh");
+
+        assert!(link.is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("real.sh"));
+        assert_eq!(fs::read_to_string(link).unwrap(), "#!/bin/sh\necho hi");
+
+        let dir_link = output.path().join("bin/folder");
+
+        assert!(dir_link.is_symlink());
+        assert_eq!(fs::read_link(&dir_link).unwrap(), Path::new("../folder"));
+        assert!(dir_link.is_dir());
+    }
+
+    fn zip_without_following(sandbox: &Path, files: &[String], archive: &Path) {
+        zip_with_options(
+            sandbox,
+            files,
+            archive,
+            None,
+            ZipOptions {
+                follow_symlinks: false,
+                ..ZipOptions::default()
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn skips_symlinks_when_not_restoring() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/real.sh", "#!/bin/sh\necho hi");
+
+        symlink("real.sh", sandbox.path().join("bin/link.sh")).unwrap();
+
+        let archive = sandbox.path().join("out.zip");
+        let output = create_temp_dir();
+
+        zip_without_following(sandbox.path(), &string_vec!["bin"], &archive);
+        unzip(&archive, output.path(), None).unwrap();
+
+        assert!(output.path().join("bin/real.sh").exists());
+        assert!(output
+            .path()
+            .join("bin/link.sh")
+            .symlink_metadata()
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "is outside of the output directory")]
+    fn errors_for_symlinks_outside_output() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/real.sh", "#!/bin/sh\necho hi");
+
+        symlink("../../../etc/passwd", sandbox.path().join("bin/link.sh")).unwrap();
+
+        let archive = sandbox.path().join("out.zip");
+        let output = create_temp_dir();
+
+        zip_without_following(sandbox.path(), &string_vec!["bin"], &archive);
+        unzip_with_options(
+            &archive,
+            output.path(),
+            None,
+            UnzipOptions {
+                restore_symlinks: true,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "is outside of the output directory")]
+    fn errors_for_absolute_symlinks() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("bin/real.sh", "#!/bin/sh\necho hi");
+
+        symlink("/etc/passwd", sandbox.path().join("bin/link.sh")).unwrap();
+
+        let archive = sandbox.path().join("out.zip");
+        let output = create_temp_dir();
+
+        zip_without_following(sandbox.path(), &string_vec!["bin"], &archive);
+        unzip_with_options(
+            &archive,
+            output.path(),
+            None,
+            UnzipOptions {
+                restore_symlinks: true,
+            },
+        )
+        .unwrap();
+    }
+}
+
+mod compression {
+    use super::*;
+
+    fn get_compression(archive: &Path, name: &str) -> CompressionMethod {
+        let mut archive = ZipArchive::new(fs::File::open(archive).unwrap()).unwrap();
+
+        archive
+            .by_name(name)
+            .map(|file| file.compression())
+            .unwrap()
+    }
+
+    #[test]
+    fn stores_small_files_and_deflates_large_files() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("mixed/tiny.txt", "tiny");
+        sandbox.create_file("mixed/empty.txt", "");
+        sandbox.create_file("mixed/large.txt", "large ".repeat(2000));
+
+        let archive = sandbox.path().join("out.zip");
+        let output = create_temp_dir();
+
+        zip(sandbox.path(), &string_vec!["mixed"], &archive, None).unwrap();
+
+        assert_eq!(
+            get_compression(&archive, "mixed/tiny.txt"),
+            CompressionMethod::Stored
+        );
+        assert_eq!(
+            get_compression(&archive, "mixed/empty.txt"),
+            CompressionMethod::Stored
+        );
+        assert_eq!(
+            get_compression(&archive, "mixed/large.txt"),
+            CompressionMethod::Deflated
+        );
+
+        unzip(&archive, output.path(), None).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(output.path().join("mixed/tiny.txt")).unwrap(),
+            "tiny"
+        );
+        assert_eq!(
+            fs::read_to_string(output.path().join("mixed/large.txt")).unwrap(),
+            "large ".repeat(2000)
+        );
+    }
+
+    #[test]
+    fn can_customize_threshold() {
+        let sandbox = create_sandbox("archives");
+        sandbox.create_file("mixed/tiny.txt", "tiny");
+
+        let archive = sandbox.path().join("out.zip");
+
+        zip_with_options(
+            sandbox.path(),
+            &string_vec!["mixed"],
+            &archive,
+            None,
+            ZipOptions {
+                compression_threshold: 0,
+                ..ZipOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_compression(&archive, "mixed/tiny.txt"),
+            CompressionMethod::Deflated
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::checksum::get_checksum_path;
+use crate::helpers::LOG_TARGET;
+use moon_common::path::standardize_separators;
+use moon_logger::{debug, trace};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use starbase_styles::color;
+use starbase_utils::json::{self, JsonValue};
+use starbase_utils::{fs, glob};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const STATES_BUNDLE_FILENAME: &str = "states.bundle.json";
+
+/// Return true if the file name is a known cache item that can be compacted.
+/// Other files in the states directory, like the project graph and snapshots,
+/// are read directly from their path and must be left as-is.
+fn is_compactable_item(name: &str) -> bool {
+    match name {
+        "codeowners.json" | "lastRun.json" | "projects.json" | "vcsHooks.json" => true,
+        _ => (name.starts_with("deps") || name.starts_with("tool")) && name.ends_with(".json"),
+    }
+}
+
+/// A single file that consolidates many state cache items, keyed by their
+/// path relative to the `.moon/cache/states` directory. This avoids reading
+/// many small files on cold starts.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StatesBundle {
+    pub items: BTreeMap<String, JsonValue>,
+
+    #[serde(skip)]
+    pub states_dir: PathBuf,
+}
+
+impl StatesBundle {
+    pub fn load(states_dir: &Path) -> miette::Result<Self> {
+        let path = states_dir.join(STATES_BUNDLE_FILENAME);
+
+        let mut bundle: StatesBundle = if path.exists() {
+            trace!(
+                target: LOG_TARGET,
+                "Loading states bundle {}",
+                color::path(&path)
+            );
+
+            json::read_file(&path)?
+        } else {
+            StatesBundle::default()
+        };
+
+        bundle.states_dir = states_dir.to_path_buf();
+
+        Ok(bundle)
+    }
+
+    /// Consolidate all per-item state files into the bundle, write the bundle,
+    /// and then delete the per-item files. Items already in the bundle are
+    /// overwritten by per-item files, as those are always newer.
+    /// Returns the number of files that were compacted.
+    pub fn compact(&mut self) -> miette::Result<usize> {
+        let files = glob::walk_files(&self.states_dir, ["**/*.json"])?
+            .into_iter()
+            .filter(|file| {
+                let name = file
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default();
+
+                is_compactable_item(name)
+            })
+            .collect::<Vec<_>>();
+
+        debug!(
+            target: LOG_TARGET,
+            "Compacting {} state files into {}",
+            files.len(),
+            color::file(STATES_BUNDLE_FILENAME)
+        );
+
+        for file in &files {
+            let value: JsonValue = json::read_file(file)?;
+
+            if let Some(key) = self.get_key(file) {
+                self.items.insert(key, value);
+            }
+        }
+
+        json::write_file(self.states_dir.join(STATES_BUNDLE_FILENAME), &self, false)?;
+
+        // Remove checksum sidecars as well, otherwise they're orphaned
+        for file in &files {
+            fs::remove_file(file)?;
+
+            let checksum = get_checksum_path(file);
+
+            if checksum.exists() {
+                fs::remove_file(checksum)?;
+            }
+        }
+
+        Ok(files.len())
+    }
+
+    /// Return an item from the bundle for the provided state file path.
+    pub fn get<T: DeserializeOwned>(&self, path: &Path) -> miette::Result<Option<T>> {
+        let Some(value) = self.get_key(path).and_then(|key| self.items.get(&key)) else {
+            return Ok(None);
+        };
+
+        Ok(Some(json::from_value(value.to_owned()).map_err(|e| {
+            miette::miette!("Failed to parse bundled state {}: {e}", path.display())
+        })?))
+    }
+
+    fn get_key(&self, path: &Path) -> Option<String> {
+        path.strip_prefix(&self.states_dir)
+            .ok()
+            .map(|rel| standardize_separators(rel.to_string_lossy()))
+    }
+}
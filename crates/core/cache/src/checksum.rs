@@ -0,0 +1,70 @@
+use miette::Diagnostic;
+use moon_utils::is_env_enabled;
+use sha2::{Digest, Sha256};
+use starbase_styles::{Style, Stylize};
+use starbase_utils::fs;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum CacheItemError {
+    #[diagnostic(
+        code(cache::item::corrupt),
+        help = "Run `moon clean` to remove the cache and try again."
+    )]
+    #[error(
+        "Cache item {} is corrupt or has been tampered with, as its checksum does not match.",
+        .path.style(Style::Path),
+    )]
+    ChecksumMismatch { path: PathBuf },
+}
+
+/// Return true if cache items should be checksummed when saved, and
+/// verified when loaded, via the `MOON_CACHE_VERIFY` environment variable.
+/// This is opt-in, as it requires additional file reads and hashing.
+pub fn is_cache_verification_enabled() -> bool {
+    is_env_enabled("MOON_CACHE_VERIFY")
+}
+
+/// Return the path of the sidecar checksum file for a cache item.
+pub fn get_checksum_path(path: &Path) -> PathBuf {
+    let mut file = OsString::from(path.as_os_str());
+    file.push(".sha256");
+
+    PathBuf::from(file)
+}
+
+fn hash_file(path: &Path) -> miette::Result<String> {
+    let mut sha = Sha256::new();
+    sha.update(fs::read_file_bytes(path)?);
+
+    Ok(format!("{:x}", sha.finalize()))
+}
+
+/// Hash the contents of a cache item, and write the checksum to a sidecar file.
+pub fn write_checksum(path: &Path) -> miette::Result<()> {
+    fs::write_file(get_checksum_path(path), hash_file(path)?)?;
+
+    Ok(())
+}
+
+/// Verify the contents of a cache item against its sidecar checksum file.
+/// Returns false if the checksum does not exist (the item was written before
+/// verification was enabled), in which case the item should not be used.
+pub fn verify_checksum(path: &Path) -> miette::Result<bool> {
+    let checksum_path = get_checksum_path(path);
+
+    if !checksum_path.exists() {
+        return Ok(false);
+    }
+
+    if fs::read_file(&checksum_path)?.trim() != hash_file(path)? {
+        return Err(CacheItemError::ChecksumMismatch {
+            path: path.to_path_buf(),
+        }
+        .into());
+    }
+
+    Ok(true)
+}
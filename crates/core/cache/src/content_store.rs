@@ -0,0 +1,188 @@
+use crate::helpers::LOG_TARGET;
+use miette::IntoDiagnostic;
+use moon_archive::TreeDiffer;
+use moon_logger::trace;
+use moon_utils::path;
+use sha2::{Digest, Sha256};
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_BLOB_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A mapping of file paths (relative from the workspace root,
+/// with forward slashes) to the hash of their contents.
+pub type ContentManifest = BTreeMap<String, String>;
+
+/// A content-addressed store of files, where each unique file is stored once
+/// under the hash of its contents. Files are restored as hardlinks to the
+/// stored blob, falling back to a copy when linking is not possible
+/// (across file systems, for example).
+///
+/// Since restored files share their contents with the blob, outputs should
+/// be replaced instead of modified in place! To guard against this, blobs are
+/// verified against their hash before being restored.
+pub struct ContentStore {
+    /// The `.moon/cache/blobs` directory.
+    pub dir: PathBuf,
+}
+
+impl ContentStore {
+    pub fn new(dir: PathBuf) -> Self {
+        ContentStore { dir }
+    }
+
+    /// Return the path to a blob, sharded by the first 2 characters of the hash.
+    pub fn get_blob_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(&hash[0..2.min(hash.len())]).join(hash)
+    }
+
+    pub fn has_blob(&self, hash: &str) -> bool {
+        self.get_blob_path(hash).exists()
+    }
+
+    /// Return true if the blob exists and its contents still match its hash.
+    /// A blob that was modified (through a hardlinked output) is removed.
+    pub fn verify_blob(&self, hash: &str) -> miette::Result<bool> {
+        let blob = self.get_blob_path(hash);
+
+        if !blob.exists() {
+            return Ok(false);
+        }
+
+        let mut file = fs::open_file(&blob)?;
+        let mut sha = Sha256::new();
+        let mut buffer = [0; 8192];
+
+        loop {
+            let read = file.read(&mut buffer).into_diagnostic()?;
+
+            if read == 0 {
+                break;
+            }
+
+            sha.update(&buffer[0..read]);
+        }
+
+        if format!("{:x}", sha.finalize()) == hash {
+            return Ok(true);
+        }
+
+        trace!(
+            target: LOG_TARGET,
+            "Blob {} no longer matches its hash, removing",
+            color::hash(hash),
+        );
+
+        fs::remove_file(&blob)?;
+
+        Ok(false)
+    }
+
+    /// Copy the file into the store, by writing to a temporary file and renaming
+    /// it, so that a partially written blob is never observed by other processes.
+    fn write_blob(&self, file: &Path, blob: &Path) -> miette::Result<()> {
+        let mut temp_name = blob.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            TEMP_BLOB_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let temp_blob = blob.with_file_name(temp_name);
+
+        fs::copy_file(file, &temp_blob)?;
+        fs::rename(&temp_blob, blob)?;
+
+        Ok(())
+    }
+
+    /// Hash and store all files within the provided paths (relative from the
+    /// workspace root), and return a manifest of each file to its hash. Files
+    /// with identical contents are only stored once.
+    pub fn store_files(
+        &self,
+        workspace_root: &Path,
+        paths: &[String],
+    ) -> miette::Result<ContentManifest> {
+        let mut differ = TreeDiffer::load(workspace_root, paths)?;
+
+        // Blobs must be addressed by their contents, and not
+        // the size and modified time of large files
+        differ.set_max_file_size(u64::MAX);
+
+        let mut files = differ.files.keys().collect::<Vec<_>>();
+        files.sort();
+
+        let hashes = differ.fingerprint_files(&files)?;
+        let mut manifest = ContentManifest::new();
+
+        for (file, hash) in files.into_iter().zip(hashes) {
+            let blob = self.get_blob_path(&hash);
+
+            if !blob.exists() {
+                trace!(
+                    target: LOG_TARGET,
+                    "Storing blob {} for {}",
+                    color::hash(&hash),
+                    color::path(file)
+                );
+
+                self.write_blob(file, &blob)?;
+            }
+
+            manifest.insert(
+                path::to_virtual_string(file.strip_prefix(workspace_root).unwrap_or(file))?,
+                hash,
+            );
+        }
+
+        Ok(manifest)
+    }
+
+    /// Restore all files in the manifest from their stored blobs into the
+    /// workspace root. Returns false, without restoring anything, if any
+    /// of the blobs no longer exist (they were cleaned up, for example),
+    /// or no longer match their hash.
+    pub fn restore_files(
+        &self,
+        workspace_root: &Path,
+        manifest: &ContentManifest,
+    ) -> miette::Result<bool> {
+        for hash in manifest.values() {
+            if !self.verify_blob(hash)? {
+                return Ok(false);
+            }
+        }
+
+        for (file, hash) in manifest {
+            let blob = self.get_blob_path(hash);
+            let dest = workspace_root.join(file);
+
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            // Links can't overwrite an existing file
+            if dest.symlink_metadata().is_ok() {
+                fs::remove_file(&dest)?;
+            }
+
+            trace!(
+                target: LOG_TARGET,
+                "Restoring {} from blob {}",
+                color::path(&dest),
+                color::hash(hash)
+            );
+
+            if std::fs::hard_link(&blob, &dest).is_err() {
+                std::fs::copy(&blob, &dest).into_diagnostic()?;
+            }
+        }
+
+        Ok(true)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::bundle::StatesBundle;
+use crate::content_store::ContentStore;
 use crate::helpers::LOG_TARGET;
 use crate::items::{CommonState, DependenciesState, ProjectsState, RunTargetState, ToolState};
 use crate::runfiles::Snapshot;
@@ -6,10 +8,12 @@ use moon_common::consts::CONFIG_DIRNAME;
 use moon_logger::{debug, trace};
 use moon_platform_runtime::Runtime;
 use moon_utils::time;
+use once_cell::sync::OnceCell;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use starbase_styles::color;
-use starbase_utils::{fs, json};
+use starbase_utils::{fs, glob, json};
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf, MAIN_SEPARATOR_STR};
 
 pub struct CacheEngine {
@@ -17,6 +21,9 @@ pub struct CacheEngine {
     /// Contains cached items pertaining to runs and processes.
     pub dir: PathBuf,
 
+    /// The `.moon/cache/blobs` directory. Stores task output files by their content hash.
+    pub blobs_dir: PathBuf,
+
     /// The `.moon/cache/hashes` directory. Stores hash manifests.
     pub hashes_dir: PathBuf,
 
@@ -26,11 +33,16 @@ pub struct CacheEngine {
     /// The `.moon/cache/states` directory. Stores state information about anything...
     /// tools, dependencies, projects, tasks, etc.
     pub states_dir: PathBuf,
+
+    /// Compacted state items, lazily loaded from `.moon/cache/states/states.bundle.json`
+    /// the first time an item is not found in its own file.
+    states_bundle: OnceCell<StatesBundle>,
 }
 
 impl CacheEngine {
     pub fn load(workspace_root: &Path) -> miette::Result<Self> {
         let dir = workspace_root.join(CONFIG_DIRNAME).join("cache");
+        let blobs_dir = dir.join("blobs");
         let hashes_dir = dir.join("hashes");
         let outputs_dir = dir.join("outputs");
         let states_dir = dir.join("states");
@@ -43,6 +55,7 @@ impl CacheEngine {
         );
 
         // Do this once instead of each time we are writing cache items
+        fs::create_dir_all(&blobs_dir)?;
         fs::create_dir_all(&hashes_dir)?;
         fs::create_dir_all(&outputs_dir)?;
         fs::create_dir_all(&states_dir)?;
@@ -58,7 +71,9 @@ impl CacheEngine {
         }
 
         Ok(CacheEngine {
+            states_bundle: OnceCell::new(),
             dir,
+            blobs_dir,
             hashes_dir,
             outputs_dir,
             states_dir,
@@ -72,11 +87,14 @@ impl CacheEngine {
     ) -> miette::Result<DependenciesState> {
         let name = format!("deps{runtime}.json");
 
-        DependenciesState::load(self.get_state_path(if let Some(id) = project_id {
-            format!("{id}{}{name}", MAIN_SEPARATOR_STR)
-        } else {
-            name
-        }))
+        DependenciesState::load_from_bundle(
+            self.get_state_path(if let Some(id) = project_id {
+                format!("{id}{}{name}", MAIN_SEPARATOR_STR)
+            } else {
+                name
+            }),
+            || self.get_states_bundle(),
+        )
     }
 
     pub fn cache_run_target_state<T: AsRef<str>>(
@@ -84,7 +102,10 @@ impl CacheEngine {
         target_id: T,
     ) -> miette::Result<RunTargetState> {
         let target_id = target_id.as_ref();
-        let mut item = RunTargetState::load(self.get_target_dir(target_id).join("lastRun.json"))?;
+        let mut item = RunTargetState::load_from_bundle(
+            self.get_target_dir(target_id).join("lastRun.json"),
+            || self.get_states_bundle(),
+        )?;
 
         if item.target.is_empty() {
             item.target = target_id.to_owned();
@@ -93,20 +114,66 @@ impl CacheEngine {
         Ok(item)
     }
 
+    /// Load the run state of every target that has checked the cache, and return
+    /// those with a cache hit ratio below the provided threshold (between 0 and 1),
+    /// sorted from the lowest ratio to the highest.
+    pub fn get_low_cache_hit_targets(&self, threshold: f64) -> miette::Result<Vec<RunTargetState>> {
+        let mut paths = glob::walk_files(&self.states_dir, ["**/lastRun.json"])?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+
+        for key in self.get_states_bundle()?.items.keys() {
+            if key.ends_with("/lastRun.json") {
+                paths.insert(self.states_dir.join(key));
+            }
+        }
+
+        let mut states = vec![];
+
+        for path in paths {
+            let state = RunTargetState::load_from_bundle(path, || self.get_states_bundle())?;
+
+            if state
+                .get_cache_hit_ratio()
+                .is_some_and(|ratio| ratio < threshold)
+            {
+                states.push(state);
+            }
+        }
+
+        states.sort_by(|a, b| {
+            a.get_cache_hit_ratio()
+                .partial_cmp(&b.get_cache_hit_ratio())
+                .unwrap()
+                .then_with(|| a.target.cmp(&b.target))
+        });
+
+        Ok(states)
+    }
+
     pub fn cache_codeowners_state(&self) -> miette::Result<CommonState> {
-        CommonState::load(self.get_state_path("codeowners.json"))
+        CommonState::load_from_bundle(self.get_state_path("codeowners.json"), || {
+            self.get_states_bundle()
+        })
     }
 
     pub fn cache_projects_state(&self) -> miette::Result<ProjectsState> {
-        ProjectsState::load(self.get_state_path("projects.json"))
+        ProjectsState::load_from_bundle(self.get_state_path("projects.json"), || {
+            self.get_states_bundle()
+        })
     }
 
     pub fn cache_tool_state(&self, runtime: &Runtime) -> miette::Result<ToolState> {
-        ToolState::load(self.get_state_path(format!("tool{}-{}.json", runtime, runtime.version())))
+        ToolState::load_from_bundle(
+            self.get_state_path(format!("tool{}-{}.json", runtime, runtime.version())),
+            || self.get_states_bundle(),
+        )
     }
 
     pub fn cache_vcs_hooks_state(&self) -> miette::Result<CommonState> {
-        CommonState::load(self.get_state_path("vcsHooks.json"))
+        CommonState::load_from_bundle(self.get_state_path("vcsHooks.json"), || {
+            self.get_states_bundle()
+        })
     }
 
     pub fn clean_stale_cache(&self, lifetime: &str) -> miette::Result<(usize, u64)> {
@@ -121,9 +188,11 @@ impl CacheEngine {
 
         let hashes_dir = fs::remove_dir_stale_contents(&self.hashes_dir, duration)?;
         let outputs_dir = fs::remove_dir_stale_contents(&self.outputs_dir, duration)?;
+        let blobs_dir = fs::remove_dir_stale_contents(&self.blobs_dir, duration)?;
 
-        let deleted = hashes_dir.files_deleted + outputs_dir.files_deleted;
-        let bytes = hashes_dir.bytes_saved + outputs_dir.bytes_saved;
+        let deleted =
+            hashes_dir.files_deleted + outputs_dir.files_deleted + blobs_dir.files_deleted;
+        let bytes = hashes_dir.bytes_saved + outputs_dir.bytes_saved + blobs_dir.bytes_saved;
 
         trace!(
             target: LOG_TARGET,
@@ -135,6 +204,16 @@ impl CacheEngine {
         Ok((deleted, bytes))
     }
 
+    /// Consolidate all state files into a single bundle file, to reduce file IO.
+    /// Legacy per-item files continue to be readable, and take precedence.
+    pub fn compact_states(&self) -> miette::Result<usize> {
+        let compacted = StatesBundle::load(&self.states_dir)?.compact()?;
+
+        trace!(target: LOG_TARGET, "Compacted {} state files", compacted);
+
+        Ok(compacted)
+    }
+
     pub fn create_hash_manifest<T>(&self, hash: &str, contents: &T) -> miette::Result<()>
     where
         T: ?Sized + Serialize,
@@ -170,10 +249,19 @@ impl CacheEngine {
         Snapshot::load(self.get_state_path(project_id).join("snapshot.json"), data)
     }
 
+    pub fn get_content_store(&self) -> ContentStore {
+        ContentStore::new(self.blobs_dir.clone())
+    }
+
     pub fn get_hash_archive_path(&self, hash: &str) -> PathBuf {
         self.outputs_dir.join(format!("{hash}.tar.gz"))
     }
 
+    /// Path to the manifest of output files in the content store, for the provided hash.
+    pub fn get_hash_content_manifest_path(&self, hash: &str) -> PathBuf {
+        self.outputs_dir.join(format!("{hash}.content.json"))
+    }
+
     pub fn get_hash_manifest_path(&self, hash: &str) -> PathBuf {
         self.hashes_dir.join(format!("{hash}.json"))
     }
@@ -182,6 +270,11 @@ impl CacheEngine {
         get_cache_mode()
     }
 
+    pub fn get_states_bundle(&self) -> miette::Result<&StatesBundle> {
+        self.states_bundle
+            .get_or_try_init(|| StatesBundle::load(&self.states_dir))
+    }
+
     pub fn get_state_path<T: AsRef<str>>(&self, file: T) -> PathBuf {
         self.states_dir.join(file.as_ref())
     }
@@ -1,6 +1,23 @@
+/// Define a cache item struct, and implement load and save methods for it.
+/// Cache files must be tolerant of format changes between moon versions, so
+/// missing fields fallback to their default, and unknown fields are ignored.
+/// When verification is enabled, a checksum is written alongside the item,
+/// and verified when loaded.
 #[macro_export]
 macro_rules! cache_item {
-    ($struct:ident) => {
+    (
+        $(#[$meta:meta])*
+        pub struct $struct:ident {
+            $($body:tt)*
+        }
+    ) => {
+        #[derive(Debug, Default, serde::Deserialize, Eq, PartialEq, serde::Serialize)]
+        #[serde(default, rename_all = "camelCase")]
+        $(#[$meta])*
+        pub struct $struct {
+            $($body)*
+        }
+
         impl $struct {
             pub fn load(path: PathBuf) -> miette::Result<Self> {
                 let mut item = Self::default();
@@ -12,13 +29,24 @@ macro_rules! cache_item {
 
                 if get_cache_mode().is_readable() {
                     if path.exists() {
-                        trace!(
-                            target: log_target,
-                            "Cache hit for {}, reading",
-                            color::path(&path)
-                        );
+                        if $crate::is_cache_verification_enabled()
+                            && !$crate::verify_checksum(&path)?
+                        {
+                            // Will be written with a checksum when next saved
+                            trace!(
+                                target: log_target,
+                                "Cache miss for {}, missing a checksum",
+                                color::path(&path)
+                            );
+                        } else {
+                            trace!(
+                                target: log_target,
+                                "Cache hit for {}, reading",
+                                color::path(&path)
+                            );
 
-                        item = json::read_file(&path)?;
+                            item = json::read_file(&path)?;
+                        }
                     } else {
                         trace!(
                             target: log_target,
@@ -33,6 +61,36 @@ macro_rules! cache_item {
                 Ok(item)
             }
 
+            /// Load the item from its own file, or from the states bundle if
+            /// the item has been compacted. The bundle is only requested when
+            /// the item's own file does not exist.
+            pub fn load_from_bundle<'bundle>(
+                path: PathBuf,
+                get_bundle: impl FnOnce() -> miette::Result<&'bundle $crate::StatesBundle>,
+            ) -> miette::Result<Self> {
+                if path.exists() || !get_cache_mode().is_readable() {
+                    return Self::load(path);
+                }
+
+                let Some(mut item) = get_bundle()?.get::<Self>(&path)? else {
+                    return Self::load(path);
+                };
+
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                trace!(
+                    target: "moon:cache:item",
+                    "Cache hit for {}, reading from bundle",
+                    color::path(&path)
+                );
+
+                item.path = path;
+
+                Ok(item)
+            }
+
             pub fn save(&self) -> miette::Result<()> {
                 let log_target = "moon:cache:item";
 
@@ -44,6 +102,10 @@ macro_rules! cache_item {
                     );
 
                     json::write_file(&self.path, &self, false)?;
+
+                    if $crate::is_cache_verification_enabled() {
+                        $crate::write_checksum(&self.path)?;
+                    }
                 }
 
                 Ok(())
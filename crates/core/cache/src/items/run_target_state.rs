@@ -1,40 +1,85 @@
 use crate::cache_item;
+use crate::content_store::{ContentManifest, ContentStore};
 use crate::helpers::get_cache_mode;
-use moon_archive::{untar_with_diff, TarArchiver, TreeDiffer};
+use moon_archive::{untar_with_diff, ArchiveMetadata, TarArchiver, TreeDiffer};
 use moon_common::path::WorkspaceRelativePathBuf;
 use moon_logger::{map_list, trace, warn};
-use serde::{Deserialize, Serialize};
+use moon_utils::path;
 use starbase_styles::color;
 use starbase_utils::{fs, glob, json};
 use std::path::{Path, PathBuf};
 use std::{thread, time};
 
-#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(default, rename_all = "camelCase")]
-pub struct RunTargetState {
-    pub exit_code: i32,
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
 
-    pub hash: String,
+cache_item!(
+    pub struct RunTargetState {
+        /// Number of times a cache lookup for this target was a hit.
+        #[serde(skip_serializing_if = "is_zero")]
+        pub cache_hits: u64,
 
-    pub last_run_time: u128,
+        /// Number of times a cache lookup for this target was a miss.
+        #[serde(skip_serializing_if = "is_zero")]
+        pub cache_misses: u64,
 
-    pub target: String,
+        pub exit_code: i32,
 
-    #[serde(skip)]
-    pub path: PathBuf,
-}
+        pub hash: String,
+
+        pub last_run_time: u128,
+
+        /// Hash of the run that produced the archive of the last archived outputs.
+        #[serde(skip_serializing_if = "String::is_empty")]
+        pub output_archive_hash: String,
+
+        /// Fingerprint of the output contents that were last archived.
+        #[serde(skip_serializing_if = "String::is_empty")]
+        pub output_hash: String,
+
+        pub target: String,
+
+        /// Files created or modified by the last run that were not declared as outputs.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub undeclared_outputs: Vec<WorkspaceRelativePathBuf>,
 
-cache_item!(RunTargetState);
+        #[serde(skip)]
+        pub path: PathBuf,
+    }
+);
 
 impl RunTargetState {
+    pub fn track_cache_hit(&mut self) {
+        self.cache_hits += 1;
+    }
+
+    pub fn track_cache_miss(&mut self) {
+        self.cache_misses += 1;
+    }
+
+    /// Return the ratio of cache hits to total lookups, between 0 and 1,
+    /// or none if the cache has never been checked for this target.
+    pub fn get_cache_hit_ratio(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+
+        if total == 0 {
+            return None;
+        }
+
+        Some(self.cache_hits as f64 / total as f64)
+    }
+
     pub fn archive_outputs(
         &self,
         archive_file: &Path,
         workspace_root: &Path,
         output_paths: &[WorkspaceRelativePathBuf],
+        metadata: ArchiveMetadata,
     ) -> miette::Result<bool> {
         if get_cache_mode().is_writable() && !archive_file.exists() {
             let mut tar = TarArchiver::new(workspace_root, archive_file);
+            tar.set_metadata(metadata);
 
             // Outputs are relative from project root (the input)
             if !output_paths.is_empty() {
@@ -47,6 +92,11 @@ impl RunTargetState {
                 }
             }
 
+            // Also include files that were generated but not declared
+            for output in &self.undeclared_outputs {
+                tar.add_source(output.to_path(workspace_root), Some(output.as_str()));
+            }
+
             // Also include stdout/stderr logs at the root of the tarball
             let (stdout_path, stderr_path) = self.get_output_logs();
 
@@ -66,6 +116,77 @@ impl RunTargetState {
         Ok(false)
     }
 
+    /// Store the outputs (and stdout/stderr logs) in the content-addressed store,
+    /// and write a manifest of the stored files. Unlike archives, identical files
+    /// across targets and runs are only stored once.
+    pub fn store_outputs(
+        &self,
+        store: &ContentStore,
+        manifest_file: &Path,
+        workspace_root: &Path,
+        output_paths: &[WorkspaceRelativePathBuf],
+    ) -> miette::Result<bool> {
+        if get_cache_mode().is_writable() && !manifest_file.exists() {
+            let (stdout_path, stderr_path) = self.get_output_logs();
+            let mut outputs = output_paths
+                .iter()
+                .chain(self.undeclared_outputs.iter())
+                .map(|o| o.as_str().to_string())
+                .collect::<Vec<_>>();
+
+            for log in [stdout_path, stderr_path] {
+                if log.exists() {
+                    outputs.push(path::to_string(log)?);
+                }
+            }
+
+            let manifest = store.store_files(workspace_root, &outputs)?;
+
+            json::write_file(manifest_file, &manifest, false)?;
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Restore outputs from the content-addressed store, by hardlinking
+    /// each file to its stored blob (or copying when not possible).
+    pub fn hydrate_outputs_from_store(
+        &self,
+        store: &ContentStore,
+        manifest_file: &Path,
+        workspace_root: &Path,
+    ) -> miette::Result<bool> {
+        if get_cache_mode().is_readable() && manifest_file.exists() {
+            let manifest: ContentManifest = json::read_file(manifest_file)?;
+
+            return store.restore_files(workspace_root, &manifest);
+        }
+
+        Ok(false)
+    }
+
+    /// Create a fingerprint of the contents of all declared
+    /// and undeclared outputs that currently exist.
+    pub fn hash_outputs(
+        &self,
+        workspace_root: &Path,
+        output_paths: &[WorkspaceRelativePathBuf],
+    ) -> miette::Result<String> {
+        let outputs = output_paths
+            .iter()
+            .chain(self.undeclared_outputs.iter())
+            .map(|o| o.as_str().to_string())
+            .collect::<Vec<_>>();
+
+        // Always fingerprint by contents, as outputs with the same size and modified
+        // time (coarse timestamps, restored files, etc) may still have changed
+        let mut differ = TreeDiffer::load(workspace_root, &outputs)?;
+        differ.set_max_file_size(u64::MAX);
+        differ.fingerprint_tree(workspace_root)
+    }
+
     pub fn hydrate_outputs(
         &self,
         archive_file: &Path,
@@ -78,6 +199,7 @@ impl RunTargetState {
             let cache_logs = self.get_output_logs();
             let outputs = output_paths
                 .iter()
+                .chain(self.undeclared_outputs.iter())
                 .map(|o| o.as_str().to_string())
                 .collect::<Vec<_>>();
 
@@ -166,6 +288,14 @@ impl RunTargetState {
     pub fn save_output_logs(&self, stdout: String, stderr: String) -> miette::Result<()> {
         let (stdout_path, stderr_path) = self.get_output_logs();
 
+        // Logs may be hardlinked to a blob in the content store,
+        // so they must be replaced instead of modified in place
+        for log in [&stdout_path, &stderr_path] {
+            if log.exists() {
+                fs::remove_file(log)?;
+            }
+        }
+
         fs::write_file(stdout_path, stdout)?;
         fs::write_file(stderr_path, stderr)?;
 
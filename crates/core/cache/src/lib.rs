@@ -1,9 +1,15 @@
+mod bundle;
+mod checksum;
+mod content_store;
 mod engine;
 mod helpers;
 mod item;
 mod items;
 mod runfiles;
 
+pub use bundle::*;
+pub use checksum::*;
+pub use content_store::*;
 pub use engine::CacheEngine;
 pub use helpers::*;
 pub use item::*;
@@ -1,4 +1,4 @@
-use moon_cache::{CacheEngine, ProjectsState, RunTargetState, ToolState};
+use moon_cache::{get_checksum_path, CacheEngine, ProjectsState, RunTargetState, ToolState};
 use moon_test_utils::{assert_fs::prelude::*, create_temp_dir};
 use serde::Serialize;
 use serial_test::serial;
@@ -33,6 +33,7 @@ mod create {
         CacheEngine::load(dir.path()).unwrap();
 
         assert!(dir.path().join(".moon/cache").exists());
+        assert!(dir.path().join(".moon/cache/blobs").exists());
         assert!(dir.path().join(".moon/cache/hashes").exists());
         assert!(dir.path().join(".moon/cache/outputs").exists());
         assert!(dir.path().join(".moon/cache/states").exists());
@@ -284,6 +285,309 @@ mod cache_run_target_state {
     }
 }
 
+mod cache_item_evolution {
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn loads_old_format_missing_fields() {
+        let dir = create_temp_dir();
+
+        // Before cache hits and misses were tracked
+        dir.child(".moon/cache/states/foo/bar/lastRun.json")
+            .write_str(r#"{"exitCode":0,"hash":"abc123","target":"foo:bar"}"#)
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let item = cache.cache_run_target_state("foo:bar").unwrap();
+
+        assert_eq!(
+            item,
+            RunTargetState {
+                hash: String::from("abc123"),
+                target: String::from("foo:bar"),
+                path: dir.path().join(".moon/cache/states/foo/bar/lastRun.json"),
+                ..RunTargetState::default()
+            }
+        );
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn loads_future_format_with_unknown_fields() {
+        let dir = create_temp_dir();
+
+        dir.child(".moon/cache/states/foo/bar/lastRun.json")
+            .write_str(r#"{"exitCode":1,"hash":"abc123","target":"foo:bar","futureField":{"nested":true},"anotherField":[1,2,3]}"#)
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let item = cache.cache_run_target_state("foo:bar").unwrap();
+
+        assert_eq!(
+            item,
+            RunTargetState {
+                exit_code: 1,
+                hash: String::from("abc123"),
+                target: String::from("foo:bar"),
+                path: dir.path().join(".moon/cache/states/foo/bar/lastRun.json"),
+                ..RunTargetState::default()
+            }
+        );
+
+        dir.close().unwrap();
+    }
+}
+
+mod cache_item_checksum {
+    use super::*;
+
+    fn run_with_verify<T, F>(callback: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        env::set_var("MOON_CACHE_VERIFY", "1");
+
+        let result = callback();
+
+        env::remove_var("MOON_CACHE_VERIFY");
+
+        result
+    }
+
+    #[test]
+    #[serial]
+    fn doesnt_write_checksum_if_not_enabled() {
+        let dir = create_temp_dir();
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let item = cache.cache_run_target_state("foo:bar").unwrap();
+
+        item.save().unwrap();
+
+        assert!(!get_checksum_path(&item.path).exists());
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn writes_and_verifies_checksum() {
+        let dir = create_temp_dir();
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let mut item = cache.cache_run_target_state("foo:bar").unwrap();
+
+        item.exit_code = 1;
+
+        run_with_verify(|| item.save()).unwrap();
+
+        assert!(get_checksum_path(&item.path).exists());
+
+        let loaded = run_with_verify(|| cache.cache_run_target_state("foo:bar")).unwrap();
+
+        assert_eq!(loaded.exit_code, 1);
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn errors_if_modified_after_save() {
+        let dir = create_temp_dir();
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let mut item = cache.cache_run_target_state("foo:bar").unwrap();
+
+        item.exit_code = 1;
+
+        run_with_verify(|| item.save()).unwrap();
+
+        fs::write(
+            &item.path,
+            r#"{"exitCode":0,"hash":"","lastRunTime":0,"target":"foo:bar"}"#,
+        )
+        .unwrap();
+
+        let error = run_with_verify(|| cache.cache_run_target_state("foo:bar")).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("is corrupt or has been tampered with"));
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn misses_if_checksum_missing() {
+        let dir = create_temp_dir();
+
+        dir.child(".moon/cache/states/foo/bar/lastRun.json")
+            .write_str(r#"{"exitCode":1,"hash":"abc123","target":"foo:bar"}"#)
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let item = run_with_verify(|| cache.cache_run_target_state("foo:bar")).unwrap();
+
+        assert_eq!(item.exit_code, 0);
+        assert_eq!(item.hash, "");
+
+        // Rewritten with a checksum when saved
+        run_with_verify(|| item.save()).unwrap();
+
+        assert!(get_checksum_path(&item.path).exists());
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn doesnt_verify_if_not_enabled() {
+        let dir = create_temp_dir();
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let item = cache.cache_run_target_state("foo:bar").unwrap();
+
+        run_with_verify(|| item.save()).unwrap();
+
+        fs::write(
+            &item.path,
+            r#"{"exitCode":2,"hash":"","lastRunTime":0,"target":"foo:bar"}"#,
+        )
+        .unwrap();
+
+        let loaded = cache.cache_run_target_state("foo:bar").unwrap();
+
+        assert_eq!(loaded.exit_code, 2);
+
+        dir.close().unwrap();
+    }
+}
+
+mod run_target_state_outputs {
+    use super::*;
+    use moon_common::path::WorkspaceRelativePathBuf;
+
+    #[test]
+    fn hashes_identical_outputs_the_same() {
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("a").unwrap();
+        dir.child("out/b.js").write_str("b").unwrap();
+
+        let state = RunTargetState::default();
+        let outputs = vec![WorkspaceRelativePathBuf::from("out")];
+        let hash = state.hash_outputs(dir.path(), &outputs).unwrap();
+
+        // Rewrite with the same content
+        dir.child("out/a.js").write_str("a").unwrap();
+
+        assert_eq!(state.hash_outputs(dir.path(), &outputs).unwrap(), hash);
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn hashes_changed_outputs_differently() {
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("a").unwrap();
+
+        let state = RunTargetState::default();
+        let outputs = vec![WorkspaceRelativePathBuf::from("out")];
+        let hash = state.hash_outputs(dir.path(), &outputs).unwrap();
+
+        dir.child("out/a.js").write_str("changed").unwrap();
+
+        assert_ne!(state.hash_outputs(dir.path(), &outputs).unwrap(), hash);
+
+        // Same size, different content
+        dir.child("out/a.js").write_str("z").unwrap();
+
+        assert_ne!(state.hash_outputs(dir.path(), &outputs).unwrap(), hash);
+
+        dir.child("out/a.js").write_str("a").unwrap();
+        dir.child("out/b.js").write_str("b").unwrap();
+
+        assert_ne!(state.hash_outputs(dir.path(), &outputs).unwrap(), hash);
+
+        dir.close().unwrap();
+    }
+}
+
+mod cache_hit_ratio {
+    use super::*;
+
+    #[test]
+    fn returns_none_if_never_checked() {
+        assert_eq!(RunTargetState::default().get_cache_hit_ratio(), None);
+    }
+
+    #[test]
+    #[serial]
+    fn accumulates_hits_and_misses_across_runs() {
+        let dir = create_temp_dir();
+        let cache = CacheEngine::load(dir.path()).unwrap();
+
+        for hit in [true, false, true, true] {
+            let mut state = cache.cache_run_target_state("foo:build").unwrap();
+
+            if hit {
+                state.track_cache_hit();
+            } else {
+                state.track_cache_miss();
+            }
+
+            state.save().unwrap();
+        }
+
+        let state = cache.cache_run_target_state("foo:build").unwrap();
+
+        assert_eq!(state.cache_hits, 3);
+        assert_eq!(state.cache_misses, 1);
+        assert_eq!(state.get_cache_hit_ratio(), Some(0.75));
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn queries_targets_below_threshold() {
+        let dir = create_temp_dir();
+
+        dir.child(".moon/cache/states/foo/build/lastRun.json")
+            .write_str(r#"{"cacheHits":9,"cacheMisses":1,"target":"foo:build"}"#)
+            .unwrap();
+
+        dir.child(".moon/cache/states/foo/test/lastRun.json")
+            .write_str(r#"{"cacheHits":1,"cacheMisses":3,"target":"foo:test"}"#)
+            .unwrap();
+
+        dir.child(".moon/cache/states/bar/lint/lastRun.json")
+            .write_str(r#"{"cacheHits":0,"cacheMisses":2,"target":"bar:lint"}"#)
+            .unwrap();
+
+        // Never checked the cache
+        dir.child(".moon/cache/states/bar/dev/lastRun.json")
+            .write_str(r#"{"target":"bar:dev"}"#)
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+
+        // Also includes compacted states
+        cache.compact_states().unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let targets = cache
+            .get_low_cache_hit_targets(0.5)
+            .unwrap()
+            .into_iter()
+            .map(|state| state.target)
+            .collect::<Vec<_>>();
+
+        assert_eq!(targets, vec!["bar:lint", "foo:test"]);
+
+        dir.close().unwrap();
+    }
+}
+
 mod cache_tool_state {
     use super::*;
     use moon_platform_runtime::{Runtime, Version};
@@ -536,3 +840,243 @@ mod create_hash_manifest {
         dir.close().unwrap();
     }
 }
+
+mod compact_states {
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn preserves_all_items() {
+        let dir = create_temp_dir();
+
+        dir.child(".moon/cache/states/projects.json")
+            .write_str(r#"{"globs":["**/*"],"projects":{"foo":"bar"}}"#)
+            .unwrap();
+
+        dir.child(".moon/cache/states/codeowners.json")
+            .write_str(r#"{"lastHash":"abc123"}"#)
+            .unwrap();
+
+        dir.child(".moon/cache/states/foo/test/lastRun.json")
+            .write_str(r#"{"exitCode":1,"hash":"def456","lastRunTime":123,"target":"foo:test"}"#)
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let projects = cache.cache_projects_state().unwrap();
+        let codeowners = cache.cache_codeowners_state().unwrap();
+        let run = cache.cache_run_target_state("foo:test").unwrap();
+
+        assert_eq!(cache.compact_states().unwrap(), 3);
+
+        assert!(!projects.path.exists());
+        assert!(!codeowners.path.exists());
+        assert!(!run.path.exists());
+        assert!(dir
+            .path()
+            .join(".moon/cache/states/states.bundle.json")
+            .exists());
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+
+        assert_eq!(cache.cache_projects_state().unwrap(), projects);
+        assert_eq!(cache.cache_codeowners_state().unwrap(), codeowners);
+        assert_eq!(cache.cache_run_target_state("foo:test").unwrap(), run);
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn prefers_item_files_over_bundle() {
+        let dir = create_temp_dir();
+
+        dir.child(".moon/cache/states/codeowners.json")
+            .write_str(r#"{"lastHash":"old"}"#)
+            .unwrap();
+
+        CacheEngine::load(dir.path())
+            .unwrap()
+            .compact_states()
+            .unwrap();
+
+        dir.child(".moon/cache/states/codeowners.json")
+            .write_str(r#"{"lastHash":"new"}"#)
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+
+        assert_eq!(cache.cache_codeowners_state().unwrap().last_hash, "new");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn ignores_files_that_arent_cache_items() {
+        let dir = create_temp_dir();
+
+        dir.child(".moon/cache/states/codeowners.json")
+            .write_str(r#"{"lastHash":"abc123"}"#)
+            .unwrap();
+
+        dir.child(".moon/cache/states/projectGraph.json")
+            .write_str("{}")
+            .unwrap();
+
+        dir.child(".moon/cache/states/foo/snapshot.json")
+            .write_str("{}")
+            .unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+
+        assert_eq!(cache.compact_states().unwrap(), 1);
+
+        assert!(!dir
+            .path()
+            .join(".moon/cache/states/codeowners.json")
+            .exists());
+        assert!(dir
+            .path()
+            .join(".moon/cache/states/projectGraph.json")
+            .exists());
+        assert!(dir
+            .path()
+            .join(".moon/cache/states/foo/snapshot.json")
+            .exists());
+
+        dir.close().unwrap();
+    }
+}
+
+mod content_store {
+    use super::*;
+    use moon_common::path::WorkspaceRelativePathBuf;
+
+    #[test]
+    #[serial]
+    fn stores_identical_files_once() {
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("same").unwrap();
+        dir.child("out/nested/b.js").write_str("same").unwrap();
+        dir.child("out/c.js").write_str("different").unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let store = cache.get_content_store();
+        let manifest = store.store_files(dir.path(), &["out".to_owned()]).unwrap();
+
+        assert_eq!(manifest.len(), 3);
+        assert_eq!(
+            manifest.get("out/a.js").unwrap(),
+            manifest.get("out/nested/b.js").unwrap()
+        );
+        assert_ne!(
+            manifest.get("out/a.js").unwrap(),
+            manifest.get("out/c.js").unwrap()
+        );
+
+        // Blobs are sharded into sub-directories
+        let blobs = fs::read_dir(&cache.blobs_dir)
+            .unwrap()
+            .flat_map(|shard| fs::read_dir(shard.unwrap().path()).unwrap())
+            .count();
+
+        assert_eq!(blobs, 2);
+
+        dir.close().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn restores_files_as_hardlinks() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("same").unwrap();
+        dir.child("out/b.js").write_str("same").unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let store = cache.get_content_store();
+        let state = RunTargetState::default();
+        let outputs = vec![WorkspaceRelativePathBuf::from("out")];
+        let manifest_file = cache.get_hash_content_manifest_path("hash123");
+
+        assert!(run_with_env("", || state
+            .store_outputs(&store, &manifest_file, dir.path(), &outputs)
+            .unwrap()));
+
+        fs::remove_dir_all(dir.path().join("out")).unwrap();
+
+        assert!(run_with_env("", || state
+            .hydrate_outputs_from_store(&store, &manifest_file, dir.path())
+            .unwrap()));
+
+        let a = fs::metadata(dir.path().join("out/a.js")).unwrap();
+        let b = fs::metadata(dir.path().join("out/b.js")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("out/a.js")).unwrap(),
+            "same"
+        );
+        assert_eq!(a.ino(), b.ino());
+        assert_eq!(a.nlink(), 3);
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn doesnt_restore_if_blob_missing() {
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("a").unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let store = cache.get_content_store();
+        let manifest = store.store_files(dir.path(), &["out".to_owned()]).unwrap();
+
+        fs::remove_dir_all(&cache.blobs_dir).unwrap();
+        fs::remove_dir_all(dir.path().join("out")).unwrap();
+
+        assert!(!store.restore_files(dir.path(), &manifest).unwrap());
+        assert!(!dir.path().join("out/a.js").exists());
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn doesnt_restore_if_blob_modified() {
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("a").unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let store = cache.get_content_store();
+        let manifest = store.store_files(dir.path(), &["out".to_owned()]).unwrap();
+        let hash = manifest.get("out/a.js").unwrap();
+
+        fs::write(store.get_blob_path(hash), "modified").unwrap();
+        fs::remove_dir_all(dir.path().join("out")).unwrap();
+
+        assert!(!store.restore_files(dir.path(), &manifest).unwrap());
+        assert!(!store.has_blob(hash));
+        assert!(!dir.path().join("out/a.js").exists());
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn doesnt_leave_temp_files_when_storing() {
+        let dir = create_temp_dir();
+        dir.child("out/a.js").write_str("a").unwrap();
+
+        let cache = CacheEngine::load(dir.path()).unwrap();
+        let store = cache.get_content_store();
+        let manifest = store.store_files(dir.path(), &["out".to_owned()]).unwrap();
+        let blob = store.get_blob_path(manifest.get("out/a.js").unwrap());
+
+        assert_eq!(fs::read_dir(blob.parent().unwrap()).unwrap().count(), 1);
+
+        dir.close().unwrap();
+    }
+}
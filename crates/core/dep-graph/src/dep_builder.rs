@@ -95,7 +95,7 @@ impl<'ws> DepGraphBuilder<'ws> {
         let mut workspace_runtime = Runtime::System;
 
         if let Some(platform) = self.platforms.find(|p| match task {
-            Some(task) => p.matches(&task.platform, None),
+            Some(task) => p.matches(&task.get_runtime_platform(), None),
             None => p.matches(&project.language.clone().into(), None),
         }) {
             project_runtime = platform.get_runtime_from_config(Some(&project.config));
@@ -379,8 +379,12 @@ impl<'ws> DepGraphBuilder<'ws> {
             }
         }
 
+        // When serial, parent depends on the last child, which transitively
+        // depends on all previous children, in their declared order
         if !parallel {
-            indexes.push(previous_target_index.unwrap());
+            if let Some(prev) = previous_target_index {
+                indexes.push(prev);
+            }
         }
 
         Ok(indexes)
@@ -1,4 +1,5 @@
 use moon::{build_dep_graph, generate_project_graph, load_workspace_from};
+use moon_action::ActionNode;
 use moon_common::path::WorkspaceRelativePathBuf;
 use moon_config::{
     PartialInheritedTasksConfig, PartialNodeConfig, PartialToolchainConfig, PartialWorkspaceConfig,
@@ -82,6 +83,7 @@ async fn create_tasks_project_graph() -> (Workspace, ProjectGraph, Sandbox) {
             ("mergeReplace".into(), "merge-replace".to_owned()),
             ("noTasks".into(), "no-tasks".to_owned()),
             ("persistent".into(), "persistent".to_owned()),
+            ("serial".into(), "serial".to_owned()),
         ]))),
         ..PartialWorkspaceConfig::default()
     };
@@ -242,6 +244,44 @@ mod run_target {
         );
     }
 
+    #[tokio::test]
+    async fn runs_deps_sequentially_in_declared_order() {
+        let (workspace, projects, _sandbox) = create_tasks_project_graph().await;
+
+        let mut graph = build_dep_graph(&workspace, &projects);
+        graph
+            .run_target(&Target::new("serial", "all").unwrap(), None)
+            .unwrap();
+        let graph = graph.build();
+
+        let targets = graph
+            .sort_batched_topological()
+            .unwrap()
+            .into_iter()
+            .filter_map(|batch| {
+                let labels = batch
+                    .iter()
+                    .filter_map(|index| match graph.get_node_from_index(index) {
+                        Some(ActionNode::RunTarget(_, target)) => Some(target.id.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                (!labels.is_empty()).then_some(labels)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            targets,
+            vec![
+                vec!["serial:c".to_owned()],
+                vec!["serial:a".to_owned()],
+                vec!["serial:b".to_owned()],
+                vec!["serial:all".to_owned()],
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn moves_persistent_tasks_last() {
         let (workspace, projects, _sandbox) = create_tasks_project_graph().await;
@@ -6,6 +6,7 @@ use moon_rust_platform::RustPlatform;
 use moon_system_platform::SystemPlatform;
 use moon_utils::{is_ci, is_test_env};
 use moon_workspace::{Workspace, WorkspaceError};
+use proto::{node::NodeLanguage, Proto, Resolvable};
 use starbase_utils::json;
 use std::env;
 use std::path::Path;
@@ -68,9 +69,22 @@ pub async fn load_workspace_from(path: &Path) -> miette::Result<Workspace> {
         )));
     }
 
+    // Resolve `latest` and `lts` aliases to a concrete version up front,
+    // so that every consumer of the config sees the same version
+    if let Some(node_config) = &mut workspace.toolchain_config.node {
+        if node_config.get_version_alias(is_ci())?.is_some() {
+            let manifest = NodeLanguage::new(Proto::new()?)
+                .load_version_manifest()
+                .await?;
+
+            node_config.resolve_version_alias(&manifest, is_ci())?;
+        }
+    }
+
     if let Some(node_config) = &workspace.toolchain_config.node {
         workspace.register_platform(Box::new(NodePlatform::new(
             node_config,
+            workspace.toolchain_config.node_package_manager(),
             &workspace.toolchain_config.typescript,
             &workspace.root,
         )));
@@ -113,18 +127,24 @@ pub async fn generate_project_graph(workspace: &mut Workspace) -> miette::Result
     let cache_path = workspace.cache.get_state_path("projectGraph.json");
     let mut builder = build_project_graph(workspace).await?;
 
+    // Project roots are cached relative from the workspace root,
+    // so that the cache can be shared across machines
     if builder.is_cached && cache_path.exists() {
-        let graph: ProjectGraph = json::read_file(&cache_path)?;
+        let mut graph: ProjectGraph = json::read_file(&cache_path)?;
+        graph.verify_schema_version()?;
+        graph.resolve_roots(&workspace.root);
 
         return Ok(graph);
     }
 
     builder.load_all()?;
 
-    let graph = builder.build()?;
+    let mut graph = builder.build()?;
 
     if !builder.hash.is_empty() {
+        graph.relativize_roots();
         json::write_file(&cache_path, &graph, false)?;
+        graph.resolve_roots(&workspace.root);
     }
 
     Ok(graph)
@@ -8,8 +8,25 @@ use moon_php_lang::{COMPOSER, PHPBREW, PHPENV};
 use moon_python_lang::{PIP, PIPENV, POETRY, PYENV};
 use moon_ruby_lang::{BUNDLER, RBENV, RVM};
 use moon_rust_lang::{CARGO, RUSTUP, RUSTUP_LEGACY};
+use std::fs;
 use std::path::Path;
 
+fn has_file_with_extension(root: &Path, extensions: &[&str]) -> bool {
+    let Ok(entries) = fs::read_dir(root) else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.contains(&ext))
+    })
+}
+
 pub fn detect_project_language(root: &Path) -> LanguageType {
     // Go
     if is_using_dependency_manager(root, &GOMOD, true)
@@ -74,5 +91,14 @@ pub fn detect_project_language(root: &Path) -> LanguageType {
         return LanguageType::JavaScript;
     }
 
+    // Scripts (only when no other language markers exist)
+    if has_file_with_extension(root, &["sh"]) {
+        return LanguageType::Bash;
+    }
+
+    if has_file_with_extension(root, &["bat", "cmd"]) {
+        return LanguageType::Batch;
+    }
+
     LanguageType::Unknown
 }
@@ -25,20 +25,31 @@ fn use_platform_if_enabled(
     PlatformType::System
 }
 
+// Strip directories and Windows extensions: `C:\bin\npm.cmd` -> `npm`
+fn get_command_bin(command: &str) -> &str {
+    let bin = command.rsplit(['/', '\\']).next().unwrap_or(command);
+
+    bin.strip_suffix(".exe")
+        .or_else(|| bin.strip_suffix(".cmd"))
+        .unwrap_or(bin)
+}
+
 pub fn detect_task_platform(
     command: &str,
     // language: &LanguageType,
     toolchain_config: &ToolchainConfig,
 ) -> PlatformType {
-    if DENO_COMMANDS.is_match(command) {
+    let bin = get_command_bin(command);
+
+    if DENO_COMMANDS.is_match(bin) {
         return use_platform_if_enabled(PlatformType::Deno, toolchain_config);
     }
 
-    if NODE_COMMANDS.is_match(command) {
+    if NODE_COMMANDS.is_match(bin) {
         return use_platform_if_enabled(PlatformType::Node, toolchain_config);
     }
 
-    if RUST_COMMANDS.is_match(command) {
+    if RUST_COMMANDS.is_match(bin) {
         return use_platform_if_enabled(PlatformType::Rust, toolchain_config);
     }
 
@@ -0,0 +1,91 @@
+use moon_config::{DenoConfig, NodeConfig, PlatformType, RustConfig, ToolchainConfig};
+use moon_platform_detector::detect_task_platform;
+
+fn create_toolchain_config() -> ToolchainConfig {
+    ToolchainConfig {
+        deno: Some(DenoConfig::default()),
+        node: Some(NodeConfig::default()),
+        rust: Some(RustConfig::default()),
+        ..ToolchainConfig::default()
+    }
+}
+
+mod task_platform {
+    use super::*;
+
+    #[test]
+    fn detects_deno() {
+        assert_eq!(
+            detect_task_platform("deno", &create_toolchain_config()),
+            PlatformType::Deno
+        );
+    }
+
+    #[test]
+    fn detects_node() {
+        let config = create_toolchain_config();
+
+        for bin in [
+            "node", "nodejs", "npm", "npx", "pnpm", "pnpx", "yarn", "yarnpkg", "corepack",
+        ] {
+            assert_eq!(detect_task_platform(bin, &config), PlatformType::Node);
+        }
+    }
+
+    #[test]
+    fn detects_rust() {
+        let config = create_toolchain_config();
+
+        for bin in [
+            "cargo",
+            "rustc",
+            "rustdoc",
+            "rustfmt",
+            "rustup",
+            "rust-analyzer",
+        ] {
+            assert_eq!(detect_task_platform(bin, &config), PlatformType::Rust);
+        }
+    }
+
+    #[test]
+    fn detects_from_bin_paths() {
+        let config = create_toolchain_config();
+
+        assert_eq!(
+            detect_task_platform("/usr/local/bin/cargo", &config),
+            PlatformType::Rust
+        );
+        assert_eq!(
+            detect_task_platform("C:\\nodejs\\npm.cmd", &config),
+            PlatformType::Node
+        );
+        assert_eq!(
+            detect_task_platform("C:\\nodejs\\node.exe", &config),
+            PlatformType::Node
+        );
+    }
+
+    #[test]
+    fn fallsback_to_system_when_not_enabled() {
+        let config = ToolchainConfig::default();
+
+        assert_eq!(detect_task_platform("deno", &config), PlatformType::System);
+        assert_eq!(detect_task_platform("npm", &config), PlatformType::System);
+        assert_eq!(
+            detect_task_platform("/usr/local/bin/cargo", &config),
+            PlatformType::System
+        );
+    }
+
+    #[test]
+    fn unknown_for_other_commands() {
+        let config = create_toolchain_config();
+
+        assert_eq!(detect_task_platform("bin", &config), PlatformType::Unknown);
+        assert_eq!(
+            detect_task_platform("./node_modules/.bin/jest", &config),
+            PlatformType::Unknown
+        );
+    }
+}
@@ -3,7 +3,7 @@ use moon_action_context::ActionContext;
 use moon_common::Id;
 use moon_config::{
     DependencyConfig, HasherConfig, PlatformType, ProjectConfig, ProjectsAliasesMap,
-    ProjectsSourcesMap, TasksConfigsMap,
+    ProjectsSourcesMap, TaskOptionsConfig, TasksConfigsMap,
 };
 use moon_hasher::HashSet;
 use moon_platform_runtime::{Runtime, Version};
@@ -66,6 +66,12 @@ pub trait Platform: Debug + Send + Sync {
         Ok(BTreeMap::new())
     }
 
+    /// During project creation, return default options for all tasks that
+    /// run on this platform. These have the lowest precedence when merging.
+    fn get_task_options_defaults(&self) -> Option<TaskOptionsConfig> {
+        None
+    }
+
     // TOOLCHAIN
 
     fn is_toolchain_enabled(&self) -> miette::Result<bool>;
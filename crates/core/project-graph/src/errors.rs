@@ -34,6 +34,14 @@ pub enum ProjectGraphError {
         .1.style(Style::Label),
     )]
     UnsupportedTargetScopeInDeps(String, String),
+
+    #[diagnostic(code(project_graph::task_dep::unknown_project))]
+    #[error(
+        "Invalid dependency {} for task {}. No project exists with this ID or alias.",
+        .0.style(Style::Label),
+        .1.style(Style::Label),
+    )]
+    UnknownProjectInDeps(String, String),
 }
 
 #[derive(Error, Debug, Diagnostic)]
@@ -2,6 +2,7 @@ use moon_common::path::WorkspaceRelativePathBuf;
 use moon_common::Id;
 use moon_config::{ProjectsAliasesMap, ProjectsSourcesMap};
 use moon_hasher::{hash_btree, Digest, Hasher, Sha256};
+use moon_project::PROJECT_SCHEMA_VERSION;
 use moon_utils::is_docker_container;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, env};
@@ -13,6 +14,10 @@ pub struct GraphHasher {
 
     configs: BTreeMap<String, String>,
 
+    // Tasks resolve their command with `commandByEnv` based on `MOON_ENV`,
+    // so a graph built for one environment can't be reused by another.
+    environment: String,
+
     sources: BTreeMap<Id, String>,
 
     // The project graph stores absolute file paths, which breaks moon when
@@ -20,6 +25,10 @@ pub struct GraphHasher {
     // This flag helps to continuously bust the cache.
     in_container: bool,
 
+    // Version of the serialized project format, so that a cached graph
+    // is never deserialized into an incompatible project shape.
+    schema_version: u16,
+
     // Version of the moon CLI. We need to include this so that the graph
     // cache is invalidated between each release, otherwise internal Rust
     // changes (in project or task crates) are not reflected until the cache
@@ -32,7 +41,9 @@ impl GraphHasher {
         GraphHasher {
             aliases: BTreeMap::default(),
             configs: BTreeMap::default(),
+            environment: env::var("MOON_ENV").unwrap_or_default(),
             in_container: is_docker_container(),
+            schema_version: PROJECT_SCHEMA_VERSION,
             sources: BTreeMap::default(),
             version: env::var("MOON_VERSION").unwrap_or_default(),
         }
@@ -58,8 +69,10 @@ impl Hasher for GraphHasher {
         hash_btree(&self.aliases, sha);
         hash_btree(&self.configs, sha);
         hash_btree(&self.sources, sha);
+        sha.update(self.environment.as_bytes());
         sha.update(self.version.as_bytes());
         sha.update(self.in_container.to_string().as_bytes());
+        sha.update(self.schema_version.to_string().as_bytes());
     }
 
     fn serialize(&self) -> serde_json::Value {
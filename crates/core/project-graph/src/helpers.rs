@@ -1,10 +1,12 @@
 use moon_common::{consts, Id};
 use moon_config::ProjectsSourcesMap;
 use moon_logger::{debug, warn};
+use moon_project_builder::ProjectBuilderError;
 use moon_utils::{path, regex};
 use moon_vcs::BoxedVcs;
 use starbase_styles::color;
 use starbase_utils::{fs, glob};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// Infer a project name from a source path, by using the name of
@@ -21,6 +23,7 @@ pub fn infer_project_name_and_source(source: &str) -> (String, String) {
 
 /// For each pattern in the globs list, glob the file system
 /// for potential projects, and infer their name and source.
+/// If multiple globbed sources infer the same name, an error is returned.
 #[track_caller]
 pub fn detect_projects_with_globs(
     workspace_root: &Path,
@@ -29,6 +32,7 @@ pub fn detect_projects_with_globs(
     vcs: Option<&BoxedVcs>,
 ) -> miette::Result<()> {
     let root_source = ".".to_owned();
+    let mut detected: BTreeMap<Id, Vec<String>> = BTreeMap::new();
 
     // Root-level project has special handling
     if globs.contains(&root_source) {
@@ -69,7 +73,12 @@ pub fn detect_projects_with_globs(
             let (id, source) = infer_project_name_and_source(&project_source);
             let id = Id::raw(regex::clean_id(&id));
 
-            if let Some(existing_source) = projects.get(&id) {
+            if let Some(sources) = detected.get_mut(&id) {
+                // Overlapping globs may match the same folder multiple times
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            } else if let Some(existing_source) = projects.get(&id) {
                 warn!(
                     target: "moon:project",
                     "A project already exists for {} at source {}. Skipping conflicting source {}. Try renaming the project folder to make it unique.",
@@ -78,10 +87,15 @@ pub fn detect_projects_with_globs(
                     color::file(&source)
                 );
             } else {
+                detected.insert(id.clone(), vec![source.clone()]);
                 projects.insert(id, source);
             }
         }
     }
 
+    if let Some((id, sources)) = detected.into_iter().find(|(_, sources)| sources.len() > 1) {
+        return Err(ProjectBuilderError::DuplicateID { id, sources }.into());
+    }
+
     Ok(())
 }
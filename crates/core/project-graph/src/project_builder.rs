@@ -14,7 +14,6 @@ use moon_project_builder::{ProjectBuilder, ProjectBuilderError};
 use moon_project_constraints::{enforce_project_type_relationships, enforce_tag_relationships};
 use moon_target::{Target, TargetScope};
 use moon_task::Task;
-use moon_utils::regex::ENV_VAR_SUBSTITUTE;
 use moon_utils::{path, time};
 use moon_workspace::Workspace;
 use petgraph::graph::{DiGraph, NodeIndex};
@@ -34,6 +33,9 @@ pub struct ProjectGraphBuilder<'ws> {
     indices: IndicesType,
     sources: ProjectsSourcesMap,
 
+    // Projects with an ID derived from their globbed folder name.
+    globbed: FxHashSet<Id>,
+
     // Project and its dependencies being created.
     // We use this to prevent circular dependencies.
     created: FxHashSet<Id>,
@@ -49,6 +51,7 @@ impl<'ws> ProjectGraphBuilder<'ws> {
         let mut graph = ProjectGraphBuilder {
             aliases: FxHashMap::default(),
             created: FxHashSet::default(),
+            globbed: FxHashSet::default(),
             graph: DiGraph::new(),
             hash: String::new(),
             indices: FxHashMap::default(),
@@ -63,6 +66,7 @@ impl<'ws> ProjectGraphBuilder<'ws> {
     }
 
     pub fn build(&mut self) -> miette::Result<ProjectGraph> {
+        self.expand_tag_deps()?;
         self.enforce_constraints()?;
         self.validate_outputs()?;
 
@@ -104,8 +108,23 @@ impl<'ws> ProjectGraphBuilder<'ws> {
 
         builder.detect_language(detect_project_language);
         builder.detect_platform(detect_task_platform, &self.workspace.toolchain_config);
+        builder.error_on_incompatible_platform(
+            self.workspace
+                .config
+                .constraints
+                .enforce_language_platform_compatibility,
+        );
+        builder.validate_id_convention(self.globbed.contains(id));
         builder.load_local_config()?;
         builder.inherit_global_config(&self.workspace.tasks_config)?;
+        builder.inherit_workspace_env(self.workspace.config.env.clone());
+        builder.inherit_global_inputs(self.workspace.config.global_inputs.clone());
+
+        for platform in self.workspace.platforms.list() {
+            if let Some(options) = platform.get_task_options_defaults() {
+                builder.inherit_platform_options(platform.get_type(), options);
+            }
+        }
 
         if let Ok(platform) = self.workspace.platforms.get(builder.language.clone()) {
             // Inherit implicit dependencies
@@ -133,6 +152,92 @@ impl<'ws> ProjectGraphBuilder<'ws> {
         Ok(project)
     }
 
+    /// Expand tag scoped task deps (`#tag:task`) to the task in every project
+    /// with the tag. Since any project may have the tag, this loads all projects.
+    fn expand_tag_deps(&mut self) -> miette::Result<()> {
+        let is_tag_dep = |dep: &Target| matches!(dep.scope, TargetScope::Tag(_));
+
+        let has_tag_deps = self.graph.node_weights().any(|project| {
+            project
+                .tasks
+                .values()
+                .any(|task| task.deps.iter().any(is_tag_dep))
+        });
+
+        if !has_tag_deps {
+            return Ok(());
+        }
+
+        self.load_all()?;
+
+        let projects = self
+            .graph
+            .node_weights()
+            .map(|project| {
+                (
+                    project.id.clone(),
+                    project.config.tags.clone(),
+                    project.tasks.keys().cloned().collect::<FxHashSet<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for project in self.graph.node_weights_mut() {
+            for task in project.tasks.values_mut() {
+                if !task.deps.iter().any(is_tag_dep) {
+                    continue;
+                }
+
+                let mut dep_targets: Vec<Target> = vec![];
+
+                for dep_target in mem::take(&mut task.deps) {
+                    let TargetScope::Tag(tag) = &dep_target.scope else {
+                        if !dep_targets.contains(&dep_target) {
+                            dep_targets.push(dep_target);
+                        }
+
+                        continue;
+                    };
+
+                    let mut tag_targets = vec![];
+
+                    for (tag_project_id, tags, task_ids) in &projects {
+                        if !tags.contains(tag) || !task_ids.contains(&dep_target.task_id) {
+                            continue;
+                        }
+
+                        // Avoid circular references
+                        if tag_project_id == &project.id && dep_target.task_id == task.id {
+                            continue;
+                        }
+
+                        tag_targets.push(Target::new(tag_project_id, &dep_target.task_id)?);
+                    }
+
+                    tag_targets.sort();
+
+                    trace!(
+                        target: LOG_TARGET,
+                        "Expanded dependency {} for target {} to {}",
+                        color::label(&dep_target.id),
+                        color::label(&task.target.id),
+                        map_list(&tag_targets, |t| color::label(t)),
+                    );
+
+                    for tag_target in tag_targets {
+                        if !dep_targets.contains(&tag_target) {
+                            dep_targets.push(tag_target);
+                        }
+                    }
+                }
+
+                task.deps = dep_targets;
+            }
+        }
+
+        Ok(())
+    }
+
     fn enforce_constraints(&self) -> miette::Result<()> {
         let type_relationships = self
             .workspace
@@ -205,7 +310,6 @@ impl<'ws> ProjectGraphBuilder<'ws> {
         // Use `mem::take` so that we can mutably borrow the project and tasks in parallel
         for (task_id, mut task) in mem::take(&mut project.tasks) {
             // Resolve in this order!
-            self.expand_task_env(project, &mut task)?;
             self.expand_task_deps(project, &mut task)?;
             self.expand_task_inputs(project, &mut task)?;
             self.expand_task_outputs(project, &mut task)?;
@@ -336,16 +440,32 @@ impl<'ws> ProjectGraphBuilder<'ws> {
                 }
                 // project:task
                 TargetScope::Project(project_id) => {
-                    if project_id == &project.id && dep_target.task_id == task.id {
+                    // Aliases (package names, etc) must resolve to the owning project's ID
+                    let project_id = self.resolve_id(project_id);
+
+                    if !self.sources.contains_key(&project_id) {
+                        return Err(ProjectGraphError::UnknownProjectInDeps(
+                            dep_target.to_string(),
+                            task.target.to_string(),
+                        )
+                        .into());
+                    }
+
+                    if project_id == project.id && dep_target.task_id == task.id {
                         // Avoid circular references
                     } else {
-                        push_target(dep_target.clone());
+                        push_target(Target::new(&project_id, &dep_target.task_id)?);
                     }
                 }
+                // #tag:task
+                TargetScope::Tag(_) if self.workspace.config.runner.expand_tag_deps => {
+                    // Expanded once all projects have been loaded
+                    push_target(dep_target.clone());
+                }
                 // :task
                 // #tag:task
                 _ => {
-                    return Err(ProjectGraphError::PersistentDepRequirement(
+                    return Err(ProjectGraphError::UnsupportedTargetScopeInDeps(
                         dep_target.to_string(),
                         task.target.to_string(),
                     )
@@ -359,21 +479,6 @@ impl<'ws> ProjectGraphBuilder<'ws> {
         Ok(())
     }
 
-    /// Expand environment variables by loading a `.env` file if configured.
-    pub fn expand_task_env(&self, _project: &mut Project, task: &mut Task) -> miette::Result<()> {
-        task.env.iter_mut().for_each(|(_, value)| {
-            while let Some(matches) = ENV_VAR_SUBSTITUTE.captures(value) {
-                let sub = matches.get(0).unwrap().as_str();
-                let sub_key = matches.get(1).unwrap().as_str();
-                let sub_value = env::var(sub_key).unwrap_or_default();
-
-                *value = value.replace(sub, &sub_value);
-            }
-        });
-
-        Ok(())
-    }
-
     /// Expand the inputs list to a set of absolute file paths, while resolving tokens.
     pub fn expand_task_inputs(&self, project: &mut Project, task: &mut Task) -> miette::Result<()> {
         task.inputs.retain(|input| {
@@ -526,6 +631,8 @@ impl<'ws> ProjectGraphBuilder<'ws> {
                 map_list(&globs, |g| color::file(g))
             );
 
+            let explicit = sources.keys().cloned().collect::<FxHashSet<_>>();
+
             detect_projects_with_globs(
                 &self.workspace.root,
                 &globs,
@@ -533,6 +640,9 @@ impl<'ws> ProjectGraphBuilder<'ws> {
                 Some(&self.workspace.vcs),
             )?;
 
+            self.globbed
+                .extend(sources.keys().filter(|id| !explicit.contains(*id)).cloned());
+
             cache.last_glob_time = time::now_millis();
         }
 
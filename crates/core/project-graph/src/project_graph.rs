@@ -56,6 +56,22 @@ impl ProjectGraph {
         }
     }
 
+    /// Convert all project roots to paths relative from the workspace root,
+    /// so that the graph can be persisted and shared across checkouts.
+    pub fn relativize_roots(&mut self) {
+        for project in self.graph.node_weights_mut() {
+            project.root = PathBuf::from(project.source.as_str());
+        }
+    }
+
+    /// Resolve all project roots to absolute paths against the provided
+    /// workspace root. Is the inverse of [`ProjectGraph::relativize_roots`].
+    pub fn resolve_roots(&mut self, workspace_root: &Path) {
+        for project in self.graph.node_weights_mut() {
+            project.root = project.source.to_logical_path(workspace_root);
+        }
+    }
+
     /// Return a list of all configured project IDs in ascending order.
     pub fn ids(&self) -> Vec<Id> {
         let mut nodes: Vec<Id> = self.sources.keys().cloned().collect();
@@ -129,6 +145,16 @@ impl ProjectGraph {
         Ok(self.graph.node_weight(*index).unwrap())
     }
 
+    /// Verify that all projects in the graph were serialized with the
+    /// current project format version, when loaded from the cache.
+    pub fn verify_schema_version(&self) -> miette::Result<()> {
+        for node in self.graph.raw_nodes() {
+            node.weight.verify_schema_version()?;
+        }
+
+        Ok(())
+    }
+
     /// Return all projects from the graph.
     pub fn get_all(&self) -> miette::Result<Vec<&Project>> {
         Ok(self.graph.raw_nodes().iter().map(|n| &n.weight).collect())
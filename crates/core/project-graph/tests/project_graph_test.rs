@@ -8,10 +8,12 @@ use moon_config::{
 use moon_project::Project;
 use moon_project_graph::ProjectGraph;
 use moon_test_utils::{
-    assert_snapshot, create_sandbox_with_config, get_project_graph_aliases_fixture_configs, Sandbox,
+    assert_snapshot, create_sandbox_with_config, create_temp_dir,
+    get_project_graph_aliases_fixture_configs, Sandbox,
 };
 use moon_utils::string_vec;
 use rustc_hash::FxHashMap;
+use starbase_utils::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::Path;
@@ -286,6 +288,37 @@ mod caching {
             .exists());
     }
 
+    #[tokio::test]
+    async fn caches_project_roots_relative_from_workspace() {
+        let (_, sandbox) = get_dependencies_graph(true).await;
+        let graph_path = sandbox.path().join(".moon/cache/states/projectGraph.json");
+
+        let contents = fs::read_file(&graph_path).unwrap();
+
+        assert!(!contents.contains(sandbox.path().to_str().unwrap()));
+
+        // Load the cache from a different workspace root
+        let other_root = create_temp_dir();
+
+        fs::copy_dir_all(sandbox.path(), sandbox.path(), other_root.path()).unwrap();
+
+        let other_graph_path = other_root
+            .path()
+            .join(".moon/cache/states/projectGraph.json");
+        let modified = fs::metadata(&other_graph_path).unwrap().modified().unwrap();
+
+        let mut workspace = load_workspace_from(other_root.path()).await.unwrap();
+        let graph = generate_project_graph(&mut workspace).await.unwrap();
+
+        // Ensure the cached graph was used, and not rebuilt
+        assert_eq!(
+            fs::metadata(&other_graph_path).unwrap().modified().unwrap(),
+            modified
+        );
+        assert_eq!(graph.get("a").unwrap().root, other_root.path().join("a"));
+        assert_eq!(graph.get("d").unwrap().root, other_root.path().join("d"));
+    }
+
     #[tokio::test]
     async fn doesnt_cache_if_no_vcs() {
         let (_, sandbox) = get_dependencies_graph(false).await;
@@ -379,6 +412,49 @@ mod globs {
             ])
         );
     }
+
+    #[tokio::test]
+    #[should_panic(expected = "Multiple projects resolved to the ID foo, from sources")]
+    async fn errors_for_duplicate_ids() {
+        let workspace_config = PartialWorkspaceConfig {
+            projects: Some(PartialWorkspaceProjects::Globs(string_vec![
+                "apps/*",
+                "packages/*"
+            ])),
+            ..PartialWorkspaceConfig::default()
+        };
+
+        let sandbox =
+            create_sandbox_with_config("project-graph/ids", Some(workspace_config), None, None);
+        sandbox.create_file("apps/foo/moon.yml", "{}");
+        sandbox.create_file("packages/foo/moon.yml", "{}");
+
+        let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+
+        generate_project_graph(&mut workspace).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn doesnt_error_for_overlapping_globs() {
+        let workspace_config = PartialWorkspaceConfig {
+            projects: Some(PartialWorkspaceProjects::Globs(string_vec![
+                "apps/*", "apps/foo"
+            ])),
+            ..PartialWorkspaceConfig::default()
+        };
+
+        let sandbox =
+            create_sandbox_with_config("project-graph/ids", Some(workspace_config), None, None);
+        sandbox.create_file("apps/foo/moon.yml", "{}");
+
+        let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+        let graph = generate_project_graph(&mut workspace).await.unwrap();
+
+        assert_eq!(
+            graph.sources,
+            FxHashMap::from_iter([("foo".into(), "apps/foo".to_owned())])
+        );
+    }
 }
 
 mod get_dependencies_of {
@@ -809,6 +885,113 @@ mod tag_constraints {
     }
 }
 
+mod tag_deps {
+    use super::*;
+    use moon_config::PartialRunnerConfig;
+    use moon_target::Target;
+
+    async fn get_tag_deps_graph(expand_tag_deps: bool) -> ProjectGraph {
+        let workspace_config = PartialWorkspaceConfig {
+            projects: Some(PartialWorkspaceProjects::Globs(string_vec!["*"])),
+            runner: Some(PartialRunnerConfig {
+                expand_tag_deps: Some(expand_tag_deps),
+                ..PartialRunnerConfig::default()
+            }),
+            ..PartialWorkspaceConfig::default()
+        };
+
+        let sandbox = create_sandbox_with_config(
+            "project-graph/tag-deps",
+            Some(workspace_config),
+            None,
+            None,
+        );
+
+        let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+
+        generate_project_graph(&mut workspace).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Invalid dependency #frontend:lint")]
+    async fn errors_by_default() {
+        get_tag_deps_graph(false).await;
+    }
+
+    #[tokio::test]
+    async fn expands_to_each_project_with_tag() {
+        let graph = get_tag_deps_graph(true).await;
+
+        assert_eq!(
+            graph.get("app").unwrap().get_task("build").unwrap().deps,
+            vec![
+                Target::parse("server:lint").unwrap(),
+                Target::parse("ui-a:lint").unwrap(),
+                Target::parse("ui-b:lint").unwrap(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_depend_on_itself() {
+        let graph = get_tag_deps_graph(true).await;
+
+        assert_eq!(
+            graph.get("ui-b").unwrap().get_task("lint").unwrap().deps,
+            vec![Target::parse("ui-a:lint").unwrap()]
+        );
+    }
+}
+
+mod alias_deps {
+    use super::*;
+    use moon_target::Target;
+
+    #[tokio::test]
+    async fn resolves_aliases_to_project_ids() {
+        let (graph, _sandbox) = get_aliases_graph().await;
+
+        assert_eq!(
+            graph
+                .get("node")
+                .unwrap()
+                .get_task("aliasDeps")
+                .unwrap()
+                .deps,
+            vec![
+                Target::parse("nodeNameScope:standard").unwrap(),
+                Target::parse("nodeNameOnly:standard").unwrap(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Invalid dependency @scope/unknown:build for task node:unknownDep")]
+    async fn errors_for_unknown_alias() {
+        let (workspace_config, toolchain_config, tasks_config) =
+            get_project_graph_aliases_fixture_configs();
+
+        let sandbox = create_sandbox_with_config(
+            "project-graph/aliases",
+            Some(workspace_config),
+            Some(toolchain_config),
+            Some(tasks_config),
+        );
+
+        append_file(
+            sandbox.path().join("node/moon.yml"),
+            r#"  unknownDep:
+    command: 'noop'
+    deps:
+      - '@scope/unknown:build'"#,
+        );
+
+        let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+
+        generate_project_graph(&mut workspace).await.unwrap();
+    }
+}
+
 mod query {
     use super::*;
     use moon_query::build_query;
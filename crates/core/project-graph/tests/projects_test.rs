@@ -7,6 +7,7 @@ use moon_config::{
     InputPath, LanguageType, OutputPath, PartialInheritedTasksConfig, PartialNodeConfig,
     PartialRustConfig, PartialTaskCommandArgs, PartialTaskConfig, PartialTaskOptionsConfig,
     PartialToolchainConfig, PartialWorkspaceConfig, PartialWorkspaceProjects, PlatformType,
+    TaskDependency,
 };
 use moon_project::Project;
 use moon_project_graph::ProjectGraph;
@@ -218,7 +219,9 @@ tasks:
             PartialTaskConfig {
                 args: Some(PartialTaskCommandArgs::List(string_vec!["--a"])),
                 command: Some(PartialTaskCommandArgs::String("standard".to_owned())),
-                deps: Some(vec![Target::parse("a:standard").unwrap()]),
+                deps: Some(vec![TaskDependency::Target(
+                    Target::parse("buildA:standard").unwrap(),
+                )]),
                 env: Some(stub_global_env_vars()),
                 inputs: Some(vec![InputPath::ProjectGlob("a.*".into())]),
                 outputs: Some(vec![OutputPath::ProjectFile("a.ts".into())]),
@@ -985,7 +988,7 @@ mod task_expansion {
         }
 
         #[tokio::test]
-        #[should_panic(expected = "target scope not supported as a task dependency")]
+        #[should_panic(expected = "Invalid dependency #tag:build")]
         async fn errors_for_tag_scope() {
             tasks_sandbox_with_setup(|sandbox| {
                 sandbox.create_file(
@@ -1103,7 +1106,10 @@ mod task_expansion {
                     ("SUB_MISSING".to_owned(), "".to_owned()),
                     ("SUB_MULTI".to_owned(), "foo-bar".to_owned()),
                     ("SUB_MULTI_SAME".to_owned(), "foo-foo".to_owned()),
-                    ("SUB_REF_SELF".to_owned(), "".to_owned())
+                    ("SUB_REF_SELF".to_owned(), "base".to_owned()),
+                    ("SUB_REF_NESTED".to_owned(), "base-nested".to_owned()),
+                    ("SUB_BARE".to_owned(), "foo/bin".to_owned()),
+                    ("SUB_ESCAPED".to_owned(), "${FOO}".to_owned())
                 ])
             );
 
@@ -1131,7 +1137,6 @@ mod task_expansion {
                     ("SUB_MISSING".to_owned(), "".to_owned()),
                     ("SUB_MULTI".to_owned(), "foo-bar".to_owned()),
                     ("SUB_MULTI_SAME".to_owned(), "foo-foo".to_owned()),
-                    // This is different than the `env` setting
                     ("SUB_REF_SELF".to_owned(), "base".to_owned())
                 ])
             );
@@ -1141,6 +1146,23 @@ mod task_expansion {
             env::remove_var("BAR");
         }
 
+        #[tokio::test]
+        #[should_panic(expected = "references the undefined environment variable UNKNOWN")]
+        async fn errors_for_undefined_vars_when_strict() {
+            tasks_sandbox_with_setup(|sandbox| {
+                sandbox.create_file(
+                    "expand-env/moon.yml",
+                    r#"tasks:
+  strict:
+    env:
+      SUB: '${UNKNOWN}'
+    options:
+      strictEnv: true"#,
+                );
+            })
+            .await;
+        }
+
         mod project_level {
             use super::*;
 
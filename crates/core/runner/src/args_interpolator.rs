@@ -0,0 +1,95 @@
+use rustc_hash::FxHashMap;
+use std::env;
+
+const SECRET_PATTERNS: [&str; 6] = ["AUTH", "CREDENTIAL", "KEY", "PASSWORD", "SECRET", "TOKEN"];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct InterpolatedArgs {
+    pub args: Vec<String>,
+
+    /// Values that were interpolated from secret-like variables,
+    /// and must be masked when logging.
+    pub secrets: Vec<String>,
+}
+
+/// Return true if the variable name looks like it contains a secret.
+pub fn is_secret_var(name: &str) -> bool {
+    let name = name.to_uppercase();
+
+    SECRET_PATTERNS.iter().any(|pattern| name.contains(pattern))
+}
+
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Only the braced form is substituted, as unbraced references (`$HOME`, `$1`, etc)
+// are typically meant for the shell. A `$${VAR}` is an escape for a literal `${VAR}`.
+fn interpolate_braced<F>(value: &str, mut lookup: F) -> String
+where
+    F: FnMut(&str) -> String,
+{
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(index) = rest.find("${") {
+        let before = &rest[..index];
+        let after = &rest[index + 2..];
+
+        let Some(end) = after.find('}') else {
+            break;
+        };
+
+        if let Some(before) = before.strip_suffix('$') {
+            result.push_str(before);
+            result.push_str("${");
+            rest = after;
+            continue;
+        }
+
+        let name = &after[..end];
+
+        result.push_str(before);
+
+        if is_valid_name(name) {
+            result.push_str(&lookup(name));
+        } else {
+            result.push_str(&rest[index..index + end + 3]);
+        }
+
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Interpolate `${VAR}` references within each argument, by resolving against the task's
+/// environment variables first, and then the process environment. Unbraced `$VAR` references
+/// are passed through as-is. Like task `env`, undefined variables are replaced with an
+/// empty string.
+pub fn interpolate_args(args: &[String], task_env: &FxHashMap<String, String>) -> InterpolatedArgs {
+    let mut result = InterpolatedArgs::default();
+
+    for arg in args {
+        // Substituted values are not interpolated again, so self-references can't loop
+        let value = interpolate_braced(arg, |name| {
+            let value = task_env
+                .get(name)
+                .cloned()
+                .or_else(|| env::var(name).ok())
+                .unwrap_or_default();
+
+            if !value.is_empty() && is_secret_var(name) && !result.secrets.contains(&value) {
+                result.secrets.push(value.clone());
+            }
+
+            value
+        });
+
+        result.args.push(value);
+    }
+
+    result
+}
@@ -12,7 +12,29 @@ pub enum RunnerError {
     )]
     MissingDependencyHash(String, String),
 
-    #[diagnostic(code(target_runner::missing_output))]
-    #[error("Target {} defines outputs, but none exist after being ran.", .0.style(Style::Label))]
-    MissingOutput(String),
+    #[diagnostic(code(target_runner::file_dep_timeout))]
+    #[error(
+        "Target {} depends on the file {}, but it did not exist after waiting {} {}.",
+        .0.style(Style::Label),
+        .1.style(Style::File),
+        .2,
+        if *.2 == 1 { "second" } else { "seconds" },
+    )]
+    FileDependencyTimeout(String, String, u64),
+
+    #[diagnostic(code(target_runner::missing_output_path))]
+    #[error(
+        "Target {} defines the output {}, but it does not exist after being ran.",
+        .0.style(Style::Label),
+        .1.style(Style::File),
+    )]
+    MissingOutputPath(String, String),
+
+    #[diagnostic(code(target_runner::task_timeout))]
+    #[error(
+        "Target {} did not complete within {} seconds, and was terminated.",
+        .0.style(Style::Label),
+        .1,
+    )]
+    TaskTimeout(String, u32),
 }
@@ -0,0 +1,42 @@
+use crate::errors::RunnerError;
+use moon_logger::debug;
+use moon_task::Task;
+use starbase_styles::color;
+use std::path::Path;
+use std::time::Instant;
+use tokio::time::{sleep, Duration};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Wait for all file dependencies of a task to exist before running it,
+// as these files are produced by external processes and not moon tasks.
+// Each file has its own timeout, and will error if it has been reached.
+pub async fn wait_for_file_deps(task: &Task, project_root: &Path) -> miette::Result<()> {
+    for dep in &task.file_deps {
+        let path = project_root.join(&dep.file);
+        let timeout = Duration::from_secs(dep.timeout);
+        let started = Instant::now();
+
+        debug!(
+            target: task.target.as_str(),
+            "Waiting for file {} to exist (timeout {}s)",
+            color::path(&path),
+            dep.timeout,
+        );
+
+        while !path.exists() {
+            if started.elapsed() >= timeout {
+                return Err(RunnerError::FileDependencyTimeout(
+                    task.target.id.clone(),
+                    dep.file.as_str().to_owned(),
+                    dep.timeout,
+                )
+                .into());
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    Ok(())
+}
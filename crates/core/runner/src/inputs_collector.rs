@@ -1,8 +1,10 @@
 use moon_common::{
     consts::CONFIG_PROJECT_FILENAME,
+    load_ignore_patterns,
     path::{standardize_separators, WorkspaceRelativePathBuf},
 };
 use moon_config::{HasherConfig, HasherWalkStrategy};
+use moon_hasher::{Digest, Sha256};
 use moon_logger::{debug, warn};
 use moon_task::Task;
 use moon_utils::{is_ci, path};
@@ -12,19 +14,30 @@ use starbase_styles::color;
 use starbase_utils::glob::{self, GlobSet};
 use std::{
     collections::BTreeMap,
+    fs,
     path::{Path, PathBuf},
 };
 
 type HashedInputs = BTreeMap<WorkspaceRelativePathBuf, String>;
 
+// Returns a list of files to hash by content, and a list of
+// symlinks to hash by their target path (when not following).
 fn convert_paths_to_strings(
     log_target: &str,
     paths: &FxHashSet<PathBuf>,
     workspace_root: &Path,
     hasher_config: &HasherConfig,
-) -> miette::Result<Vec<String>> {
+) -> miette::Result<(Vec<String>, Vec<String>)> {
     let mut files: Vec<String> = vec![];
-    let ignore = GlobSet::new(&hasher_config.ignore_patterns)?;
+    let mut symlinks: Vec<String> = vec![];
+    let workspace_ignore = load_ignore_patterns(workspace_root);
+    let ignore_patterns = hasher_config
+        .ignore_patterns
+        .iter()
+        .map(|pattern| pattern.as_str())
+        .chain(workspace_ignore.iter().map(|pattern| pattern.as_str()))
+        .collect::<Vec<_>>();
+    let ignore = GlobSet::new(&ignore_patterns)?;
     let ignore_missing = GlobSet::new(&hasher_config.ignore_missing_patterns)?;
 
     for path in paths {
@@ -36,9 +49,34 @@ fn convert_paths_to_strings(
             path
         };
 
+        if path.is_symlink() {
+            if !hasher_config.follow_symlinks {
+                if ignore.is_match(rel_path) {
+                    continue;
+                }
+
+                symlinks.push(standardize_separators(path::to_string(rel_path)?));
+
+                continue;
+            }
+
+            // Guard against circular (and broken) links, as they can't be resolved
+            if fs::canonicalize(path).is_err() {
+                warn!(
+                    target: log_target,
+                    "Attempted to hash input {} but it's a symlink that could not be resolved, skipping",
+                    color::path(rel_path),
+                );
+
+                continue;
+            }
+        }
+
         // `git hash-object` will fail if you pass an unknown file
         if !path.exists() && hasher_config.warn_on_missing_inputs {
-            if hasher_config.ignore_missing_patterns.is_empty() || !ignore_missing.is_match(path) {
+            if hasher_config.ignore_missing_patterns.is_empty()
+                || !ignore_missing.is_match(rel_path)
+            {
                 warn!(
                     target: log_target,
                     "Attempted to hash input {} but it does not exist, skipping",
@@ -59,7 +97,7 @@ fn convert_paths_to_strings(
             continue;
         }
 
-        if ignore.is_match(path) {
+        if ignore.is_match(rel_path) {
             debug!(
                 target: log_target,
                 "Not hashing input {} as it matches an ignore pattern",
@@ -70,7 +108,17 @@ fn convert_paths_to_strings(
         }
     }
 
-    Ok(files)
+    Ok((files, symlinks))
+}
+
+fn hash_symlink_target(path: &Path) -> miette::Result<String> {
+    let target = fs::read_link(path)
+        .map_err(|error| miette::miette!("Failed to read symlink {}: {error}", path.display()))?;
+
+    let mut sha = Sha256::new();
+    sha.update(standardize_separators(path::to_string(target)?).as_bytes());
+
+    Ok(format!("{:x}", sha.finalize()))
 }
 
 fn is_valid_input_source(
@@ -114,7 +162,10 @@ pub async fn collect_and_hash_inputs(
     let mut files_to_hash = FxHashSet::default(); // Absolute paths
     let globset = task.create_globset()?;
     let use_globs = project_root == workspace_root
-        || matches!(hasher_config.walk_strategy, HasherWalkStrategy::Glob);
+        || matches!(
+            hasher_config.walk_strategy.resolve(workspace_root),
+            HasherWalkStrategy::Glob
+        );
 
     // 1: Collect inputs as a set of absolute paths
 
@@ -169,7 +220,7 @@ pub async fn collect_and_hash_inputs(
 
     // 2: Convert to workspace relative paths and filter out invalid inputs
 
-    let mut files_to_hash = convert_paths_to_strings(
+    let (mut files_to_hash, mut symlinks_to_hash) = convert_paths_to_strings(
         task.target.as_str(),
         &files_to_hash,
         workspace_root,
@@ -177,6 +228,7 @@ pub async fn collect_and_hash_inputs(
     )?;
 
     files_to_hash.retain(|f| is_valid_input_source(task, &globset, f));
+    symlinks_to_hash.retain(|f| is_valid_input_source(task, &globset, f));
 
     // 3: Extract hashes
 
@@ -184,10 +236,21 @@ pub async fn collect_and_hash_inputs(
 
     if !files_to_hash.is_empty() {
         hashed_inputs.extend(
-            vcs.get_file_hashes(&files_to_hash, true, hasher_config.batch_size)
-                .await?,
+            vcs.get_file_hashes(
+                &files_to_hash,
+                true,
+                hasher_config.resolve_batch_size(files_to_hash.len()),
+            )
+            .await?,
         );
     }
 
+    // Symlinks that aren't followed are hashed by their target path
+    for symlink in symlinks_to_hash {
+        let hash = hash_symlink_target(&workspace_root.join(&symlink))?;
+
+        hashed_inputs.insert(WorkspaceRelativePathBuf::from(symlink), hash);
+    }
+
     Ok(hashed_inputs)
 }
@@ -1,7 +1,11 @@
+pub mod args_interpolator;
 mod errors;
+pub mod file_deps;
 pub mod inputs_collector;
+pub mod output_record;
+pub mod outputs_collector;
 mod runner;
-mod target_hasher;
+pub mod target_hasher;
 
 pub use errors::*;
 pub use runner::*;
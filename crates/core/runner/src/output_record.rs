@@ -0,0 +1,141 @@
+use miette::IntoDiagnostic;
+use moon_action::{ActionStatus, Attempt};
+use moon_cache::RunTargetState;
+use moon_target::Target;
+use serde::Serialize;
+use starbase_utils::fs;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A machine-readable record of a task run, appended as a single line of JSON
+/// to the records file (see [`get_output_records_path`]) when the task's
+/// `outputStyle` is `json`. A record is emitted once the task has finished
+/// (after all retry attempts), has failed to run (timed out or could not be
+/// spawned), or has been hydrated from the cache.
+///
+/// ```json
+/// {"target":"app:build","status":"passed","attempt":1,"duration":1520,"exitCode":0,"hash":"4f1c...","stdout":"/repo/.moon/cache/states/app/build/stdout.log","stderr":"/repo/.moon/cache/states/app/build/stderr.log"}
+/// {"target":"app:serve","status":"failed","attempt":1,"duration":30000,"exitCode":null,"hash":"9b2e...","stdout":"...","stderr":"...","error":"Task app:serve timed out after 30 seconds."}
+/// ```
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskOutputRecord {
+    /// Fully qualified target of the task, e.g. `app:build`.
+    pub target: String,
+
+    /// Status of the run, one of `passed`, `failed`, `cached`,
+    /// `cached-from-remote`, or `skipped` (for no-op tasks).
+    pub status: ActionStatus,
+
+    /// Number of the final attempt starting from 1, or 0 when the task did not run.
+    pub attempt: u8,
+
+    /// Duration of the attempt in milliseconds, or `null` when the task did not run.
+    pub duration: Option<u128>,
+
+    /// Exit code of the process, or `null` when the task did not run.
+    pub exit_code: Option<i32>,
+
+    /// Hash of the task's inputs, or `null` when caching is disabled.
+    pub hash: Option<String>,
+
+    /// Absolute path to the log file containing the captured stdout
+    /// of the final attempt.
+    pub stdout: PathBuf,
+
+    /// Absolute path to the log file containing the captured stderr
+    /// of the final attempt.
+    pub stderr: PathBuf,
+
+    /// Message of the error that stopped the process from completing,
+    /// like a timeout or failing to spawn. Omitted otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TaskOutputRecord {
+    pub fn new(target: &Target, state: &RunTargetState) -> Self {
+        let (stdout, stderr) = state.get_output_logs();
+
+        TaskOutputRecord {
+            target: target.id.clone(),
+            status: ActionStatus::Skipped,
+            attempt: 0,
+            duration: None,
+            exit_code: None,
+            hash: if state.hash.is_empty() {
+                None
+            } else {
+                Some(state.hash.clone())
+            },
+            stdout,
+            stderr,
+            error: None,
+        }
+    }
+
+    pub fn with_attempt(mut self, attempt: &Attempt) -> Self {
+        self.status = attempt.status;
+        self.attempt = attempt.index;
+        self.duration = attempt.duration.map(|duration| duration.as_millis());
+        self.exit_code = attempt.exit_code;
+        self
+    }
+
+    pub fn with_status(mut self, status: ActionStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_error(mut self, error: &miette::Report) -> Self {
+        self.status = ActionStatus::Failed;
+        self.error = Some(error.to_string());
+        self
+    }
+
+    /// Serialize the record into a single line of JSON.
+    pub fn to_json_line(&self) -> miette::Result<String> {
+        serde_json::to_string(self).into_diagnostic()
+    }
+}
+
+/// Return the path of the file that records are appended to. Defaults to
+/// `.moon/cache/outputRecords.jsonl`, but can be customized with the
+/// `MOON_OUTPUT_RECORDS_FILE` environment variable (`/dev/fd/3` for example).
+pub fn get_output_records_path(cache_dir: &Path) -> PathBuf {
+    match env::var("MOON_OUTPUT_RECORDS_FILE") {
+        Ok(path) if !path.is_empty() => PathBuf::from(path),
+        _ => cache_dir.join("outputRecords.jsonl"),
+    }
+}
+
+/// Remove the records from a previous run, when using the default records file.
+/// A custom file is owned by the consumer, so is left untouched.
+pub fn reset_output_records(cache_dir: &Path) -> miette::Result<()> {
+    if env::var("MOON_OUTPUT_RECORDS_FILE").is_err() {
+        let path = get_output_records_path(cache_dir);
+
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Append the record to the records file as a single line of JSON. The line is
+/// written in a single call, so that records from parallel tasks don't interleave.
+pub fn append_output_record(path: &Path, record: &TaskOutputRecord) -> miette::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .into_diagnostic()?;
+
+    file.write_all(format!("{}\n", record.to_json_line()?).as_bytes())
+        .into_diagnostic()?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::errors::RunnerError;
+use moon_logger::warn;
+use moon_task::Task;
+use starbase_styles::color;
+use starbase_utils::glob;
+use std::path::{Path, PathBuf};
+
+// Verify that a task produced its declared outputs after running. Literal
+// file paths are required to exist, as a missing path means the task failed
+// to produce it, while globs that match nothing are only warned about.
+// Returns all files that were matched by the output globs.
+pub fn verify_outputs(task: &Task, workspace_root: &Path) -> miette::Result<Vec<PathBuf>> {
+    let mut paths = task.output_paths.iter().collect::<Vec<_>>();
+    paths.sort();
+
+    for output in paths {
+        if !output.to_path(workspace_root).exists() {
+            return Err(
+                RunnerError::MissingOutputPath(task.target.id.clone(), output.to_string()).into(),
+            );
+        }
+    }
+
+    if task.output_globs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let files = glob::walk_files(workspace_root, &task.output_globs)?;
+
+    if files.is_empty() {
+        warn!(
+            target: task.target.as_str(),
+            "Output globs for target {} did not match any files, nothing will be cached from them",
+            color::label(&task.target),
+        );
+    }
+
+    Ok(files)
+}
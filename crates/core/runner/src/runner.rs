@@ -1,10 +1,17 @@
+use crate::args_interpolator::interpolate_args;
+use crate::errors::RunnerError;
+use crate::file_deps::wait_for_file_deps;
+use crate::inputs_collector;
+use crate::output_record::{append_output_record, get_output_records_path, TaskOutputRecord};
+use crate::outputs_collector::verify_outputs;
 use crate::target_hasher::TargetHasher;
-use crate::{errors::RunnerError, inputs_collector};
 use console::Term;
 use miette::IntoDiagnostic;
 use moon_action::{ActionStatus, Attempt};
 use moon_action_context::{ActionContext, TargetState};
+use moon_archive::TreeDiffer;
 use moon_cache::RunTargetState;
+use moon_common::path::WorkspaceRelativePathBuf;
 use moon_config::{TaskOptionAffectedFiles, TaskOutputStyle};
 use moon_emitter::{Emitter, Event, EventFlow};
 use moon_hasher::HashSet;
@@ -17,12 +24,13 @@ use moon_task::Task;
 use moon_terminal::{label_checkpoint, Checkpoint};
 use moon_utils::{is_ci, is_test_env, path, time};
 use moon_workspace::Workspace;
+use regex::Regex;
 use rustc_hash::FxHashMap;
 use starbase_styles::color;
-use starbase_utils::glob;
+use starbase_utils::{fs, glob};
 use tokio::{
     task,
-    time::{sleep, Duration},
+    time::{sleep, timeout, Duration},
 };
 
 const LOG_TARGET: &str = "moon:runner";
@@ -70,18 +78,73 @@ impl<'a> Runner<'a> {
     /// Cache outputs to the `.moon/cache/outputs` folder and to the cloud,
     /// so that subsequent builds are faster, and any local outputs
     /// can be hydrated easily.
-    pub async fn archive_outputs(&self) -> miette::Result<()> {
-        let hash = &self.cache.hash;
-
-        if hash.is_empty() || !self.is_archivable()? {
+    pub async fn archive_outputs(&mut self) -> miette::Result<()> {
+        if self.cache.hash.is_empty() || !self.is_archivable()? {
             return Ok(());
         }
 
-        // Check that outputs actually exist
-        if !self.task.outputs.is_empty() && !self.has_outputs()? {
-            return Err(RunnerError::MissingOutput(self.task.target.id.clone()).into());
+        // Check that literal outputs actually exist, while globs may match nothing
+        if !self.task.outputs.is_empty() {
+            verify_outputs(self.task, &self.workspace.root)?;
         }
 
+        // Avoid archiving (and uploading) outputs that are identical to the
+        // previous run, as the run state has already been updated with the new hash
+        let output_paths = self
+            .task
+            .outputs
+            .iter()
+            .filter_map(|o| o.to_workspace_relative(&self.project.source))
+            .collect::<Vec<_>>();
+        let has_outputs = !output_paths.is_empty() || !self.cache.undeclared_outputs.is_empty();
+        let output_hash = if has_outputs {
+            self.cache
+                .hash_outputs(&self.workspace.root, &output_paths)?
+        } else {
+            String::new()
+        };
+
+        if !output_hash.is_empty() && self.cache.output_hash == output_hash {
+            let prev_archive = self
+                .workspace
+                .cache
+                .get_hash_archive_path(&self.cache.output_archive_hash);
+            let next_archive = self.workspace.cache.get_hash_archive_path(&self.cache.hash);
+
+            if prev_archive.exists() {
+                debug!(
+                    target: LOG_TARGET,
+                    "Outputs for target {} are identical to the previous run, reusing archive",
+                    color::label(&self.task.target),
+                );
+
+                // Link the previous archive to the new hash, so that it's also a cache hit
+                if !next_archive.exists()
+                    && std::fs::hard_link(&prev_archive, &next_archive).is_err()
+                {
+                    fs::copy_file(&prev_archive, &next_archive)?;
+                }
+
+                // And the content manifest, since the stored blobs are also identical
+                let prev_manifest = self
+                    .workspace
+                    .cache
+                    .get_hash_content_manifest_path(&self.cache.output_archive_hash);
+                let next_manifest = self
+                    .workspace
+                    .cache
+                    .get_hash_content_manifest_path(&self.cache.hash);
+
+                if prev_manifest.exists() && !next_manifest.exists() {
+                    fs::copy_file(&prev_manifest, &next_manifest)?;
+                }
+
+                return Ok(());
+            }
+        }
+
+        let hash = &self.cache.hash;
+
         // If so, then cache the archive
         if let EventFlow::Return(archive_path) = self
             .emitter
@@ -103,6 +166,10 @@ impl<'a> Runner<'a> {
                     task: self.task,
                 })
                 .await?;
+
+            self.cache.output_archive_hash = self.cache.hash.clone();
+            self.cache.output_hash = output_hash;
+            self.cache.save()?;
         }
 
         Ok(())
@@ -125,15 +192,20 @@ impl<'a> Runner<'a> {
             comments.push(self.get_short_hash());
         }
 
+        let status = if matches!(from, HydrateFrom::RemoteCache) {
+            ActionStatus::CachedFromRemote
+        } else {
+            ActionStatus::Cached
+        };
+
         self.print_checkpoint(Checkpoint::RunPassed, &comments)?;
         self.print_cache_item()?;
+        self.print_output_record(
+            TaskOutputRecord::new(&self.task.target, &self.cache).with_status(status),
+        )?;
         self.flush_output()?;
 
-        Ok(if matches!(from, HydrateFrom::RemoteCache) {
-            ActionStatus::CachedFromRemote
-        } else {
-            ActionStatus::Cached
-        })
+        Ok(status)
     }
 
     /// If we are cached (hash match), hydrate the project with the
@@ -201,7 +273,10 @@ impl<'a> Runner<'a> {
                 task,
                 &project.root,
                 &workspace.root,
-                &workspace.config.hasher,
+                &workspace
+                    .config
+                    .hasher
+                    .with_project_overrides(&project.config.hasher),
             )
             .await?,
         );
@@ -232,21 +307,37 @@ impl<'a> Runner<'a> {
             color::path(working_dir)
         );
 
+        // Interpolate `${VAR}` references in args before the platform consumes them
+        let interpolated = interpolate_args(&task.args, &task.env);
+        let mut task = self.task.clone();
+        task.args = interpolated.args;
+
         let mut command = self
             .workspace
             .platforms
-            .get(task.platform)?
-            .create_run_target_command(context, project, task, runtime, working_dir)
+            .get(task.get_runtime_platform())?
+            .create_run_target_command(context, project, &task, runtime, working_dir)
             .await?;
 
+        for secret in interpolated.secrets {
+            command.mask_secret(secret);
+        }
+
         command
             .cwd(working_dir)
             .envs(self.create_env_vars().await?)
             // We need to handle non-zero's manually
             .set_error_on_nonzero(false);
 
+        // Isolate from the parent environment
+        if !task.options.inherit_env {
+            command
+                .set_inherit_env(false)
+                .env_passthrough(&task.options.env_passthrough);
+        }
+
         // Wrap in a shell
-        if task.platform.is_system() && task.options.shell {
+        if task.get_runtime_platform().is_system() && task.options.shell {
             command.with_shell();
         }
 
@@ -348,6 +439,47 @@ impl<'a> Runner<'a> {
         Ok(env_vars)
     }
 
+    /// Diff the project against a snapshot taken before running, and return all
+    /// created or modified files that do not exist within the declared outputs.
+    pub fn collect_undeclared_outputs(
+        &self,
+        snapshot: &TreeDiffer,
+    ) -> miette::Result<Vec<WorkspaceRelativePathBuf>> {
+        let mut outputs = vec![];
+
+        for file in self.snapshot_project()?.diff(snapshot) {
+            if file.starts_with(&self.workspace.cache.dir) {
+                continue;
+            }
+
+            let Ok(rel_file) = file.strip_prefix(&self.workspace.root) else {
+                continue;
+            };
+
+            let rel_file = WorkspaceRelativePathBuf::from(path::to_virtual_string(rel_file)?);
+
+            if self
+                .task
+                .output_paths
+                .iter()
+                .any(|output| rel_file == *output || rel_file.starts_with(output))
+            {
+                continue;
+            }
+
+            debug!(
+                target: LOG_TARGET,
+                "Target {} created undeclared output {}, caching it",
+                color::label(&self.task.target),
+                color::file(&rel_file),
+            );
+
+            outputs.push(rel_file);
+        }
+
+        Ok(outputs)
+    }
+
     pub fn flush_output(&self) -> miette::Result<()> {
         self.stdout.flush().into_diagnostic()?;
         self.stderr.flush().into_diagnostic()?;
@@ -426,12 +558,16 @@ impl<'a> Runner<'a> {
 
         self.workspace
             .platforms
-            .get(self.task.platform)?
+            .get(self.task.get_runtime_platform())?
             .hash_run_target(
                 self.project,
                 runtime,
                 &mut hashset,
-                &self.workspace.config.hasher,
+                &self
+                    .workspace
+                    .config
+                    .hasher
+                    .with_project_overrides(&self.project.config.hasher),
             )
             .await?;
 
@@ -450,14 +586,19 @@ impl<'a> Runner<'a> {
         );
 
         // Hash is the same as the previous build, so simply abort!
-        // However, ensure the outputs also exist, otherwise we should hydrate
-        if self.cache.hash == hash && self.has_outputs()? {
+        // However, ensure the previous build passed and the outputs
+        // also exist, otherwise we should hydrate or run again
+        if self.cache.hash == hash && self.cache.exit_code == 0 && self.has_outputs()? {
             debug!(
                 target: LOG_TARGET,
                 "Cache hit for hash {}, reusing previous build",
                 color::hash(&hash),
             );
 
+            // Hydration won't save the state for this case, so persist the hit now
+            self.cache.track_cache_hit();
+            self.cache.save()?;
+
             return Ok(Some(HydrateFrom::PreviousOutput));
         }
 
@@ -483,6 +624,8 @@ impl<'a> Runner<'a> {
                         color::hash(&hash),
                     );
 
+                    self.cache.track_cache_hit();
+
                     return Ok(Some(HydrateFrom::LocalCache));
                 }
                 "remote-cache" => {
@@ -492,6 +635,8 @@ impl<'a> Runner<'a> {
                         color::hash(&hash),
                     );
 
+                    self.cache.track_cache_hit();
+
                     return Ok(Some(HydrateFrom::RemoteCache));
                 }
                 _ => {}
@@ -504,6 +649,9 @@ impl<'a> Runner<'a> {
             color::hash(&hash),
         );
 
+        // The state is saved after the run completes
+        self.cache.track_cache_miss();
+
         Ok(None)
     }
 
@@ -521,23 +669,66 @@ impl<'a> Runner<'a> {
         let is_primary = context.primary_targets.contains(&self.task.target);
         let is_real_ci = is_ci() && !is_test_env();
         let is_persistent = self.task.options.persistent;
+        let is_json_output = self.is_json_output();
         let output;
 
-        // When a task is configured as local (no caching), or the interactive flag is passed,
-        // we don't "capture" stdout/stderr (which breaks stdin) and let it stream natively.
-        let is_interactive =
-            (!self.task.options.cache && context.primary_targets.len() == 1) || context.interactive;
+        // Wait for files produced by external processes before running
+        if !self.task.file_deps.is_empty() {
+            wait_for_file_deps(self.task, &self.project.root).await?;
+        }
+
+        // Snapshot the project before running, so that we can determine
+        // which files were created or modified by the task afterwards.
+        let snapshot = if self.task.options.cache_undeclared_outputs {
+            Some(self.snapshot_project()?)
+        } else {
+            None
+        };
+
+        // When a task is configured as local (no caching) or interactive, or the interactive
+        // flag is passed, we don't "capture" stdout/stderr (which breaks stdin) and let it
+        // stream natively.
+        let is_interactive = (!self.task.options.cache && context.primary_targets.len() == 1)
+            || self.task.options.interactive
+            || context.interactive;
 
         // When the primary target, always stream the output for a better developer experience.
         // However, transitive targets can opt into streaming as well.
-        let should_stream_output = if let Some(output_style) = &self.task.options.output_style {
-            matches!(output_style, TaskOutputStyle::Stream)
+        let should_stream_output = if self.task.options.interactive {
+            true
+        } else if let Some(output_style) = &self.task.options.output_style {
+            matches!(
+                output_style,
+                TaskOutputStyle::Stream | TaskOutputStyle::BufferUntilReady
+            )
         } else {
             is_primary || is_real_ci
         };
 
+        // Withhold output until the task is ready, otherwise this falls back to streaming
+        let ready_pattern = match (
+            &self.task.options.output_style,
+            &self.task.options.ready_pattern,
+        ) {
+            (Some(TaskOutputStyle::BufferUntilReady), Some(pattern)) => {
+                Some(Regex::new(pattern).into_diagnostic()?)
+            }
+            _ => None,
+        };
+
+        // Kill the entire process tree (not just the shell) when timing out. Commands that
+        // inherit the terminal must remain in its foreground process group, otherwise they
+        // would be stopped when reading from or writing to it, so only the child is killed.
+        if self.task.options.timeout.is_some()
+            && !(should_stream_output && ready_pattern.is_none() && is_interactive)
+        {
+            command.set_kill_process_group(true);
+        }
+
         // Transitive targets may run concurrently, so differentiate them with a prefix.
-        let stream_prefix = if is_real_ci || !is_primary || context.primary_targets.len() > 1 {
+        let stream_prefix = if self.task.options.interactive {
+            None
+        } else if is_real_ci || !is_primary || context.primary_targets.len() > 1 {
             Some(&self.task.target.id)
         } else {
             None
@@ -546,7 +737,7 @@ impl<'a> Runner<'a> {
         // For long-running process, log a message every 30 seconds to indicate it's still running
         let interval_target = self.task.target.clone();
         let interval_handle = task::spawn(async move {
-            if is_persistent {
+            if is_persistent || is_json_output {
                 return;
             }
 
@@ -571,21 +762,40 @@ impl<'a> Runner<'a> {
             self.print_target_command(context, command)?;
             self.flush_output()?;
 
-            let possible_output = if should_stream_output {
+            if should_stream_output {
                 if let Some(prefix) = stream_prefix {
                     command.set_prefix(prefix, primary_longest_width);
                 }
+            }
 
-                if is_interactive {
-                    command.create_async().exec_stream_output().await
+            let run_command = async {
+                if should_stream_output {
+                    if let Some(ready_pattern) = &ready_pattern {
+                        command
+                            .create_async()
+                            .exec_stream_output_when_ready(ready_pattern.clone())
+                            .await
+                    } else if is_interactive {
+                        command.create_async().exec_stream_output().await
+                    } else {
+                        command
+                            .create_async()
+                            .exec_stream_and_capture_output()
+                            .await
+                    }
                 } else {
-                    command
-                        .create_async()
-                        .exec_stream_and_capture_output()
-                        .await
+                    command.create_async().exec_capture_output().await
                 }
-            } else {
-                command.create_async().exec_capture_output().await
+            };
+
+            // The child process (and its group) is killed when dropped, so abandoning it is enough
+            let possible_output = match self.task.options.timeout {
+                Some(secs) => timeout(Duration::from_secs(secs as u64), run_command)
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(RunnerError::TaskTimeout(self.task.target.id.clone(), secs).into())
+                    }),
+                None => run_command.await,
             };
 
             match possible_output {
@@ -622,10 +832,19 @@ impl<'a> Runner<'a> {
                 // process itself failed
                 Err(error) => {
                     attempt.done(ActionStatus::Failed);
-                    attempts.push(attempt);
 
                     interval_handle.abort();
 
+                    // Timeouts and spawn failures must also produce a record,
+                    // otherwise consumers would never see this task finish
+                    self.print_output_record(
+                        TaskOutputRecord::new(&self.task.target, &self.cache)
+                            .with_attempt(&attempt)
+                            .with_error(&error),
+                    )?;
+
+                    attempts.push(attempt);
+
                     return Err(error);
                 }
             }
@@ -633,6 +852,11 @@ impl<'a> Runner<'a> {
 
         interval_handle.abort();
 
+        self.cache.undeclared_outputs = match snapshot {
+            Some(snapshot) => self.collect_undeclared_outputs(&snapshot)?,
+            None => vec![],
+        };
+
         // Write the cache with the result and output
         self.cache.exit_code = output.status.code().unwrap_or(0);
         self.cache.save_output_logs(
@@ -640,6 +864,13 @@ impl<'a> Runner<'a> {
             output_to_string(&output.stderr),
         )?;
 
+        // Emit the record after the logs have been written, so that they can be read
+        if let Some(attempt) = attempts.last() {
+            self.print_output_record(
+                TaskOutputRecord::new(&self.task.target, &self.cache).with_attempt(attempt),
+            )?;
+        }
+
         Ok(attempts)
     }
 
@@ -656,6 +887,7 @@ impl<'a> Runner<'a> {
             );
 
             self.print_target_label(Checkpoint::RunPassed, &Attempt::new(0), 0)?;
+            self.print_output_record(TaskOutputRecord::new(&self.task.target, &self.cache))?;
             self.flush_output()?;
 
             vec![]
@@ -671,6 +903,10 @@ impl<'a> Runner<'a> {
         Ok(attempts)
     }
 
+    pub fn snapshot_project(&self) -> miette::Result<TreeDiffer> {
+        TreeDiffer::load(&self.workspace.root, &[self.project.source.to_string()])
+    }
+
     pub fn print_cache_item(&self) -> miette::Result<()> {
         let item = &self.cache;
         let (stdout, stderr) = item.load_output_logs()?;
@@ -685,6 +921,11 @@ impl<'a> Runner<'a> {
         checkpoint: Checkpoint,
         comments: &[T],
     ) -> miette::Result<()> {
+        // Labels are replaced by the record when emitting JSON
+        if self.is_json_output() {
+            return Ok(());
+        }
+
         let label = label_checkpoint(&self.task.target, checkpoint);
 
         if comments.is_empty() {
@@ -748,6 +989,8 @@ impl<'a> Runner<'a> {
                     self.stderr.write_line(hash).into_diagnostic()?;
                 }
             }
+            // Show nothing, as the record references the output logs
+            Some(TaskOutputStyle::Json) => {}
             // Show nothing
             Some(TaskOutputStyle::None) => {}
             // Show output on both success and failure
@@ -760,26 +1003,38 @@ impl<'a> Runner<'a> {
         Ok(())
     }
 
+    /// Append a machine-readable record of the task run to the records file, instead
+    /// of the console, but only when the task is configured with the `json` output style.
+    pub fn print_output_record(&self, record: TaskOutputRecord) -> miette::Result<()> {
+        if !self.is_json_output() {
+            return Ok(());
+        }
+
+        append_output_record(&get_output_records_path(&self.workspace.cache.dir), &record)
+    }
+
     pub fn print_target_command(
         &self,
         context: &ActionContext,
         command: &Command,
     ) -> miette::Result<()> {
-        if !self.workspace.config.runner.log_running_command {
+        if !self.workspace.config.runner.log_running_command || self.is_json_output() {
             return Ok(());
         }
 
         let task = &self.task;
+        let interpolated = interpolate_args(&task.args, &task.env);
         let mut args = vec![&task.command];
-        args.extend(&task.args);
+        args.extend(&interpolated.args);
 
         if context.should_inherit_args(&task.target) {
             args.extend(&context.passthrough_args);
         }
 
-        let command_line = args::join_args(args);
+        let inspector = command.inspect();
+        let command_line = inspector.mask_secrets(&args::join_args(args));
 
-        let message = color::muted_light(command.inspect().format_command(
+        let message = color::muted_light(inspector.format_command(
             &command_line,
             &self.workspace.root,
             Some(if task.options.run_from_workspace_root {
@@ -879,6 +1134,10 @@ impl<'a> Runner<'a> {
         Ok(())
     }
 
+    fn is_json_output(&self) -> bool {
+        matches!(self.task.options.output_style, Some(TaskOutputStyle::Json))
+    }
+
     fn should_print_short_hash(&self) -> bool {
         // Do not include the hash while testing, as the hash
         // constantly changes and breaks our local snapshots
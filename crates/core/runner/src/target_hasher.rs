@@ -10,9 +10,58 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::env;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HashContributorKind {
+    Arg,
+    CacheKeyExtra,
+    Command,
+    Dep,
+    EnvVar,
+    Input,
+    Output,
+    ProjectDep,
+    Target,
+    Version,
+}
+
+/// A single value that contributed to a target's hash, for debugging cache misses.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct HashContributor {
+    pub kind: HashContributorKind,
+
+    // File path, variable name, or target, when the value is keyed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+
+    pub value: String,
+}
+
+impl HashContributor {
+    fn new(kind: HashContributorKind, value: impl ToString) -> Self {
+        HashContributor {
+            kind,
+            key: None,
+            value: value.to_string(),
+        }
+    }
+
+    fn keyed(kind: HashContributorKind, key: impl ToString, value: impl ToString) -> Self {
+        HashContributor {
+            kind,
+            key: Some(key.to_string()),
+            value: value.to_string(),
+        }
+    }
+}
+
 #[derive(Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TargetHasher {
+    // Task `options.cacheKeyExtra`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_key_extra: Option<String>,
+
     // Task `command`
     command: String,
 
@@ -79,6 +128,12 @@ impl TargetHasher {
 
     /// Hash `args`, `inputs`, `deps`, and `env` vars from a task.
     pub fn hash_task(&mut self, task: &Task) {
+        self.cache_key_extra = task
+            .options
+            .cache_key_extra
+            .as_ref()
+            .filter(|extra| !extra.is_empty())
+            .cloned();
         self.command = task.command.clone();
         self.args = task.args.clone();
         self.env_vars.extend(task.env.clone());
@@ -126,6 +181,50 @@ impl TargetHasher {
 
         Ok(())
     }
+
+    /// Return every value that contributes to the hash, in the order they are hashed.
+    pub fn get_contributors(&self) -> Vec<HashContributor> {
+        use HashContributorKind as Kind;
+
+        let mut list = vec![
+            HashContributor::new(Kind::Version, &self.version),
+            HashContributor::new(Kind::Command, &self.command),
+            HashContributor::new(Kind::Target, &self.target),
+        ];
+
+        list.extend(self.args.iter().map(|v| HashContributor::new(Kind::Arg, v)));
+        list.extend(
+            self.deps
+                .iter()
+                .map(|(k, v)| HashContributor::keyed(Kind::Dep, k, v)),
+        );
+        list.extend(
+            self.env_vars
+                .iter()
+                .map(|(k, v)| HashContributor::keyed(Kind::EnvVar, k, v)),
+        );
+        list.extend(
+            self.inputs
+                .iter()
+                .map(|(k, v)| HashContributor::keyed(Kind::Input, k, v)),
+        );
+        list.extend(
+            self.outputs
+                .iter()
+                .map(|v| HashContributor::new(Kind::Output, v)),
+        );
+        list.extend(
+            self.project_deps
+                .iter()
+                .map(|v| HashContributor::new(Kind::ProjectDep, v)),
+        );
+
+        if let Some(extra) = &self.cache_key_extra {
+            list.push(HashContributor::new(Kind::CacheKeyExtra, extra));
+        }
+
+        list
+    }
 }
 
 impl Hasher for TargetHasher {
@@ -140,6 +239,10 @@ impl Hasher for TargetHasher {
         hash_btree(&self.inputs, sha);
         hash_vec(&self.outputs, sha);
         hash_vec(&self.project_deps, sha);
+
+        if let Some(extra) = &self.cache_key_extra {
+            sha.update(extra.as_bytes());
+        }
     }
 
     fn serialize(&self) -> serde_json::Value {
@@ -0,0 +1,131 @@
+use moon_runner::args_interpolator::{interpolate_args, is_secret_var};
+use moon_utils::string_vec;
+use rustc_hash::FxHashMap;
+use std::env;
+
+fn create_env() -> FxHashMap<String, String> {
+    FxHashMap::from_iter([
+        ("MODE".to_owned(), "production".to_owned()),
+        ("API_TOKEN".to_owned(), "abc123".to_owned()),
+    ])
+}
+
+mod interpolate_args {
+    use super::*;
+
+    #[test]
+    fn returns_args_as_is() {
+        let result = interpolate_args(&string_vec!["--foo", "bar"], &create_env());
+
+        assert_eq!(result.args, string_vec!["--foo", "bar"]);
+        assert!(result.secrets.is_empty());
+    }
+
+    #[test]
+    fn substitutes_from_task_env() {
+        let result = interpolate_args(
+            &string_vec!["--mode", "${MODE}", "--url=http://${MODE}.com"],
+            &create_env(),
+        );
+
+        assert_eq!(
+            result.args,
+            string_vec!["--mode", "production", "--url=http://production.com"]
+        );
+    }
+
+    #[test]
+    fn substitutes_from_process_env() {
+        env::set_var("MOON_TEST_INTERPOLATE_ARG", "process");
+
+        let result = interpolate_args(&string_vec!["${MOON_TEST_INTERPOLATE_ARG}"], &create_env());
+
+        env::remove_var("MOON_TEST_INTERPOLATE_ARG");
+
+        assert_eq!(result.args, string_vec!["process"]);
+    }
+
+    #[test]
+    fn prefers_task_env_over_process_env() {
+        env::set_var("MODE", "development");
+
+        let result = interpolate_args(&string_vec!["${MODE}"], &create_env());
+
+        env::remove_var("MODE");
+
+        assert_eq!(result.args, string_vec!["production"]);
+    }
+
+    #[test]
+    fn passes_through_unbraced_vars() {
+        let result = interpolate_args(
+            &string_vec!["$FOO", "--mode=$MODE", "$HOME/dist", "'$1'", "$$"],
+            &create_env(),
+        );
+
+        assert_eq!(
+            result.args,
+            string_vec!["$FOO", "--mode=$MODE", "$HOME/dist", "'$1'", "$$"]
+        );
+    }
+
+    #[test]
+    fn supports_escaped_braces() {
+        let result = interpolate_args(&string_vec!["$${MODE}", "cost=$$5"], &create_env());
+
+        assert_eq!(result.args, string_vec!["${MODE}", "cost=$$5"]);
+    }
+
+    #[test]
+    fn passes_through_invalid_names() {
+        let result = interpolate_args(&string_vec!["${}", "${1}", "${MODE"], &create_env());
+
+        assert_eq!(result.args, string_vec!["${}", "${1}", "${MODE"]);
+    }
+
+    #[test]
+    fn doesnt_loop_on_self_references() {
+        let env = FxHashMap::from_iter([
+            ("FOO".to_owned(), "${FOO}".to_owned()),
+            ("A".to_owned(), "${B}".to_owned()),
+            ("B".to_owned(), "${A}".to_owned()),
+        ]);
+
+        let result = interpolate_args(&string_vec!["${FOO}", "${A}"], &env);
+
+        assert_eq!(result.args, string_vec!["${FOO}", "${B}"]);
+    }
+
+    #[test]
+    fn replaces_undefined_with_empty() {
+        let result = interpolate_args(&string_vec!["--flag=${MOON_UNKNOWN_VAR}"], &create_env());
+
+        assert_eq!(result.args, string_vec!["--flag="]);
+    }
+
+    #[test]
+    fn tracks_secret_values() {
+        let result = interpolate_args(
+            &string_vec!["--token", "${API_TOKEN}", "--mode", "${MODE}"],
+            &create_env(),
+        );
+
+        assert_eq!(
+            result.args,
+            string_vec!["--token", "abc123", "--mode", "production"]
+        );
+        assert_eq!(result.secrets, string_vec!["abc123"]);
+    }
+}
+
+mod is_secret_var {
+    use super::*;
+
+    #[test]
+    fn detects_secrets() {
+        assert!(is_secret_var("API_TOKEN"));
+        assert!(is_secret_var("AWS_SECRET_ACCESS_KEY"));
+        assert!(is_secret_var("DB_PASSWORD"));
+        assert!(!is_secret_var("NODE_ENV"));
+    }
+}
@@ -0,0 +1,58 @@
+use moon_config::{FilePath, TaskFileDependency};
+use moon_runner::file_deps::wait_for_file_deps;
+use moon_target::Target;
+use moon_task::Task;
+use moon_test_utils::assert_fs::TempDir;
+use std::fs;
+use std::time::Duration;
+
+fn create_task(timeout: u64) -> Task {
+    Task {
+        file_deps: vec![TaskFileDependency {
+            file: FilePath("server.ready".into()),
+            timeout,
+        }],
+        target: Target::new("project", "test").unwrap(),
+        ..Task::default()
+    }
+}
+
+#[tokio::test]
+async fn proceeds_when_file_already_exists() {
+    let fixture = TempDir::new().unwrap();
+    fs::write(fixture.path().join("server.ready"), "").unwrap();
+
+    assert!(wait_for_file_deps(&create_task(0), fixture.path())
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn proceeds_when_file_appears_before_timeout() {
+    let fixture = TempDir::new().unwrap();
+    let file = fixture.path().join("server.ready");
+
+    let handle = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        fs::write(file, "").unwrap();
+    });
+
+    assert!(wait_for_file_deps(&create_task(5), fixture.path())
+        .await
+        .is_ok());
+
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn fails_when_file_doesnt_appear() {
+    let fixture = TempDir::new().unwrap();
+
+    let error = wait_for_file_deps(&create_task(1), fixture.path())
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains(
+        "depends on the file server.ready, but it did not exist after waiting 1 second."
+    ));
+}
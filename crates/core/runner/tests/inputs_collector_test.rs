@@ -365,3 +365,207 @@ async fn ignores_from_hasher_patterns() {
         ]
     );
 }
+
+#[tokio::test]
+async fn ignored_file_changes_dont_alter_hash() {
+    env::set_var("MOON_DISABLE_OVERLAPPING_OUTPUTS", "true");
+
+    let sandbox = cases_sandbox();
+    sandbox.enable_git();
+
+    let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+    let project_graph = generate_project_graph(&mut workspace).await.unwrap();
+    let vcs = load_vcs(&workspace.root, &workspace.config);
+
+    env::remove_var("MOON_DISABLE_OVERLAPPING_OUTPUTS");
+
+    workspace.config.hasher.ignore_patterns = vec![
+        GlobPath("**/.DS_Store".into()),
+        GlobPath("outputs-filtering/*.timestamp".into()),
+    ];
+
+    let project = project_graph.get("outputsFiltering").unwrap();
+
+    fs::write(project.root.join("index.js"), "content").unwrap();
+
+    for walk_strategy in [HasherWalkStrategy::Vcs, HasherWalkStrategy::Glob] {
+        workspace.config.hasher.walk_strategy = walk_strategy;
+
+        fs::write(project.root.join(".DS_Store"), "1").unwrap();
+        fs::write(project.root.join("build.timestamp"), "1").unwrap();
+
+        let before = collect_and_hash_inputs(
+            &vcs,
+            project.get_task("inGlobOutFile").unwrap(),
+            &project.root,
+            &workspace.root,
+            &workspace.config.hasher,
+        )
+        .await
+        .unwrap();
+
+        fs::write(project.root.join(".DS_Store"), "2").unwrap();
+        fs::write(project.root.join("build.timestamp"), "2").unwrap();
+
+        let after = collect_and_hash_inputs(
+            &vcs,
+            project.get_task("inGlobOutFile").unwrap(),
+            &project.root,
+            &workspace.root,
+            &workspace.config.hasher,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(before, after);
+        assert!(after
+            .keys()
+            .any(|file| file.as_str() == "outputs-filtering/index.js"));
+        assert!(!after.keys().any(|file| {
+            file.as_str().ends_with(".DS_Store") || file.as_str().ends_with(".timestamp")
+        }));
+    }
+}
+
+#[tokio::test]
+async fn ignores_from_moonignore_across_projects() {
+    env::set_var("MOON_DISABLE_OVERLAPPING_OUTPUTS", "true");
+
+    let sandbox = cases_sandbox();
+    sandbox.create_file(".moonignore", "# Logs\n**/*.log\n");
+    sandbox.create_file("outputs-filtering/index.js", "");
+    sandbox.create_file("outputs-filtering/debug.log", "");
+    sandbox.create_file("affected/primary.js", "");
+    sandbox.create_file("affected/primary.log", "");
+    sandbox.enable_git();
+
+    let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+    let project_graph = generate_project_graph(&mut workspace).await.unwrap();
+    let vcs = load_vcs(&workspace.root, &workspace.config);
+
+    env::remove_var("MOON_DISABLE_OVERLAPPING_OUTPUTS");
+
+    for (project_id, task_id, kept) in [
+        (
+            "outputsFiltering",
+            "inGlobOutFile",
+            "outputs-filtering/index.js",
+        ),
+        ("affected", "primary", "affected/primary.js"),
+    ] {
+        let project = project_graph.get(project_id).unwrap();
+
+        let files = collect_and_hash_inputs(
+            &vcs,
+            project.get_task(task_id).unwrap(),
+            &project.root,
+            &workspace.root,
+            &workspace.config.hasher,
+        )
+        .await
+        .unwrap();
+
+        assert!(files.keys().any(|file| file.as_str() == kept));
+        assert!(!files.keys().any(|file| file.as_str().ends_with(".log")));
+    }
+}
+
+#[cfg(unix)]
+mod symlinks {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    async fn collect_with_symlinks(follow_symlinks: bool) -> Vec<(String, String)> {
+        env::set_var("MOON_DISABLE_OVERLAPPING_OUTPUTS", "true");
+
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+        let project_graph = generate_project_graph(&mut workspace).await.unwrap();
+        let vcs = load_vcs(&workspace.root, &workspace.config);
+
+        workspace.config.hasher.follow_symlinks = follow_symlinks;
+
+        env::remove_var("MOON_DISABLE_OVERLAPPING_OUTPUTS");
+
+        let project = project_graph.get("outputsFiltering").unwrap();
+
+        fs::write(project.root.join("target.txt"), "content").unwrap();
+        symlink("target.txt", project.root.join("link.txt")).unwrap();
+
+        let files = collect_and_hash_inputs(
+            &vcs,
+            project.get_task("inGlobOutFile").unwrap(),
+            &project.root,
+            &workspace.root,
+            &workspace.config.hasher,
+        )
+        .await
+        .unwrap();
+
+        files
+            .into_iter()
+            .filter(|(file, _)| file.as_str().starts_with("outputs-filtering/"))
+            .map(|(file, hash)| (file.as_str().to_owned(), hash))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn hashes_target_content_when_following() {
+        let files = collect_with_symlinks(true).await;
+
+        assert_eq!(files[0].0, "outputs-filtering/link.txt");
+        assert_eq!(files[1].0, "outputs-filtering/target.txt");
+        assert_eq!(files[0].1, files[1].1);
+    }
+
+    #[tokio::test]
+    async fn hashes_link_path_when_not_following() {
+        let files = collect_with_symlinks(false).await;
+
+        assert_eq!(files[0].0, "outputs-filtering/link.txt");
+        assert_eq!(files[1].0, "outputs-filtering/target.txt");
+        assert_ne!(files[0].1, files[1].1);
+        assert_eq!(
+            files[0].1,
+            "199b3badd968634ea14e351d1134ada738894a90a2efa66983101ece99a33572"
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_circular_links() {
+        env::set_var("MOON_DISABLE_OVERLAPPING_OUTPUTS", "true");
+
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        let mut workspace = load_workspace_from(sandbox.path()).await.unwrap();
+        let project_graph = generate_project_graph(&mut workspace).await.unwrap();
+        let vcs = load_vcs(&workspace.root, &workspace.config);
+
+        env::remove_var("MOON_DISABLE_OVERLAPPING_OUTPUTS");
+
+        let project = project_graph.get("outputsFiltering").unwrap();
+
+        symlink("loop-b", project.root.join("loop-a")).unwrap();
+        symlink("loop-a", project.root.join("loop-b")).unwrap();
+        symlink(".", project.root.join("self")).unwrap();
+
+        for walk_strategy in [HasherWalkStrategy::Vcs, HasherWalkStrategy::Glob] {
+            workspace.config.hasher.walk_strategy = walk_strategy;
+
+            let files = collect_and_hash_inputs(
+                &vcs,
+                project.get_task("inGlobOutFile").unwrap(),
+                &project.root,
+                &workspace.root,
+                &workspace.config.hasher,
+            )
+            .await
+            .unwrap();
+
+            assert!(!files.keys().any(|file| file.as_str().contains("loop-")));
+        }
+    }
+}
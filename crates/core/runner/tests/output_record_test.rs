@@ -0,0 +1,89 @@
+use moon_action::{ActionStatus, Attempt};
+use moon_cache::RunTargetState;
+use moon_runner::output_record::{append_output_record, get_output_records_path, TaskOutputRecord};
+use moon_target::Target;
+use moon_test_utils::create_temp_dir;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn create_state(hash: &str) -> RunTargetState {
+    RunTargetState {
+        hash: hash.into(),
+        path: PathBuf::from("/repo/.moon/cache/states/app/build/lastRun.json"),
+        target: "app:build".into(),
+        ..RunTargetState::default()
+    }
+}
+
+#[test]
+fn serializes_a_finished_attempt() {
+    let target = Target::new("app", "build").unwrap();
+    let mut attempt = Attempt::new(2);
+    attempt.status = ActionStatus::Failed;
+    attempt.duration = Some(Duration::from_millis(1520));
+    attempt.exit_code = Some(1);
+
+    let record = TaskOutputRecord::new(&target, &create_state("abc123")).with_attempt(&attempt);
+
+    assert_eq!(
+        record.to_json_line().unwrap(),
+        r#"{"target":"app:build","status":"failed","attempt":2,"duration":1520,"exitCode":1,"hash":"abc123","stdout":"/repo/.moon/cache/states/app/build/stdout.log","stderr":"/repo/.moon/cache/states/app/build/stderr.log"}"#
+    );
+}
+
+#[test]
+fn serializes_a_hydrated_task() {
+    let target = Target::new("app", "build").unwrap();
+
+    let record = TaskOutputRecord::new(&target, &create_state("abc123"))
+        .with_status(ActionStatus::CachedFromRemote);
+
+    assert_eq!(
+        record.to_json_line().unwrap(),
+        r#"{"target":"app:build","status":"cached-from-remote","attempt":0,"duration":null,"exitCode":null,"hash":"abc123","stdout":"/repo/.moon/cache/states/app/build/stdout.log","stderr":"/repo/.moon/cache/states/app/build/stderr.log"}"#
+    );
+}
+
+#[test]
+fn omits_hash_when_caching_is_disabled() {
+    let target = Target::new("app", "build").unwrap();
+
+    let record = TaskOutputRecord::new(&target, &create_state(""));
+
+    assert_eq!(record.hash, None);
+    assert!(matches!(record.status, ActionStatus::Skipped));
+}
+
+#[test]
+fn serializes_an_error() {
+    let target = Target::new("app", "build").unwrap();
+    let mut attempt = Attempt::new(1);
+    attempt.duration = Some(Duration::from_millis(30000));
+
+    let record = TaskOutputRecord::new(&target, &create_state("abc123"))
+        .with_attempt(&attempt)
+        .with_error(&miette::miette!(
+            "Task app:build timed out after 30 seconds."
+        ));
+
+    assert_eq!(
+        record.to_json_line().unwrap(),
+        r#"{"target":"app:build","status":"failed","attempt":1,"duration":30000,"exitCode":null,"hash":"abc123","stdout":"/repo/.moon/cache/states/app/build/stdout.log","stderr":"/repo/.moon/cache/states/app/build/stderr.log","error":"Task app:build timed out after 30 seconds."}"#
+    );
+}
+
+#[test]
+fn appends_records_to_file() {
+    let dir = create_temp_dir();
+    let path = get_output_records_path(dir.path());
+    let target = Target::new("app", "build").unwrap();
+
+    append_output_record(&path, &TaskOutputRecord::new(&target, &create_state("a"))).unwrap();
+    append_output_record(&path, &TaskOutputRecord::new(&target, &create_state("b"))).unwrap();
+
+    assert_eq!(path, dir.path().join("outputRecords.jsonl"));
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    dir.close().unwrap();
+}
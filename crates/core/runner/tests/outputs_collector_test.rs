@@ -0,0 +1,129 @@
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_runner::outputs_collector::verify_outputs;
+use moon_target::Target;
+use moon_task::Task;
+use moon_test_utils::assert_fs::prelude::*;
+use moon_test_utils::assert_fs::TempDir;
+use std::sync::{Mutex, Once};
+
+static LOGS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+struct CaptureLogger;
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            LOGS.lock()
+                .unwrap()
+                .push((record.target().to_owned(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn capture_warnings() {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        log::set_logger(&CaptureLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+    });
+}
+
+fn get_warnings(target: &str) -> Vec<String> {
+    LOGS.lock()
+        .unwrap()
+        .iter()
+        .filter(|(log_target, _)| log_target == target)
+        .map(|(_, message)| message.to_owned())
+        .collect()
+}
+
+fn create_task() -> Task {
+    Task {
+        target: Target::new("project", "build").unwrap(),
+        ..Task::default()
+    }
+}
+
+#[test]
+fn passes_when_literal_output_exists() {
+    let fixture = TempDir::new().unwrap();
+    fixture.child("project/out.js").write_str("").unwrap();
+
+    let mut task = create_task();
+    task.output_paths
+        .insert(WorkspaceRelativePathBuf::from("project/out.js"));
+
+    assert!(verify_outputs(&task, fixture.path()).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "defines the output project/out.js, but it does not exist")]
+fn errors_when_literal_output_is_missing() {
+    let fixture = TempDir::new().unwrap();
+
+    let mut task = create_task();
+    task.output_paths
+        .insert(WorkspaceRelativePathBuf::from("project/out.js"));
+
+    verify_outputs(&task, fixture.path()).unwrap();
+}
+
+#[test]
+fn warns_when_glob_matches_nothing() {
+    let fixture = TempDir::new().unwrap();
+    fixture.child("project/src/index.js").write_str("").unwrap();
+
+    let mut task = create_task();
+    task.output_globs
+        .insert(WorkspaceRelativePathBuf::from("project/dist/**/*"));
+
+    capture_warnings();
+
+    let files = verify_outputs(&task, fixture.path()).unwrap();
+
+    assert!(files.is_empty());
+    assert!(get_warnings("project:build")
+        .iter()
+        .any(|message| message.contains("did not match any files")));
+}
+
+#[test]
+fn returns_files_matched_by_globs() {
+    let fixture = TempDir::new().unwrap();
+    fixture.child("project/dist/a.js").write_str("").unwrap();
+    fixture.child("project/dist/b.js").write_str("").unwrap();
+
+    let mut task = create_task();
+    task.output_globs
+        .insert(WorkspaceRelativePathBuf::from("project/dist/**/*"));
+
+    let files = verify_outputs(&task, fixture.path()).unwrap();
+
+    assert_eq!(files.len(), 2);
+}
+
+#[test]
+fn doesnt_warn_when_glob_matches_files() {
+    let fixture = TempDir::new().unwrap();
+    fixture.child("project/dist/a.js").write_str("").unwrap();
+
+    let mut task = Task {
+        target: Target::new("project", "bundle").unwrap(),
+        ..Task::default()
+    };
+    task.output_globs
+        .insert(WorkspaceRelativePathBuf::from("project/dist/**/*"));
+
+    capture_warnings();
+
+    verify_outputs(&task, fixture.path()).unwrap();
+
+    assert!(get_warnings("project:bundle").is_empty());
+}
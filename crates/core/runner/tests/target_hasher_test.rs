@@ -0,0 +1,130 @@
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_hasher::{to_hash, Hasher};
+use moon_runner::target_hasher::{HashContributor, HashContributorKind, TargetHasher};
+use moon_task::Task;
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+
+fn hash_task(cache_key_extra: Option<&str>) -> String {
+    let mut task = Task {
+        command: "build".into(),
+        ..Task::default()
+    };
+    task.options.cache_key_extra = cache_key_extra.map(|extra| extra.to_owned());
+
+    let mut hasher = TargetHasher::new();
+    hasher.hash_task(&task);
+
+    to_hash(&hasher)
+}
+
+mod cache_key_extra {
+    use super::*;
+
+    #[test]
+    fn changes_hash() {
+        assert_ne!(hash_task(Some("v1")), hash_task(Some("v2")));
+        assert_ne!(hash_task(None), hash_task(Some("v1")));
+    }
+
+    #[test]
+    fn is_deterministic() {
+        assert_eq!(hash_task(Some("v1")), hash_task(Some("v1")));
+    }
+
+    #[test]
+    fn empty_has_no_effect() {
+        assert_eq!(hash_task(None), hash_task(Some("")));
+    }
+
+    #[test]
+    fn unset_is_not_serialized() {
+        let task = Task {
+            command: "build".into(),
+            ..Task::default()
+        };
+
+        let mut hasher = TargetHasher::new();
+        hasher.hash_task(&task);
+
+        // Ensures existing hashes are not invalidated when not configured
+        assert!(hasher.serialize().get("cacheKeyExtra").is_none());
+    }
+}
+
+mod contributors {
+    use super::*;
+
+    #[test]
+    fn lists_contributors_in_order() {
+        let mut task = Task {
+            command: "build".into(),
+            args: vec!["--fast".into()],
+            env: FxHashMap::from_iter([("KEY".into(), "value".into())]),
+            ..Task::default()
+        };
+        task.options.cache_key_extra = Some("v1".into());
+
+        let mut hasher = TargetHasher::new();
+        hasher.hash_task(&task);
+        hasher.hash_inputs(BTreeMap::from_iter([(
+            WorkspaceRelativePathBuf::from("src/index.ts"),
+            "abc123".into(),
+        )]));
+
+        let contributors = hasher.get_contributors();
+
+        assert_eq!(
+            contributors.first().unwrap(),
+            &HashContributor {
+                kind: HashContributorKind::Version,
+                key: None,
+                value: "1".into(),
+            }
+        );
+        assert_eq!(
+            contributors.last().unwrap(),
+            &HashContributor {
+                kind: HashContributorKind::CacheKeyExtra,
+                key: None,
+                value: "v1".into(),
+            }
+        );
+        assert!(contributors.contains(&HashContributor {
+            kind: HashContributorKind::Command,
+            key: None,
+            value: "build".into(),
+        }));
+        assert!(contributors.contains(&HashContributor {
+            kind: HashContributorKind::Arg,
+            key: None,
+            value: "--fast".into(),
+        }));
+        assert!(contributors.contains(&HashContributor {
+            kind: HashContributorKind::EnvVar,
+            key: Some("KEY".into()),
+            value: "value".into(),
+        }));
+        assert!(contributors.contains(&HashContributor {
+            kind: HashContributorKind::Input,
+            key: Some("src/index.ts".into()),
+            value: "abc123".into(),
+        }));
+    }
+
+    #[test]
+    fn omits_empty_cache_key_extra() {
+        let task = Task {
+            command: "build".into(),
+            ..Task::default()
+        };
+
+        let mut hasher = TargetHasher::new();
+        hasher.hash_task(&task);
+
+        assert!(!hasher
+            .get_contributors()
+            .iter()
+            .any(|c| c.kind == HashContributorKind::CacheKeyExtra));
+    }
+}
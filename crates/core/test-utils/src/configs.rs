@@ -20,6 +20,7 @@ pub fn get_default_toolchain() -> PartialToolchainConfig {
             sync_project_workspace_dependencies: Some(false),
             npm: Some(PartialNpmConfig {
                 version: Some("8.19.0".into()),
+                ..PartialNpmConfig::default()
             }),
             ..PartialNodeConfig::default()
         }),
@@ -148,6 +149,7 @@ pub fn get_project_graph_aliases_fixture_configs() -> (
             dedupe_on_lockfile_change: Some(false),
             npm: Some(PartialNpmConfig {
                 version: Some("8.19.0".into()),
+                ..PartialNpmConfig::default()
             }),
             ..PartialNodeConfig::default()
         }),
@@ -346,6 +348,7 @@ pub fn get_node_depman_fixture_configs(
                 node_config.package_manager = Some(NodePackageManager::Npm);
                 node_config.npm = Some(PartialNpmConfig {
                     version: Some("8.0.0".into()),
+                    ..PartialNpmConfig::default()
                 });
             }
             "pnpm" => {
@@ -0,0 +1,131 @@
+use miette::Diagnostic;
+use moon_process::{output_to_string, Output};
+use starbase_styles::{Style, Stylize};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InstallHint {
+    LockfileOutdated,
+    Network,
+    PeerDependencyConflict,
+    Permissions,
+}
+
+impl InstallHint {
+    /// Map common npm, pnpm, and yarn failure signatures to a hint.
+    pub fn from_output(output: &str) -> Option<InstallHint> {
+        let signatures: [(&[&str], InstallHint); 4] = [
+            (
+                &[
+                    "ERESOLVE",
+                    "ERR_PNPM_PEER_DEP_ISSUES",
+                    "YN0060",
+                    "incorrect peer dependency",
+                ],
+                InstallHint::PeerDependencyConflict,
+            ),
+            (
+                &[
+                    "ERR_PNPM_OUTDATED_LOCKFILE",
+                    "YN0028",
+                    "can only install packages when your package.json and package-lock.json",
+                    "Your lockfile needs to be updated",
+                ],
+                InstallHint::LockfileOutdated,
+            ),
+            (
+                &[
+                    "ENOTFOUND",
+                    "ETIMEDOUT",
+                    "ECONNREFUSED",
+                    "ECONNRESET",
+                    "EAI_AGAIN",
+                    "ERR_PNPM_META_FETCH_FAIL",
+                    // Registry 5xx responses
+                    "E500",
+                    "E502",
+                    "E503",
+                    "E504",
+                    "ERR_PNPM_FETCH_50",
+                    "Request failed \"50",
+                ],
+                InstallHint::Network,
+            ),
+            (&["EACCES", "EPERM"], InstallHint::Permissions),
+        ];
+
+        signatures
+            .into_iter()
+            .find(|(patterns, _)| patterns.iter().any(|pattern| output.contains(pattern)))
+            .map(|(_, hint)| hint)
+    }
+}
+
+impl fmt::Display for InstallHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            InstallHint::LockfileOutdated => {
+                "The lockfile is out of sync with package.json. Run an install locally and commit the updated lockfile."
+            }
+            InstallHint::Network => {
+                "Unable to reach the package registry. Check your network connection, proxy, and registry settings."
+            }
+            InstallHint::PeerDependencyConflict => {
+                "Dependencies have conflicting peer dependency requirements. Align the conflicting versions, or configure the package manager to allow them (for example, npm's --legacy-peer-deps)."
+            }
+            InstallHint::Permissions => {
+                "Permission denied while writing to disk. Check ownership of node_modules and the package manager's cache directory."
+            }
+        };
+
+        write!(f, "{message}")
+    }
+}
+
+#[derive(Error, Debug, Diagnostic)]
+#[diagnostic(code(tool::install::failed))]
+#[error(
+    "Failed to install dependencies with {}, exited with a {} code.",
+    .manager.style(Style::Shell),
+    .code.style(Style::Symbol),
+)]
+pub struct InstallError {
+    pub manager: String,
+
+    pub code: i32,
+
+    pub output: String,
+
+    #[help]
+    pub hint: Option<InstallHint>,
+}
+
+impl InstallError {
+    pub fn new(manager: &str, code: i32, output: String) -> Self {
+        InstallError {
+            hint: InstallHint::from_output(&output),
+            manager: manager.to_owned(),
+            code,
+            output,
+        }
+    }
+
+    /// Create an error from the output of a failed install process,
+    /// or return none if the process was successful.
+    pub fn from_output(manager: &str, output: &Output) -> Option<Self> {
+        if output.status.success() {
+            return None;
+        }
+
+        let mut message = output_to_string(&output.stderr);
+        message.push('\n');
+        message.push_str(&output_to_string(&output.stdout));
+
+        Some(InstallError::new(
+            manager,
+            output.status.code().unwrap_or(-1),
+            message.trim().to_owned(),
+        ))
+    }
+}
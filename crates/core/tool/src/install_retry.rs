@@ -0,0 +1,69 @@
+use crate::install_error::{InstallError, InstallHint};
+use moon_logger::warn;
+use starbase_styles::color;
+use std::future::Future;
+use std::time::Duration;
+
+const LOG_TARGET: &str = "moon:tool:install";
+
+/// Upper bound for the delay between attempts, regardless of the retry count.
+pub const MAX_INSTALL_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+impl InstallError {
+    /// Return true if the failure was caused by a transient network issue
+    /// (timeouts, connection resets, registry 5xx), and may pass if retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self.hint, Some(InstallHint::Network))
+    }
+}
+
+/// Return the delay before the next attempt, which doubles the `backoff` for each
+/// previous attempt, and is capped at [`MAX_INSTALL_RETRY_DELAY`].
+pub fn get_install_retry_delay(backoff: Duration, attempt: u8) -> Duration {
+    2u32.checked_pow(attempt as u32)
+        .and_then(|factor| backoff.checked_mul(factor))
+        .unwrap_or(MAX_INSTALL_RETRY_DELAY)
+        .min(MAX_INSTALL_RETRY_DELAY)
+}
+
+/// Run an install operation, and retry it when it fails with a transient network
+/// error, waiting an exponentially increasing `backoff` between each attempt.
+/// Deterministic failures (peer dependency conflicts, etc) are never retried.
+pub async fn install_with_retry<F, Fut>(
+    retry_count: u8,
+    backoff: Duration,
+    mut op: F,
+) -> miette::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = miette::Result<()>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        let Err(error) = op().await else {
+            return Ok(());
+        };
+
+        let is_transient = error
+            .downcast_ref::<InstallError>()
+            .is_some_and(|error| error.is_transient());
+
+        if !is_transient || attempt >= retry_count {
+            return Err(error);
+        }
+
+        let delay = get_install_retry_delay(backoff, attempt);
+        attempt += 1;
+
+        warn!(
+            target: LOG_TARGET,
+            "Install failed with a network error, retrying in {} (attempt {} of {})",
+            color::muted_light(format!("{}ms", delay.as_millis())),
+            attempt,
+            retry_count,
+        );
+
+        tokio::time::sleep(delay).await;
+    }
+}
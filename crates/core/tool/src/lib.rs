@@ -1,11 +1,20 @@
 mod errors;
+mod install_error;
+mod install_retry;
 mod manager;
 mod tool;
 
 pub use errors::*;
+pub use install_error::*;
+pub use install_retry::*;
 pub use manager::*;
 pub use tool::*;
 
+use moon_logger::info;
+use moon_process::Command;
+use moon_utils::is_env_enabled;
+use starbase_styles::color;
+use starbase_utils::fs;
 use std::env;
 use std::path::Path;
 
@@ -21,3 +30,47 @@ pub fn get_path_env_var(bin_dir: &Path) -> std::ffi::OsString {
 
     env::join_paths(paths).unwrap()
 }
+
+/// Return the total size in bytes of all files within the directory,
+/// recursively. If the directory does not exist, returns 0.
+pub fn get_dir_size(dir: &Path) -> miette::Result<u64> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut size = 0;
+
+    for entry in fs::read_dir_all(dir)? {
+        if let Ok(metadata) = entry.metadata() {
+            size += metadata.len();
+        }
+    }
+
+    Ok(size)
+}
+
+/// Return true if installing dependencies should only log the command
+/// that would be executed, via the `MOON_INSTALL_DRY_RUN` environment variable.
+pub fn is_install_dry_run() -> bool {
+    is_env_enabled("MOON_INSTALL_DRY_RUN")
+}
+
+/// Log the fully assembled command, with secrets masked, instead of executing it.
+pub fn log_dry_run(command: &Command) {
+    let inspector = command.inspect();
+    let line = inspector.mask_secrets(&inspector.get_command_line().to_string());
+
+    match &command.cwd {
+        Some(cwd) => info!(
+            target: "moon:tool",
+            "Dry run, would execute {} in {}",
+            color::shell(line),
+            color::path(cwd),
+        ),
+        None => info!(
+            target: "moon:tool",
+            "Dry run, would execute {}",
+            color::shell(line),
+        ),
+    };
+}
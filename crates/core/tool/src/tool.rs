@@ -13,6 +13,12 @@ pub trait Tool: Any + Debug + Send + Sync {
     /// Return an absolute path to the tool's binary.
     fn get_bin_path(&self) -> miette::Result<PathBuf>;
 
+    /// Return the size in bytes of the tool's installation on disk.
+    /// Tools that are global, or unable to report, return 0.
+    fn get_install_size(&self) -> miette::Result<u64> {
+        Ok(0)
+    }
+
     /// Return an absolute path to an applicable tool shim.
     fn get_shim_path(&self) -> Option<PathBuf> {
         None
@@ -33,8 +39,80 @@ pub trait Tool: Any + Debug + Send + Sync {
     }
 }
 
+/// Status of a lockfile in relation to its manifest.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockfileStatus {
+    /// Lockfile is out of sync with the manifest.
+    Drift,
+
+    /// Lockfile and manifest are in sync.
+    InSync,
+
+    /// Lockfile does not exist.
+    Missing,
+}
+
+impl LockfileStatus {
+    /// Map the exit code of a dependency manager's verify command to a status.
+    pub fn from_exit_code(code: Option<i32>) -> LockfileStatus {
+        match code {
+            Some(0) => LockfileStatus::InSync,
+            _ => LockfileStatus::Drift,
+        }
+    }
+}
+
+/// Outcome of installing dependencies, determined by comparing
+/// the lockfile before and after the install.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InstallOutcome {
+    /// Lockfile was created, modified, or removed.
+    Changed,
+
+    /// Lockfile was left untouched.
+    Unchanged,
+}
+
+impl InstallOutcome {
+    /// Compare the current contents of the lockfile against a snapshot
+    /// of its contents that was taken before installing.
+    pub fn from_lockfile(lockfile: &Path, snapshot: Option<&[u8]>) -> InstallOutcome {
+        if std::fs::read(lockfile).ok().as_deref() == snapshot {
+            InstallOutcome::Unchanged
+        } else {
+            InstallOutcome::Changed
+        }
+    }
+
+    /// Return true if dependencies should be deduped after installing,
+    /// which is only required when something changed, unless forced.
+    pub fn should_dedupe(&self, always: bool) -> bool {
+        always || matches!(self, InstallOutcome::Changed)
+    }
+}
+
+/// Operations supported by a dependency manager, so that consumers
+/// can branch on them without knowledge of the specific tool.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ToolCapabilities {
+    /// Can dedupe dependencies after they have been installed.
+    pub dedupe: bool,
+
+    /// Can install dependencies for a subset of workspace packages.
+    pub focused_install: bool,
+
+    /// Supports workspaces (multiple packages in a single repository).
+    pub workspaces: bool,
+}
+
 #[async_trait]
 pub trait DependencyManager<T: Send + Sync>: Send + Sync + Tool {
+    /// Return the operations that this dependency manager supports.
+    fn capabilities(&self) -> ToolCapabilities;
+
+    /// Verify the lockfile is in sync with the manifest, without installing dependencies.
+    async fn check_lockfile(&self, tool: &T, working_dir: &Path) -> miette::Result<LockfileStatus>;
+
     /// Create a command to run that wraps the binary.
     fn create_command(&self, tool: &T) -> miette::Result<Command>;
 
@@ -59,19 +137,23 @@ pub trait DependencyManager<T: Send + Sync>: Send + Sync + Tool {
         project_root: &Path,
     ) -> miette::Result<LockfileDependencyVersions>;
 
-    /// Install dependencies for a defined manifest.
+    /// Install dependencies for a defined manifest. When `dry_run` is enabled,
+    /// the command is logged instead of executed.
     async fn install_dependencies(
         &self,
         tool: &T,
         working_dir: &Path,
         log: bool,
+        dry_run: bool,
     ) -> miette::Result<()>;
 
-    /// Install dependencies for a single package in the workspace.
+    /// Install dependencies for a single package in the workspace. When `dry_run`
+    /// is enabled, the command is logged instead of executed.
     async fn install_focused_dependencies(
         &self,
         tool: &T,
         packages: &[String],
         production_only: bool,
+        dry_run: bool,
     ) -> miette::Result<()>;
 }
@@ -0,0 +1,63 @@
+use moon_tool::{InstallError, InstallHint};
+
+mod install_hint {
+    use super::*;
+
+    #[test]
+    fn maps_npm_eresolve() {
+        assert_eq!(
+            InstallHint::from_output(
+                "npm ERR! code ERESOLVE\nnpm ERR! ERESOLVE unable to resolve dependency tree"
+            ),
+            Some(InstallHint::PeerDependencyConflict)
+        );
+    }
+
+    #[test]
+    fn maps_npm_enotfound() {
+        assert_eq!(
+            InstallHint::from_output(
+                "npm ERR! code ENOTFOUND\nnpm ERR! request to https://registry.npmjs.org/react failed, reason: getaddrinfo ENOTFOUND registry.npmjs.org"
+            ),
+            Some(InstallHint::Network)
+        );
+    }
+
+    #[test]
+    fn maps_pnpm_outdated_lockfile() {
+        assert_eq!(
+            InstallHint::from_output(
+                " ERR_PNPM_OUTDATED_LOCKFILE  Cannot install with \"frozen-lockfile\" because pnpm-lock.yaml is not up to date"
+            ),
+            Some(InstallHint::LockfileOutdated)
+        );
+    }
+
+    #[test]
+    fn maps_yarn_immutable_lockfile() {
+        assert_eq!(
+            InstallHint::from_output(
+                "➤ YN0028: The lockfile would have been modified by this install, which is explicitly forbidden."
+            ),
+            Some(InstallHint::LockfileOutdated)
+        );
+    }
+
+    #[test]
+    fn returns_none_for_unknown() {
+        assert_eq!(InstallHint::from_output("Something went wrong"), None);
+    }
+}
+
+mod install_error {
+    use super::*;
+
+    #[test]
+    fn captures_manager_code_and_hint() {
+        let error = InstallError::new("npm", 1, "npm ERR! code ERESOLVE".into());
+
+        assert_eq!(error.manager, "npm");
+        assert_eq!(error.code, 1);
+        assert_eq!(error.hint, Some(InstallHint::PeerDependencyConflict));
+    }
+}
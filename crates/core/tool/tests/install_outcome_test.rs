@@ -0,0 +1,77 @@
+use moon_test_utils::create_temp_dir;
+use moon_tool::InstallOutcome;
+use std::fs;
+
+#[test]
+fn unchanged_if_lockfile_untouched() {
+    let dir = create_temp_dir();
+    let lockfile = dir.path().join("yarn.lock");
+
+    fs::write(&lockfile, "lock").unwrap();
+
+    let snapshot = fs::read(&lockfile).ok();
+
+    assert_eq!(
+        InstallOutcome::from_lockfile(&lockfile, snapshot.as_deref()),
+        InstallOutcome::Unchanged
+    );
+}
+
+#[test]
+fn unchanged_if_lockfile_never_existed() {
+    let dir = create_temp_dir();
+
+    assert_eq!(
+        InstallOutcome::from_lockfile(&dir.path().join("yarn.lock"), None),
+        InstallOutcome::Unchanged
+    );
+}
+
+#[test]
+fn changed_if_lockfile_modified() {
+    let dir = create_temp_dir();
+    let lockfile = dir.path().join("yarn.lock");
+
+    fs::write(&lockfile, "lock").unwrap();
+
+    let snapshot = fs::read(&lockfile).ok();
+
+    fs::write(&lockfile, "lock\nnew-dep").unwrap();
+
+    assert_eq!(
+        InstallOutcome::from_lockfile(&lockfile, snapshot.as_deref()),
+        InstallOutcome::Changed
+    );
+}
+
+#[test]
+fn changed_if_lockfile_created() {
+    let dir = create_temp_dir();
+    let lockfile = dir.path().join("yarn.lock");
+
+    fs::write(&lockfile, "lock").unwrap();
+
+    assert_eq!(
+        InstallOutcome::from_lockfile(&lockfile, None),
+        InstallOutcome::Changed
+    );
+}
+
+mod should_dedupe {
+    use super::*;
+
+    #[test]
+    fn skips_when_unchanged() {
+        assert!(!InstallOutcome::Unchanged.should_dedupe(false));
+    }
+
+    #[test]
+    fn runs_when_changed() {
+        assert!(InstallOutcome::Changed.should_dedupe(false));
+    }
+
+    #[test]
+    fn runs_when_unchanged_but_forced() {
+        assert!(InstallOutcome::Unchanged.should_dedupe(true));
+    }
+}
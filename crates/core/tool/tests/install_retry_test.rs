@@ -0,0 +1,115 @@
+use moon_tool::{
+    get_install_retry_delay, install_with_retry, InstallError, MAX_INSTALL_RETRY_DELAY,
+};
+use std::time::Duration;
+
+async fn run_with_failures(
+    output: &str,
+    failures: u8,
+    retry_count: u8,
+) -> (miette::Result<()>, u8) {
+    let mut calls = 0;
+
+    let result = install_with_retry(retry_count, Duration::ZERO, || {
+        calls += 1;
+
+        let result: miette::Result<()> = if calls <= failures {
+            Err(InstallError::new("npm", 1, output.to_owned()).into())
+        } else {
+            Ok(())
+        };
+
+        async move { result }
+    })
+    .await;
+
+    (result, calls)
+}
+
+#[tokio::test]
+async fn doesnt_retry_on_success() {
+    let (result, calls) = run_with_failures("", 0, 3).await;
+
+    assert!(result.is_ok());
+    assert_eq!(calls, 1);
+}
+
+#[tokio::test]
+async fn retries_transient_network_failures() {
+    let (result, calls) = run_with_failures(
+        "npm ERR! code ETIMEDOUT\nnpm ERR! network request to https://registry.npmjs.org/react failed",
+        2,
+        3,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(calls, 3);
+}
+
+#[tokio::test]
+async fn retries_registry_server_errors() {
+    let (result, calls) = run_with_failures(
+        "npm ERR! code E503\nnpm ERR! 503 Service Unavailable - GET https://registry.npmjs.org/react",
+        1,
+        3,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(calls, 2);
+}
+
+#[tokio::test]
+async fn stops_after_retry_count() {
+    let (result, calls) = run_with_failures("npm ERR! code ECONNRESET", 5, 2).await;
+
+    assert!(result.is_err());
+    assert_eq!(calls, 3);
+}
+
+#[tokio::test]
+async fn doesnt_retry_deterministic_failures() {
+    let (result, calls) = run_with_failures(
+        "npm ERR! code ERESOLVE\nnpm ERR! ERESOLVE unable to resolve dependency tree",
+        1,
+        3,
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn doubles_delay_for_each_attempt() {
+    let backoff = Duration::from_millis(100);
+
+    assert_eq!(get_install_retry_delay(backoff, 0), backoff);
+    assert_eq!(
+        get_install_retry_delay(backoff, 1),
+        Duration::from_millis(200)
+    );
+    assert_eq!(
+        get_install_retry_delay(backoff, 3),
+        Duration::from_millis(800)
+    );
+}
+
+#[test]
+fn caps_delay_without_overflowing() {
+    let backoff = Duration::from_millis(100);
+
+    assert_eq!(
+        get_install_retry_delay(backoff, 20),
+        MAX_INSTALL_RETRY_DELAY
+    );
+    assert_eq!(
+        get_install_retry_delay(backoff, 32),
+        MAX_INSTALL_RETRY_DELAY
+    );
+    assert_eq!(
+        get_install_retry_delay(backoff, u8::MAX),
+        MAX_INSTALL_RETRY_DELAY
+    );
+}
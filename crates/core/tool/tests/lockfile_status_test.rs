@@ -0,0 +1,26 @@
+use moon_tool::LockfileStatus;
+
+#[test]
+fn success_is_in_sync() {
+    assert_eq!(
+        LockfileStatus::from_exit_code(Some(0)),
+        LockfileStatus::InSync
+    );
+}
+
+#[test]
+fn failure_is_drift() {
+    assert_eq!(
+        LockfileStatus::from_exit_code(Some(1)),
+        LockfileStatus::Drift
+    );
+    assert_eq!(
+        LockfileStatus::from_exit_code(Some(127)),
+        LockfileStatus::Drift
+    );
+}
+
+#[test]
+fn no_code_is_drift() {
+    assert_eq!(LockfileStatus::from_exit_code(None), LockfileStatus::Drift);
+}
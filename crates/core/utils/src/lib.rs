@@ -62,6 +62,23 @@ pub fn is_docker_container() -> bool {
     PathBuf::from("/.dockerenv").exists()
 }
 
+/// Return true if the environment variable is set to a truthy value,
+/// that is, anything other than an empty string, `0`, or `false`.
+#[inline]
+pub fn is_env_enabled(name: &str) -> bool {
+    match env::var(name) {
+        Ok(var) => !var.is_empty() && var != "0" && var != "false",
+        Err(_) => false,
+    }
+}
+
+/// Return true if explicitly running offline via `MOON_OFFLINE`,
+/// in which case tools should not attempt to reach a registry.
+#[inline]
+pub fn is_offline_mode() -> bool {
+    is_env_enabled("MOON_OFFLINE")
+}
+
 #[inline]
 pub fn is_test_env() -> bool {
     env::var("MOON_TEST").is_ok()
@@ -21,9 +21,6 @@ pub static TARGET_PATTERN: Lazy<regex::Regex> = Lazy::new(|| {
 // Input values
 pub static ENV_VAR: Lazy<regex::Regex> = Lazy::new(|| create_regex("^\\$[A-Z0-9_]+$").unwrap());
 
-pub static ENV_VAR_SUBSTITUTE: Lazy<regex::Regex> =
-    Lazy::new(|| create_regex("\\$\\{([A-Z0-9_]+)\\}").unwrap());
-
 // Token function: `@func(arg)`
 pub static TOKEN_GROUP: &str = "([0-9A-Za-z_-]+)";
 
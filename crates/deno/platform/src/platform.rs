@@ -22,11 +22,17 @@ use proto::{get_sha256_hash_of_file, Proto};
 use rustc_hash::FxHashMap;
 use std::{
     collections::BTreeMap,
+    ffi::OsString,
     path::{Path, PathBuf},
 };
 
 const LOG_TARGET: &str = "moon:deno-platform";
 
+// Subcommands that support the `--import-map` option
+const IMPORT_MAP_SUBCOMMANDS: [&str; 11] = [
+    "bench", "bundle", "cache", "check", "compile", "doc", "info", "install", "repl", "run", "test",
+];
+
 #[derive(Debug)]
 pub struct DenoPlatform {
     config: DenoConfig,
@@ -51,6 +57,18 @@ impl DenoPlatform {
             workspace_root: workspace_root.to_path_buf(),
         }
     }
+
+    /// Return arguments for the configured import map (if any), with an absolute
+    /// path, as commands may be ran from a project root.
+    fn get_import_map_args(&self) -> Vec<OsString> {
+        match &self.config.import_map {
+            Some(import_map) => vec![
+                OsString::from("--import-map"),
+                self.workspace_root.join(import_map).into_os_string(),
+            ],
+            None => vec![],
+        }
+    }
 }
 
 #[async_trait]
@@ -177,13 +195,9 @@ impl Platform for DenoPlatform {
         print_checkpoint("deno cache", Checkpoint::Setup);
 
         Command::new(tool.get_bin_path()?)
-            .args([
-                "cache",
-                "--lock",
-                DENO_DEPS.lockfile,
-                "--lock-write",
-                &self.config.deps_file,
-            ])
+            .args(["cache", "--lock", DENO_DEPS.lockfile, "--lock-write"])
+            .args(self.get_import_map_args())
+            .arg(&self.config.deps_file)
             .cwd(working_dir)
             .create_async()
             .exec_stream_output()
@@ -201,7 +215,6 @@ impl Platform for DenoPlatform {
 
             for bin in &self.config.bins {
                 let mut args = vec![
-                    "install",
                     "--allow-net",
                     "--allow-read",
                     "--no-prompt",
@@ -230,6 +243,8 @@ impl Platform for DenoPlatform {
                 };
 
                 Command::new(tool.get_bin_path()?)
+                    .arg("install")
+                    .args(self.get_import_map_args())
                     .args(args)
                     .cwd(working_dir)
                     .create_async()
@@ -357,7 +372,24 @@ impl Platform for DenoPlatform {
     ) -> miette::Result<Command> {
         let mut command = Command::new(&task.command);
 
-        command.args(&task.args).envs(&task.env).cwd(working_dir);
+        // Inject the import map after the subcommand, unless one has been provided
+        match task.args.split_first() {
+            Some((subcommand, args))
+                if task.command == "deno"
+                    && IMPORT_MAP_SUBCOMMANDS.contains(&subcommand.as_str())
+                    && !task.args.iter().any(|arg| arg.starts_with("--import-map")) =>
+            {
+                command
+                    .arg(subcommand)
+                    .args(self.get_import_map_args())
+                    .args(args);
+            }
+            _ => {
+                command.args(&task.args);
+            }
+        }
+
+        command.envs(&task.env).cwd(working_dir);
 
         Ok(command)
     }
@@ -0,0 +1,131 @@
+// Lockfiles in large monorepos can be tens of megabytes, so instead of parsing the
+// entire document into memory, we stream it and only extract the fields we need.
+// Every other field (`resolved`, `requires`, nested `dependencies`, etc) is skipped
+// with `IgnoredAny`, which does not allocate.
+
+use miette::IntoDiagnostic;
+use moon_lang::LockfileDependencyVersions;
+use rustc_hash::FxHashMap;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+
+#[derive(Deserialize)]
+struct SlimDependency {
+    integrity: Option<String>,
+    version: Option<String>,
+
+    // Bundled packages are shipped within their parent's tarball
+    #[serde(default, rename = "inBundle")]
+    in_bundle: bool,
+}
+
+impl SlimDependency {
+    // Prefer the integrity hash, as versions are not unique across registries
+    fn into_resolved(self) -> Option<String> {
+        self.integrity.or(self.version)
+    }
+}
+
+#[derive(Deserialize)]
+struct SlimPackageLock {
+    // v2, v3
+    #[serde(default, deserialize_with = "deserialize_packages")]
+    packages: Option<LockfileDependencyVersions>,
+
+    // v1, v2
+    #[serde(default)]
+    dependencies: Option<FxHashMap<String, SlimDependency>>,
+}
+
+// node_modules/cacache -> cacache
+// node_modules/node-gyp/node_modules/cacache -> cacache
+// workspaces/libnpmdiff -> libnpmdiff
+fn resolve_package_name(path: &str) -> &str {
+    if path.starts_with("node_modules") {
+        path.rsplit("node_modules/").next().unwrap_or_default()
+    } else if path.starts_with("workspaces") {
+        path.rsplit("workspaces/").next().unwrap_or_default()
+    } else {
+        path
+    }
+}
+
+struct PackagesVisitor;
+
+impl<'de> Visitor<'de> for PackagesVisitor {
+    type Value = Option<LockfileDependencyVersions>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of package paths to packages")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let mut packages: LockfileDependencyVersions = FxHashMap::default();
+
+        // Packages are keyed by their resolved name as we go, so that only the resolved
+        // values are held in memory. The same package nested at multiple depths may resolve
+        // to different versions, so every distinct value is kept.
+        while let Some((path, dep)) = map.next_entry::<String, SlimDependency>()? {
+            let name = resolve_package_name(&path);
+
+            if name.is_empty() || dep.in_bundle {
+                continue;
+            }
+
+            // Links (and the root package) have no version or integrity
+            let Some(resolved) = dep.into_resolved() else {
+                continue;
+            };
+
+            match packages.get_mut(name) {
+                Some(list) => {
+                    if !list.contains(&resolved) {
+                        list.push(resolved);
+                    }
+                }
+                None => {
+                    packages.insert(name.to_owned(), vec![resolved]);
+                }
+            }
+        }
+
+        Ok(Some(packages))
+    }
+}
+
+fn deserialize_packages<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<LockfileDependencyVersions>, D::Error> {
+    deserializer.deserialize_map(PackagesVisitor)
+}
+
+/// Stream a `package-lock.json` from the provided reader, and extract a mapping
+/// of package names to their resolved integrity hashes (or versions), without
+/// materializing the entire document in memory.
+pub fn parse_lockfile_dependencies<R: Read>(
+    reader: R,
+) -> miette::Result<LockfileDependencyVersions> {
+    let lockfile: SlimPackageLock = serde_json::from_reader(reader).into_diagnostic()?;
+    let mut deps: LockfileDependencyVersions = FxHashMap::default();
+
+    if let Some(packages) = lockfile.packages {
+        return Ok(packages);
+    }
+
+    // This isn't entirely accurate as npm does not hoist all dependencies
+    // to the root of the lockfile. We'd need to recursively extract everything,
+    // but for now, this will get us most of the way.
+    for (name, dep) in lockfile.dependencies.unwrap_or_default() {
+        if name.is_empty() {
+            continue;
+        }
+
+        if let Some(resolved) = dep.into_resolved() {
+            deps.insert(name, vec![resolved]);
+        }
+    }
+
+    Ok(deps)
+}
@@ -1,94 +1,31 @@
-use crate::NPM;
-use cached::proc_macro::cached;
-use miette::IntoDiagnostic;
-use moon_lang::{config_cache_container, LockfileDependencyVersions};
-use package_lock_json_parser::{parse, PackageLockJson};
-use rustc_hash::FxHashMap;
-use starbase_utils::fs;
-use std::path::{Path, PathBuf};
+mod lockfile_stream;
 
-fn read_file(path: &Path) -> miette::Result<PackageLockJson> {
-    parse(fs::read_file(path)?).into_diagnostic()
-}
+pub use lockfile_stream::*;
 
-config_cache_container!(
-    PackageLockJsonCache,
-    PackageLockJson,
-    NPM.lockfile,
-    read_file
-);
+use moon_lang::LockfileDependencyVersions;
+use rustc_hash::FxHashMap;
+use starbase_utils::fs;
+use std::io::BufReader;
+use std::path::PathBuf;
 
 // https://docs.npmjs.com/cli/v9/configuring-npm/package-lock-json?v=true
-#[cached(result)]
+// Not cached, as the lockfile may change during a run (`NpmTool` caches by modified time)
 pub fn load_lockfile_dependencies(path: PathBuf) -> miette::Result<LockfileDependencyVersions> {
-    let mut deps: LockfileDependencyVersions = FxHashMap::default();
-
-    let mut add_dep = |name: &str, version: &str, integrity: Option<&String>| {
-        if !name.is_empty() {
-            let mut list = vec![];
-
-            if let Some(int) = integrity {
-                list.push(int.to_owned());
-            } else {
-                list.push(version.to_owned());
-            }
-
-            deps.entry(name.to_owned())
-                .and_modify(|data| {
-                    data.extend(list.clone());
-                })
-                .or_insert(list);
-        }
-    };
-
-    if let Some(lockfile) = PackageLockJsonCache::read(path)? {
-        let has_packages = lockfile.packages.is_some();
-
-        // v2, v3
-        for (name, dep) in lockfile.packages.unwrap_or_default() {
-            // node_modules/cacache
-            // node_modules/node-gyp/node_modules/cacache
-            if name.starts_with("node_modules") {
-                let name_parts = name.split("node_modules/");
-                let resolved_name = name_parts.last().unwrap_or_default();
-
-                add_dep(resolved_name, &dep.version, dep.integrity.as_ref());
-
-                // workspaces/libnpmdiff
-            } else if name.starts_with("workspaces") {
-                let name_parts = name.split("workspaces/");
-                let resolved_name = name_parts.last().unwrap_or_default();
-
-                add_dep(resolved_name, &dep.version, dep.integrity.as_ref());
-
-                // other
-            } else if !name.is_empty() {
-                add_dep(&name, &dep.version, dep.integrity.as_ref());
-            }
-        }
-
-        // v1, v2
-        if !has_packages {
-            // This isn't entirely accurate as npm does not hoist all dependencies
-            // to the root of the lockfile. We'd need to recursively extract everything,
-            // but for now, this will get us most of the way.
-            for (name, dep) in lockfile.dependencies.unwrap_or_default() {
-                add_dep(&name, &dep.version, dep.integrity.as_ref());
-            }
-        }
+    if !path.exists() {
+        return Ok(FxHashMap::default());
     }
 
-    Ok(deps)
+    // Stream the file, as we don't want to keep the entire lockfile in memory
+    let file = fs::open_file(&path)?;
+
+    parse_lockfile_dependencies(BufReader::new(file))
 }
 
 #[cfg(test)]
-#[allow(clippy::disallowed_types)]
 mod tests {
     use super::*;
     use moon_test_utils::{assert_fs::prelude::*, create_temp_dir, pretty_assertions::assert_eq};
     use moon_utils::string_vec;
-    use package_lock_json_parser::V1Dependency;
-    use std::collections::HashMap;
 
     #[test]
     fn parses_lockfile() {
@@ -125,43 +62,6 @@ mod tests {
             )
             .unwrap();
 
-        let lockfile: PackageLockJson = read_file(&temp.path().join("package-lock.json")).unwrap();
-
-        assert_eq!(
-            lockfile,
-            PackageLockJson {
-                name: "moon-examples".into(),
-                version: Some("1.2.3".into()),
-                lockfile_version: 2,
-                dependencies: Some(HashMap::from_iter([(
-                    "@babel/helper-function-name".to_owned(),
-                    V1Dependency {
-                        integrity: Some("sha512-fJgWlZt7nxGksJS9a0XdSaI4XvpExnNIgRP+rVefWh5U7BL8pPuir6SJUmFKRfjWQ51OtWSzwOxhaH/EBWWc0A==".into()),
-                        requires: Some(HashMap::from_iter([
-                            ("@babel/template".to_owned(), "^7.18.6".to_owned()),
-                            ("@babel/types".to_owned(), "^7.18.9".to_owned())
-                        ])),
-                        resolved: Some("https://registry.npmjs.org/@babel/helper-function-name/-/helper-function-name-7.18.9.tgz".into()),
-                        version: "7.18.9".into(),
-                        ..V1Dependency::default()
-                    }
-                ), (
-                    "rollup-plugin-polyfill-node".to_owned(),
-                    V1Dependency {
-                        is_dev: true,
-                        integrity: Some("sha512-5GMywXiLiuQP6ZzED/LO/Q0HyDi2W6b8VN+Zd3oB0opIjyRs494Me2ZMaqKWDNbGiW4jvvzl6L2n4zRgxS9cSQ==".into()),
-                        requires: Some(HashMap::from_iter([
-                            ("@rollup/plugin-inject".to_owned(), "^4.0.0".to_owned())
-                        ])),
-                        resolved: Some("https://registry.npmjs.org/rollup-plugin-polyfill-node/-/rollup-plugin-polyfill-node-0.10.2.tgz".into()),
-                        version: "0.10.2".into(),
-                        ..V1Dependency::default()
-                    }
-                )])),
-                ..PackageLockJson::default()
-            }
-        );
-
         assert_eq!(
             load_lockfile_dependencies(temp.path().join("package-lock.json")).unwrap(),
             FxHashMap::from_iter([
@@ -238,8 +138,6 @@ mod tests {
             )
             .unwrap();
 
-        let _: PackageLockJson = read_file(&temp.path().join("package-lock.json")).unwrap();
-
         assert_eq!(
             load_lockfile_dependencies(temp.path().join("package-lock.json")).unwrap(),
             FxHashMap::from_iter([
@@ -266,6 +164,8 @@ mod tests {
         .text()
         .unwrap();
 
-        let _: PackageLockJson = serde_json::from_str(&content).unwrap();
+        let deps = parse_lockfile_dependencies(content.as_bytes()).unwrap();
+
+        assert!(!deps.is_empty());
     }
 }
@@ -0,0 +1,152 @@
+use moon_node_lang::npm::parse_lockfile_dependencies;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Track the current and peak heap usage, so that we can verify that parsing
+// does not hold the entire lockfile in memory.
+struct TrackingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn create_large_lockfile(count: usize) -> String {
+    let mut lockfile = String::from(
+        r#"{
+  "name": "large-monorepo",
+  "version": "1.0.0",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": { "name": "large-monorepo", "version": "1.0.0", "workspaces": ["packages/*"] },
+    "node_modules/other/node_modules/package-0": { "version": "0.0.1" }"#,
+    );
+
+    for i in 0..count {
+        let mut deps = String::new();
+
+        for d in 0..40 {
+            write!(deps, r#""dependency-of-{i}-number-{d}": "^{d}.0.0","#).unwrap();
+        }
+
+        deps.pop();
+
+        write!(
+            lockfile,
+            r#",
+    "node_modules/package-{i}": {{
+      "version": "1.0.{i}",
+      "resolved": "https://registry.npmjs.org/package-{i}/-/package-{i}-1.0.{i}.tgz",
+      "integrity": "sha512-{i:0>86}==",
+      "dev": true,
+      "license": "MIT",
+      "dependencies": {{ {deps} }},
+      "engines": {{ "node": ">=14.17.0 || ^16.13.0 || >=18.0.0" }},
+      "funding": {{ "url": "https://github.com/sponsors/package-{i}" }}
+    }}"#
+        )
+        .unwrap();
+    }
+
+    lockfile.push_str(
+        r#",
+    "node_modules/package-0/node_modules/package-1": {
+      "version": "0.1.0",
+      "license": "MIT"
+    },
+    "node_modules/workspace-a": {
+      "resolved": "packages/workspace-a",
+      "link": true
+    },
+    "packages/workspace-a": {
+      "version": "2.0.0"
+    }
+  }
+}"#,
+    );
+
+    lockfile
+}
+
+#[test]
+fn parses_large_lockfile_with_bounded_memory() {
+    let lockfile = create_large_lockfile(10_000);
+
+    // Reset the peak to the current usage, which includes the lockfile itself
+    let baseline = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let deps = parse_lockfile_dependencies(lockfile.as_bytes()).unwrap();
+
+    let used = PEAK.load(Ordering::SeqCst) - baseline;
+
+    assert!(
+        used < lockfile.len() / 2,
+        "parsing used {used} bytes for a {} byte lockfile",
+        lockfile.len()
+    );
+
+    // Assert a subset
+    assert_eq!(deps.len(), 10_001);
+    assert_eq!(
+        deps.get("package-0").unwrap(),
+        &vec!["0.0.1".to_owned(), format!("sha512-{:0>86}==", 0)]
+    );
+    assert_eq!(
+        deps.get("package-1").unwrap(),
+        &vec![format!("sha512-{:0>86}==", 1), "0.1.0".to_owned()]
+    );
+    assert_eq!(
+        deps.get("package-9999").unwrap(),
+        &vec![format!("sha512-{:0>86}==", 9999)]
+    );
+    assert_eq!(
+        deps.get("packages/workspace-a").unwrap(),
+        &vec!["2.0.0".to_owned()]
+    );
+    assert!(!deps.contains_key("workspace-a"));
+    assert!(!deps.contains_key(""));
+}
+
+#[test]
+fn keeps_each_distinct_version_of_nested_packages() {
+    let deps = parse_lockfile_dependencies(
+        r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "node_modules/yaml": { "version": "2.2.2" },
+    "node_modules/a/node_modules/yaml": { "version": "1.10.2" },
+    "node_modules/b/node_modules/yaml": { "version": "1.10.2" },
+    "node_modules/tap/node_modules/yaml": { "version": "1.9.0", "inBundle": true }
+  }
+}"#
+        .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        deps.get("yaml").unwrap(),
+        &vec!["2.2.2".to_owned(), "1.10.2".to_owned()]
+    );
+}
@@ -4,6 +4,7 @@ use moon_logger::{debug, warn};
 use moon_node_lang::NODE;
 use moon_node_tool::NodeTool;
 use moon_terminal::{print_checkpoint, Checkpoint};
+use moon_tool::{is_install_dry_run, InstallOutcome};
 use moon_utils::{is_ci, is_test_env};
 use std::path::Path;
 
@@ -22,6 +23,9 @@ pub async fn install_deps(node: &NodeTool, working_dir: &Path) -> miette::Result
     }
 
     let package_manager = node.get_package_manager();
+    let lockfile = working_dir.join(package_manager.get_lock_filename());
+    let lockfile_snapshot = std::fs::read(&lockfile).ok();
+    let dry_run = is_install_dry_run();
 
     // Install dependencies
     {
@@ -37,12 +41,24 @@ pub async fn install_deps(node: &NodeTool, working_dir: &Path) -> miette::Result
         );
 
         package_manager
-            .install_dependencies(node, working_dir, !is_test_env())
+            .install_dependencies(node, working_dir, !is_test_env(), dry_run)
             .await?;
     }
 
-    // Dedupe dependencies
-    if !is_ci() && node.config.dedupe_on_lockfile_change {
+    // Dedupe dependencies, but only when the install changed something
+    if !is_ci()
+        && !dry_run
+        && node.config.dedupe_on_lockfile_change
+        && package_manager.capabilities().dedupe
+    {
+        let outcome = InstallOutcome::from_lockfile(&lockfile, lockfile_snapshot.as_deref());
+
+        if !outcome.should_dedupe(node.config.dedupe_always) {
+            debug!(target: LOG_TARGET, "Lockfile unchanged, skipping dedupe");
+
+            return Ok(());
+        }
+
         debug!(target: LOG_TARGET, "Deduping dependencies");
 
         print_checkpoint(
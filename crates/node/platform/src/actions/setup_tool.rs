@@ -2,7 +2,7 @@ use moon_config::{NodeConfig, NodePackageManager, NodeVersionManager};
 use moon_logger::debug;
 use moon_node_lang::{PackageJson, NODENV, NPM, NVM, PNPM, YARN};
 use moon_node_tool::NodeTool;
-
+use semver::Version;
 use starbase_styles::color;
 use starbase_utils::fs;
 use std::path::Path;
@@ -10,21 +10,13 @@ use std::path::Path;
 const LOG_TARGET: &str = "moon:node-platform:setup-tool";
 
 /// Add `packageManager` to `package.json`.
-fn add_package_manager(node_config: &NodeConfig, package_json: &mut PackageJson) -> bool {
-    let manager_version = match node_config.package_manager {
-        NodePackageManager::Npm => node_config.npm.version.as_ref().map(|v| format!("npm@{v}")),
-        NodePackageManager::Pnpm => node_config.pnpm.as_ref().map(|cfg| {
-            cfg.version
-                .as_ref()
-                .map(|v| format!("pnpm@{v}"))
-                .unwrap_or_default()
-        }),
-        NodePackageManager::Yarn => node_config.yarn.as_ref().map(|cfg| {
-            cfg.version
-                .as_ref()
-                .map(|v| format!("yarn@{v}"))
-                .unwrap_or_default()
-        }),
+fn add_package_manager(
+    package_manager: &Option<(NodePackageManager, Option<Version>)>,
+    package_json: &mut PackageJson,
+) -> bool {
+    let manager_version = match package_manager {
+        Some((manager, Some(version))) => Some(format!("{manager}@{version}")),
+        _ => None,
     };
 
     if let Some(version) = manager_version {
@@ -59,7 +51,11 @@ fn add_engines_constraint(node_config: &NodeConfig, package_json: &mut PackageJs
     false
 }
 
-pub async fn setup_tool(node: &NodeTool, workspace_root: &Path) -> miette::Result<()> {
+pub async fn setup_tool(
+    node: &NodeTool,
+    package_manager: &Option<(NodePackageManager, Option<Version>)>,
+    workspace_root: &Path,
+) -> miette::Result<()> {
     // Find the `package.json` workspaces root
     let lockfile = match node.config.package_manager {
         NodePackageManager::Npm => NPM.lockfile,
@@ -75,7 +71,7 @@ pub async fn setup_tool(node: &NodeTool, workspace_root: &Path) -> miette::Resul
 
     // Sync values to root `package.json`
     PackageJson::sync(packages_root, |package_json| {
-        let added_manager = add_package_manager(&node.config, package_json);
+        let added_manager = add_package_manager(package_manager, package_json);
         let added_constraint = add_engines_constraint(&node.config, package_json);
 
         Ok(added_manager || added_constraint)
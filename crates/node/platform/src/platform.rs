@@ -4,8 +4,8 @@ use moon_action_context::ActionContext;
 use moon_common::Id;
 use moon_config::{
     Config, DependencyConfig, DependencyScope, DependencySource, HasherConfig, NodeConfig,
-    PlatformType, ProjectConfig, ProjectsAliasesMap, ProjectsSourcesMap, TaskConfig,
-    TasksConfigsMap, TypeScriptConfig,
+    NodePackageManager, PlatformType, ProjectConfig, ProjectsAliasesMap, ProjectsSourcesMap,
+    TaskConfig, TasksConfigsMap, TypeScriptConfig,
 };
 use moon_hasher::{DepsHasher, HashSet};
 use moon_logger::{debug, warn};
@@ -32,6 +32,8 @@ const LOG_TARGET: &str = "moon:node-platform";
 pub struct NodePlatform {
     config: NodeConfig,
 
+    package_manager: Option<(NodePackageManager, Option<semver::Version>)>,
+
     package_names: FxHashMap<String, Id>,
 
     toolchain: ToolManager<NodeTool>,
@@ -44,11 +46,13 @@ pub struct NodePlatform {
 impl NodePlatform {
     pub fn new(
         config: &NodeConfig,
+        package_manager: Option<(NodePackageManager, Option<semver::Version>)>,
         typescript_config: &Option<TypeScriptConfig>,
         workspace_root: &Path,
     ) -> Self {
         NodePlatform {
             config: config.to_owned(),
+            package_manager,
             package_names: FxHashMap::default(),
             toolchain: ToolManager::new(Runtime::Node(Version::new_global())),
             typescript_config: typescript_config.to_owned(),
@@ -310,6 +314,7 @@ impl Platform for NodePlatform {
 
         actions::setup_tool(
             self.toolchain.get_for_version(runtime.version())?,
+            &self.package_manager,
             &self.workspace_root,
         )
         .await?;
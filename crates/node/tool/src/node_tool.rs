@@ -7,11 +7,15 @@ use moon_node_lang::node;
 use moon_platform_runtime::Version;
 use moon_process::Command;
 use moon_terminal::{print_checkpoint, Checkpoint};
-use moon_tool::{get_path_env_var, DependencyManager, Tool, ToolError};
+use moon_tool::{get_dir_size, get_path_env_var, DependencyManager, Tool, ToolError};
 use proto::{async_trait, node::NodeLanguage, Executable, Installable, Proto, Tool as ProtoTool};
 use rustc_hash::FxHashMap;
 use std::path::{Path, PathBuf};
 
+/// Scripts that pnpm and yarn can run directly, without the `run` keyword.
+/// Other names may collide with built-in commands (`install`, etc).
+pub(crate) const LIFECYCLE_SCRIPTS: [&str; 4] = ["restart", "start", "stop", "test"];
+
 #[derive(Debug)]
 pub struct NodeTool {
     pub config: NodeConfig,
@@ -84,6 +88,19 @@ impl NodeTool {
         Ok(())
     }
 
+    /// Run a `package.json` script using the configured package manager.
+    pub async fn exec_script(&self, script: &str, working_dir: &Path) -> miette::Result<()> {
+        match self.config.package_manager {
+            NodePackageManager::Npm => self.get_npm()?.run_script(self, script, working_dir).await,
+            NodePackageManager::Pnpm => {
+                self.get_pnpm()?.run_script(self, script, working_dir).await
+            }
+            NodePackageManager::Yarn => {
+                self.get_yarn()?.run_script(self, script, working_dir).await
+            }
+        }
+    }
+
     /// Return the `npm` package manager.
     pub fn get_npm(&self) -> miette::Result<&NpmTool> {
         match &self.npm {
@@ -150,6 +167,14 @@ impl Tool for NodeTool {
         })
     }
 
+    fn get_install_size(&self) -> miette::Result<u64> {
+        if self.global {
+            return Ok(0);
+        }
+
+        get_dir_size(&self.tool.get_install_dir()?)
+    }
+
     async fn setup(&mut self, last_versions: &mut FxHashMap<String, String>) -> miette::Result<u8> {
         let mut installed = 0;
 
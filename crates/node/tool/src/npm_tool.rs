@@ -1,20 +1,31 @@
 use crate::node_tool::NodeTool;
 use moon_config::NpmConfig;
 use moon_logger::debug;
-use moon_node_lang::{npm, LockfileDependencyVersions, NPM};
+use moon_node_lang::{
+    node::get_package_manager_workspaces, npm, LockfileDependencyVersions, PackageJson, NPM,
+};
 use moon_process::Command;
 use moon_terminal::{print_checkpoint, Checkpoint};
-use moon_tool::{get_path_env_var, DependencyManager, Tool};
-use moon_utils::is_ci;
+use moon_tool::{
+    get_dir_size, get_path_env_var, install_with_retry, log_dry_run, DependencyManager,
+    InstallError, LockfileStatus, Tool, ToolCapabilities,
+};
+use moon_utils::{get_workspace_root, is_ci, is_offline_mode};
 use proto::{
     async_trait,
     node::{NodeDependencyManager, NodeDependencyManagerType},
     Executable, Installable, Proto, Shimable, Tool as ProtoTool,
 };
 use rustc_hash::FxHashMap;
-use starbase_utils::fs;
+use starbase_utils::{fs, glob};
 use std::env;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+// Lockfile path -> (modified time, dependencies)
+type ResolvedDependenciesCache =
+    FxHashMap<PathBuf, (Option<SystemTime>, Arc<LockfileDependencyVersions>)>;
 
 #[derive(Debug)]
 pub struct NpmTool {
@@ -23,6 +34,8 @@ pub struct NpmTool {
     pub global: bool,
 
     pub tool: NodeDependencyManager,
+
+    resolved_dependencies: RwLock<ResolvedDependenciesCache>,
 }
 
 impl NpmTool {
@@ -31,8 +44,153 @@ impl NpmTool {
             global: config.version.is_none(),
             config: config.to_owned(),
             tool: NodeDependencyManager::new(proto, NodeDependencyManagerType::Npm),
+            resolved_dependencies: RwLock::new(FxHashMap::default()),
         })
     }
+
+    /// Return arguments for installing dependencies, based on the configured
+    /// settings, whether running in CI (`ci` requires a lockfile), and whether
+    /// running offline (resolves from the cache only).
+    pub fn get_install_args(&self, working_dir: &Path, ci: bool, offline: bool) -> Vec<&str> {
+        let mut args = vec!["install"];
+
+        // npm will error if using `ci` and a lockfile does not exist!
+        if ci && working_dir.join(self.get_lock_filename()).exists() {
+            args[0] = "ci";
+        }
+
+        // `--prefer-offline` will still hit the registry for cache misses
+        if offline {
+            args.push("--offline");
+        }
+
+        // When not configured, only audit in CI, which has always been the default
+        if !self.config.audit.unwrap_or(ci) {
+            args.push("--no-audit");
+        }
+
+        if !self.config.fund {
+            args.push("--no-fund");
+        }
+
+        args
+    }
+
+    /// Return registry arguments for installing the dependencies of the package at the
+    /// provided root. A `registry` defined in the package's `.npmrc` takes precedence over
+    /// the configured registry, as npm ignores this file for workspace members.
+    /// Returns an empty list when neither exist.
+    pub fn get_registry_args(&self, package_root: &Path) -> miette::Result<Vec<String>> {
+        let registry = match read_npmrc_registry(package_root)? {
+            Some(registry) => Some(registry),
+            None => self.config.registry.clone(),
+        };
+
+        Ok(match registry {
+            Some(registry) => vec![format!("--registry={registry}")],
+            None => vec![],
+        })
+    }
+
+    /// Group the workspace packages by the registry arguments they should be installed
+    /// with, so that packages with different registries are installed separately.
+    /// Groups are returned in the order in which they were first encountered.
+    pub fn group_packages_by_registry(
+        &self,
+        workspace_root: &Path,
+        package_names: &[String],
+    ) -> miette::Result<Vec<(Vec<String>, Vec<String>)>> {
+        let package_roots = get_workspace_package_roots(workspace_root)?;
+        let mut groups: Vec<(Vec<String>, Vec<String>)> = vec![];
+
+        for package_name in package_names {
+            let registry_args = self.get_registry_args(
+                package_roots
+                    .get(package_name)
+                    .map(|root| root.as_path())
+                    .unwrap_or(workspace_root),
+            )?;
+
+            match groups.iter_mut().find(|group| group.0 == registry_args) {
+                Some(group) => group.1.push(package_name.to_owned()),
+                None => groups.push((registry_args, vec![package_name.to_owned()])),
+            };
+        }
+
+        Ok(groups)
+    }
+
+    async fn install_focused_group(
+        &self,
+        node: &NodeTool,
+        registry_args: &[String],
+        package_names: &[String],
+        production_only: bool,
+        dry_run: bool,
+    ) -> miette::Result<()> {
+        install_with_retry(
+            node.config.install_retry_count,
+            Duration::from_millis(node.config.install_retry_backoff),
+            || async {
+                let mut cmd = self.create_command(node)?;
+                cmd.args(["install"]);
+
+                if production_only {
+                    cmd.arg("--production");
+                }
+
+                if is_offline_mode() {
+                    cmd.arg("--offline");
+                }
+
+                cmd.args(registry_args);
+
+                for package_name in package_names {
+                    cmd.args(["--workspace", package_name]);
+                }
+
+                // We need to handle non-zero's manually
+                cmd.set_error_on_nonzero(false);
+
+                if dry_run {
+                    log_dry_run(&cmd);
+
+                    return Ok(());
+                }
+
+                let output = cmd.create_async().exec_stream_and_capture_output().await?;
+
+                if let Some(error) = InstallError::from_output("npm", &output) {
+                    return Err(error.into());
+                }
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Return arguments for running a `package.json` script.
+    pub fn get_run_script_args<'a>(&self, script: &'a str) -> Vec<&'a str> {
+        vec!["run", script]
+    }
+
+    /// Run a `package.json` script with `npm run`.
+    pub async fn run_script(
+        &self,
+        node: &NodeTool,
+        script: &str,
+        working_dir: &Path,
+    ) -> miette::Result<()> {
+        self.create_command(node)?
+            .args(self.get_run_script_args(script))
+            .cwd(working_dir)
+            .create_async()
+            .exec_stream_output()
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -53,6 +211,14 @@ impl Tool for NpmTool {
         self.tool.get_shim_path().map(|p| p.to_path_buf())
     }
 
+    fn get_install_size(&self) -> miette::Result<u64> {
+        if self.global {
+            return Ok(0);
+        }
+
+        get_dir_size(&self.tool.get_install_dir()?)
+    }
+
     async fn setup(&mut self, last_versions: &mut FxHashMap<String, String>) -> miette::Result<u8> {
         let mut count = 0;
         let version = self.config.version.clone();
@@ -103,6 +269,44 @@ impl Tool for NpmTool {
 
 #[async_trait]
 impl DependencyManager<NodeTool> for NpmTool {
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities {
+            dedupe: true,
+            focused_install: true,
+            workspaces: true,
+        }
+    }
+
+    async fn check_lockfile(
+        &self,
+        node: &NodeTool,
+        working_dir: &Path,
+    ) -> miette::Result<LockfileStatus> {
+        if !working_dir.join(self.get_lock_filename()).exists() {
+            return Ok(LockfileStatus::Missing);
+        }
+
+        // `npm ci` errors when the lockfile and manifests are out of sync,
+        // and a dry run will verify this without installing anything
+        let mut cmd = self.create_command(node)?;
+        cmd.args([
+            "ci",
+            "--dry-run",
+            "--ignore-scripts",
+            "--no-audit",
+            "--no-fund",
+        ]);
+
+        let output = cmd
+            .cwd(working_dir)
+            .set_error_on_nonzero(false)
+            .create_async()
+            .exec_capture_output()
+            .await?;
+
+        Ok(LockfileStatus::from_exit_code(output.status.code()))
+    }
+
     fn create_command(&self, node: &NodeTool) -> miette::Result<Command> {
         let mut cmd = if self.global {
             Command::new("npm")
@@ -156,7 +360,43 @@ impl DependencyManager<NodeTool> for NpmTool {
             return Ok(FxHashMap::default());
         };
 
-        Ok(npm::load_lockfile_dependencies(lockfile_path)?)
+        // This is called for every project, so avoid re-parsing the
+        // lockfile unless it has been modified since the last call
+        let modified = std::fs::metadata(&lockfile_path)
+            .and_then(|meta| meta.modified())
+            .ok();
+
+        let get_cached = |cache: &ResolvedDependenciesCache| {
+            cache
+                .get(&lockfile_path)
+                .filter(|(last_modified, _)| modified.is_some() && *last_modified == modified)
+                .map(|(_, deps)| Arc::clone(deps))
+        };
+
+        // Only clone the pointer while locked, and the dependencies after releasing
+        let cached = get_cached(&*self.resolved_dependencies.read().unwrap());
+
+        if let Some(deps) = cached {
+            return Ok((*deps).clone());
+        }
+
+        // Hold the write lock while parsing, so that concurrent calls
+        // wait for the result instead of parsing the lockfile again
+        let deps = {
+            let mut cache = self.resolved_dependencies.write().unwrap();
+
+            match get_cached(&*cache) {
+                Some(deps) => deps,
+                None => {
+                    let deps = Arc::new(npm::load_lockfile_dependencies(lockfile_path.clone())?);
+
+                    cache.insert(lockfile_path.clone(), (modified, Arc::clone(&deps)));
+                    deps
+                }
+            }
+        };
+
+        Ok((*deps).clone())
     }
 
     async fn install_dependencies(
@@ -164,57 +404,140 @@ impl DependencyManager<NodeTool> for NpmTool {
         node: &NodeTool,
         working_dir: &Path,
         log: bool,
+        dry_run: bool,
     ) -> miette::Result<()> {
-        let mut args = vec!["install"];
+        install_with_retry(
+            node.config.install_retry_count,
+            Duration::from_millis(node.config.install_retry_backoff),
+            || async {
+                let mut cmd = self.create_command(node)?;
+
+                cmd.args(self.get_install_args(working_dir, is_ci(), is_offline_mode()))
+                    .args(self.get_registry_args(working_dir)?)
+                    .cwd(working_dir)
+                    .set_print_command(log)
+                    // We need to handle non-zero's manually
+                    .set_error_on_nonzero(false);
+
+                if dry_run {
+                    log_dry_run(&cmd);
+
+                    return Ok(());
+                }
+
+                let mut cmd = cmd.create_async();
+
+                let output = if env::var("MOON_TEST_HIDE_INSTALL_OUTPUT").is_ok() {
+                    cmd.exec_capture_output().await?
+                } else {
+                    cmd.exec_stream_and_capture_output().await?
+                };
+
+                if let Some(error) = InstallError::from_output("npm", &output) {
+                    return Err(error.into());
+                }
+
+                Ok(())
+            },
+        )
+        .await
+    }
 
-        if is_ci() {
-            let lockfile = working_dir.join(self.get_lock_filename());
+    async fn install_focused_dependencies(
+        &self,
+        node: &NodeTool,
+        package_names: &[String],
+        production_only: bool,
+        dry_run: bool,
+    ) -> miette::Result<()> {
+        let workspace_root = get_workspace_root();
+        let mut groups = self.group_packages_by_registry(&workspace_root, package_names)?;
 
-            // npm will error if using `ci` and a lockfile does not exist!
-            if lockfile.exists() {
-                args.clear();
-                args.push("ci");
-            }
-        } else {
-            args.push("--no-audit");
+        if groups.is_empty() {
+            groups.push((self.get_registry_args(&workspace_root)?, vec![]));
         }
 
-        args.push("--no-fund");
+        for (registry_args, package_names) in &groups {
+            self.install_focused_group(
+                node,
+                registry_args,
+                package_names,
+                production_only,
+                dry_run,
+            )
+            .await?;
+        }
 
-        let mut cmd = self.create_command(node)?;
+        Ok(())
+    }
+}
 
-        cmd.args(args).cwd(working_dir).set_print_command(log);
+// Read the default `registry` from the `.npmrc` in the provided directory, while
+// expanding `${ENV}` placeholders like npm does. Scoped registries are not read.
+fn read_npmrc_registry(dir: &Path) -> miette::Result<Option<String>> {
+    let npmrc_path = dir.join(".npmrc");
 
-        let mut cmd = cmd.create_async();
+    if !npmrc_path.exists() {
+        return Ok(None);
+    }
 
-        if env::var("MOON_TEST_HIDE_INSTALL_OUTPUT").is_ok() {
-            cmd.exec_capture_output().await?;
-        } else {
-            cmd.exec_stream_output().await?;
+    for line in fs::read_file(npmrc_path)?.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        if key.trim() == "registry" {
+            return Ok(Some(expand_env_placeholders(value.trim())));
         }
+    }
 
-        Ok(())
+    Ok(None)
+}
+
+fn expand_env_placeholders(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+
+        let name = &rest[start + 2..start + end];
+
+        result.push_str(&rest[..start]);
+
+        match env::var(name) {
+            Ok(var) => result.push_str(&var),
+            Err(_) => result.push_str(&rest[start..=start + end]),
+        };
+
+        rest = &rest[start + end + 1..];
     }
 
-    async fn install_focused_dependencies(
-        &self,
-        node: &NodeTool,
-        package_names: &[String],
-        production_only: bool,
-    ) -> miette::Result<()> {
-        let mut cmd = self.create_command(node)?;
-        cmd.args(["install"]);
+    result.push_str(rest);
+    result
+}
 
-        if production_only {
-            cmd.arg("--production");
-        }
+// Map the name of each package in the workspace to its root directory.
+fn get_workspace_package_roots(
+    workspace_root: &Path,
+) -> miette::Result<FxHashMap<String, PathBuf>> {
+    let mut roots = FxHashMap::default();
 
-        for package_name in package_names {
-            cmd.args(["--workspace", package_name]);
-        }
+    let Some(globs) = get_package_manager_workspaces(workspace_root.to_path_buf())? else {
+        return Ok(roots);
+    };
 
-        cmd.create_async().exec_stream_output().await?;
+    for package_root in glob::walk(workspace_root, &globs)? {
+        if !package_root.is_dir() {
+            continue;
+        }
 
-        Ok(())
+        if let Some(name) = PackageJson::read(&package_root)?.and_then(|package| package.name) {
+            roots.insert(name, package_root);
+        }
     }
+
+    Ok(roots)
 }
@@ -1,10 +1,13 @@
-use crate::node_tool::NodeTool;
+use crate::node_tool::{NodeTool, LIFECYCLE_SCRIPTS};
 use moon_config::PnpmConfig;
 use moon_logger::debug;
 use moon_node_lang::{pnpm, LockfileDependencyVersions, PNPM};
 use moon_process::Command;
 use moon_terminal::{print_checkpoint, Checkpoint};
-use moon_tool::{get_path_env_var, DependencyManager, Tool};
+use moon_tool::{
+    get_path_env_var, install_with_retry, log_dry_run, DependencyManager, InstallError,
+    LockfileStatus, Tool, ToolCapabilities,
+};
 use moon_utils::{is_ci, semver};
 use proto::{
     async_trait,
@@ -15,6 +18,7 @@ use rustc_hash::FxHashMap;
 use starbase_utils::fs;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct PnpmTool {
@@ -35,6 +39,33 @@ impl PnpmTool {
             tool: NodeDependencyManager::new(proto, NodeDependencyManagerType::Pnpm),
         })
     }
+
+    /// Return arguments for running a `package.json` script.
+    /// Lifecycle scripts (`test`, etc) are ran without `run`.
+    pub fn get_run_script_args<'a>(&self, script: &'a str) -> Vec<&'a str> {
+        if LIFECYCLE_SCRIPTS.contains(&script) {
+            vec![script]
+        } else {
+            vec!["run", script]
+        }
+    }
+
+    /// Run a `package.json` script with pnpm.
+    pub async fn run_script(
+        &self,
+        node: &NodeTool,
+        script: &str,
+        working_dir: &Path,
+    ) -> miette::Result<()> {
+        self.create_command(node)?
+            .args(self.get_run_script_args(script))
+            .cwd(working_dir)
+            .create_async()
+            .exec_stream_output()
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -105,6 +136,37 @@ impl Tool for PnpmTool {
 
 #[async_trait]
 impl DependencyManager<NodeTool> for PnpmTool {
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities {
+            // Requires a version to determine the dedupe strategy
+            dedupe: self.config.version.is_some(),
+            focused_install: true,
+            workspaces: true,
+        }
+    }
+
+    async fn check_lockfile(
+        &self,
+        node: &NodeTool,
+        working_dir: &Path,
+    ) -> miette::Result<LockfileStatus> {
+        if !working_dir.join(self.get_lock_filename()).exists() {
+            return Ok(LockfileStatus::Missing);
+        }
+
+        let mut cmd = self.create_command(node)?;
+        cmd.args(["install", "--frozen-lockfile", "--lockfile-only"]);
+
+        let output = cmd
+            .cwd(working_dir)
+            .set_error_on_nonzero(false)
+            .create_async()
+            .exec_capture_output()
+            .await?;
+
+        Ok(LockfileStatus::from_exit_code(output.status.code()))
+    }
+
     fn create_command(&self, node: &NodeTool) -> miette::Result<Command> {
         let mut cmd = if self.global {
             Command::new("pnpm")
@@ -180,6 +242,7 @@ impl DependencyManager<NodeTool> for PnpmTool {
         node: &NodeTool,
         working_dir: &Path,
         log: bool,
+        dry_run: bool,
     ) -> miette::Result<()> {
         let mut args = vec!["install"];
 
@@ -192,19 +255,40 @@ impl DependencyManager<NodeTool> for PnpmTool {
             }
         }
 
-        let mut cmd = self.create_command(node)?;
+        install_with_retry(
+            node.config.install_retry_count,
+            Duration::from_millis(node.config.install_retry_backoff),
+            || async {
+                let mut cmd = self.create_command(node)?;
 
-        cmd.args(args).cwd(working_dir).set_print_command(log);
+                cmd.args(&args)
+                    .cwd(working_dir)
+                    .set_print_command(log)
+                    // We need to handle non-zero's manually
+                    .set_error_on_nonzero(false);
 
-        let mut cmd = cmd.create_async();
+                if dry_run {
+                    log_dry_run(&cmd);
 
-        if env::var("MOON_TEST_HIDE_INSTALL_OUTPUT").is_ok() {
-            cmd.exec_capture_output().await?;
-        } else {
-            cmd.exec_stream_output().await?;
-        }
+                    return Ok(());
+                }
 
-        Ok(())
+                let mut cmd = cmd.create_async();
+
+                let output = if env::var("MOON_TEST_HIDE_INSTALL_OUTPUT").is_ok() {
+                    cmd.exec_capture_output().await?
+                } else {
+                    cmd.exec_stream_and_capture_output().await?
+                };
+
+                if let Some(error) = InstallError::from_output("pnpm", &output) {
+                    return Err(error.into());
+                }
+
+                Ok(())
+            },
+        )
+        .await
     }
 
     async fn install_focused_dependencies(
@@ -212,27 +296,48 @@ impl DependencyManager<NodeTool> for PnpmTool {
         node: &NodeTool,
         packages: &[String],
         production_only: bool,
+        dry_run: bool,
     ) -> miette::Result<()> {
-        let mut cmd = self.create_command(node)?;
-        cmd.arg("install");
-
-        if production_only {
-            cmd.arg("--prod");
-        }
-
-        for package in packages {
-            cmd.arg(if production_only {
-                "--filter-prod"
-            } else {
-                "--filter"
-            });
-
-            // https://pnpm.io/filtering#--filter-package_name-1
-            cmd.arg(format!("{package}..."));
-        }
-
-        cmd.create_async().exec_stream_output().await?;
-
-        Ok(())
+        install_with_retry(
+            node.config.install_retry_count,
+            Duration::from_millis(node.config.install_retry_backoff),
+            || async {
+                let mut cmd = self.create_command(node)?;
+                cmd.arg("install");
+
+                if production_only {
+                    cmd.arg("--prod");
+                }
+
+                for package in packages {
+                    cmd.arg(if production_only {
+                        "--filter-prod"
+                    } else {
+                        "--filter"
+                    });
+
+                    // https://pnpm.io/filtering#--filter-package_name-1
+                    cmd.arg(format!("{package}..."));
+                }
+
+                // We need to handle non-zero's manually
+                cmd.set_error_on_nonzero(false);
+
+                if dry_run {
+                    log_dry_run(&cmd);
+
+                    return Ok(());
+                }
+
+                let output = cmd.create_async().exec_stream_and_capture_output().await?;
+
+                if let Some(error) = InstallError::from_output("pnpm", &output) {
+                    return Err(error.into());
+                }
+
+                Ok(())
+            },
+        )
+        .await
     }
 }
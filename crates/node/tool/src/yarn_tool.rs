@@ -1,10 +1,13 @@
-use crate::node_tool::NodeTool;
+use crate::node_tool::{NodeTool, LIFECYCLE_SCRIPTS};
 use moon_config::YarnConfig;
 use moon_logger::debug;
 use moon_node_lang::{yarn, LockfileDependencyVersions, YARN};
 use moon_process::Command;
 use moon_terminal::{print_checkpoint, Checkpoint};
-use moon_tool::{get_path_env_var, DependencyManager, Tool, ToolError};
+use moon_tool::{
+    get_path_env_var, install_with_retry, log_dry_run, DependencyManager, InstallError,
+    LockfileStatus, Tool, ToolCapabilities, ToolError,
+};
 use moon_utils::{get_workspace_root, is_ci};
 use proto::{
     async_trait,
@@ -16,6 +19,7 @@ use starbase_styles::color;
 use starbase_utils::fs;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct YarnTool {
@@ -45,6 +49,33 @@ impl YarnTool {
             .unwrap_or(false)
     }
 
+    /// Return arguments for running a `package.json` script.
+    /// Lifecycle scripts (`test`, etc) are ran without `run`.
+    pub fn get_run_script_args<'a>(&self, script: &'a str) -> Vec<&'a str> {
+        if LIFECYCLE_SCRIPTS.contains(&script) {
+            vec![script]
+        } else {
+            vec!["run", script]
+        }
+    }
+
+    /// Run a `package.json` script with yarn.
+    pub async fn run_script(
+        &self,
+        node: &NodeTool,
+        script: &str,
+        working_dir: &Path,
+    ) -> miette::Result<()> {
+        self.create_command(node)?
+            .args(self.get_run_script_args(script))
+            .cwd(working_dir)
+            .create_async()
+            .exec_stream_output()
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn set_version(&mut self, node: &NodeTool) -> miette::Result<()> {
         if !self.is_berry() {
             return Ok(());
@@ -151,6 +182,43 @@ impl Tool for YarnTool {
 
 #[async_trait]
 impl DependencyManager<NodeTool> for YarnTool {
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities {
+            // Requires a version to determine the dedupe strategy
+            dedupe: self.config.version.is_some(),
+            // Yarn v1 has no way to focus, so installs everything
+            focused_install: self.is_berry(),
+            workspaces: true,
+        }
+    }
+
+    async fn check_lockfile(
+        &self,
+        node: &NodeTool,
+        working_dir: &Path,
+    ) -> miette::Result<LockfileStatus> {
+        if !working_dir.join(self.get_lock_filename()).exists() {
+            return Ok(LockfileStatus::Missing);
+        }
+
+        let mut cmd = self.create_command(node)?;
+
+        if self.is_berry() {
+            cmd.args(["install", "--immutable", "--mode=update-lockfile"]);
+        } else {
+            cmd.args(["check", "--integrity"]);
+        }
+
+        let output = cmd
+            .cwd(working_dir)
+            .set_error_on_nonzero(false)
+            .create_async()
+            .exec_capture_output()
+            .await?;
+
+        Ok(LockfileStatus::from_exit_code(output.status.code()))
+    }
+
     fn create_command(&self, node: &NodeTool) -> miette::Result<Command> {
         let mut cmd = if self.global {
             Command::new("yarn")
@@ -230,6 +298,7 @@ impl DependencyManager<NodeTool> for YarnTool {
         node: &NodeTool,
         working_dir: &Path,
         log: bool,
+        dry_run: bool,
     ) -> miette::Result<()> {
         let mut args = vec!["install"];
 
@@ -247,19 +316,40 @@ impl DependencyManager<NodeTool> for YarnTool {
             }
         }
 
-        let mut cmd = self.create_command(node)?;
+        install_with_retry(
+            node.config.install_retry_count,
+            Duration::from_millis(node.config.install_retry_backoff),
+            || async {
+                let mut cmd = self.create_command(node)?;
 
-        cmd.args(args).cwd(working_dir).set_print_command(log);
+                cmd.args(&args)
+                    .cwd(working_dir)
+                    .set_print_command(log)
+                    // We need to handle non-zero's manually
+                    .set_error_on_nonzero(false);
 
-        let mut cmd = cmd.create_async();
+                if dry_run {
+                    log_dry_run(&cmd);
 
-        if env::var("MOON_TEST_HIDE_INSTALL_OUTPUT").is_ok() {
-            cmd.exec_capture_output().await?;
-        } else {
-            cmd.exec_stream_output().await?;
-        }
+                    return Ok(());
+                }
 
-        Ok(())
+                let mut cmd = cmd.create_async();
+
+                let output = if env::var("MOON_TEST_HIDE_INSTALL_OUTPUT").is_ok() {
+                    cmd.exec_capture_output().await?
+                } else {
+                    cmd.exec_stream_and_capture_output().await?
+                };
+
+                if let Some(error) = InstallError::from_output("yarn", &output) {
+                    return Err(error.into());
+                }
+
+                Ok(())
+            },
+        )
+        .await
     }
 
     async fn install_focused_dependencies(
@@ -267,13 +357,9 @@ impl DependencyManager<NodeTool> for YarnTool {
         node: &NodeTool,
         packages: &[String],
         production_only: bool,
+        dry_run: bool,
     ) -> miette::Result<()> {
-        let mut cmd = self.create_command(node)?;
-
         if self.is_berry() {
-            cmd.args(["workspaces", "focus"]);
-            cmd.args(packages);
-
             let workspace_plugin =
                 get_workspace_root().join(".yarn/plugins/@yarnpkg/plugin-workspace-tools.cjs");
 
@@ -282,16 +368,43 @@ impl DependencyManager<NodeTool> for YarnTool {
                     ToolError::RequiresPlugin("yarn plugin import workspace-tools".into()).into(),
                 );
             }
-        } else {
-            cmd.arg("install");
-        };
-
-        if production_only {
-            cmd.arg("--production");
         }
 
-        cmd.create_async().exec_stream_output().await?;
+        install_with_retry(
+            node.config.install_retry_count,
+            Duration::from_millis(node.config.install_retry_backoff),
+            || async {
+                let mut cmd = self.create_command(node)?;
 
-        Ok(())
+                if self.is_berry() {
+                    cmd.args(["workspaces", "focus"]);
+                    cmd.args(packages);
+                } else {
+                    cmd.arg("install");
+                };
+
+                if production_only {
+                    cmd.arg("--production");
+                }
+
+                // We need to handle non-zero's manually
+                cmd.set_error_on_nonzero(false);
+
+                if dry_run {
+                    log_dry_run(&cmd);
+
+                    return Ok(());
+                }
+
+                let output = cmd.create_async().exec_stream_and_capture_output().await?;
+
+                if let Some(error) = InstallError::from_output("yarn", &output) {
+                    return Err(error.into());
+                }
+
+                Ok(())
+            },
+        )
+        .await
     }
 }
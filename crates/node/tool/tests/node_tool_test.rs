@@ -0,0 +1,40 @@
+use moon_config::NodeConfig;
+use moon_node_tool::NodeTool;
+use moon_platform_runtime::Version;
+use moon_tool::Tool;
+use proto::Proto;
+use starbase_sandbox::create_empty_sandbox;
+
+mod install_size {
+    use super::*;
+
+    #[test]
+    fn returns_zero_when_global() {
+        let sandbox = create_empty_sandbox();
+        let tool = NodeTool::new(
+            &Proto::from(sandbox.path()),
+            &NodeConfig::default(),
+            &Version::new_global(),
+        )
+        .unwrap();
+
+        assert_eq!(tool.get_install_size().unwrap(), 0);
+    }
+
+    #[test]
+    fn sums_file_sizes_in_install_dir() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("tools/node/18.0.0/bin/node", "binary");
+        sandbox.create_file("tools/node/18.0.0/lib/node_modules/npm/package.json", "{}");
+
+        let mut tool = NodeTool::new(
+            &Proto::from(sandbox.path()),
+            &NodeConfig::default(),
+            &Version::new("18.0.0"),
+        )
+        .unwrap();
+        tool.tool.version = Some("18.0.0".into());
+
+        assert_eq!(tool.get_install_size().unwrap(), 8);
+    }
+}
@@ -0,0 +1,433 @@
+use moon_config::{NodeConfig, NpmConfig};
+use moon_node_tool::{NodeTool, NpmTool};
+use moon_platform_runtime::Version;
+use moon_tool::{DependencyManager, Tool, ToolCapabilities};
+use proto::Proto;
+use starbase_sandbox::create_empty_sandbox;
+
+fn create_tool(config: NpmConfig) -> NpmTool {
+    NpmTool::new(&Proto::new().unwrap(), &config).unwrap()
+}
+
+mod install_args {
+    use super::*;
+
+    #[test]
+    fn disables_audit_and_fund_by_default_locally() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), false, false),
+            vec!["install", "--no-audit", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn enables_audit() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig {
+            audit: Some(true),
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), false, false),
+            vec!["install", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn enables_fund() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig {
+            fund: true,
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), false, false),
+            vec!["install", "--no-audit"]
+        );
+    }
+
+    #[test]
+    fn uses_ci_with_lockfile() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package-lock.json", "{}");
+
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), true, false),
+            vec!["ci", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn respects_audit_in_ci() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package-lock.json", "{}");
+
+        let tool = create_tool(NpmConfig {
+            audit: Some(true),
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), true, false),
+            vec!["ci", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn disables_audit_in_ci() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package-lock.json", "{}");
+
+        let tool = create_tool(NpmConfig {
+            audit: Some(false),
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), true, false),
+            vec!["ci", "--no-audit", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn adds_offline() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), false, true),
+            vec!["install", "--offline", "--no-audit", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn adds_offline_in_ci() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package-lock.json", "{}");
+
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), true, true),
+            vec!["ci", "--offline", "--no-fund"]
+        );
+    }
+
+    #[test]
+    fn uses_install_in_ci_without_lockfile() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(
+            tool.get_install_args(sandbox.path(), true, false),
+            vec!["install", "--no-fund"]
+        );
+    }
+}
+
+mod registry_args {
+    use super::*;
+
+    #[test]
+    fn empty_when_not_configured() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig::default());
+
+        assert!(tool.get_registry_args(sandbox.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn uses_configured_registry() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig {
+            registry: Some("https://registry.company.com".into()),
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.get_registry_args(sandbox.path()).unwrap(),
+            vec!["--registry=https://registry.company.com"]
+        );
+    }
+
+    #[test]
+    fn prefers_package_npmrc_registry() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".npmrc", "# comment\nregistry = https://npm.team.com\n");
+
+        let tool = create_tool(NpmConfig {
+            registry: Some("https://registry.company.com".into()),
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.get_registry_args(sandbox.path()).unwrap(),
+            vec!["--registry=https://npm.team.com"]
+        );
+    }
+
+    #[test]
+    fn groups_projects_with_different_registries() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package.json", r#"{ "workspaces": ["packages/*"] }"#);
+        sandbox.create_file("packages/a/package.json", r#"{ "name": "a" }"#);
+        sandbox.create_file("packages/a/.npmrc", "registry=https://npm.a.com");
+        sandbox.create_file("packages/b/package.json", r#"{ "name": "b" }"#);
+        sandbox.create_file("packages/b/.npmrc", "registry=https://npm.b.com");
+        sandbox.create_file("packages/c/package.json", r#"{ "name": "c" }"#);
+
+        let tool = create_tool(NpmConfig {
+            registry: Some("https://registry.company.com".into()),
+            ..NpmConfig::default()
+        });
+
+        assert_eq!(
+            tool.group_packages_by_registry(sandbox.path(), &["a".into(), "b".into(), "c".into()])
+                .unwrap(),
+            vec![
+                (
+                    vec!["--registry=https://npm.a.com".to_string()],
+                    vec!["a".to_string()]
+                ),
+                (
+                    vec!["--registry=https://npm.b.com".to_string()],
+                    vec!["b".to_string()]
+                ),
+                (
+                    vec!["--registry=https://registry.company.com".to_string()],
+                    vec!["c".to_string()]
+                ),
+            ]
+        );
+    }
+}
+
+mod install_size {
+    use super::*;
+
+    #[test]
+    fn returns_zero_when_global() {
+        let tool = create_tool(NpmConfig::default());
+
+        assert!(tool.global);
+        assert_eq!(tool.get_install_size().unwrap(), 0);
+    }
+
+    #[test]
+    fn returns_zero_when_not_installed() {
+        let sandbox = create_empty_sandbox();
+        let mut tool = NpmTool::new(
+            &Proto::from(sandbox.path()),
+            &NpmConfig {
+                version: Some("9.0.0".into()),
+                ..NpmConfig::default()
+            },
+        )
+        .unwrap();
+        tool.tool.version = Some("9.0.0".into());
+
+        assert_eq!(tool.get_install_size().unwrap(), 0);
+    }
+
+    #[test]
+    fn sums_file_sizes_in_install_dir() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("tools/npm/9.0.0/package.json", "{}");
+        sandbox.create_file("tools/npm/9.0.0/bin/npm-cli.js", "console.log();");
+
+        let mut tool = NpmTool::new(
+            &Proto::from(sandbox.path()),
+            &NpmConfig {
+                version: Some("9.0.0".into()),
+                ..NpmConfig::default()
+            },
+        )
+        .unwrap();
+        tool.tool.version = Some("9.0.0".into());
+
+        assert_eq!(tool.get_install_size().unwrap(), 16);
+    }
+}
+
+mod run_script_args {
+    use super::*;
+
+    #[test]
+    fn always_uses_run() {
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(tool.get_run_script_args("build"), vec!["run", "build"]);
+        assert_eq!(tool.get_run_script_args("test"), vec!["run", "test"]);
+    }
+}
+
+mod resolved_dependencies {
+    use super::*;
+    use filetime::FileTime;
+
+    fn create_lockfile(version: &str) -> String {
+        format!(
+            r#"{{
+    "name": "root",
+    "lockfileVersion": 3,
+    "packages": {{
+        "node_modules/yaml": {{
+            "version": "{version}"
+        }}
+    }}
+}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn returns_empty_without_lockfile() {
+        let sandbox = create_empty_sandbox();
+        let tool = create_tool(NpmConfig::default());
+
+        assert!(tool
+            .get_resolved_dependencies(sandbox.path())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn reuses_until_lockfile_modified() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package-lock.json", create_lockfile("1.0.0"));
+        sandbox.create_file("app/package.json", "{}");
+
+        let tool = create_tool(NpmConfig::default());
+
+        let deps = tool
+            .get_resolved_dependencies(sandbox.path())
+            .await
+            .unwrap();
+
+        assert_eq!(deps.get("yaml").unwrap(), &vec!["1.0.0".to_owned()]);
+
+        // Lookups from other projects resolve to the same lockfile
+        let deps = tool
+            .get_resolved_dependencies(&sandbox.path().join("app"))
+            .await
+            .unwrap();
+
+        assert_eq!(deps.get("yaml").unwrap(), &vec!["1.0.0".to_owned()]);
+
+        // Ensure the modified time changes, regardless of the file system's precision
+        let lockfile_path = sandbox.path().join("package-lock.json");
+        let modified = FileTime::from_last_modification_time(&lockfile_path.metadata().unwrap());
+
+        sandbox.create_file("package-lock.json", create_lockfile("2.0.0"));
+
+        filetime::set_file_mtime(
+            &lockfile_path,
+            FileTime::from_unix_time(modified.unix_seconds() + 10, 0),
+        )
+        .unwrap();
+
+        let deps = tool
+            .get_resolved_dependencies(sandbox.path())
+            .await
+            .unwrap();
+
+        assert_eq!(deps.get("yaml").unwrap(), &vec!["2.0.0".to_owned()]);
+    }
+}
+
+mod dry_run {
+    use super::*;
+
+    fn create_node() -> NodeTool {
+        NodeTool::new(
+            &Proto::new().unwrap(),
+            &NodeConfig::default(),
+            &Version::new_global(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn doesnt_install_dependencies() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package.json", r#"{ "name": "root" }"#);
+
+        let node = create_node();
+
+        node.get_npm()
+            .unwrap()
+            .install_dependencies(&node, sandbox.path(), false, true)
+            .await
+            .unwrap();
+
+        assert!(!sandbox.path().join("node_modules").exists());
+        assert!(!sandbox.path().join("package-lock.json").exists());
+    }
+
+    #[tokio::test]
+    async fn doesnt_install_focused_dependencies() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package.json", r#"{ "name": "root" }"#);
+
+        let node = create_node();
+
+        node.get_npm()
+            .unwrap()
+            .install_focused_dependencies(&node, &["app".into()], true, true)
+            .await
+            .unwrap();
+
+        assert!(!sandbox.path().join("node_modules").exists());
+    }
+}
+
+mod capabilities {
+    use super::*;
+
+    #[test]
+    fn supports_dedupe_and_workspaces() {
+        let tool = create_tool(NpmConfig::default());
+
+        assert_eq!(
+            tool.capabilities(),
+            ToolCapabilities {
+                dedupe: true,
+                focused_install: true,
+                workspaces: true,
+            }
+        );
+    }
+}
+
+mod check_lockfile {
+    use super::*;
+    use moon_tool::LockfileStatus;
+
+    #[tokio::test]
+    async fn returns_missing_without_lockfile() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package.json", "{}");
+
+        let node = NodeTool::new(
+            &Proto::from(sandbox.path()),
+            &NodeConfig::default(),
+            &Version::new_global(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            create_tool(NpmConfig::default())
+                .check_lockfile(&node, sandbox.path())
+                .await
+                .unwrap(),
+            LockfileStatus::Missing
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use moon_config::PnpmConfig;
+use moon_node_tool::PnpmTool;
+use proto::Proto;
+
+fn create_tool() -> PnpmTool {
+    PnpmTool::new(&Proto::new().unwrap(), &Some(PnpmConfig::default())).unwrap()
+}
+
+mod run_script_args {
+    use super::*;
+
+    #[test]
+    fn uses_run_for_custom_scripts() {
+        let tool = create_tool();
+
+        assert_eq!(tool.get_run_script_args("build"), vec!["run", "build"]);
+        assert_eq!(tool.get_run_script_args("install"), vec!["run", "install"]);
+    }
+
+    #[test]
+    fn omits_run_for_lifecycle_scripts() {
+        let tool = create_tool();
+
+        assert_eq!(tool.get_run_script_args("start"), vec!["start"]);
+        assert_eq!(tool.get_run_script_args("test"), vec!["test"]);
+    }
+}
+
+mod check_lockfile {
+    use super::*;
+    use moon_config::NodeConfig;
+    use moon_node_tool::NodeTool;
+    use moon_platform_runtime::Version;
+    use moon_tool::{DependencyManager, LockfileStatus};
+    use starbase_sandbox::create_empty_sandbox;
+
+    #[tokio::test]
+    async fn returns_missing_without_lockfile() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package.json", "{}");
+
+        let node = NodeTool::new(
+            &Proto::from(sandbox.path()),
+            &NodeConfig::default(),
+            &Version::new_global(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            create_tool()
+                .check_lockfile(&node, sandbox.path())
+                .await
+                .unwrap(),
+            LockfileStatus::Missing
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use moon_config::YarnConfig;
+use moon_node_tool::YarnTool;
+use moon_tool::DependencyManager;
+use proto::Proto;
+
+fn create_tool() -> YarnTool {
+    YarnTool::new(&Proto::new().unwrap(), &Some(YarnConfig::default())).unwrap()
+}
+
+fn create_tool_with_version(version: &str) -> YarnTool {
+    YarnTool::new(
+        &Proto::new().unwrap(),
+        &Some(YarnConfig {
+            version: Some(version.into()),
+            ..YarnConfig::default()
+        }),
+    )
+    .unwrap()
+}
+
+mod run_script_args {
+    use super::*;
+
+    #[test]
+    fn uses_run_for_custom_scripts() {
+        let tool = create_tool();
+
+        assert_eq!(tool.get_run_script_args("build"), vec!["run", "build"]);
+        assert_eq!(tool.get_run_script_args("install"), vec!["run", "install"]);
+    }
+
+    #[test]
+    fn omits_run_for_lifecycle_scripts() {
+        let tool = create_tool();
+
+        assert_eq!(tool.get_run_script_args("start"), vec!["start"]);
+        assert_eq!(tool.get_run_script_args("test"), vec!["test"]);
+    }
+}
+
+mod capabilities {
+    use super::*;
+
+    #[test]
+    fn cant_dedupe_without_version() {
+        assert!(!create_tool().capabilities().dedupe);
+    }
+
+    #[test]
+    fn cant_focus_with_v1() {
+        let caps = create_tool_with_version("1.22.0").capabilities();
+
+        assert!(caps.dedupe);
+        assert!(!caps.focused_install);
+        assert!(caps.workspaces);
+    }
+
+    #[test]
+    fn can_focus_with_berry() {
+        let caps = create_tool_with_version("3.6.0").capabilities();
+
+        assert!(caps.dedupe);
+        assert!(caps.focused_install);
+    }
+}
+
+mod check_lockfile {
+    use super::*;
+    use moon_config::NodeConfig;
+    use moon_node_tool::NodeTool;
+    use moon_platform_runtime::Version;
+    use moon_tool::LockfileStatus;
+    use starbase_sandbox::create_empty_sandbox;
+
+    #[tokio::test]
+    async fn returns_missing_without_lockfile() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("package.json", "{}");
+
+        let node = NodeTool::new(
+            &Proto::from(sandbox.path()),
+            &NodeConfig::default(),
+            &Version::new_global(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            create_tool()
+                .check_lockfile(&node, sandbox.path())
+                .await
+                .unwrap(),
+            LockfileStatus::Missing
+        );
+    }
+}
@@ -3,7 +3,7 @@ use moon_action_context::ActionContext;
 use moon_common::{is_ci, Id};
 use moon_config::{
     BinEntry, HasherConfig, PlatformType, ProjectConfig, ProjectsAliasesMap, ProjectsSourcesMap,
-    RustConfig,
+    RustConfig, TaskOptionsConfig,
 };
 use moon_hasher::HashSet;
 use moon_logger::{debug, map_list};
@@ -134,6 +134,14 @@ impl Platform for RustPlatform {
         Ok(())
     }
 
+    fn get_task_options_defaults(&self) -> Option<TaskOptionsConfig> {
+        // Compilation is expensive, so always cache
+        Some(TaskOptionsConfig {
+            cache: Some(true),
+            ..TaskOptionsConfig::default()
+        })
+    }
+
     // TOOLCHAIN
 
     fn is_toolchain_enabled(&self) -> miette::Result<bool> {
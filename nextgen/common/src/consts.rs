@@ -6,6 +6,8 @@ pub const BIN_NAME: &str = "moon";
 
 pub const CONFIG_DIRNAME: &str = ".moon";
 
+pub const IGNORE_FILENAME: &str = ".moonignore";
+
 pub const CONFIG_TOOLCHAIN_FILENAME: &str = "toolchain.yml";
 
 pub const CONFIG_WORKSPACE_FILENAME: &str = "workspace.yml";
@@ -0,0 +1,38 @@
+use crate::consts::IGNORE_FILENAME;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+static IGNORE_PATTERNS: Lazy<Mutex<FxHashMap<PathBuf, Arc<Vec<String>>>>> =
+    Lazy::new(|| Mutex::new(FxHashMap::default()));
+
+fn parse_ignore_patterns(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_owned())
+        .collect()
+}
+
+/// Load glob patterns from the `.moonignore` file in the workspace root,
+/// which are used to exclude files from all inputs and file groups.
+/// Empty lines and comments (`#`) are skipped. The file is only read
+/// once per workspace root, and is optional.
+pub fn load_ignore_patterns(workspace_root: &Path) -> Arc<Vec<String>> {
+    let mut cache = IGNORE_PATTERNS.lock().unwrap();
+
+    Arc::clone(
+        cache
+            .entry(workspace_root.to_path_buf())
+            .or_insert_with(|| {
+                Arc::new(
+                    fs::read_to_string(workspace_root.join(IGNORE_FILENAME))
+                        .map(|content| parse_ignore_patterns(&content))
+                        .unwrap_or_default(),
+                )
+            }),
+    )
+}
@@ -1,10 +1,12 @@
 pub mod consts;
 mod env;
 mod id;
+mod ignore;
 mod macros;
 pub mod path;
 
 pub use env::*;
 pub use id::*;
+pub use ignore::*;
 pub use macros::*;
 pub use starbase_styles::*;
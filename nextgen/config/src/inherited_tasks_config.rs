@@ -1,5 +1,5 @@
 use crate::language_platform::{LanguageType, PlatformType};
-use crate::project::{validate_deps, TaskConfig};
+use crate::project::{validate_deps, TaskConfig, TaskOptionsConfig};
 use crate::project_config::ProjectType;
 use crate::shapes::InputPath;
 use moon_common::cacheable;
@@ -8,9 +8,10 @@ use moon_target::Target;
 use once_map::OnceMap;
 use rustc_hash::FxHashMap;
 use schematic::{merge, validate, Config, ConfigError, ConfigLoader, PartialConfig};
+use std::collections::BTreeMap;
 use std::fs;
 use std::hash::Hash;
-use std::{collections::BTreeMap, path::Path};
+use std::path::{Path, PathBuf};
 
 pub fn merge_fxhashmap<K, V, C>(
     mut prev: FxHashMap<K, V>,
@@ -49,6 +50,10 @@ cacheable!(
         #[setting(merge = merge::append_vec)]
         pub implicit_inputs: Vec<InputPath>,
 
+        // Named task options that can be referenced with `optionsPreset`
+        #[setting(nested, merge = merge_fxhashmap)]
+        pub options_presets: FxHashMap<Id, TaskOptionsConfig>,
+
         #[setting(nested, merge = merge::merge_btreemap)]
         pub tasks: BTreeMap<Id, TaskConfig>,
     }
@@ -213,7 +218,37 @@ impl InheritedTasksManager {
         project: &ProjectType,
         tags: &[Id],
     ) -> miette::Result<InheritedTasksResult> {
-        let lookup_order = self.get_lookup_order(platform, language, project, tags);
+        self.get_inherited_config_with_files(platform, language, project, tags, &[])
+    }
+
+    /// Like [`get_inherited_config`], but will also inherit from the explicitly
+    /// named `.moon/tasks/*.yml` files, after the automatically resolved files.
+    pub fn get_inherited_config_with_files(
+        &self,
+        platform: &PlatformType,
+        language: &LanguageType,
+        project: &ProjectType,
+        tags: &[Id],
+        files: &[String],
+    ) -> miette::Result<InheritedTasksResult> {
+        let mut lookup_order = self.get_lookup_order(platform, language, project, tags);
+
+        for file in files {
+            let name = file.strip_suffix(".yml").unwrap_or(file);
+
+            if !self.configs.contains_key(name) {
+                return Err(ConfigError::MissingFile(PathBuf::from(format!(
+                    "{}/tasks/{name}.yml",
+                    consts::CONFIG_DIRNAME
+                )))
+                .into());
+            }
+
+            if !lookup_order.iter().any(|lookup| lookup == name) {
+                lookup_order.push(name.to_owned());
+            }
+        }
+
         let lookup_key = lookup_order.join(":");
 
         // Cache the result as this lookup may be the same for a large number of projects,
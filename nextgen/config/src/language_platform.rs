@@ -1,7 +1,25 @@
 use moon_common::Id;
-use schematic::{derive_enum, ConfigEnum};
+use rustc_hash::FxHashSet;
+use schematic::ConfigEnum;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+static WARNED_ALIASES: OnceLock<Mutex<FxHashSet<String>>> = OnceLock::new();
+
+// Only warn once per alias, as the same value is typically
+// found in many project configs
+fn warn_deprecated_alias(kind: &str, alias: &str, current: &str) {
+    let mut warned = WARNED_ALIASES
+        .get_or_init(|| Mutex::new(FxHashSet::default()))
+        .lock()
+        .unwrap();
+
+    if warned.insert(format!("{kind}:{alias}")) {
+        warn!("The {kind} \"{alias}\" is deprecated, use \"{current}\" instead");
+    }
+}
 
 #[derive(Clone, ConfigEnum, Debug, Default, Eq, PartialEq)]
 pub enum LanguageType {
@@ -33,7 +51,22 @@ impl<'de> Deserialize<'de> for LanguageType {
         D: Deserializer<'de>,
     {
         match String::deserialize(deserializer) {
-            Ok(buffer) => LanguageType::from_str(&buffer).map_err(de::Error::custom),
+            Ok(buffer) => {
+                let current = match buffer.as_str() {
+                    "golang" => Some(LanguageType::Go),
+                    "js" => Some(LanguageType::JavaScript),
+                    "ts" => Some(LanguageType::TypeScript),
+                    _ => None,
+                };
+
+                if let Some(current) = current {
+                    warn_deprecated_alias("language", &buffer, &current.to_string());
+
+                    return Ok(current);
+                }
+
+                LanguageType::from_str(&buffer).map_err(de::Error::custom)
+            }
             Err(error) => {
                 // Not aware of another way to handle nulls/undefined
                 if error.to_string().contains("invalid type: null") {
@@ -56,17 +89,55 @@ impl Serialize for LanguageType {
     }
 }
 
-derive_enum!(
-    #[derive(ConfigEnum, Copy, Default, Hash)]
-    pub enum PlatformType {
-        Deno,
-        Node,
-        Rust,
-        System,
-        #[default]
-        Unknown,
+impl LanguageType {
+    /// Return true if tasks for this language can be ran on the provided platform.
+    /// The system platform can run any language, while other platforms are
+    /// limited to the languages they support.
+    pub fn is_compatible_with(&self, platform: &PlatformType) -> bool {
+        match platform {
+            PlatformType::System | PlatformType::Unknown => true,
+            PlatformType::Deno | PlatformType::Node => matches!(
+                self,
+                LanguageType::JavaScript | LanguageType::TypeScript | LanguageType::Unknown
+            ),
+            PlatformType::Rust => matches!(self, LanguageType::Rust | LanguageType::Unknown),
+        }
+    }
+}
+
+#[derive(Clone, ConfigEnum, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlatformType {
+    Deno,
+    Node,
+    Rust,
+    System,
+    #[default]
+    Unknown,
+}
+
+// Required to handle deprecated aliases
+impl<'de> Deserialize<'de> for PlatformType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let buffer = String::deserialize(deserializer)?;
+
+        let current = match buffer.as_str() {
+            "javascript" | "nodejs" => Some(PlatformType::Node),
+            _ => None,
+        };
+
+        if let Some(current) = current {
+            warn_deprecated_alias("platform", &buffer, &current.to_string());
+
+            return Ok(current);
+        }
+
+        PlatformType::from_str(&buffer).map_err(de::Error::custom)
     }
-);
+}
 
 impl PlatformType {
     pub fn is_system(&self) -> bool {
@@ -153,4 +224,72 @@ mod tests {
             LanguageType::Other(Id::raw("dotnet")),
         );
     }
+
+    #[test]
+    fn checks_lang_platform_compatibility() {
+        assert!(LanguageType::TypeScript.is_compatible_with(&PlatformType::Node));
+        assert!(LanguageType::JavaScript.is_compatible_with(&PlatformType::Deno));
+        assert!(LanguageType::Rust.is_compatible_with(&PlatformType::Rust));
+        assert!(LanguageType::Rust.is_compatible_with(&PlatformType::System));
+        assert!(LanguageType::Unknown.is_compatible_with(&PlatformType::Node));
+
+        assert!(!LanguageType::Rust.is_compatible_with(&PlatformType::Node));
+        assert!(!LanguageType::TypeScript.is_compatible_with(&PlatformType::Rust));
+        assert!(!LanguageType::Go.is_compatible_with(&PlatformType::Deno));
+        assert!(!LanguageType::Other(Id::raw("dotnet")).is_compatible_with(&PlatformType::Node));
+    }
+
+    fn has_warned(key: &str) -> bool {
+        WARNED_ALIASES
+            .get()
+            .is_some_and(|warned| warned.lock().unwrap().contains(key))
+    }
+
+    #[test]
+    fn deserializes_deprecated_lang_aliases() {
+        for (alias, current) in [
+            ("golang", LanguageType::Go),
+            ("js", LanguageType::JavaScript),
+            ("ts", LanguageType::TypeScript),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<LanguageType>(&format!("\"{alias}\"")).unwrap(),
+                current,
+            );
+            assert!(has_warned(&format!("language:{alias}")));
+        }
+    }
+
+    #[test]
+    fn deserializes_platform_to_enum() {
+        assert_eq!(
+            serde_json::from_str::<PlatformType>("\"node\"").unwrap(),
+            PlatformType::Node,
+        );
+        assert_eq!(
+            serde_json::from_str::<PlatformType>("\"system\"").unwrap(),
+            PlatformType::System,
+        );
+        assert!(serde_json::from_str::<PlatformType>("\"unknown-platform\"").is_err());
+    }
+
+    #[test]
+    fn deserializes_deprecated_platform_aliases() {
+        for alias in ["javascript", "nodejs"] {
+            assert_eq!(
+                serde_json::from_str::<PlatformType>(&format!("\"{alias}\"")).unwrap(),
+                PlatformType::Node,
+            );
+            assert!(has_warned(&format!("platform:{alias}")));
+        }
+    }
+
+    #[test]
+    fn doesnt_warn_for_current_values() {
+        serde_json::from_str::<LanguageType>("\"rust\"").unwrap();
+        serde_json::from_str::<PlatformType>("\"rust\"").unwrap();
+
+        assert!(!has_warned("language:rust"));
+        assert!(!has_warned("platform:rust"));
+    }
 }
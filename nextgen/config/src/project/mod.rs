@@ -2,10 +2,14 @@ mod dep_config;
 mod overrides_config;
 mod owners_config;
 mod task_config;
+mod task_dependency;
 mod task_options_config;
+mod tasks_validator;
 
 pub use dep_config::*;
 pub use overrides_config::*;
 pub use owners_config::*;
 pub use task_config::*;
+pub use task_dependency::*;
 pub use task_options_config::*;
+pub use tasks_validator::*;
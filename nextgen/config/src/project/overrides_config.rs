@@ -1,9 +1,18 @@
 use crate::validate::validate_semver;
+use crate::workspace::{HasherOptimization, HasherWalkStrategy};
 use moon_common::cacheable;
 use moon_common::Id;
 use rustc_hash::FxHashMap;
 use schematic::Config;
 
+cacheable!(
+    #[derive(Clone, Config, Debug)]
+    pub struct ProjectHasherConfig {
+        pub optimization: Option<HasherOptimization>,
+        pub walk_strategy: Option<HasherWalkStrategy>,
+    }
+);
+
 cacheable!(
     #[derive(Clone, Config, Debug)]
     pub struct ProjectToolchainCommonToolConfig {
@@ -55,6 +64,9 @@ cacheable!(
         // [...] = Specific includes
         pub include: Option<Vec<Id>>,
 
+        // Additional `.moon/tasks/*.yml` files to inherit from, by name
+        pub include_files: Vec<String>,
+
         pub rename: FxHashMap<Id, Id>,
     }
 );
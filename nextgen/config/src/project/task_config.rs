@@ -1,12 +1,20 @@
 use crate::language_platform::PlatformType;
-use crate::project::{PartialTaskOptionsConfig, TaskOptionsConfig};
+use crate::portable_path::is_glob;
+use crate::project::{
+    PartialTaskOptionsConfig, TaskDependency, TaskFileDependency, TaskOptionsConfig,
+};
 use crate::shapes::{InputPath, OutputPath};
-use moon_common::cacheable;
+use crate::types::TasksConfigsMap;
+use crate::validate::validate_no_absolute_path;
+use moon_common::{cacheable, Id};
 use moon_target::{Target, TargetScope};
 use rustc_hash::FxHashMap;
 use schematic::{
-    derive_enum, merge, Config, ConfigEnum, ConfigLoader, Format, PathSegment, ValidateError,
+    derive_enum, merge, Config, ConfigEnum, ConfigError, ConfigLoader, Format, PartialConfig,
+    PathSegment, ValidateError,
 };
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
 
 fn validate_command<D, C>(args: &str, _task: &D, _ctx: &C) -> Result<(), ValidateError> {
     let mut parts = args.split(' ');
@@ -31,9 +39,69 @@ fn validate_command_list<D, C>(args: &[String], _task: &D, _ctx: &C) -> Result<(
     Ok(())
 }
 
-pub fn validate_deps<D, C>(deps: &[Target], _data: &D, _context: &C) -> Result<(), ValidateError> {
+fn validate_args_map<D, C>(
+    args: &BTreeMap<String, String>,
+    _task: &D,
+    _ctx: &C,
+) -> Result<(), ValidateError> {
+    if args.is_empty() {
+        return Err(ValidateError::new(
+            "at least one named argument is required; omit `args` otherwise",
+        ));
+    }
+
+    for key in args.keys() {
+        if key.trim().is_empty() {
+            return Err(ValidateError::with_segment(
+                "an argument name is required",
+                PathSegment::Key(key.to_owned()),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Named argument maps only make sense for `args`, as a command
+// requires a binary to execute
+fn validate_command_args<D, C>(
+    command: &PartialTaskCommandArgs,
+    _task: &D,
+    _ctx: &C,
+) -> Result<(), ValidateError> {
+    if matches!(command, PartialTaskCommandArgs::Map(_)) {
+        return Err(ValidateError::new(
+            "a map of arguments is only supported for `args`, not a command",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_command_by_env<D, C>(
+    commands: &FxHashMap<String, PartialTaskCommandArgs>,
+    task: &D,
+    ctx: &C,
+) -> Result<(), ValidateError> {
+    for (env, command) in commands {
+        validate_command_args(command, task, ctx).map_err(|error| {
+            ValidateError::with_segment(error.message, PathSegment::Key(env.to_owned()))
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn validate_deps<D, C>(
+    deps: &[TaskDependency],
+    _data: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
     for (i, dep) in deps.iter().enumerate() {
-        if matches!(dep.scope, TargetScope::All | TargetScope::Tag(_)) {
+        if dep
+            .as_target()
+            .is_some_and(|target| matches!(target.scope, TargetScope::All))
+        {
             return Err(ValidateError::with_segment(
                 "target scope not supported as a task dependency",
                 PathSegment::Index(i),
@@ -44,11 +112,228 @@ pub fn validate_deps<D, C>(deps: &[Target], _data: &D, _context: &C) -> Result<(
     Ok(())
 }
 
+pub fn validate_extends<D, C>(
+    extends: &Target,
+    _data: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    if !matches!(extends.scope, TargetScope::OwnSelf) {
+        return Err(ValidateError::new(
+            "only tasks within the same project can be extended; use a task ID or ~:task",
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_inputs<D, C>(
+    inputs: &[InputPath],
+    _data: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    for (i, input) in inputs.iter().enumerate() {
+        if matches!(
+            input,
+            InputPath::EnvVar(_) | InputPath::TokenFunc(_) | InputPath::TokenVar(_)
+        ) {
+            continue;
+        }
+
+        let value: String = input.to_owned().into();
+
+        validate_no_absolute_path(value)
+            .map_err(|error| ValidateError::with_segment(error.message, PathSegment::Index(i)))?;
+    }
+
+    Ok(())
+}
+
+// Compare a single path segment, where either side may be a glob. Only `*` is
+// matched, other glob syntax is assumed to match anything.
+fn segments_match(a: &str, b: &str) -> bool {
+    let (pattern, value) = match (is_glob(a), is_glob(b)) {
+        (false, false) => return a == b,
+        (true, true) => return true,
+        (true, false) => (a, b),
+        (false, true) => (b, a),
+    };
+
+    if pattern.contains('{') || pattern.contains('[') {
+        return true;
+    }
+
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    let last = parts.len() - 1;
+    let mut rest = value;
+
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(remaining) => rest = remaining,
+                None => return false,
+            }
+        } else if i == last {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    rest.is_empty()
+}
+
+// Determine whether an output structurally covers an input, by walking both
+// paths segment by segment. An output covers an input when it is equal to,
+// or a parent of, the input. Broad input globs (`**/*`) are not considered
+// overlapping, otherwise every task with default inputs would fail.
+fn output_overlaps_input(output: &str, input: &str) -> bool {
+    segments_overlap(
+        &split_path_segments(output),
+        &split_path_segments(input),
+        is_glob(input),
+    )
+}
+
+fn segments_overlap(output: &[&str], input: &[&str], input_is_glob: bool) -> bool {
+    match (output.first(), input.first()) {
+        (Some(&"**"), _) => {
+            let rest = &output[1..];
+
+            // A trailing `**` covers everything below it, otherwise it matches
+            // zero or more segments, and the rest must match the input's tail
+            rest.is_empty()
+                || (0..input.len())
+                    .any(|skip| segments_overlap(rest, &input[skip..], input_is_glob))
+        }
+        (_, Some(&"**")) => false,
+        (Some(out), Some(inp)) => {
+            segments_match(out, inp) && segments_overlap(&output[1..], &input[1..], input_is_glob)
+        }
+        // Output is the input, or a parent directory of it
+        (None, _) => true,
+        // Input is a directory that contains the output
+        (Some(_), None) => !input_is_glob,
+    }
+}
+
+fn split_path_segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect()
+}
+
+pub fn validate_outputs<C>(
+    outputs: &[OutputPath],
+    data: &PartialTaskConfig,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    for (i, output) in outputs.iter().enumerate() {
+        if matches!(output, OutputPath::TokenFunc(_)) {
+            continue;
+        }
+
+        let value: String = output.to_owned().into();
+
+        validate_no_absolute_path(value)
+            .map_err(|error| ValidateError::with_segment(error.message, PathSegment::Index(i)))?;
+    }
+
+    if data
+        .options
+        .as_ref()
+        .is_some_and(|options| options.allow_output_overlap == Some(true))
+    {
+        return Ok(());
+    }
+
+    let Some(inputs) = &data.inputs else {
+        return Ok(());
+    };
+
+    for (i, output) in outputs.iter().enumerate() {
+        for input in inputs {
+            let overlaps = match (output, input) {
+                (
+                    OutputPath::ProjectFile(out) | OutputPath::ProjectGlob(out),
+                    InputPath::ProjectFile(inp) | InputPath::ProjectGlob(inp),
+                )
+                | (
+                    OutputPath::WorkspaceFile(out) | OutputPath::WorkspaceGlob(out),
+                    InputPath::WorkspaceFile(inp) | InputPath::WorkspaceGlob(inp),
+                ) => !inp.starts_with('!') && output_overlaps_input(out, inp),
+                _ => false,
+            };
+
+            if overlaps {
+                let output: String = output.clone().into();
+                let input: String = input.clone().into();
+
+                return Err(ValidateError::with_segment(
+                    format!(
+                        "output {output} overlaps with input {input}, which will cause the task to invalidate its own cache; enable the `allowOutputOverlap` option if this is intended",
+                    ),
+                    PathSegment::Index(i),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_artifact_name<D, C>(name: &str, _data: &D, _ctx: &C) -> Result<(), ValidateError> {
+    if name.trim().is_empty() {
+        return Err(ValidateError::new("an artifact name is required"));
+    }
+
+    Ok(())
+}
+
+fn validate_artifact_path<D, C>(path: &str, _data: &D, _ctx: &C) -> Result<(), ValidateError> {
+    if path.is_empty() {
+        return Err(ValidateError::new("an artifact path is required"));
+    }
+
+    validate_no_absolute_path(path)
+}
+
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default)]
+    pub enum TaskArtifactKind {
+        Archive,
+        Binary,
+        Container,
+        #[default]
+        File,
+        Package,
+    }
+);
+
+cacheable!(
+    /// Metadata about an artifact produced by a task, for consumption by
+    /// external tooling (release automation, etc). Does not affect execution.
+    #[derive(Clone, Config, Debug, Eq, PartialEq)]
+    pub struct TaskArtifactConfig {
+        #[setting(validate = validate_artifact_name)]
+        pub name: String,
+
+        pub kind: TaskArtifactKind,
+
+        // Project relative file path or glob
+        #[setting(validate = validate_artifact_path)]
+        pub path: String,
+    }
+);
+
 derive_enum!(
     #[derive(ConfigEnum, Copy, Default)]
     pub enum TaskType {
         Build,
         Run,
+        Serve,
         #[default]
         Test,
     }
@@ -56,7 +341,10 @@ derive_enum!(
 
 cacheable!(
     #[derive(Clone, Config, Debug, Eq, PartialEq)]
-    #[serde(untagged, expecting = "expected a string or a list of strings")]
+    #[serde(
+        untagged,
+        expecting = "expected a string, a list of strings, or a map of strings"
+    )]
     pub enum TaskCommandArgs {
         #[setting(default, null)]
         None,
@@ -64,6 +352,9 @@ cacheable!(
         String(String),
         #[setting(validate = validate_command_list)]
         List(Vec<String>),
+        // Flattened to `--key value` pairs, in key order
+        #[setting(validate = validate_args_map)]
+        Map(BTreeMap<String, String>),
     }
 );
 
@@ -71,33 +362,52 @@ cacheable!(
     #[derive(Clone, Config, Debug, Eq, PartialEq)]
     pub struct TaskConfig {
         #[setting(nested)]
+        pub artifacts: Vec<TaskArtifactConfig>,
+
+        #[setting(nested, validate = validate_command_args)]
         pub command: TaskCommandArgs,
 
+        // Overrides `command` when `MOON_ENV` matches the key
+        #[setting(nested, validate = validate_command_by_env)]
+        pub command_by_env: FxHashMap<String, TaskCommandArgs>,
+
         #[setting(nested)]
         pub args: TaskCommandArgs,
 
         #[setting(validate = validate_deps)]
-        pub deps: Vec<Target>,
+        pub deps: Vec<TaskDependency>,
 
         pub env: FxHashMap<String, String>,
 
-        #[setting(skip, merge = merge::append_vec)]
+        // Another task in the same project to inherit from
+        #[setting(validate = validate_extends)]
+        pub extends: Option<Target>,
+
+        #[setting(validate = validate_inputs, merge = merge::append_vec)]
         pub global_inputs: Vec<InputPath>,
 
         // None = All inputs (**/*)
         // [] = No inputs
         // [...] = Specific inputs
+        #[setting(validate = validate_inputs)]
         pub inputs: Option<Vec<InputPath>>,
 
         pub local: Option<bool>,
 
+        #[setting(validate = validate_outputs)]
         pub outputs: Option<Vec<OutputPath>>,
 
         #[setting(nested)]
         pub options: TaskOptionsConfig,
 
+        // Name of a preset in `optionsPresets` to merge into `options`
+        pub options_preset: Option<Id>,
+
         pub platform: PlatformType,
 
+        // Explicit toolchain to run with, otherwise derived from platform
+        pub toolchain: Option<Id>,
+
         #[serde(rename = "type")]
         pub type_of: Option<TaskType>,
     }
@@ -111,4 +421,200 @@ impl TaskConfig {
 
         Ok(result.config)
     }
+
+    /// Parse a map of task IDs to task configurations (the `tasks` setting in
+    /// `moon.yml`). Bails on the first invalid task, with an error that names
+    /// the task ID. Use [`validate_tasks_configs`](crate::validate_tasks_configs)
+    /// to report all invalid tasks instead.
+    pub fn parse_map<T: AsRef<str>>(code: T) -> miette::Result<TasksConfigsMap> {
+        let code = code.as_ref();
+        let mut tasks = TasksConfigsMap::new();
+
+        if code.trim().is_empty() {
+            return Ok(tasks);
+        }
+
+        for (key, value) in parse_tasks_mapping(code)? {
+            let (id, config) = parse_task_entry(key, value)?;
+
+            tasks.insert(id, config);
+        }
+
+        Ok(tasks)
+    }
+}
+
+// Duplicate task IDs are a syntax error when parsing the mapping
+pub(crate) fn parse_tasks_mapping(code: &str) -> miette::Result<Mapping> {
+    serde_yaml::from_str(code).map_err(|error| {
+        miette::miette!(
+            code = "config::tasks::parse_failed",
+            "Failed to parse tasks. {error}"
+        )
+    })
+}
+
+// Parse a single entry of a tasks mapping, attributing errors to the task ID
+pub(crate) fn parse_task_entry(key: Value, value: Value) -> miette::Result<(Id, TaskConfig)> {
+    let Value::String(key) = key else {
+        return Err(miette::miette!(
+            code = "config::tasks::invalid_id",
+            "Task IDs must be strings."
+        ));
+    };
+
+    let id = Id::new(key)?;
+
+    let config = serde_yaml::to_string(&value)
+        .map_err(|error| miette::miette!("{error}"))
+        .and_then(TaskConfig::parse)
+        .map_err(|error| error.wrap_err(format!("Invalid task {id}.")))?;
+
+    Ok((id, config))
+}
+
+/// Fluently assembles a [`PartialTaskConfig`] programmatically (for codegen,
+/// migrations, etc). The built partial can be merged with other partials,
+/// or finalized into a [`TaskConfig`] with defaults applied and validated.
+#[derive(Debug, Default)]
+pub struct TaskConfigBuilder {
+    partial: PartialTaskConfig,
+}
+
+impl TaskConfigBuilder {
+    pub fn new() -> Self {
+        TaskConfigBuilder::default()
+    }
+
+    /// Set the command as a single string, which may include arguments.
+    pub fn command<T: AsRef<str>>(mut self, command: T) -> Self {
+        self.partial.command = Some(PartialTaskCommandArgs::String(command.as_ref().to_owned()));
+        self
+    }
+
+    /// Set the command as a list, where the first item is the binary.
+    pub fn command_list<I, V>(mut self, command: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: AsRef<str>,
+    {
+        self.partial.command = Some(PartialTaskCommandArgs::List(
+            command.into_iter().map(|c| c.as_ref().to_owned()).collect(),
+        ));
+        self
+    }
+
+    /// Append an artifact to the current list of artifacts.
+    pub fn artifact(mut self, artifact: PartialTaskArtifactConfig) -> Self {
+        self.partial.artifacts.get_or_insert(vec![]).push(artifact);
+        self
+    }
+
+    pub fn args<I, V>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: AsRef<str>,
+    {
+        self.partial.args = Some(PartialTaskCommandArgs::List(
+            args.into_iter().map(|a| a.as_ref().to_owned()).collect(),
+        ));
+        self
+    }
+
+    /// Append a dependency to the current list of dependencies.
+    pub fn dep(mut self, dep: Target) -> Self {
+        self.partial
+            .deps
+            .get_or_insert(vec![])
+            .push(TaskDependency::Target(dep));
+        self
+    }
+
+    pub fn deps<I: IntoIterator<Item = Target>>(mut self, deps: I) -> Self {
+        self.partial.deps = Some(deps.into_iter().map(TaskDependency::Target).collect());
+        self
+    }
+
+    /// Append a dependency on a file produced by an external process.
+    pub fn file_dep(mut self, dep: TaskFileDependency) -> Self {
+        self.partial
+            .deps
+            .get_or_insert(vec![])
+            .push(TaskDependency::File(dep));
+        self
+    }
+
+    pub fn extends(mut self, task: Target) -> Self {
+        self.partial.extends = Some(task);
+        self
+    }
+
+    /// Insert an environment variable into the current map of variables.
+    pub fn env<K: AsRef<str>, V: AsRef<str>>(mut self, key: K, value: V) -> Self {
+        self.partial
+            .env
+            .get_or_insert_with(FxHashMap::default)
+            .insert(key.as_ref().to_owned(), value.as_ref().to_owned());
+        self
+    }
+
+    pub fn inputs<I: IntoIterator<Item = InputPath>>(mut self, inputs: I) -> Self {
+        self.partial.inputs = Some(inputs.into_iter().collect());
+        self
+    }
+
+    pub fn local(mut self, local: bool) -> Self {
+        self.partial.local = Some(local);
+        self
+    }
+
+    pub fn outputs<I: IntoIterator<Item = OutputPath>>(mut self, outputs: I) -> Self {
+        self.partial.outputs = Some(outputs.into_iter().collect());
+        self
+    }
+
+    pub fn options(mut self, options: PartialTaskOptionsConfig) -> Self {
+        self.partial.options = Some(options);
+        self
+    }
+
+    pub fn options_preset(mut self, preset: Id) -> Self {
+        self.partial.options_preset = Some(preset);
+        self
+    }
+
+    pub fn platform(mut self, platform: PlatformType) -> Self {
+        self.partial.platform = Some(platform);
+        self
+    }
+
+    pub fn toolchain(mut self, toolchain: Id) -> Self {
+        self.partial.toolchain = Some(toolchain);
+        self
+    }
+
+    pub fn type_of(mut self, type_of: TaskType) -> Self {
+        self.partial.type_of = Some(type_of);
+        self
+    }
+
+    /// Return the assembled partial configuration.
+    pub fn build(self) -> PartialTaskConfig {
+        self.partial
+    }
+
+    /// Apply default values to the assembled partial configuration,
+    /// validate it, and convert it into a full configuration.
+    pub fn finalize(self) -> miette::Result<TaskConfig> {
+        let partial = self.partial.finalize(&())?;
+
+        partial
+            .validate(&())
+            .map_err(|error| ConfigError::Validator {
+                config: "task".into(),
+                error,
+            })?;
+
+        Ok(TaskConfig::from_partial(partial))
+    }
 }
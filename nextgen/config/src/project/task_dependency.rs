@@ -0,0 +1,110 @@
+use crate::portable_path::{FilePath, Portable};
+use moon_common::cacheable;
+use moon_target::Target;
+use schematic::{Config, SchemaType, Schematic};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_yaml::Value;
+
+cacheable!(
+    #[derive(Clone, Config, Debug, Eq, PartialEq)]
+    pub struct TaskFileDependency {
+        // Relative from the project root
+        pub file: FilePath,
+
+        // In seconds
+        #[setting(default = 30)]
+        pub timeout: u64,
+    }
+);
+
+/// A dependency of a task, that must be satisfied before the task runs.
+/// Either another task (by target), or a file produced by an external process.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TaskDependency {
+    File(TaskFileDependency),
+    Target(Target),
+}
+
+impl TaskDependency {
+    pub fn as_target(&self) -> Option<&Target> {
+        match self {
+            TaskDependency::Target(target) => Some(target),
+            TaskDependency::File(_) => None,
+        }
+    }
+}
+
+impl From<Target> for TaskDependency {
+    fn from(target: Target) -> Self {
+        TaskDependency::Target(target)
+    }
+}
+
+impl PartialEq<Target> for TaskDependency {
+    fn eq(&self, other: &Target) -> bool {
+        self.as_target().is_some_and(|target| target == other)
+    }
+}
+
+impl Schematic for TaskDependency {
+    fn generate_schema() -> SchemaType {
+        let mut schema = SchemaType::union(vec![
+            SchemaType::string(),
+            TaskFileDependency::generate_schema(),
+        ]);
+        schema.set_name("TaskDependency");
+        schema
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskDependency {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::String(value) => Target::parse(&value)
+                .map(TaskDependency::Target)
+                .map_err(de::Error::custom),
+            Value::Mapping(map) => {
+                let mut file = None;
+                let mut timeout = TaskFileDependency::default().timeout;
+
+                for (key, value) in map {
+                    match key.as_str() {
+                        Some("file") => {
+                            let path = value.as_str().ok_or_else(|| {
+                                de::Error::custom("expected `file` to be a string")
+                            })?;
+
+                            file = Some(
+                                FilePath::from_str(path)
+                                    .map_err(|error| de::Error::custom(error.message))?,
+                            );
+                        }
+                        Some("timeout") => {
+                            timeout = value.as_u64().ok_or_else(|| {
+                                de::Error::custom("expected `timeout` to be a number of seconds")
+                            })?;
+                        }
+                        _ => {
+                            return Err(de::Error::custom(
+                                "unknown field, expected one of `file`, `timeout`",
+                            ));
+                        }
+                    };
+                }
+
+                let Some(file) = file else {
+                    return Err(de::Error::missing_field("file"));
+                };
+
+                Ok(TaskDependency::File(TaskFileDependency { file, timeout }))
+            }
+            _ => Err(de::Error::custom(
+                "expected a target or a `{ file, timeout }` object",
+            )),
+        }
+    }
+}
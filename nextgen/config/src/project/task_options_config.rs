@@ -4,10 +4,112 @@ use crate::portable_path::FilePath;
 use crate::shapes::InputPath;
 use moon_common::cacheable;
 use schematic::schema::StringType;
-use schematic::{derive_enum, Config, ConfigEnum, SchemaType, Schematic};
+use schematic::{
+    derive_enum, Config, ConfigEnum, PathSegment, SchemaType, Schematic, ValidateError,
+};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_yaml::Value;
 
+fn validate_ready_pattern<D, C>(
+    pattern: &str,
+    _data: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    if let Err(error) = regex::Regex::new(pattern) {
+        return Err(ValidateError::new(format!(
+            "invalid ready pattern: {error}"
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_env_passthrough<D, C>(
+    names: &[String],
+    _data: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    for (i, name) in names.iter().enumerate() {
+        if name.trim().is_empty() {
+            return Err(ValidateError::with_segment(
+                "environment variable name cannot be empty",
+                PathSegment::Index(i),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_env_file_path(path: &FilePath) -> Result<(), ValidateError> {
+    if path.as_str().starts_with('$') {
+        return Err(ValidateError::new(
+            "environment variables are not supported",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_env_file<D, C>(
+    env_file: &TaskOptionEnvFile,
+    _data: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    match env_file {
+        TaskOptionEnvFile::Enabled(_) => {}
+        TaskOptionEnvFile::File(path) => {
+            validate_env_file_path(path)?;
+        }
+        TaskOptionEnvFile::Files(paths) => {
+            for (i, path) in paths.iter().enumerate() {
+                validate_env_file_path(path).map_err(|error| {
+                    ValidateError::with_segment(error.message, PathSegment::Index(i))
+                })?;
+            }
+        }
+    };
+
+    Ok(())
+}
+
+fn validate_timeout<D, C>(secs: &u32, _data: &D, _context: &C) -> Result<(), ValidateError> {
+    // 24 hours, anything larger is most likely a typo
+    if *secs > 86400 {
+        return Err(ValidateError::new(
+            "timeout cannot be larger than 86400 seconds (24 hours)",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_interactive<C>(
+    enabled: &bool,
+    data: &PartialTaskOptionsConfig,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    if !*enabled {
+        return Ok(());
+    }
+
+    if data.persistent == Some(true) {
+        return Err(ValidateError::new(
+            "an interactive task cannot also be persistent",
+        ));
+    }
+
+    if let Some(output_style) = &data.output_style {
+        if !matches!(output_style, TaskOutputStyle::Stream) {
+            return Err(ValidateError::new(
+                "an interactive task requires the `stream` output style, as other styles buffer output",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 #[serde(untagged, rename_all = "kebab-case")]
 pub enum TaskOptionAffectedFiles {
@@ -48,26 +150,42 @@ impl<'de> Deserialize<'de> for TaskOptionAffectedFiles {
 }
 
 derive_enum!(
-    #[serde(untagged, expecting = "expected a boolean or a file system path")]
+    #[serde(
+        untagged,
+        expecting = "expected a boolean, a file system path, or a list of file system paths"
+    )]
     pub enum TaskOptionEnvFile {
         Enabled(bool),
         File(FilePath),
+        Files(Vec<FilePath>),
     }
 );
 
 impl TaskOptionEnvFile {
-    pub fn to_input_path(&self) -> Option<InputPath> {
+    pub fn to_input_paths(&self) -> Option<Vec<InputPath>> {
         match self {
-            TaskOptionEnvFile::Enabled(true) => Some(InputPath::ProjectFile(".env".into())),
+            TaskOptionEnvFile::Enabled(true) => Some(vec![InputPath::ProjectFile(".env".into())]),
             TaskOptionEnvFile::Enabled(false) => None,
-            TaskOptionEnvFile::File(path) => InputPath::from_str(path.as_str()).ok(),
+            TaskOptionEnvFile::File(path) => InputPath::from_str(path.as_str())
+                .ok()
+                .map(|input| vec![input]),
+            TaskOptionEnvFile::Files(paths) => Some(
+                paths
+                    .iter()
+                    .filter_map(|path| InputPath::from_str(path.as_str()).ok())
+                    .collect(),
+            ),
         }
     }
 }
 
 impl Schematic for TaskOptionEnvFile {
     fn generate_schema() -> SchemaType {
-        let mut schema = SchemaType::union(vec![SchemaType::boolean(), SchemaType::string()]);
+        let mut schema = SchemaType::union(vec![
+            SchemaType::boolean(),
+            SchemaType::string(),
+            SchemaType::array(SchemaType::string()),
+        ]);
         schema.set_name("TaskOptionEnvFile");
         schema
     }
@@ -89,7 +207,10 @@ derive_enum!(
         #[default]
         Buffer,
         BufferOnlyFailure,
+        BufferUntilReady,
         Hash,
+        // Emits a machine-readable JSON record per task run
+        Json,
         None,
         Stream,
     }
@@ -100,10 +221,26 @@ cacheable!(
     pub struct TaskOptionsConfig {
         pub affected_files: Option<TaskOptionAffectedFiles>,
 
+        // Skip validating that outputs don't overlap with inputs
+        pub allow_output_overlap: Option<bool>,
+
         pub cache: Option<bool>,
 
+        pub cache_key_extra: Option<String>,
+
+        pub cache_undeclared_outputs: Option<bool>,
+
+        #[setting(validate = validate_env_file)]
         pub env_file: Option<TaskOptionEnvFile>,
 
+        #[setting(validate = validate_env_passthrough)]
+        pub env_passthrough: Option<Vec<String>>,
+
+        pub inherit_env: Option<bool>,
+
+        #[setting(validate = validate_interactive)]
+        pub interactive: Option<bool>,
+
         pub merge_args: Option<TaskMergeStrategy>,
 
         pub merge_deps: Option<TaskMergeStrategy>,
@@ -119,6 +256,10 @@ cacheable!(
 
         pub persistent: Option<bool>,
 
+        // Regex matched against each line of output to determine readiness
+        #[setting(validate = validate_ready_pattern)]
+        pub ready_pattern: Option<String>,
+
         #[setting(env = "MOON_RETRY_COUNT")]
         pub retry_count: Option<u8>,
 
@@ -130,5 +271,12 @@ cacheable!(
         pub run_from_workspace_root: Option<bool>,
 
         pub shell: Option<bool>,
+
+        // Error for undefined variables when interpolating `env`
+        pub strict_env: Option<bool>,
+
+        // In seconds, where 0 disables the timeout
+        #[setting(validate = validate_timeout)]
+        pub timeout: Option<u32>,
     }
 );
@@ -0,0 +1,161 @@
+use crate::project::task_config::{parse_task_entry, parse_tasks_mapping};
+use crate::types::TasksConfigsMap;
+use moon_common::Id;
+use moon_target::TargetScope;
+
+/// Validate a map of task configurations (the `tasks` setting in `moon.yml`)
+/// in isolation, without loading the workspace, the file system, or the project
+/// graph. Unlike [`TaskConfig::parse_map`](crate::TaskConfig::parse_map), this
+/// does not bail on the first error, and instead returns diagnostics for every
+/// invalid task, for self referencing or cyclic `~:` dependencies between tasks
+/// in the map, and for tasks that extend themselves, or extend in a cycle.
+///
+/// Dependencies on tasks not in the map are *not* reported, as they
+/// may be inherited from global tasks.
+pub fn validate_tasks_configs<T: AsRef<str>>(code: T) -> Vec<miette::Report> {
+    let code = code.as_ref();
+
+    if code.trim().is_empty() {
+        return vec![];
+    }
+
+    let map = match parse_tasks_mapping(code) {
+        Ok(map) => map,
+        Err(error) => {
+            return vec![error];
+        }
+    };
+
+    let mut diagnostics = vec![];
+    let mut tasks = TasksConfigsMap::new();
+
+    for (key, value) in map {
+        match parse_task_entry(key, value) {
+            Ok((id, config)) => {
+                tasks.insert(id, config);
+            }
+            Err(error) => {
+                diagnostics.push(error);
+            }
+        };
+    }
+
+    validate_own_deps(&tasks, &mut diagnostics);
+    validate_extends_chains(&tasks, &mut diagnostics);
+
+    diagnostics
+}
+
+fn get_own_deps<'a>(tasks: &'a TasksConfigsMap, id: &Id) -> Vec<&'a Id> {
+    tasks
+        .get(id)
+        .map(|config| {
+            config
+                .deps
+                .iter()
+                .filter_map(|dep| dep.as_target())
+                .filter(|dep| matches!(dep.scope, TargetScope::OwnSelf))
+                .map(|dep| &dep.task_id)
+                .filter(|dep_id| tasks.contains_key(*dep_id))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn validate_own_deps(tasks: &TasksConfigsMap, diagnostics: &mut Vec<miette::Report>) {
+    let mut visited: Vec<&Id> = vec![];
+
+    for id in tasks.keys() {
+        if get_own_deps(tasks, id).contains(&id) {
+            diagnostics.push(miette::miette!(
+                code = "config::tasks::self_dependency",
+                "Task {id} cannot depend on itself."
+            ));
+        }
+
+        if !visited.contains(&id) {
+            detect_cycles(tasks, id, &mut vec![], &mut visited, diagnostics);
+        }
+    }
+}
+
+fn get_extends<'a>(tasks: &'a TasksConfigsMap, id: &Id) -> Option<&'a Id> {
+    tasks
+        .get(id)
+        .and_then(|config| config.extends.as_ref())
+        .map(|extends| &extends.task_id)
+        .filter(|extends_id| tasks.contains_key(*extends_id))
+}
+
+fn validate_extends_chains(tasks: &TasksConfigsMap, diagnostics: &mut Vec<miette::Report>) {
+    for id in tasks.keys() {
+        let mut chain = vec![id];
+
+        while let Some(extends_id) = get_extends(tasks, chain[chain.len() - 1]) {
+            let Some(index) = chain.iter().position(|entry| *entry == extends_id) else {
+                chain.push(extends_id);
+                continue;
+            };
+
+            // Only report each cycle once, from its lowest task ID
+            if index == 0 && chain.iter().all(|entry| *entry >= id) {
+                if chain.len() == 1 {
+                    diagnostics.push(miette::miette!(
+                        code = "config::tasks::self_extends",
+                        "Task {id} cannot extend itself."
+                    ));
+                } else {
+                    let cycle = chain
+                        .iter()
+                        .chain([&extends_id])
+                        .map(|entry| entry.as_str())
+                        .collect::<Vec<_>>();
+
+                    diagnostics.push(miette::miette!(
+                        code = "config::tasks::extends_cycle_detected",
+                        "Task extends cycle detected: {}.",
+                        cycle.join(" -> ")
+                    ));
+                }
+            }
+
+            break;
+        }
+    }
+}
+
+fn detect_cycles<'a>(
+    tasks: &'a TasksConfigsMap,
+    id: &'a Id,
+    stack: &mut Vec<&'a Id>,
+    visited: &mut Vec<&'a Id>,
+    diagnostics: &mut Vec<miette::Report>,
+) {
+    stack.push(id);
+    visited.push(id);
+
+    for dep_id in get_own_deps(tasks, id) {
+        // Self references are reported separately
+        if dep_id == id {
+            continue;
+        }
+
+        if let Some(index) = stack.iter().position(|entry| *entry == dep_id) {
+            let cycle = stack[index..]
+                .iter()
+                .chain([&dep_id])
+                .map(|entry| entry.as_str())
+                .collect::<Vec<_>>();
+
+            diagnostics.push(miette::miette!(
+                code = "config::tasks::cycle_detected",
+                "Task dependency cycle detected: {}.",
+                cycle.join(" -> ")
+            ));
+        } else if !visited.contains(&dep_id) {
+            detect_cycles(tasks, dep_id, stack, visited, diagnostics);
+        }
+    }
+
+    stack.pop();
+}
@@ -76,8 +76,15 @@ cacheable!(
 
         pub file_groups: FxHashMap<Id, Vec<InputPath>>,
 
+        #[setting(nested)]
+        pub hasher: ProjectHasherConfig,
+
         pub language: LanguageType,
 
+        // Named task options that can be referenced with `optionsPreset`
+        #[setting(nested)]
+        pub options_presets: FxHashMap<Id, TaskOptionsConfig>,
+
         #[setting(nested)]
         pub owners: OwnersConfig,
 
@@ -0,0 +1,13 @@
+use super::bin_config::BinEntry;
+use crate::validate::validate_semver;
+use schematic::Config;
+
+/// Docs: https://moonrepo.dev/docs/config/toolchain#bun
+#[derive(Clone, Config, Debug)]
+pub struct BunConfig {
+    #[setting(nested)]
+    pub bins: Vec<BinEntry>,
+
+    #[setting(validate = validate_semver)]
+    pub version: Option<String>,
+}
@@ -1,5 +1,23 @@
 use super::bin_config::BinEntry;
+use crate::portable_path::FilePath;
 use schematic::Config;
+use serde::Deserialize;
+use starbase_utils::json;
+use std::path::Path;
+
+// This isn't everything, just what we infer settings from
+#[derive(Default, Deserialize)]
+struct DenoJson {
+    lock: Option<DenoJsonLock>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DenoJsonLock {
+    Enabled(bool),
+    Path(String),
+    Object {},
+}
 
 /// Docs: https://moonrepo.dev/docs/config/toolchain#deno
 #[derive(Clone, Config, Debug)]
@@ -10,5 +28,36 @@ pub struct DenoConfig {
     #[setting(default = "deps.ts")]
     pub deps_file: String,
 
+    pub import_map: Option<FilePath>,
+
     pub lockfile: bool,
 }
+
+impl DenoConfig {
+    /// Infer settings from a `deno.json` or `deno.jsonc` file in the provided
+    /// directory, but only for settings not explicitly configured in the partial.
+    pub fn inherit_deno_json(
+        &mut self,
+        dir: &Path,
+        partial: Option<&PartialDenoConfig>,
+    ) -> miette::Result<()> {
+        let Some(deno_json) = ["deno.json", "deno.jsonc"]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+        else {
+            return Ok(());
+        };
+
+        // JSONC comments are stripped while reading
+        let deno_json: DenoJson = json::read_file(deno_json)?;
+
+        if partial.and_then(|p| p.lockfile).is_none() {
+            if let Some(lock) = deno_json.lock {
+                self.lockfile = !matches!(lock, DenoJsonLock::Enabled(false));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,15 +1,49 @@
 mod bin_config;
+mod bun_config;
 mod deno_config;
 mod node_config;
 mod rust_config;
+mod tool_versions;
 mod typescript_config;
 
 pub use bin_config::*;
+pub use bun_config::*;
 pub use deno_config::*;
 pub use node_config::*;
 pub use rust_config::*;
+pub use tool_versions::*;
 pub use typescript_config::*;
 
+use schematic::ValidateError;
+use std::env;
+
+/// Override a version with the value of an environment variable, if it has been set.
+pub(crate) fn inherit_env_version(
+    version: &mut Option<String>,
+    name: &str,
+    validator: fn(&str, &(), &()) -> Result<(), ValidateError>,
+) -> miette::Result<()> {
+    let Ok(value) = env::var(name) else {
+        return Ok(());
+    };
+
+    if value.is_empty() {
+        return Ok(());
+    }
+
+    validator(&value, &(), &()).map_err(|error| {
+        miette::miette!(
+            code = "config::env::invalid_version",
+            "Invalid {name} environment variable: {}",
+            error.message
+        )
+    })?;
+
+    *version = Some(value);
+
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! inherit_tool {
     ($config:ident, $tool:ident, $key:expr, $method:ident) => {
@@ -1,7 +1,20 @@
+use crate::toolchain::inherit_env_version;
 use crate::validate::validate_semver;
 use crate::{inherit_tool, inherit_tool_required};
-use proto::ToolsConfig;
-use schematic::{derive_enum, Config, ConfigEnum};
+use proto::{remove_v_prefix, ToolsConfig, VersionManifest};
+use schematic::{derive_enum, Config, ConfigEnum, ValidateError};
+
+/// Aliases that may be used in place of an explicit node version,
+/// and are resolved to a concrete version when the workspace is loaded.
+pub const NODE_VERSION_ALIASES: [&str; 2] = ["latest", "lts"];
+
+fn validate_node_version<D, C>(value: &str, data: &D, ctx: &C) -> Result<(), ValidateError> {
+    if NODE_VERSION_ALIASES.contains(&value) {
+        return Ok(());
+    }
+
+    validate_semver(value, data, ctx)
+}
 
 derive_enum!(
     #[derive(ConfigEnum, Copy, Default)]
@@ -65,13 +78,20 @@ derive_enum!(
 
 #[derive(Clone, Config, Debug)]
 pub struct NpmConfig {
-    #[setting(env = "MOON_NPM_VERSION", validate = validate_semver)]
+    pub audit: Option<bool>,
+
+    pub fund: bool,
+
+    // Passed to npm with `--registry`, unless a project's `.npmrc` defines its own
+    pub registry: Option<String>,
+
+    #[setting(validate = validate_semver)]
     pub version: Option<String>,
 }
 
 #[derive(Clone, Config, Debug)]
 pub struct PnpmConfig {
-    #[setting(env = "MOON_PNPM_VERSION", validate = validate_semver)]
+    #[setting(validate = validate_semver)]
     pub version: Option<String>,
 }
 
@@ -79,7 +99,7 @@ pub struct PnpmConfig {
 pub struct YarnConfig {
     pub plugins: Vec<String>,
 
-    #[setting(env = "MOON_YARN_VERSION", validate = validate_semver)]
+    #[setting(validate = validate_semver)]
     pub version: Option<String>,
 }
 
@@ -89,11 +109,15 @@ pub struct NodeConfig {
     #[setting(default = true)]
     pub add_engines_constraint: bool,
 
+    pub allow_version_alias_in_ci: bool,
+
     #[deprecated]
     pub alias_package_names: NodeProjectAliasFormat,
 
     pub bin_exec_args: Vec<String>,
 
+    pub dedupe_always: bool,
+
     #[setting(default = true)]
     pub dedupe_on_lockfile_change: bool,
 
@@ -101,6 +125,14 @@ pub struct NodeConfig {
 
     pub infer_tasks_from_scripts: bool,
 
+    // In milliseconds, doubled after each attempt
+    #[setting(default = 1000)]
+    pub install_retry_backoff: u64,
+
+    // Only for transient network failures
+    #[setting(default = 2)]
+    pub install_retry_count: u8,
+
     #[setting(nested)]
     pub npm: NpmConfig,
 
@@ -114,7 +146,7 @@ pub struct NodeConfig {
 
     pub sync_version_manager_config: Option<NodeVersionManager>,
 
-    #[setting(env = "MOON_NODE_VERSION", validate = validate_semver)]
+    #[setting(validate = validate_node_version)]
     pub version: Option<String>,
 
     #[setting(nested)]
@@ -141,4 +173,75 @@ impl NodeConfig {
 
         Ok(())
     }
+
+    /// Override versions with the `MOON_NODE_VERSION`, `MOON_NPM_VERSION`,
+    /// `MOON_PNPM_VERSION`, and `MOON_YARN_VERSION` environment variables.
+    pub fn inherit_env_versions(&mut self) -> miette::Result<()> {
+        inherit_env_version(
+            &mut self.version,
+            "MOON_NODE_VERSION",
+            validate_node_version,
+        )?;
+        inherit_env_version(&mut self.npm.version, "MOON_NPM_VERSION", validate_semver)?;
+
+        if let Some(pnpm_config) = &mut self.pnpm {
+            inherit_env_version(
+                &mut pnpm_config.version,
+                "MOON_PNPM_VERSION",
+                validate_semver,
+            )?;
+        }
+
+        if let Some(yarn_config) = &mut self.yarn {
+            inherit_env_version(
+                &mut yarn_config.version,
+                "MOON_YARN_VERSION",
+                validate_semver,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Return the configured version alias, if any. Aliases are not reproducible,
+    /// so they are rejected in CI unless explicitly allowed.
+    pub fn get_version_alias(&self, ci: bool) -> miette::Result<Option<&str>> {
+        let Some(alias) = self
+            .version
+            .as_deref()
+            .filter(|version| NODE_VERSION_ALIASES.contains(version))
+        else {
+            return Ok(None);
+        };
+
+        if ci && !self.allow_version_alias_in_ci {
+            return Err(miette::miette!(
+                code = "config::node::version_alias",
+                help = "Configure an explicit version, or enable node.allowVersionAliasInCi.",
+                "Node.js version alias \"{alias}\" is not allowed in CI, as it's not reproducible."
+            ));
+        }
+
+        Ok(Some(alias))
+    }
+
+    /// Resolve a version alias against the versions known to proto,
+    /// and replace it with the resolved concrete version.
+    pub fn resolve_version_alias(
+        &mut self,
+        manifest: &VersionManifest,
+        ci: bool,
+    ) -> miette::Result<()> {
+        let Some(alias) = self.get_version_alias(ci)? else {
+            return Ok(());
+        };
+
+        // proto tracks the latest LTS version as "stable"
+        let version =
+            manifest.get_version_from_alias(if alias == "lts" { "stable" } else { alias })?;
+
+        self.version = Some(remove_v_prefix(version));
+
+        Ok(())
+    }
 }
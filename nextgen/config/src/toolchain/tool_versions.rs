@@ -0,0 +1,44 @@
+use miette::IntoDiagnostic;
+use proto::ToolsConfig;
+use semver::Version;
+use std::fs;
+use std::path::Path;
+
+pub const TOOL_VERSIONS_NAME: &str = ".tool-versions";
+
+/// Load an asdf `.tool-versions` file from the provided directory (if it exists),
+/// and convert it to a proto tools config, so that it can be inherited with the
+/// same semantics. Only the first version of each tool is used, and asdf plugin
+/// names are mapped to proto tool names. Versions that are not a valid semantic
+/// version, like `system`, `lts`, or `ref:<sha>`, are skipped.
+pub fn load_tool_versions(dir: &Path) -> miette::Result<ToolsConfig> {
+    let path = dir.join(TOOL_VERSIONS_NAME);
+    let mut config = ToolsConfig::default();
+
+    if !path.exists() {
+        return Ok(config);
+    }
+
+    for line in fs::read_to_string(&path).into_diagnostic()?.lines() {
+        // Strip trailing comments
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let mut parts = line.split_whitespace();
+
+        let (Some(tool), Some(version)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+
+        if Version::parse(version).is_err() {
+            continue;
+        }
+
+        let tool = match tool {
+            "nodejs" => "node",
+            other => other,
+        };
+
+        config.tools.insert(tool.to_owned(), version.to_owned());
+    }
+
+    Ok(config)
+}
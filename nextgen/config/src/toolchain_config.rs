@@ -1,12 +1,68 @@
 // .moon/toolchain.yml
 
+use crate::language_platform::PlatformType;
 use crate::toolchain::*;
+use crate::validate::validate_semver;
 use crate::{inherit_tool, inherit_tool_without_version};
-use moon_common::consts;
-use proto::ToolsConfig;
+use moon_common::{consts, Id};
+use proto::{ToolsConfig, TOOLS_CONFIG_NAME};
 use schematic::{validate, Config, ConfigLoader};
+use semver::Version;
+use std::collections::BTreeMap;
 use std::path::Path;
 
+/// A version change for a single tool between two toolchain configurations.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ToolchainVersionDelta {
+    pub tool: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Differences between two toolchain configurations, used to determine
+/// which tools need to be re-provisioned.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ToolchainDiff {
+    /// Tools that were not configured previously.
+    pub added: Vec<String>,
+
+    /// Tools that were configured previously, but no longer are.
+    pub removed: Vec<String>,
+
+    /// Tools that are configured in both, but with a different version.
+    pub changed: Vec<ToolchainVersionDelta>,
+}
+
+impl ToolchainDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn compare(
+        &mut self,
+        tool: &str,
+        prev: Option<Option<&String>>,
+        next: Option<Option<&String>>,
+    ) {
+        match (prev, next) {
+            (None, Some(_)) => {
+                self.added.push(tool.to_owned());
+            }
+            (Some(_), None) => {
+                self.removed.push(tool.to_owned());
+            }
+            (Some(from), Some(to)) if from != to => {
+                self.changed.push(ToolchainVersionDelta {
+                    tool: tool.to_owned(),
+                    from: from.cloned(),
+                    to: to.cloned(),
+                });
+            }
+            _ => {}
+        };
+    }
+}
+
 /// Docs: https://moonrepo.dev/docs/config/toolchain
 #[derive(Config, Debug)]
 pub struct ToolchainConfig {
@@ -19,6 +75,11 @@ pub struct ToolchainConfig {
     #[setting(extend, validate = validate::extends_string)]
     pub extends: Option<String>,
 
+    pub inherit_tool_versions: bool,
+
+    #[setting(nested)]
+    pub bun: Option<BunConfig>,
+
     #[setting(nested)]
     pub deno: Option<DenoConfig>,
 
@@ -33,6 +94,8 @@ pub struct ToolchainConfig {
 }
 
 impl ToolchainConfig {
+    inherit_tool!(BunConfig, bun, "bun", inherit_proto_bun);
+
     inherit_tool_without_version!(DenoConfig, deno, "deno", inherit_proto_deno);
 
     inherit_tool!(RustConfig, rust, "rust", inherit_proto_rust);
@@ -46,7 +109,230 @@ impl ToolchainConfig {
         inherit_proto_typescript
     );
 
+    /// Return a list of platforms that have been enabled, based on
+    /// which toolchain settings have been configured.
+    ///
+    /// Bun and TypeScript are *not* included, as they do not have a [`PlatformType`]
+    /// (TypeScript runs on Node.js). Use [`ToolchainConfig::enabled_toolchains`]
+    /// when every configured tool is required.
+    pub fn enabled_platforms(&self) -> Vec<PlatformType> {
+        let mut platforms = vec![];
+
+        if self.deno.is_some() {
+            platforms.push(PlatformType::Deno);
+        }
+
+        if self.node.is_some() {
+            platforms.push(PlatformType::Node);
+        }
+
+        if self.rust.is_some() {
+            platforms.push(PlatformType::Rust);
+        }
+
+        platforms
+    }
+
+    /// Return a list of toolchains that have been enabled, based on which
+    /// toolchain settings have been configured. Unlike platforms, this
+    /// includes tools that run on another platform (Bun, TypeScript).
+    pub fn enabled_toolchains(&self) -> Vec<Id> {
+        let mut toolchains = vec![];
+
+        if self.bun.is_some() {
+            toolchains.push(Id::raw("bun"));
+        }
+
+        if self.deno.is_some() {
+            toolchains.push(Id::raw("deno"));
+        }
+
+        if self.node.is_some() {
+            toolchains.push(Id::raw("node"));
+        }
+
+        if self.rust.is_some() {
+            toolchains.push(Id::raw("rust"));
+        }
+
+        if self.typescript.is_some() {
+            toolchains.push(Id::raw("typescript"));
+        }
+
+        toolchains
+    }
+
+    /// Return the Node.js package manager that has been selected with `node.packageManager`,
+    /// and its configured version (if any). Returns `None` if Node.js is not configured.
+    pub fn node_package_manager(&self) -> Option<(NodePackageManager, Option<Version>)> {
+        let node_config = self.node.as_ref()?;
+
+        let version = match node_config.package_manager {
+            NodePackageManager::Npm => node_config.npm.version.as_ref(),
+            NodePackageManager::Pnpm => node_config
+                .pnpm
+                .as_ref()
+                .and_then(|config| config.version.as_ref()),
+            NodePackageManager::Yarn => node_config
+                .yarn
+                .as_ref()
+                .and_then(|config| config.version.as_ref()),
+        };
+
+        Some((
+            node_config.package_manager,
+            version.and_then(|version| Version::parse(version).ok()),
+        ))
+    }
+
+    /// Compare against a newer configuration, and return which tools have been
+    /// added, removed, or had their version changed. Node.js package managers
+    /// are only compared when Node.js is configured in both.
+    pub fn diff(&self, other: &ToolchainConfig) -> ToolchainDiff {
+        let mut diff = ToolchainDiff::default();
+
+        diff.compare(
+            "bun",
+            self.bun.as_ref().map(|c| c.version.as_ref()),
+            other.bun.as_ref().map(|c| c.version.as_ref()),
+        );
+
+        diff.compare(
+            "deno",
+            self.deno.as_ref().map(|_| None),
+            other.deno.as_ref().map(|_| None),
+        );
+
+        diff.compare(
+            "node",
+            self.node.as_ref().map(|c| c.version.as_ref()),
+            other.node.as_ref().map(|c| c.version.as_ref()),
+        );
+
+        if let (Some(prev), Some(next)) = (&self.node, &other.node) {
+            diff.compare(
+                "npm",
+                Some(prev.npm.version.as_ref()),
+                Some(next.npm.version.as_ref()),
+            );
+
+            diff.compare(
+                "pnpm",
+                prev.pnpm.as_ref().map(|c| c.version.as_ref()),
+                next.pnpm.as_ref().map(|c| c.version.as_ref()),
+            );
+
+            diff.compare(
+                "yarn",
+                prev.yarn.as_ref().map(|c| c.version.as_ref()),
+                next.yarn.as_ref().map(|c| c.version.as_ref()),
+            );
+        }
+
+        diff.compare(
+            "rust",
+            self.rust.as_ref().map(|c| c.version.as_ref()),
+            other.rust.as_ref().map(|c| c.version.as_ref()),
+        );
+
+        diff.compare(
+            "typescript",
+            self.typescript.as_ref().map(|_| None),
+            other.typescript.as_ref().map(|_| None),
+        );
+
+        diff
+    }
+
+    /// Return a map of tools to their resolved version. Tools that
+    /// do not support a version (Deno, TypeScript) are not included.
+    pub fn get_tool_versions(&self) -> BTreeMap<String, String> {
+        let mut versions = BTreeMap::new();
+
+        if let Some(version) = self.bun.as_ref().and_then(|c| c.version.as_ref()) {
+            versions.insert("bun".into(), version.to_owned());
+        }
+
+        if let Some(node_config) = &self.node {
+            if let Some(version) = &node_config.version {
+                versions.insert("node".into(), version.to_owned());
+            }
+
+            if let Some(version) = &node_config.npm.version {
+                versions.insert("npm".into(), version.to_owned());
+            }
+
+            if let Some(version) = node_config.pnpm.as_ref().and_then(|c| c.version.as_ref()) {
+                versions.insert("pnpm".into(), version.to_owned());
+            }
+
+            if let Some(version) = node_config.yarn.as_ref().and_then(|c| c.version.as_ref()) {
+                versions.insert("yarn".into(), version.to_owned());
+            }
+        }
+
+        if let Some(version) = self.rust.as_ref().and_then(|c| c.version.as_ref()) {
+            versions.insert("rust".into(), version.to_owned());
+        }
+
+        versions
+    }
+
+    /// Merge the resolved tool versions into the provided proto tools config.
+    /// Tools that moon does not manage, and plugins, are preserved.
+    /// Returns true if a version was added or changed.
+    pub fn merge_proto_tools(&self, proto_tools: &mut ToolsConfig) -> bool {
+        let mut changed = false;
+
+        for (tool, version) in self.get_tool_versions() {
+            if proto_tools.tools.get(&tool) != Some(&version) {
+                proto_tools.tools.insert(tool, version);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Write the resolved tool versions to the `.prototools` file in the provided
+    /// directory, so that other tools use the same versions. The file is serialized
+    /// by proto, and is only written when a version has changed. Returns true if written.
+    pub fn write_proto_tools<P: AsRef<Path>>(&self, dir: P) -> miette::Result<bool> {
+        let mut proto_tools = ToolsConfig::load(dir.as_ref().join(TOOLS_CONFIG_NAME))?;
+
+        if !self.merge_proto_tools(&mut proto_tools) {
+            return Ok(false);
+        }
+
+        proto_tools.save()?;
+
+        Ok(true)
+    }
+
+    /// Override the versions of configured tools with their `MOON_*_VERSION`
+    /// environment variables, which take precedence over all other sources.
+    pub fn inherit_env_versions(&mut self) -> miette::Result<()> {
+        if let Some(bun_config) = &mut self.bun {
+            inherit_env_version(&mut bun_config.version, "MOON_BUN_VERSION", validate_semver)?;
+        }
+
+        if let Some(node_config) = &mut self.node {
+            node_config.inherit_env_versions()?;
+        }
+
+        if let Some(rust_config) = &mut self.rust {
+            inherit_env_version(
+                &mut rust_config.version,
+                "MOON_RUST_VERSION",
+                validate_semver,
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub fn inherit_proto(&mut self, proto_tools: &ToolsConfig) -> miette::Result<()> {
+        self.inherit_proto_bun(proto_tools)?;
         self.inherit_proto_deno(proto_tools)?;
         self.inherit_proto_rust(proto_tools)?;
         self.inherit_proto_node(proto_tools)?;
@@ -64,28 +350,73 @@ impl ToolchainConfig {
         path: P,
         proto_tools: &ToolsConfig,
     ) -> miette::Result<ToolchainConfig> {
+        Self::load_with_env_overrides(workspace_root, path, proto_tools, true)
+    }
+
+    /// Load the toolchain configuration, and when `env_overrides` is false,
+    /// ignore `MOON_*_VERSION` environment variables so that the versions
+    /// are only sourced from the configuration file (and proto).
+    pub fn load_with_env_overrides<R: AsRef<Path>, P: AsRef<Path>>(
+        workspace_root: R,
+        path: P,
+        proto_tools: &ToolsConfig,
+        env_overrides: bool,
+    ) -> miette::Result<ToolchainConfig> {
+        let workspace_root = workspace_root.as_ref();
+        let path = path.as_ref();
+
         let mut result = ConfigLoader::<ToolchainConfig>::new()
             .set_root(workspace_root)
-            .file_optional(path.as_ref())?
+            .file_optional(path)?
             .load()?;
 
+        if env_overrides {
+            result.config.inherit_env_versions()?;
+        }
+
+        // Explicitly configured settings are only known from the partial
+        if let Some(deno_config) = &mut result.config.deno {
+            let partial = ConfigLoader::<ToolchainConfig>::new()
+                .set_root(workspace_root)
+                .file_optional(path)?
+                .load_partial(&())?;
+
+            deno_config.inherit_deno_json(workspace_root, partial.deno.as_ref())?;
+        }
+
         result.config.inherit_proto(proto_tools)?;
 
+        // Lowest precedence, as only versions that are not configured are inherited
+        if result.config.inherit_tool_versions {
+            result
+                .config
+                .inherit_proto(&load_tool_versions(workspace_root)?)?;
+        }
+
         Ok(result.config)
     }
 
     pub fn load_from<R: AsRef<Path>>(
         workspace_root: R,
         proto_tools: &ToolsConfig,
+    ) -> miette::Result<ToolchainConfig> {
+        Self::load_from_with_env_overrides(workspace_root, proto_tools, true)
+    }
+
+    pub fn load_from_with_env_overrides<R: AsRef<Path>>(
+        workspace_root: R,
+        proto_tools: &ToolsConfig,
+        env_overrides: bool,
     ) -> miette::Result<ToolchainConfig> {
         let workspace_root = workspace_root.as_ref();
 
-        Self::load(
+        Self::load_with_env_overrides(
             workspace_root,
             workspace_root
                 .join(consts::CONFIG_DIRNAME)
                 .join(consts::CONFIG_TOOLCHAIN_FILENAME),
             proto_tools,
+            env_overrides,
         )
     }
 }
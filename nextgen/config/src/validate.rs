@@ -41,6 +41,25 @@ pub fn validate_child_or_root_path<T: AsRef<str>>(value: T) -> Result<(), Valida
     Ok(())
 }
 
+// Validate the value is not an absolute path for a specific machine, like a Windows
+// drive ("C:\") or a network share ("\\server"). Workspace-relative paths ("/")
+// are portable and are not considered absolute.
+pub fn validate_no_absolute_path<T: AsRef<str>>(value: T) -> Result<(), ValidateError> {
+    let value = value.as_ref();
+    let bytes = value.as_bytes();
+
+    let is_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    let is_unc = value.starts_with("\\\\") || value.starts_with("//");
+
+    if is_drive || is_unc {
+        return Err(ValidateError::new(
+            "absolute paths are not supported, as they break portability across machines",
+        ));
+    }
+
+    Ok(())
+}
+
 pub fn validate_semver<D, C>(value: &str, _data: &D, _ctx: &C) -> Result<(), ValidateError> {
     Version::parse(value)
         .map_err(|error| ValidateError::new(format!("not a valid semantic version: {}", error)))?;
@@ -4,6 +4,8 @@ use schematic::Config;
 
 #[derive(Config, Debug)]
 pub struct ConstraintsConfig {
+    pub enforce_language_platform_compatibility: bool,
+
     #[setting(default = true)]
     pub enforce_project_type_relationships: bool,
 
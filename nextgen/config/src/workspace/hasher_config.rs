@@ -1,5 +1,15 @@
 use crate::portable_path::GlobPath;
+use crate::project::ProjectHasherConfig;
+use rustc_hash::FxHashMap;
 use schematic::{derive_enum, Config, ConfigEnum};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+// Bounds for the automatic batch size, when `batch_size` is 0
+pub const AUTO_BATCH_SIZE_MIN: u16 = 500;
+pub const AUTO_BATCH_SIZE_MAX: u16 = 10_000;
+
+static DETECTED_REPOSITORIES: OnceLock<Mutex<FxHashMap<PathBuf, bool>>> = OnceLock::new();
 
 derive_enum!(
     #[derive(ConfigEnum, Copy, Default)]
@@ -13,17 +23,53 @@ derive_enum!(
 derive_enum!(
     #[derive(ConfigEnum, Copy, Default)]
     pub enum HasherWalkStrategy {
-        Glob,
         #[default]
+        Auto,
+        Glob,
         Vcs,
     }
 );
 
-#[derive(Config, Debug)]
+impl HasherWalkStrategy {
+    /// Resolve the `auto` strategy to `vcs` when a repository exists at the
+    /// workspace root or one of its parents, otherwise `glob`. Detection is
+    /// cached per workspace root.
+    pub fn resolve(&self, workspace_root: &Path) -> HasherWalkStrategy {
+        if !matches!(self, HasherWalkStrategy::Auto) {
+            return *self;
+        }
+
+        let mut detected = DETECTED_REPOSITORIES
+            .get_or_init(|| Mutex::new(FxHashMap::default()))
+            .lock()
+            .unwrap();
+
+        // Search upwards like the VCS does, as the workspace may be nested within
+        // the repository. A worktree or submodule uses a `.git` file instead of a directory.
+        let has_repository = *detected
+            .entry(workspace_root.to_path_buf())
+            .or_insert_with(|| {
+                workspace_root
+                    .ancestors()
+                    .any(|dir| dir.join(".git").exists())
+            });
+
+        if has_repository {
+            HasherWalkStrategy::Vcs
+        } else {
+            HasherWalkStrategy::Glob
+        }
+    }
+}
+
+#[derive(Clone, Config, Debug)]
 pub struct HasherConfig {
     #[setting(default = 2500)]
     pub batch_size: u16,
 
+    #[setting(default = true)]
+    pub follow_symlinks: bool,
+
     pub ignore_patterns: Vec<GlobPath>,
 
     pub ignore_missing_patterns: Vec<GlobPath>,
@@ -35,3 +81,32 @@ pub struct HasherConfig {
     #[setting(default = true)]
     pub warn_on_missing_inputs: bool,
 }
+
+impl HasherConfig {
+    /// Return the number of files to hash in each batch. When the configured
+    /// batch size is 0, it will be derived from the total number of files
+    /// (roughly 10 batches), clamped between a minimum and maximum.
+    pub fn resolve_batch_size(&self, file_count: usize) -> u16 {
+        if self.batch_size > 0 {
+            return self.batch_size;
+        }
+
+        (file_count / 10).clamp(AUTO_BATCH_SIZE_MIN as usize, AUTO_BATCH_SIZE_MAX as usize) as u16
+    }
+
+    /// Return a copy of this configuration with the project-level
+    /// overrides applied, which take precedence when defined.
+    pub fn with_project_overrides(&self, overrides: &ProjectHasherConfig) -> HasherConfig {
+        let mut config = self.clone();
+
+        if let Some(optimization) = overrides.optimization {
+            config.optimization = optimization;
+        }
+
+        if let Some(walk_strategy) = overrides.walk_strategy {
+            config.walk_strategy = walk_strategy;
+        }
+
+        config
+    }
+}
@@ -8,6 +8,8 @@ pub struct RunnerConfig {
     #[setting(default = "7 days")]
     pub cache_lifetime: String,
 
+    pub expand_tag_deps: bool,
+
     #[setting(default = true)]
     pub inherit_colors_for_piped_tasks: bool,
 
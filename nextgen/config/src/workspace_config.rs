@@ -1,6 +1,8 @@
 // .moon/workspace.yml
 
 use crate::portable_path::{Portable, ProjectFilePath, ProjectGlobPath};
+use crate::project::validate_inputs;
+use crate::shapes::InputPath;
 use crate::validate::validate_semver_requirement;
 use crate::workspace::*;
 use moon_common::{consts, Id};
@@ -98,12 +100,17 @@ pub struct WorkspaceConfig {
     #[setting(nested)]
     pub constraints: ConstraintsConfig,
 
+    pub env: FxHashMap<String, String>,
+
     #[setting(extend, validate = validate::extends_string)]
     pub extends: Option<String>,
 
     #[setting(nested)]
     pub generator: GeneratorConfig,
 
+    #[setting(validate = validate_inputs)]
+    pub global_inputs: Vec<InputPath>,
+
     #[setting(nested)]
     pub hasher: HasherConfig,
 
@@ -330,12 +330,17 @@ implicitDeps:
             });
         }
 
+        // Validated during project graph construction, based on `runner.expandTagDeps`
         #[test]
-        #[should_panic(expected = "target scope not supported as a task dependency")]
-        fn errors_on_tag_scope() {
-            test_load_config(FILENAME, "implicitDeps: ['#tag:task']", |path| {
+        fn supports_tag_scope() {
+            let config = test_load_config(FILENAME, "implicitDeps: ['#tag:task']", |path| {
                 InheritedTasksConfig::load(path.join(FILENAME))
             });
+
+            assert_eq!(
+                config.implicit_deps,
+                vec![Target::parse("#tag:task").unwrap()]
+            );
         }
     }
 
@@ -760,6 +765,48 @@ mod task_manager {
             );
         }
 
+        #[test]
+        fn includes_named_files() {
+            let sandbox = create_sandbox("inheritance/named");
+            let manager = InheritedTasksManager::load(sandbox.path(), sandbox.path()).unwrap();
+
+            let config = manager
+                .get_inherited_config_with_files(
+                    &PlatformType::System,
+                    &LanguageType::Unknown,
+                    &ProjectType::Application,
+                    &[],
+                    &["custom.yml".into()],
+                )
+                .unwrap();
+
+            assert_eq!(
+                config.config.tasks,
+                BTreeMap::from_iter([
+                    ("global".into(), stub_task("global", PlatformType::Unknown)),
+                    ("custom".into(), stub_task("custom", PlatformType::System)),
+                ]),
+            );
+            assert_eq!(config.order.last().unwrap(), "custom");
+        }
+
+        #[test]
+        #[should_panic(expected = "File path .moon/tasks/missing.yml does not exist.")]
+        fn errors_for_missing_named_file() {
+            let sandbox = create_sandbox("inheritance/named");
+            let manager = InheritedTasksManager::load(sandbox.path(), sandbox.path()).unwrap();
+
+            manager
+                .get_inherited_config_with_files(
+                    &PlatformType::System,
+                    &LanguageType::Unknown,
+                    &ProjectType::Application,
+                    &[],
+                    &["missing".into()],
+                )
+                .unwrap();
+        }
+
         #[test]
         fn entirely_overrides_task_of_same_name_for_other_lang() {
             let sandbox = create_sandbox("inheritance/override");
@@ -2,8 +2,9 @@ mod utils;
 
 use moon_common::{consts::CONFIG_PROJECT_FILENAME, Id};
 use moon_config::{
-    DependencyConfig, DependencyScope, InputPath, LanguageType, OwnersPaths, PlatformType,
-    ProjectConfig, ProjectDependsOn, ProjectType, TaskCommandArgs,
+    DependencyConfig, DependencyScope, HasherOptimization, HasherWalkStrategy, InputPath,
+    LanguageType, OwnersPaths, PlatformType, ProjectConfig, ProjectDependsOn, ProjectType,
+    TaskCommandArgs,
 };
 use rustc_hash::FxHashMap;
 use utils::*;
@@ -13,7 +14,7 @@ mod project_config {
 
     #[test]
     #[should_panic(
-        expected = "unknown field `unknown`, expected one of `$schema`, `dependsOn`, `env`, `fileGroups`, `language`, `owners`, `platform`, `project`, `tags`, `tasks`, `toolchain`, `type`, `workspace`"
+        expected = "unknown field `unknown`, expected one of `$schema`, `dependsOn`, `env`, `fileGroups`, `hasher`, `language`, `optionsPresets`, `owners`, `platform`, `project`, `tags`, `tasks`, `toolchain`, `type`, `workspace`"
     )]
     fn error_unknown_field() {
         test_load_config(CONFIG_PROJECT_FILENAME, "unknown: 123", |path| {
@@ -243,6 +244,39 @@ fileGroups:
         }
     }
 
+    mod hasher {
+        use super::*;
+
+        #[test]
+        fn defaults_to_workspace() {
+            let config = test_load_config(CONFIG_PROJECT_FILENAME, "{}", |path| {
+                ProjectConfig::load_from(path, ".")
+            });
+
+            assert_eq!(config.hasher.optimization, None);
+            assert_eq!(config.hasher.walk_strategy, None);
+        }
+
+        #[test]
+        fn can_set_settings() {
+            let config = test_load_config(
+                CONFIG_PROJECT_FILENAME,
+                r"
+hasher:
+  optimization: performance
+  walkStrategy: glob
+",
+                |path| ProjectConfig::load_from(path, "."),
+            );
+
+            assert_eq!(
+                config.hasher.optimization,
+                Some(HasherOptimization::Performance)
+            );
+            assert_eq!(config.hasher.walk_strategy, Some(HasherWalkStrategy::Glob));
+        }
+    }
+
     mod language {
         use super::*;
 
@@ -1,10 +1,13 @@
 mod utils;
 
 use moon_config::{
-    FilePath, InputPath, OutputPath, PlatformType, TaskCommandArgs, TaskConfig, TaskMergeStrategy,
-    TaskOutputStyle, TaskType,
+    validate_tasks_configs, FilePath, InputPath, OutputPath, PartialTaskOptionsConfig,
+    PlatformType, TaskArtifactConfig, TaskArtifactKind, TaskCommandArgs, TaskConfig,
+    TaskConfigBuilder, TaskDependency, TaskFileDependency, TaskMergeStrategy, TaskOutputStyle,
+    TaskType,
 };
 use moon_target::Target;
+use std::collections::BTreeMap;
 use utils::*;
 
 mod task_config {
@@ -12,7 +15,7 @@ mod task_config {
 
     #[test]
     #[should_panic(
-        expected = "unknown field `unknown`, expected one of `command`, `args`, `deps`, `env`, `inputs`, `local`, `outputs`, `options`, `platform`, `type`"
+        expected = "unknown field `unknown`, expected one of `artifacts`, `command`, `commandByEnv`, `args`, `deps`, `env`, `extends`, `inputs`, `local`, `outputs`, `options`, `optionsPreset`, `platform`, `toolchain`, `type`"
     )]
     fn error_unknown_field() {
         test_parse_config("unknown: 123", |code| TaskConfig::parse(code));
@@ -27,11 +30,115 @@ mod task_config {
         assert_eq!(config.type_of, None);
     }
 
+    mod artifacts {
+        use super::*;
+
+        #[test]
+        fn defaults_to_empty() {
+            let config = test_parse_config("{}", |code| TaskConfig::parse(code));
+
+            assert!(config.artifacts.is_empty());
+        }
+
+        #[test]
+        fn round_trips() {
+            let config = test_parse_config(
+                r"
+artifacts:
+  - name: cli
+    kind: binary
+    path: target/release/cli
+  - name: dist
+    path: 'dist/*.tgz'
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(
+                config.artifacts,
+                vec![
+                    TaskArtifactConfig {
+                        name: "cli".into(),
+                        kind: TaskArtifactKind::Binary,
+                        path: "target/release/cli".into(),
+                    },
+                    TaskArtifactConfig {
+                        name: "dist".into(),
+                        kind: TaskArtifactKind::File,
+                        path: "dist/*.tgz".into(),
+                    },
+                ]
+            );
+
+            let code = format!(
+                "artifacts:\n{}",
+                serde_yaml::to_string(&config.artifacts).unwrap()
+            );
+            let reparsed = test_parse_config(&code, |code| TaskConfig::parse(code));
+
+            assert_eq!(reparsed.artifacts, config.artifacts);
+        }
+
+        #[test]
+        #[should_panic(expected = "an artifact name is required")]
+        fn errors_on_empty_name() {
+            test_parse_config(
+                r"
+artifacts:
+  - name: ''
+    path: dist/app.js
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "an artifact path is required")]
+        fn errors_on_empty_path() {
+            test_parse_config(
+                r"
+artifacts:
+  - name: app
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "absolute paths are not supported")]
+        fn errors_on_drive_path() {
+            test_parse_config(
+                r"
+artifacts:
+  - name: app
+    path: 'C:\x'
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "unknown variant `image`, expected one of `archive`, `binary`, `container`, `file`, `package`"
+        )]
+        fn errors_on_invalid_kind() {
+            test_parse_config(
+                r"
+artifacts:
+  - name: app
+    kind: image
+    path: dist
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+    }
+
     mod command {
         use super::*;
 
         #[test]
-        #[should_panic(expected = "expected a string or a list of strings")]
+        #[should_panic(expected = "expected a string, a list of strings, or a map of strings")]
         fn errors_on_invalid_type() {
             test_parse_config("command: 123", |code| TaskConfig::parse(code));
         }
@@ -67,6 +174,55 @@ mod task_config {
 
             assert_eq!(config.command, TaskCommandArgs::List(vec!["bin".into()]));
         }
+
+        #[test]
+        #[should_panic(expected = "a map of arguments is only supported for `args`")]
+        fn errors_for_map() {
+            test_parse_config("command:\n  foo: bar", |code| TaskConfig::parse(code));
+        }
+
+        #[test]
+        #[should_panic(expected = "a map of arguments is only supported for `args`")]
+        fn errors_for_empty_map() {
+            test_parse_config("command: {}", |code| TaskConfig::parse(code));
+        }
+    }
+
+    mod command_by_env {
+        use super::*;
+
+        #[test]
+        fn parses_strings_and_lists() {
+            let config = test_parse_config(
+                "command: bin\ncommandByEnv:\n  production: 'bin --prod'\n  staging: [bin, --stage]",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(
+                config.command_by_env.get("production").unwrap(),
+                &TaskCommandArgs::String("bin --prod".into())
+            );
+            assert_eq!(
+                config.command_by_env.get("staging").unwrap(),
+                &TaskCommandArgs::List(vec!["bin".into(), "--stage".into()])
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "a command is required; use \"noop\" otherwise")]
+        fn errors_for_empty_string() {
+            test_parse_config("commandByEnv:\n  production: ''", |code| {
+                TaskConfig::parse(code)
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "a map of arguments is only supported for `args`")]
+        fn errors_for_map() {
+            test_parse_config("commandByEnv:\n  production:\n    foo: bar", |code| {
+                TaskConfig::parse(code)
+            });
+        }
     }
 
     mod args {
@@ -86,6 +242,40 @@ mod task_config {
             assert_eq!(config.args, TaskCommandArgs::List(vec!["bin".into()]));
         }
 
+        #[test]
+        fn parses_map() {
+            let config = test_parse_config("args:\n  foo: bar\n  baz: ''", |code| {
+                TaskConfig::parse(code)
+            });
+
+            assert_eq!(
+                config.args,
+                TaskCommandArgs::Map(BTreeMap::from_iter([
+                    ("foo".into(), "bar".into()),
+                    ("baz".into(), "".into()),
+                ]))
+            );
+        }
+
+        #[test]
+        fn parses_none() {
+            let config = test_parse_config("args: null", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.args, TaskCommandArgs::None);
+        }
+
+        #[test]
+        #[should_panic(expected = "at least one named argument is required")]
+        fn errors_for_empty_map() {
+            test_parse_config("args: {}", |code| TaskConfig::parse(code));
+        }
+
+        #[test]
+        #[should_panic(expected = "an argument name is required")]
+        fn errors_for_empty_map_key() {
+            test_parse_config("args:\n  '': bar", |code| TaskConfig::parse(code));
+        }
+
         #[test]
         fn supports_variants() {
             let config = test_parse_config(
@@ -154,10 +344,123 @@ deps:
             test_parse_config("deps: [':task']", |code| TaskConfig::parse(code));
         }
 
+        // Validated during project graph construction, based on `runner.expandTagDeps`
         #[test]
-        #[should_panic(expected = "target scope not supported as a task dependency")]
-        fn errors_on_tag_scope() {
-            test_parse_config("deps: ['#tag:task']", |code| TaskConfig::parse(code));
+        fn supports_tag_scope() {
+            let config = test_parse_config("deps: ['#tag:task']", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.deps, vec![Target::parse("#tag:task").unwrap()]);
+        }
+
+        #[test]
+        fn supports_files() {
+            let config = test_parse_config(
+                r"
+deps:
+  - task
+  - file: .cache/server.ready
+  - file: out/schema.json
+    timeout: 60
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(
+                config.deps,
+                vec![
+                    TaskDependency::Target(Target::parse("task").unwrap()),
+                    TaskDependency::File(TaskFileDependency {
+                        file: FilePath(".cache/server.ready".into()),
+                        timeout: 30,
+                    }),
+                    TaskDependency::File(TaskFileDependency {
+                        file: FilePath("out/schema.json".into()),
+                        timeout: 60,
+                    }),
+                ]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "globs are not supported, expected a literal file path")]
+        fn errors_on_file_glob() {
+            test_parse_config("deps: [{ file: 'out/**/*' }]", |code| {
+                TaskConfig::parse(code)
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "missing field `file`")]
+        fn errors_on_missing_file() {
+            test_parse_config("deps: [{ timeout: 10 }]", |code| TaskConfig::parse(code));
+        }
+    }
+
+    mod extends {
+        use super::*;
+
+        #[test]
+        fn parses_task_id_as_own_target() {
+            let config = test_parse_config("extends: build", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.extends, Some(Target::parse("~:build").unwrap()));
+        }
+
+        #[test]
+        fn supports_own_scope() {
+            let config = test_parse_config("extends: '~:build'", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.extends, Some(Target::parse("~:build").unwrap()));
+        }
+
+        #[test]
+        #[should_panic(expected = "only tasks within the same project can be extended")]
+        fn errors_for_other_project() {
+            test_parse_config("extends: 'app:build'", |code| TaskConfig::parse(code));
+        }
+
+        #[test]
+        #[should_panic(expected = "only tasks within the same project can be extended")]
+        fn errors_for_deps_scope() {
+            test_parse_config("extends: '^:build'", |code| TaskConfig::parse(code));
+        }
+    }
+
+    mod global_inputs {
+        use super::*;
+
+        #[test]
+        fn supports_path_patterns() {
+            let config = test_parse_config(
+                r"
+globalInputs:
+  - /ws/path
+  - proj/path
+  - $FOO_BAR
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(
+                config.global_inputs,
+                vec![
+                    InputPath::WorkspaceFile("ws/path".into()),
+                    InputPath::ProjectFile("proj/path".into()),
+                    InputPath::EnvVar("FOO_BAR".into()),
+                ]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "absolute paths are not supported")]
+        fn errors_on_drive_path() {
+            test_parse_config(
+                r"
+globalInputs:
+  - 'C:\x'
+",
+                |code| TaskConfig::parse(code),
+            );
         }
     }
 
@@ -211,6 +514,38 @@ inputs:
                 ]
             );
         }
+
+        #[test]
+        #[should_panic(expected = "absolute paths are not supported")]
+        fn errors_on_drive_path() {
+            test_parse_config(
+                r"
+inputs:
+  - 'C:\x'
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        fn treats_root_as_workspace_relative() {
+            let config = test_parse_config(
+                r"
+inputs:
+  - /etc/foo
+  - '/dev/**/*'
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(
+                config.inputs.unwrap(),
+                vec![
+                    InputPath::WorkspaceFile("etc/foo".into()),
+                    InputPath::WorkspaceGlob("dev/**/*".into())
+                ]
+            );
+        }
     }
 
     mod outputs {
@@ -256,47 +591,220 @@ outputs:
                 |code| TaskConfig::parse(code),
             );
         }
-    }
-
-    mod platform {
-        use super::*;
 
         #[test]
-        fn supports_variant() {
-            let config = test_parse_config("platform: rust", |code| TaskConfig::parse(code));
-
-            assert_eq!(config.platform, PlatformType::Rust);
+        #[should_panic(expected = "absolute paths are not supported")]
+        fn errors_on_drive_path() {
+            test_parse_config(
+                r"
+outputs:
+  - 'C:\x'
+",
+                |code| TaskConfig::parse(code),
+            );
         }
 
         #[test]
-        #[should_panic(
-            expected = "unknown variant `perl`, expected one of `deno`, `node`, `rust`, `system`, `unknown`"
-        )]
-        fn errors_on_invalid_variant() {
-            test_parse_config("platform: perl", |code| TaskConfig::parse(code));
-        }
-    }
+        fn treats_root_as_workspace_relative() {
+            let config = test_parse_config(
+                r"
+outputs:
+  - '/usr/local/**/*'
+",
+                |code| TaskConfig::parse(code),
+            );
 
-    mod type_of {
-        use super::*;
+            assert_eq!(
+                config.outputs.unwrap(),
+                vec![OutputPath::WorkspaceGlob("usr/local/**/*".into())]
+            );
+        }
 
         #[test]
-        fn supports_variant() {
-            let config = test_parse_config("type: build", |code| TaskConfig::parse(code));
-
-            assert_eq!(config.type_of, Some(TaskType::Build));
+        #[should_panic(expected = "output dist overlaps with input dist")]
+        fn errors_when_equal_to_input() {
+            test_parse_config(
+                r"
+inputs:
+  - dist
+outputs:
+  - dist
+",
+                |code| TaskConfig::parse(code),
+            );
         }
 
         #[test]
-        #[should_panic(
-            expected = "unknown variant `cache`, expected one of `build`, `run`, `test`"
-        )]
-        fn errors_on_invalid_variant() {
-            test_parse_config("type: cache", |code| TaskConfig::parse(code));
+        #[should_panic(expected = "output dist overlaps with input dist/index.js")]
+        fn errors_when_parent_of_input() {
+            test_parse_config(
+                r"
+inputs:
+  - src/**/*
+  - dist/index.js
+outputs:
+  - dist
+",
+                |code| TaskConfig::parse(code),
+            );
         }
-    }
 
-    mod options {
+        #[test]
+        #[should_panic(expected = "output dist/** overlaps with input dist/index.js")]
+        fn errors_when_glob_matches_input() {
+            test_parse_config(
+                r"
+inputs:
+  - dist/index.js
+outputs:
+  - dist/**
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "output dist/*.js overlaps with input dist/index.js")]
+        fn errors_when_wildcard_matches_input() {
+            test_parse_config(
+                r"
+inputs:
+  - dist/index.js
+outputs:
+  - dist/*.js
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "output /dist/** overlaps with input /dist/index.js")]
+        fn errors_when_workspace_glob_matches_input() {
+            test_parse_config(
+                r"
+inputs:
+  - /dist/index.js
+outputs:
+  - /dist/**
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        fn doesnt_error_for_non_overlapping_paths() {
+            let config = test_parse_config(
+                r"
+inputs:
+  - '**/*'
+  - src/**/*
+  - dist/index.ts
+  - /dist/index.js
+  - '!dist/**/*'
+outputs:
+  - dist/*.js
+  - lib
+  - src/generated.ts
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(config.outputs.unwrap().len(), 3);
+        }
+
+        #[test]
+        #[should_panic(expected = "output **/*.js overlaps with input src/index.js")]
+        fn errors_when_globstar_matches_input() {
+            test_parse_config(
+                r"
+inputs:
+  - src/index.js
+outputs:
+  - '**/*.js'
+",
+                |code| TaskConfig::parse(code),
+            );
+        }
+
+        #[test]
+        fn doesnt_error_when_globstar_doesnt_match_input() {
+            let config = test_parse_config(
+                r"
+inputs:
+  - src/index.ts
+outputs:
+  - '**/*.d.ts'
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(config.outputs.unwrap().len(), 1);
+        }
+
+        #[test]
+        fn doesnt_error_when_overlap_allowed() {
+            let config = test_parse_config(
+                r"
+inputs:
+  - dist/index.js
+outputs:
+  - dist/**
+options:
+  allowOutputOverlap: true
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(config.options.allow_output_overlap, Some(true));
+        }
+    }
+
+    mod platform {
+        use super::*;
+
+        #[test]
+        fn supports_variant() {
+            let config = test_parse_config("platform: rust", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.platform, PlatformType::Rust);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "unknown variant `perl`, expected one of `deno`, `node`, `rust`, `system`, `unknown`"
+        )]
+        fn errors_on_invalid_variant() {
+            test_parse_config("platform: perl", |code| TaskConfig::parse(code));
+        }
+    }
+
+    mod type_of {
+        use super::*;
+
+        #[test]
+        fn supports_variant() {
+            let config = test_parse_config("type: build", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.type_of, Some(TaskType::Build));
+        }
+
+        #[test]
+        fn supports_serve() {
+            let config = test_parse_config("type: serve", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.type_of, Some(TaskType::Serve));
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "unknown variant `cache`, expected one of `build`, `run`, `serve`, `test`"
+        )]
+        fn errors_on_invalid_variant() {
+            test_parse_config("type: cache", |code| TaskConfig::parse(code));
+        }
+    }
+
+    mod options {
         use super::*;
 
         #[test]
@@ -314,6 +822,8 @@ outputs:
                 r"
 options:
   cache: false
+  cacheKeyExtra: v2
+  cacheUndeclaredOutputs: true
   runDepsInParallel: false
   mergeDeps: replace
   outputStyle: stream
@@ -323,11 +833,26 @@ options:
             let opts = config.options;
 
             assert_eq!(opts.cache, Some(false));
+            assert_eq!(opts.cache_key_extra, Some("v2".into()));
+            assert_eq!(opts.cache_undeclared_outputs, Some(true));
             assert_eq!(opts.run_deps_in_parallel, Some(false));
             assert_eq!(opts.merge_deps, Some(TaskMergeStrategy::Replace));
             assert_eq!(opts.output_style, Some(TaskOutputStyle::Stream));
         }
 
+        #[test]
+        fn can_set_json_output_style() {
+            let config = test_parse_config(
+                r"
+options:
+  outputStyle: json
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(config.options.output_style, Some(TaskOutputStyle::Json));
+        }
+
         mod affected_files {
             use super::*;
             use moon_config::TaskOptionAffectedFiles;
@@ -478,7 +1003,68 @@ options:
             }
 
             #[test]
-            #[should_panic(expected = "expected a boolean or a file system path")]
+            fn can_set_list_of_paths() {
+                let config = test_parse_config(
+                    r"
+options:
+  envFile:
+    - .env
+    - /.env.shared
+    - .env.local
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(
+                    config.options.env_file,
+                    Some(TaskOptionEnvFile::Files(vec![
+                        FilePath(".env".to_owned()),
+                        FilePath("/.env.shared".to_owned()),
+                        FilePath(".env.local".to_owned()),
+                    ]))
+                );
+            }
+
+            #[test]
+            fn round_trips_list_of_paths() {
+                let env_file = TaskOptionEnvFile::Files(vec![
+                    FilePath(".env".to_owned()),
+                    FilePath(".env.local".to_owned()),
+                ]);
+
+                let yaml = serde_yaml::to_string(&env_file).unwrap();
+
+                assert_eq!(yaml, "- .env\n- .env.local\n");
+                assert_eq!(
+                    serde_yaml::from_str::<TaskOptionEnvFile>(&yaml).unwrap(),
+                    env_file
+                );
+            }
+
+            #[test]
+            fn converts_to_input_paths() {
+                assert_eq!(
+                    TaskOptionEnvFile::Enabled(true).to_input_paths(),
+                    Some(vec![InputPath::ProjectFile(".env".into())])
+                );
+                assert_eq!(TaskOptionEnvFile::Enabled(false).to_input_paths(), None);
+                assert_eq!(
+                    TaskOptionEnvFile::Files(vec![
+                        FilePath(".env".to_owned()),
+                        FilePath("/.env.shared".to_owned()),
+                    ])
+                    .to_input_paths(),
+                    Some(vec![
+                        InputPath::ProjectFile(".env".into()),
+                        InputPath::WorkspaceFile(".env.shared".into()),
+                    ])
+                );
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "expected a boolean, a file system path, or a list of file system paths"
+            )]
             fn errors_on_glob() {
                 test_parse_config(
                     r"
@@ -489,17 +1075,500 @@ options:
                 );
             }
 
-            //             #[test]
-            //             #[should_panic(expected = "environment variables are not supported")]
-            //             fn errors_on_env_var() {
-            //                 test_parse_config(
-            //                     r"
-            // options:
-            //   envFile: $ENV_VAR
-            // ",
-            //                     |code| TaskConfig::parse(code),
-            //                 );
-            //             }
+            #[test]
+            #[should_panic(
+                expected = "expected a boolean, a file system path, or a list of file system paths"
+            )]
+            fn errors_on_glob_in_list() {
+                test_parse_config(
+                    r"
+options:
+  envFile: [.env, .env.*]
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "environment variables are not supported")]
+            fn errors_on_env_var() {
+                test_parse_config(
+                    r"
+options:
+  envFile: $ENV_VAR
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "environment variables are not supported")]
+            fn errors_on_env_var_in_list() {
+                test_parse_config(
+                    r"
+options:
+  envFile: [.env, $ENV_VAR]
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
         }
+
+        mod ready_pattern {
+            use super::*;
+
+            #[test]
+            fn can_set_with_output_style() {
+                let config = test_parse_config(
+                    r"
+options:
+  outputStyle: buffer-until-ready
+  readyPattern: 'listening on \d+'
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(
+                    config.options.output_style,
+                    Some(TaskOutputStyle::BufferUntilReady)
+                );
+                assert_eq!(
+                    config.options.ready_pattern,
+                    Some("listening on \\d+".into())
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "invalid ready pattern")]
+            fn errors_on_invalid_regex() {
+                test_parse_config(
+                    r"
+options:
+  readyPattern: 'listening on ('
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+        }
+
+        mod interactive {
+            use super::*;
+
+            #[test]
+            fn can_set_with_stream_output() {
+                let config = test_parse_config(
+                    r"
+options:
+  interactive: true
+  outputStyle: stream
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(config.options.interactive, Some(true));
+                assert_eq!(config.options.output_style, Some(TaskOutputStyle::Stream));
+            }
+
+            #[test]
+            #[should_panic(expected = "an interactive task requires the `stream` output style")]
+            fn errors_with_buffered_output() {
+                test_parse_config(
+                    r"
+options:
+  interactive: true
+  outputStyle: buffer-only-failure
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "an interactive task cannot also be persistent")]
+            fn errors_when_persistent() {
+                test_parse_config(
+                    r"
+options:
+  interactive: true
+  persistent: true
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            fn can_disable_when_persistent() {
+                let config = test_parse_config(
+                    r"
+options:
+  interactive: false
+  persistent: true
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(config.options.interactive, Some(false));
+            }
+        }
+
+        mod timeout {
+            use super::*;
+
+            #[test]
+            fn can_set() {
+                let config =
+                    test_parse_config("options:\n  timeout: 300", |code| TaskConfig::parse(code));
+
+                assert_eq!(config.options.timeout, Some(300));
+            }
+
+            #[test]
+            fn can_set_zero() {
+                let config =
+                    test_parse_config("options:\n  timeout: 0", |code| TaskConfig::parse(code));
+
+                assert_eq!(config.options.timeout, Some(0));
+            }
+
+            #[test]
+            #[should_panic(expected = "timeout cannot be larger than 86400 seconds")]
+            fn errors_when_too_large() {
+                test_parse_config("options:\n  timeout: 86401", |code| TaskConfig::parse(code));
+            }
+        }
+
+        mod env_passthrough {
+            use super::*;
+
+            #[test]
+            fn can_set_with_inherit_env() {
+                let config = test_parse_config(
+                    r"
+options:
+  inheritEnv: false
+  envPassthrough: [CI, GITHUB_TOKEN]
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(config.options.inherit_env, Some(false));
+                assert_eq!(
+                    config.options.env_passthrough,
+                    Some(vec!["CI".into(), "GITHUB_TOKEN".into()])
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "environment variable name cannot be empty")]
+            fn errors_on_empty_name() {
+                test_parse_config(
+                    r"
+options:
+  envPassthrough: [CI, '']
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+        }
+    }
+}
+
+mod task_config_builder {
+    use super::*;
+    use schematic::{Config, PartialConfig};
+
+    #[test]
+    fn builds_and_finalizes() {
+        let config = TaskConfigBuilder::new()
+            .command("webpack")
+            .args(["build", "--mode", "production"])
+            .dep(Target::parse("^:build").unwrap())
+            .dep(Target::parse("app:codegen").unwrap())
+            .inputs([InputPath::ProjectGlob("src/**/*".into())])
+            .outputs([OutputPath::ProjectFile("dist".into())])
+            .options(PartialTaskOptionsConfig {
+                cache: Some(false),
+                ..PartialTaskOptionsConfig::default()
+            })
+            .platform(PlatformType::Node)
+            .type_of(TaskType::Build)
+            .finalize()
+            .unwrap();
+
+        assert_eq!(config.command, TaskCommandArgs::String("webpack".into()));
+        assert_eq!(
+            config.args,
+            TaskCommandArgs::List(vec!["build".into(), "--mode".into(), "production".into()])
+        );
+        assert_eq!(
+            config.deps,
+            vec![
+                Target::parse("^:build").unwrap(),
+                Target::parse("app:codegen").unwrap()
+            ]
+        );
+        assert_eq!(
+            config.inputs,
+            Some(vec![InputPath::ProjectGlob("src/**/*".into())])
+        );
+        assert_eq!(
+            config.outputs,
+            Some(vec![OutputPath::ProjectFile("dist".into())])
+        );
+        assert_eq!(config.options.cache, Some(false));
+        assert_eq!(config.options.run_in_ci, None);
+        assert_eq!(config.platform, PlatformType::Node);
+        assert_eq!(config.type_of, Some(TaskType::Build));
+    }
+
+    #[test]
+    fn merges_partials() {
+        let mut partial = TaskConfigBuilder::new().command("webpack").build();
+
+        partial
+            .merge(&(), TaskConfigBuilder::new().env("KEY", "value").build())
+            .unwrap();
+
+        let config = TaskConfig::from_partial(partial.finalize(&()).unwrap());
+
+        assert_eq!(config.command, TaskCommandArgs::String("webpack".into()));
+        assert_eq!(config.env.get("KEY").unwrap(), "value");
+    }
+
+    #[test]
+    #[should_panic(expected = "target scope not supported as a task dependency")]
+    fn validates_when_finalizing() {
+        TaskConfigBuilder::new()
+            .command("webpack")
+            .dep(Target::parse(":build").unwrap())
+            .finalize()
+            .unwrap();
+    }
+}
+
+mod parse_map {
+    use super::*;
+
+    fn get_message(code: &str) -> String {
+        TaskConfig::parse_map(code)
+            .unwrap_err()
+            .chain()
+            .map(|cause| cause.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn returns_empty_for_empty_code() {
+        assert!(TaskConfig::parse_map("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_multiple_tasks() {
+        let tasks = TaskConfig::parse_map(
+            r"
+build:
+  command: webpack
+  outputs: ['dist']
+test:
+  command: jest
+  deps: ['~:build']
+",
+        )
+        .unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks.get("build").unwrap().command,
+            TaskCommandArgs::String("webpack".into())
+        );
+        assert_eq!(
+            tasks.get("test").unwrap().deps,
+            vec![TaskDependency::Target(Target::parse("~:build").unwrap())]
+        );
+    }
+
+    #[test]
+    fn names_the_invalid_task() {
+        let message = get_message(
+            r"
+build:
+  command: webpack
+bad:
+  command: ''
+test:
+  command: jest
+",
+        );
+
+        assert!(message.starts_with("Invalid task bad."));
+        assert!(message.contains("a command is required"));
+    }
+
+    #[test]
+    fn errors_on_syntax() {
+        let message = get_message("build:\n  command: [");
+
+        assert!(message.starts_with("Failed to parse tasks."));
+    }
+}
+
+mod validate_tasks_configs {
+    use super::*;
+
+    fn get_messages(code: &str) -> Vec<String> {
+        validate_tasks_configs(code)
+            .into_iter()
+            .map(|error| {
+                error
+                    .chain()
+                    .map(|cause| cause.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn returns_nothing_for_valid_tasks() {
+        let messages = get_messages(
+            r"
+build:
+  command: webpack
+test:
+  command: jest
+  deps: ['~:build']
+lint:
+  command: eslint
+  deps: ['~:inherited', 'other:build']
+",
+        );
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn reports_invalid_command_for_the_right_task() {
+        let messages = get_messages(
+            r"
+build:
+  command: webpack
+bad:
+  command: ''
+test:
+  command: jest
+",
+        );
+
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("Invalid task bad."));
+        assert!(messages[0].contains("a command is required"));
+    }
+
+    #[test]
+    fn reports_all_invalid_tasks() {
+        let messages = get_messages(
+            r"
+a:
+  command: []
+b:
+  unknown: true
+",
+        );
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("Invalid task a."));
+        assert!(messages[1].starts_with("Invalid task b."));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let messages = get_messages("build:\n  command: [");
+
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("Failed to parse tasks."));
+    }
+
+    #[test]
+    fn reports_duplicate_tasks() {
+        let messages = get_messages("build:\n  command: a\nbuild:\n  command: b");
+
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("duplicate entry"));
+    }
+
+    #[test]
+    fn reports_self_dependencies() {
+        let messages = get_messages("build:\n  command: a\n  deps: ['~:build']");
+
+        assert_eq!(messages, vec!["Task build cannot depend on itself."]);
+    }
+
+    #[test]
+    fn reports_self_extends() {
+        let messages = get_messages("build:\n  command: a\n  extends: build");
+
+        assert_eq!(messages, vec!["Task build cannot extend itself."]);
+    }
+
+    #[test]
+    fn reports_extends_cycles_once() {
+        let messages = get_messages(
+            r"
+a:
+  command: a
+  extends: c
+b:
+  extends: a
+c:
+  extends: b
+d:
+  extends: a
+",
+        );
+
+        assert_eq!(
+            messages,
+            vec!["Task extends cycle detected: a -> c -> b -> a."]
+        );
+    }
+
+    #[test]
+    fn allows_extends_chains() {
+        let messages = get_messages(
+            r"
+a:
+  command: a
+b:
+  extends: a
+c:
+  extends: b
+d:
+  extends: inherited
+",
+        );
+
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn reports_dependency_cycles() {
+        let messages = get_messages(
+            r"
+a:
+  command: a
+  deps: ['~:b']
+b:
+  command: b
+  deps: ['~:c']
+c:
+  command: c
+  deps: ['~:a']
+",
+        );
+
+        assert_eq!(
+            messages,
+            vec!["Task dependency cycle detected: a -> b -> c -> a."]
+        );
     }
 }
@@ -1,8 +1,12 @@
 mod utils;
 
-use moon_config::{BinConfig, BinEntry, NodePackageManager, ToolchainConfig};
+use moon_common::Id;
+use moon_config::{
+    BinConfig, BinEntry, FilePath, NodePackageManager, PlatformType, ToolchainConfig,
+    ToolchainDiff, ToolchainVersionDelta,
+};
 use proto::ToolsConfig;
-use starbase_sandbox::create_sandbox;
+use starbase_sandbox::{create_empty_sandbox, create_sandbox};
 use std::env;
 use utils::*;
 
@@ -13,7 +17,7 @@ mod toolchain_config {
 
     #[test]
     #[should_panic(
-        expected = "unknown field `unknown`, expected one of `$schema`, `extends`, `deno`, `node`, `rust`, `typescript`"
+        expected = "unknown field `unknown`, expected one of `$schema`, `extends`, `inheritToolVersions`, `bun`, `deno`, `node`, `rust`, `typescript`"
     )]
     fn error_unknown_field() {
         test_load_config(FILENAME, "unknown: 123", |path| {
@@ -31,6 +35,32 @@ mod toolchain_config {
         assert!(config.node.is_none());
         assert!(config.rust.is_none());
         assert!(config.typescript.is_none());
+        assert!(config.enabled_platforms().is_empty());
+    }
+
+    #[test]
+    fn returns_enabled_platforms() {
+        let config = test_load_config(FILENAME, "node: {}\nrust: {}", |path| {
+            ToolchainConfig::load_from(path, &ToolsConfig::default())
+        });
+
+        assert_eq!(
+            config.enabled_platforms(),
+            vec![PlatformType::Node, PlatformType::Rust]
+        );
+    }
+
+    #[test]
+    fn doesnt_return_platforms_for_bun_and_typescript() {
+        let config = test_load_config(FILENAME, "bun: {}\nnode: {}\ntypescript: {}", |path| {
+            ToolchainConfig::load_from(path, &ToolsConfig::default())
+        });
+
+        assert_eq!(config.enabled_platforms(), vec![PlatformType::Node]);
+        assert_eq!(
+            config.enabled_toolchains(),
+            vec![Id::raw("bun"), Id::raw("node"), Id::raw("typescript")]
+        );
     }
 
     mod extends {
@@ -70,6 +100,122 @@ mod toolchain_config {
         }
     }
 
+    mod bun {
+        use super::*;
+
+        #[test]
+        fn uses_defaults() {
+            let config = test_load_config(FILENAME, "bun: {}", |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            let cfg = config.bun.unwrap();
+
+            assert!(cfg.bins.is_empty());
+            assert!(cfg.version.is_none());
+        }
+
+        #[test]
+        fn sets_values() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+bun:
+  bins:
+    - prettier
+    - bin: typescript
+      local: true
+  version: 1.0.0
+",
+                |path| ToolchainConfig::load_from(path, &ToolsConfig::default()),
+            );
+
+            let cfg = config.bun.unwrap();
+
+            assert_eq!(
+                cfg.bins,
+                vec![
+                    BinEntry::Name("prettier".into()),
+                    BinEntry::Config(BinConfig {
+                        bin: "typescript".into(),
+                        local: true,
+                        ..BinConfig::default()
+                    }),
+                ]
+            );
+            assert_eq!(cfg.version.unwrap(), "1.0.0");
+        }
+
+        #[test]
+        fn enables_via_proto() {
+            let config = test_load_config(FILENAME, "{}", |path| {
+                let mut proto = ToolsConfig::default();
+                proto.tools.insert("bun".into(), "1.0.0".into());
+
+                ToolchainConfig::load_from(path, &proto)
+            });
+
+            assert!(config.bun.is_some());
+            assert_eq!(config.bun.unwrap().version.unwrap(), "1.0.0");
+        }
+
+        #[test]
+        fn proto_version_doesnt_override() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+bun:
+  version: 0.8.0
+",
+                |path| {
+                    let mut proto = ToolsConfig::default();
+                    proto.tools.insert("bun".into(), "1.0.0".into());
+
+                    ToolchainConfig::load_from(path, &proto)
+                },
+            );
+
+            assert!(config.bun.is_some());
+            assert_eq!(config.bun.unwrap().version.unwrap(), "0.8.0");
+        }
+
+        #[test]
+        #[should_panic(expected = "not a valid semantic version")]
+        fn validates_version() {
+            test_load_config(
+                FILENAME,
+                r"
+bun:
+  version: '1'
+",
+                |path| ToolchainConfig::load_from(path, &ToolsConfig::default()),
+            );
+        }
+
+        #[test]
+        fn inherits_version_from_env_var() {
+            env::set_var("MOON_BUN_VERSION", "1.0.1");
+
+            let config = test_load_config(
+                FILENAME,
+                r"
+bun:
+  version: 0.8.0
+",
+                |path| {
+                    let mut proto = ToolsConfig::default();
+                    proto.tools.insert("bun".into(), "1.0.0".into());
+
+                    ToolchainConfig::load_from(path, &proto)
+                },
+            );
+
+            env::remove_var("MOON_BUN_VERSION");
+
+            assert_eq!(config.bun.unwrap().version.unwrap(), "1.0.1");
+        }
+    }
+
     mod deno {
         use super::*;
 
@@ -103,6 +249,58 @@ deno:
             assert!(cfg.lockfile);
         }
 
+        #[test]
+        fn sets_import_map() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+deno:
+  importMap: import_map.json
+",
+                |path| ToolchainConfig::load_from(path, &ToolsConfig::default()),
+            );
+
+            let cfg = config.deno.unwrap();
+
+            assert_eq!(cfg.import_map, Some(FilePath("import_map.json".into())));
+        }
+
+        #[test]
+        fn infers_lockfile_from_deno_json() {
+            let sandbox = create_sandbox("deno/json");
+            sandbox.create_file(FILENAME, "deno: {}");
+
+            let config = test_config(sandbox.path(), |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            assert!(config.deno.unwrap().lockfile);
+        }
+
+        #[test]
+        fn infers_lockfile_from_deno_jsonc_with_comments() {
+            let sandbox = create_sandbox("deno/jsonc");
+            sandbox.create_file(FILENAME, "deno: {}");
+
+            let config = test_config(sandbox.path(), |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            assert!(config.deno.unwrap().lockfile);
+        }
+
+        #[test]
+        fn doesnt_infer_lockfile_when_explicitly_set() {
+            let sandbox = create_sandbox("deno/json");
+            sandbox.create_file(FILENAME, "deno:\n  lockfile: false");
+
+            let config = test_config(sandbox.path(), |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            assert!(!config.deno.unwrap().lockfile);
+        }
+
         #[test]
         fn can_set_bin_objects() {
             let config = test_load_config(
@@ -161,6 +359,8 @@ deno:
 
             assert!(cfg.dedupe_on_lockfile_change);
             assert!(!cfg.infer_tasks_from_scripts);
+            assert_eq!(cfg.install_retry_backoff, 1000);
+            assert_eq!(cfg.install_retry_count, 2);
         }
 
         #[test]
@@ -171,6 +371,7 @@ deno:
 node:
   dedupeOnLockfileChange: false
   inferTasksFromScripts: true
+  installRetryCount: 0
 ",
                 |path| ToolchainConfig::load_from(path, &ToolsConfig::default()),
             );
@@ -179,6 +380,7 @@ node:
 
             assert!(!cfg.dedupe_on_lockfile_change);
             assert!(cfg.infer_tasks_from_scripts);
+            assert_eq!(cfg.install_retry_count, 0);
         }
 
         #[test]
@@ -250,6 +452,125 @@ node:
             assert_eq!(config.node.unwrap().version.unwrap(), "19.0.0");
         }
 
+        #[test]
+        fn ignores_version_from_env_var_when_disabled() {
+            env::set_var("MOON_NODE_VERSION", "19.0.0");
+
+            let config = test_load_config(
+                FILENAME,
+                r"
+node:
+  version: 20.0.0
+",
+                |path| {
+                    ToolchainConfig::load_from_with_env_overrides(
+                        path,
+                        &ToolsConfig::default(),
+                        false,
+                    )
+                },
+            );
+
+            env::remove_var("MOON_NODE_VERSION");
+
+            assert_eq!(config.node.unwrap().version.unwrap(), "20.0.0");
+        }
+
+        mod version_alias {
+            use super::*;
+            use proto::{VersionManifest, VersionManifestEntry};
+
+            fn create_manifest() -> VersionManifest {
+                let mut manifest = VersionManifest::default();
+
+                for (version, alias) in [("20.5.0", None), ("18.17.0", Some("hydrogen"))] {
+                    manifest.versions.insert(
+                        version.into(),
+                        VersionManifestEntry {
+                            alias: alias.map(|a| a.into()),
+                            version: version.into(),
+                        },
+                    );
+                }
+
+                manifest.aliases.insert("latest".into(), "v20.5.0".into());
+                manifest.aliases.insert("stable".into(), "v18.17.0".into());
+                manifest
+                    .aliases
+                    .insert("hydrogen".into(), "v18.17.0".into());
+                manifest
+            }
+
+            fn load_alias_config(code: &str) -> ToolchainConfig {
+                test_load_config(FILENAME, code, |path| {
+                    ToolchainConfig::load_from(path, &ToolsConfig::default())
+                })
+            }
+
+            #[test]
+            fn supports_aliases() {
+                let config = load_alias_config("node:\n  version: latest");
+
+                assert_eq!(config.node.unwrap().version.unwrap(), "latest");
+
+                let config = load_alias_config("node:\n  version: lts");
+
+                assert_eq!(config.node.unwrap().version.unwrap(), "lts");
+            }
+
+            #[test]
+            fn resolves_lts_to_a_concrete_version() {
+                let mut node = load_alias_config("node:\n  version: lts").node.unwrap();
+
+                node.resolve_version_alias(&create_manifest(), false)
+                    .unwrap();
+
+                assert_eq!(node.version.unwrap(), "18.17.0");
+            }
+
+            #[test]
+            fn resolves_latest_to_a_concrete_version() {
+                let mut node = load_alias_config("node:\n  version: latest").node.unwrap();
+
+                node.resolve_version_alias(&create_manifest(), false)
+                    .unwrap();
+
+                assert_eq!(node.version.unwrap(), "20.5.0");
+            }
+
+            #[test]
+            fn doesnt_change_explicit_versions() {
+                let mut node = load_alias_config("node:\n  version: 19.0.0").node.unwrap();
+
+                node.resolve_version_alias(&create_manifest(), true)
+                    .unwrap();
+
+                assert_eq!(node.version.unwrap(), "19.0.0");
+            }
+
+            #[test]
+            #[should_panic(expected = "is not allowed in CI")]
+            fn errors_on_latest_in_ci() {
+                let mut node = load_alias_config("node:\n  version: latest").node.unwrap();
+
+                node.resolve_version_alias(&create_manifest(), true)
+                    .unwrap();
+            }
+
+            #[test]
+            fn allows_alias_in_ci_when_enabled() {
+                let mut node =
+                    load_alias_config("node:\n  version: latest\n  allowVersionAliasInCi: true")
+                        .node
+                        .unwrap();
+
+                node.resolve_version_alias(&create_manifest(), true)
+                    .unwrap();
+
+                assert_eq!(node.version.unwrap(), "20.5.0");
+            }
+        }
+
         mod npm {
             use super::*;
 
@@ -572,6 +893,116 @@ rust:
         }
     }
 
+    mod tool_versions {
+        use super::*;
+        use std::fs;
+
+        #[test]
+        fn enables_via_tool_versions() {
+            let config = test_load_config(FILENAME, "inheritToolVersions: true", |path| {
+                fs::write(
+                    path.join(".tool-versions"),
+                    "# Runtimes\nnodejs 20.0.0\nrust 1.70.0 system\ndeno 1.36.0\npython 3.11.0",
+                )
+                .unwrap();
+
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            assert_eq!(config.node.unwrap().version.unwrap(), "20.0.0");
+            assert_eq!(config.rust.unwrap().version.unwrap(), "1.70.0");
+            assert!(config.deno.is_some());
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let config = test_load_config(FILENAME, "{}", |path| {
+                fs::write(path.join(".tool-versions"), "nodejs 20.0.0\nrust 1.70.0").unwrap();
+
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            assert!(!config.inherit_tool_versions);
+            assert!(config.node.is_none());
+            assert!(config.rust.is_none());
+        }
+
+        #[test]
+        fn skips_non_semver_versions() {
+            let config = test_load_config(FILENAME, "inheritToolVersions: true", |path| {
+                fs::write(
+                    path.join(".tool-versions"),
+                    "nodejs system\nrust lts\nbun ref:abc123",
+                )
+                .unwrap();
+
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            });
+
+            assert!(config.node.is_none());
+            assert!(config.rust.is_none());
+            assert!(config.bun.is_none());
+        }
+
+        #[test]
+        fn config_version_takes_precedence() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+inheritToolVersions: true
+rust:
+  version: 1.60.0
+",
+                |path| {
+                    fs::write(path.join(".tool-versions"), "rust 1.70.0").unwrap();
+
+                    ToolchainConfig::load_from(path, &ToolsConfig::default())
+                },
+            );
+
+            assert_eq!(config.rust.unwrap().version.unwrap(), "1.60.0");
+        }
+
+        #[test]
+        fn proto_version_takes_precedence() {
+            let config = test_load_config(FILENAME, "inheritToolVersions: true", |path| {
+                fs::write(path.join(".tool-versions"), "rust 1.70.0").unwrap();
+
+                let mut proto = ToolsConfig::default();
+                proto.tools.insert("rust".into(), "1.65.0".into());
+
+                ToolchainConfig::load_from(path, &proto)
+            });
+
+            assert_eq!(config.rust.unwrap().version.unwrap(), "1.65.0");
+        }
+
+        #[test]
+        fn env_var_takes_precedence() {
+            env::set_var("MOON_NODE_VERSION", "18.0.0");
+
+            let config = test_load_config(
+                FILENAME,
+                r"
+inheritToolVersions: true
+node: {}
+",
+                |path| {
+                    fs::write(path.join(".tool-versions"), "nodejs 20.0.0").unwrap();
+
+                    let mut proto = ToolsConfig::default();
+                    proto.tools.insert("node".into(), "19.0.0".into());
+
+                    ToolchainConfig::load_from(path, &proto)
+                },
+            );
+
+            env::remove_var("MOON_NODE_VERSION");
+
+            assert_eq!(config.node.unwrap().version.unwrap(), "18.0.0");
+        }
+    }
+
     mod typescript {
         use super::*;
 
@@ -618,4 +1049,204 @@ typescript:
             // assert_eq!(config.typescript.unwrap().version.unwrap(), "1.30.0");
         }
     }
+    mod diff {
+        use super::*;
+
+        fn load(code: &str) -> ToolchainConfig {
+            test_load_config(FILENAME, code, |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            })
+        }
+
+        #[test]
+        fn no_changes() {
+            let prev = load("node:\n  version: '18.0.0'");
+            let next = load("node:\n  version: '18.0.0'");
+
+            assert!(prev.diff(&next).is_empty());
+        }
+
+        #[test]
+        fn detects_added_removed_and_changed() {
+            let prev = load("node:\n  version: '18.0.0'\nrust:\n  version: '1.70.0'");
+            let next = load("node:\n  version: '20.0.0'\ntypescript: {}");
+
+            assert_eq!(
+                prev.diff(&next),
+                ToolchainDiff {
+                    added: vec!["typescript".into()],
+                    removed: vec!["rust".into()],
+                    changed: vec![ToolchainVersionDelta {
+                        tool: "node".into(),
+                        from: Some("18.0.0".into()),
+                        to: Some("20.0.0".into()),
+                    }],
+                }
+            );
+        }
+
+        #[test]
+        fn detects_bun_changes() {
+            let prev = load("bun:\n  version: '1.0.0'");
+            let next = load("bun:\n  version: '1.0.1'");
+
+            assert_eq!(
+                prev.diff(&next).changed,
+                vec![ToolchainVersionDelta {
+                    tool: "bun".into(),
+                    from: Some("1.0.0".into()),
+                    to: Some("1.0.1".into()),
+                }]
+            );
+        }
+
+        #[test]
+        fn detects_package_manager_changes() {
+            let prev = load("node:\n  npm:\n    version: '9.0.0'");
+            let next = load(
+                "node:\n  npm:\n    version: '9.1.0'\n  packageManager: pnpm\n  pnpm:\n    version: '8.0.0'",
+            );
+
+            assert_eq!(
+                prev.diff(&next),
+                ToolchainDiff {
+                    added: vec!["pnpm".into()],
+                    removed: vec![],
+                    changed: vec![ToolchainVersionDelta {
+                        tool: "npm".into(),
+                        from: Some("9.0.0".into()),
+                        to: Some("9.1.0".into()),
+                    }],
+                }
+            );
+        }
+    }
+
+    mod node_package_manager {
+        use super::*;
+        use semver::Version;
+
+        fn load(code: &str) -> ToolchainConfig {
+            test_load_config(FILENAME, code, |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            })
+        }
+
+        #[test]
+        fn none_without_node() {
+            assert_eq!(load("{}").node_package_manager(), None);
+        }
+
+        #[test]
+        fn defaults_to_npm() {
+            assert_eq!(
+                load("node: {}").node_package_manager(),
+                Some((NodePackageManager::Npm, None))
+            );
+
+            assert_eq!(
+                load("node:\n  npm:\n    version: '9.0.0'").node_package_manager(),
+                Some((NodePackageManager::Npm, Some(Version::new(9, 0, 0))))
+            );
+        }
+
+        #[test]
+        fn returns_pnpm() {
+            assert_eq!(
+                load("node:\n  packageManager: pnpm\n  pnpm:\n    version: '8.1.0'")
+                    .node_package_manager(),
+                Some((NodePackageManager::Pnpm, Some(Version::new(8, 1, 0))))
+            );
+        }
+
+        #[test]
+        fn returns_yarn() {
+            assert_eq!(
+                load("node:\n  packageManager: yarn\n  yarn:\n    version: '3.6.0'")
+                    .node_package_manager(),
+                Some((NodePackageManager::Yarn, Some(Version::new(3, 6, 0))))
+            );
+        }
+
+        #[test]
+        fn ignores_versions_of_other_managers() {
+            assert_eq!(
+                load("node:\n  packageManager: yarn\n  npm:\n    version: '9.0.0'")
+                    .node_package_manager(),
+                Some((NodePackageManager::Yarn, None))
+            );
+        }
+    }
+
+    mod proto_tools {
+        use super::*;
+        use std::fs;
+
+        fn load(code: &str) -> ToolchainConfig {
+            test_load_config(FILENAME, code, |path| {
+                ToolchainConfig::load_from(path, &ToolsConfig::default())
+            })
+        }
+
+        #[test]
+        fn merges_resolved_versions() {
+            let config = load(
+                "node:\n  version: '18.0.0'\n  npm:\n    version: '9.0.0'\nrust:\n  version: '1.70.0'\ntypescript: {}",
+            );
+
+            let mut proto = ToolsConfig::default();
+
+            assert!(config.merge_proto_tools(&mut proto));
+            assert_eq!(proto.tools.len(), 3);
+            assert_eq!(proto.tools.get("node").unwrap(), "18.0.0");
+            assert_eq!(proto.tools.get("npm").unwrap(), "9.0.0");
+            assert_eq!(proto.tools.get("rust").unwrap(), "1.70.0");
+
+            assert!(!config.merge_proto_tools(&mut proto));
+        }
+
+        #[test]
+        fn preserves_unmanaged_proto_tools() {
+            let config = load("node:\n  version: '18.0.0'");
+
+            let mut proto = ToolsConfig::default();
+            proto.tools.insert("go".into(), "1.20.0".into());
+            proto.tools.insert("node".into(), "16.0.0".into());
+
+            assert!(config.merge_proto_tools(&mut proto));
+            assert_eq!(proto.tools.get("go").unwrap(), "1.20.0");
+            assert_eq!(proto.tools.get("node").unwrap(), "18.0.0");
+        }
+
+        #[test]
+        fn writes_file_only_when_changed() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                "go = \"1.20.0\"\n\n[plugins]\nmy-tool = \"source:./my-tool.toml\"\n",
+            );
+
+            let config = load("node:\n  version: '18.0.0'");
+
+            assert!(config.write_proto_tools(sandbox.path()).unwrap());
+
+            let proto = ToolsConfig::load(sandbox.path().join(".prototools")).unwrap();
+
+            assert_eq!(proto.tools.get("go").unwrap(), "1.20.0");
+            assert_eq!(proto.tools.get("node").unwrap(), "18.0.0");
+            assert!(proto.plugins.contains_key("my-tool"));
+
+            let content = fs::read_to_string(sandbox.path().join(".prototools")).unwrap();
+
+            assert!(!config.write_proto_tools(sandbox.path()).unwrap());
+            assert_eq!(
+                fs::read_to_string(sandbox.path().join(".prototools")).unwrap(),
+                content
+            );
+
+            let config = load("node:\n  version: '20.0.0'");
+
+            assert!(config.write_proto_tools(sandbox.path()).unwrap());
+        }
+    }
 }
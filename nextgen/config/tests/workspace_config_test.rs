@@ -1,8 +1,11 @@
 mod utils;
 
-use moon_config::{FilePath, VcsProvider, WorkspaceConfig, WorkspaceProjects};
+use moon_config::{
+    FilePath, HasherOptimization, HasherWalkStrategy, InputPath, ProjectHasherConfig, VcsProvider,
+    WorkspaceConfig, WorkspaceProjects, AUTO_BATCH_SIZE_MAX, AUTO_BATCH_SIZE_MIN,
+};
 use rustc_hash::FxHashMap;
-use starbase_sandbox::create_sandbox;
+use starbase_sandbox::{create_empty_sandbox, create_sandbox};
 use utils::*;
 
 const FILENAME: &str = ".moon/workspace.yml";
@@ -244,10 +247,25 @@ projects:
                 WorkspaceConfig::load_from(path)
             });
 
+            assert!(!config.constraints.enforce_language_platform_compatibility);
             assert!(config.constraints.enforce_project_type_relationships);
             assert!(config.constraints.tag_relationships.is_empty());
         }
 
+        #[test]
+        fn can_enforce_language_platform_compatibility() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+constraints:
+  enforceLanguagePlatformCompatibility: true
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            assert!(config.constraints.enforce_language_platform_compatibility);
+        }
+
         #[test]
         fn can_set_tags() {
             let config = test_load_config(
@@ -360,6 +378,53 @@ generator:
         }
     }
 
+    mod global_inputs {
+        use super::*;
+
+        #[test]
+        fn defaults_to_empty() {
+            let config = test_load_config(FILENAME, "{}", |path| WorkspaceConfig::load_from(path));
+
+            assert!(config.global_inputs.is_empty());
+        }
+
+        #[test]
+        fn supports_paths_and_env_vars() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+globalInputs:
+  - /package-lock.json
+  - '/.moon/*.yml'
+  - $NODE_ENV
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            assert_eq!(
+                config.global_inputs,
+                vec![
+                    InputPath::WorkspaceFile("package-lock.json".into()),
+                    InputPath::WorkspaceGlob(".moon/*.yml".into()),
+                    InputPath::EnvVar("NODE_ENV".into()),
+                ]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "absolute paths are not supported")]
+        fn errors_on_drive_path() {
+            test_load_config(
+                FILENAME,
+                r"
+globalInputs:
+  - 'C:\x'
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+    }
+
     mod hasher {
         use super::*;
 
@@ -370,6 +435,7 @@ generator:
             });
 
             assert_eq!(config.hasher.batch_size, 2500);
+            assert_eq!(config.hasher.walk_strategy, HasherWalkStrategy::Auto);
             assert!(config.hasher.warn_on_missing_inputs);
         }
 
@@ -390,7 +456,9 @@ hasher:
         }
 
         #[test]
-        #[should_panic(expected = "unknown variant `unknown`, expected `glob` or `vcs`")]
+        #[should_panic(
+            expected = "unknown variant `unknown`, expected one of `auto`, `glob`, `vcs`"
+        )]
         fn errors_on_invalid_variant() {
             test_load_config(
                 FILENAME,
@@ -401,6 +469,120 @@ hasher:
                 |path| WorkspaceConfig::load_from(path),
             );
         }
+
+        #[test]
+        fn resolves_default_batch_size() {
+            let config = test_load_config(FILENAME, "hasher: {}", |path| {
+                WorkspaceConfig::load_from(path)
+            });
+
+            assert_eq!(config.hasher.resolve_batch_size(10), 2500);
+            assert_eq!(config.hasher.resolve_batch_size(1_000_000), 2500);
+        }
+
+        #[test]
+        fn resolves_explicit_batch_size() {
+            let config = test_load_config(FILENAME, "hasher:\n  batchSize: 10", |path| {
+                WorkspaceConfig::load_from(path)
+            });
+
+            assert_eq!(config.hasher.resolve_batch_size(5), 10);
+            assert_eq!(config.hasher.resolve_batch_size(1_000_000), 10);
+        }
+
+        #[test]
+        fn resolves_auto_batch_size() {
+            let config = test_load_config(FILENAME, "hasher:\n  batchSize: 0", |path| {
+                WorkspaceConfig::load_from(path)
+            });
+
+            assert_eq!(config.hasher.batch_size, 0);
+
+            // Clamped to min
+            assert_eq!(config.hasher.resolve_batch_size(0), AUTO_BATCH_SIZE_MIN);
+            assert_eq!(config.hasher.resolve_batch_size(100), AUTO_BATCH_SIZE_MIN);
+
+            // Proportional
+            assert_eq!(config.hasher.resolve_batch_size(50_000), 5_000);
+
+            // Clamped to max
+            assert_eq!(
+                config.hasher.resolve_batch_size(1_000_000),
+                AUTO_BATCH_SIZE_MAX
+            );
+        }
+
+        #[test]
+        fn auto_resolves_to_vcs_with_repository() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(".git/HEAD", "");
+
+            assert_eq!(
+                HasherWalkStrategy::Auto.resolve(sandbox.path()),
+                HasherWalkStrategy::Vcs
+            );
+        }
+
+        #[test]
+        fn auto_resolves_to_vcs_with_parent_repository() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(".git/HEAD", "");
+            sandbox.create_file("workspace/.moon/workspace.yml", "");
+
+            assert_eq!(
+                HasherWalkStrategy::Auto.resolve(&sandbox.path().join("workspace")),
+                HasherWalkStrategy::Vcs
+            );
+        }
+
+        #[test]
+        fn auto_resolves_to_glob_without_repository() {
+            let sandbox = create_empty_sandbox();
+
+            assert_eq!(
+                HasherWalkStrategy::Auto.resolve(sandbox.path()),
+                HasherWalkStrategy::Glob
+            );
+        }
+
+        #[test]
+        fn explicit_strategies_dont_resolve() {
+            let sandbox = create_empty_sandbox();
+
+            assert_eq!(
+                HasherWalkStrategy::Vcs.resolve(sandbox.path()),
+                HasherWalkStrategy::Vcs
+            );
+
+            sandbox.create_file(".git/HEAD", "");
+
+            assert_eq!(
+                HasherWalkStrategy::Glob.resolve(sandbox.path()),
+                HasherWalkStrategy::Glob
+            );
+        }
+
+        #[test]
+        fn project_overrides_take_precedence() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+hasher:
+  optimization: accuracy
+  walkStrategy: vcs
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            let hasher = config.hasher.with_project_overrides(&ProjectHasherConfig {
+                optimization: None,
+                walk_strategy: Some(HasherWalkStrategy::Glob),
+            });
+
+            assert_eq!(hasher.optimization, HasherOptimization::Accuracy);
+            assert_eq!(hasher.walk_strategy, HasherWalkStrategy::Glob);
+            assert_eq!(hasher.batch_size, config.hasher.batch_size);
+        }
     }
 
     mod notifier {
@@ -1,7 +1,7 @@
 use crate::file_group_error::FileGroupError;
 use common_path::common_path_all;
 use moon_common::path::WorkspaceRelativePathBuf;
-use moon_common::Id;
+use moon_common::{load_ignore_patterns, Id};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use starbase_utils::glob;
@@ -49,6 +49,8 @@ impl FileGroup {
 
     /// Add patterns (file paths or globs) to the file group, while expanding to a
     /// workspace relative path based on the provided project source.
+    /// Patterns prefixed with `!` are exclusions, and are applied after all
+    /// other patterns have been expanded, regardless of order.
     /// This will overwrite any existing patterns!
     pub fn set_patterns<I>(&mut self, patterns: I) -> &mut Self
     where
@@ -62,7 +64,8 @@ impl FileGroup {
         for path in patterns {
             log_patterns.push(path.as_str().to_owned());
 
-            if glob::is_glob(&path) {
+            // Negations are always globs, even when a literal path
+            if path.as_str().starts_with('!') || glob::is_glob(&path) {
                 self.globs.push(path);
             } else {
                 self.files.push(path);
@@ -173,6 +176,28 @@ impl FileGroup {
             }
         }
 
+        // Exclusions are applied last, so that they apply to files as well
+        let negations = self
+            .globs
+            .iter()
+            .filter_map(|glob| glob.as_str().strip_prefix('!'))
+            .collect::<Vec<_>>();
+
+        if !negations.is_empty() {
+            let excluded = glob::GlobSet::new(&negations)?;
+
+            list.retain(|path| !excluded.is_match(path.as_str()));
+        }
+
+        // Workspace-wide exclusions from `.moonignore` always take precedence
+        let ignored = load_ignore_patterns(workspace_root);
+
+        if !ignored.is_empty() {
+            let excluded = glob::GlobSet::new(ignored.as_ref())?;
+
+            list.retain(|path| !excluded.is_match(path.as_str()));
+        }
+
         Ok(list)
     }
 }
@@ -135,6 +135,78 @@ mod files {
         );
     }
 
+    #[test]
+    fn excludes_negated_globs_regardless_of_order() {
+        let workspace_root = locate_fixture("negated");
+        let file_group = FileGroup::new_with_source(
+            "id",
+            [
+                RelativePathBuf::from("!project/src/**/*.test.ts"),
+                file("src/**/*.ts"),
+            ],
+        )
+        .unwrap();
+
+        let mut results = file_group.files(&workspace_root).unwrap();
+        results.sort();
+
+        assert_eq!(
+            results,
+            vec![
+                RelativePathBuf::from("project/src/generated.ts"),
+                RelativePathBuf::from("project/src/index.ts"),
+                RelativePathBuf::from("project/src/utils/helper.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn excludes_negated_files() {
+        let workspace_root = locate_fixture("negated");
+        let file_group = FileGroup::new_with_source(
+            "id",
+            [
+                file("src/**/*.ts"),
+                file("src/index.ts"),
+                RelativePathBuf::from("!project/src/**/*.test.ts"),
+                RelativePathBuf::from("!project/src/index.ts"),
+                RelativePathBuf::from("!project/src/generated.ts"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            file_group.globs,
+            vec![
+                RelativePathBuf::from("project/src/**/*.ts"),
+                RelativePathBuf::from("!project/src/**/*.test.ts"),
+                RelativePathBuf::from("!project/src/index.ts"),
+                RelativePathBuf::from("!project/src/generated.ts"),
+            ]
+        );
+        assert_eq!(
+            file_group.files(&workspace_root).unwrap(),
+            vec![RelativePathBuf::from("project/src/utils/helper.ts")]
+        );
+    }
+
+    #[test]
+    fn excludes_moonignore_patterns() {
+        let workspace_root = locate_fixture("moonignore");
+        let file_group = FileGroup::new_with_source("id", [file("**/*")]).unwrap();
+
+        let mut results = file_group.files(&workspace_root).unwrap();
+        results.sort();
+
+        assert_eq!(
+            results,
+            vec![
+                RelativePathBuf::from("project/src/index.ts"),
+                RelativePathBuf::from("project/src/utils.ts"),
+            ]
+        );
+    }
+
     #[test]
     fn doesnt_return_dirs() {
         let workspace_root = locate_fixture("file-group");
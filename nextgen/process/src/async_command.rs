@@ -1,15 +1,88 @@
 use crate::command_inspector::CommandInspector;
 use crate::output_to_error;
 use crate::process_error::ProcessError;
+use crate::ready_output::{OutputStream, ReadyOutputBuffer};
+use regex::Regex;
 use std::process::{Output, Stdio};
-use std::sync::{Arc, RwLock};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::task;
+use tokio::task::{self, JoinHandle};
+
+// When the command was spawned as the leader of a new process group, kill
+// the entire group if dropped before the command completes (for example,
+// when the future was abandoned from a timeout). Otherwise only the direct
+// child would be killed, leaving grandchildren (spawned by a shell) running.
+struct ProcessGroupGuard {
+    pgid: Option<u32>,
+    forwarder: Option<JoinHandle<()>>,
+}
+
+impl ProcessGroupGuard {
+    fn new(child: &Child, enabled: bool) -> Self {
+        let pgid = if enabled && cfg!(unix) {
+            child.id()
+        } else {
+            None
+        };
+
+        ProcessGroupGuard {
+            pgid,
+            forwarder: pgid.and_then(forward_interrupt_to_group),
+        }
+    }
+
+    fn disarm(&mut self) {
+        self.pgid.take();
+
+        if let Some(forwarder) = self.forwarder.take() {
+            forwarder.abort();
+        }
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pgid.take() {
+            unsafe {
+                libc::killpg(pgid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+
+        if let Some(forwarder) = self.forwarder.take() {
+            forwarder.abort();
+        }
+    }
+}
+
+// The group is no longer in the terminal's foreground process group, so Ctrl+C
+// would only reach our process, and not the command. Forward it to the group instead.
+// Other signals (SIGTERM, etc) are sent to a single process, so don't need forwarding.
+#[cfg(unix)]
+fn forward_interrupt_to_group(pgid: u32) -> Option<JoinHandle<()>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut interrupt = signal(SignalKind::interrupt()).ok()?;
+
+    Some(task::spawn(async move {
+        while interrupt.recv().await.is_some() {
+            unsafe {
+                libc::killpg(pgid as libc::pid_t, libc::SIGINT);
+            }
+        }
+    }))
+}
+
+#[cfg(not(unix))]
+fn forward_interrupt_to_group(_pgid: u32) -> Option<JoinHandle<()>> {
+    None
+}
 
 pub struct AsyncCommand<'cmd> {
     pub inner: Command,
     pub inspector: CommandInspector<'cmd>,
+    pub kill_process_group: bool,
 }
 
 impl<'cmd> AsyncCommand<'cmd> {
@@ -17,38 +90,37 @@ impl<'cmd> AsyncCommand<'cmd> {
         self.inspector.log_command();
 
         let command = &mut self.inner;
-        let output: Output;
 
-        if self.inspector.should_pass_stdin() {
-            let mut child = command
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|error| ProcessError::Capture {
-                    bin: self.get_bin_name(),
-                    error,
-                })?;
+        let mut child = command
+            .stdin(if self.inspector.should_pass_stdin() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| ProcessError::Capture {
+                bin: self.get_bin_name(),
+                error,
+            })?;
 
-            self.write_input_to_child(&mut child).await?;
+        let mut guard = ProcessGroupGuard::new(&child, self.kill_process_group);
 
-            output = child
-                .wait_with_output()
-                .await
-                .map_err(|error| ProcessError::Capture {
-                    bin: self.get_bin_name(),
-                    error,
-                })?;
-        } else {
-            output = command
-                .output()
-                .await
-                .map_err(|error| ProcessError::Capture {
-                    bin: self.get_bin_name(),
-                    error,
-                })?;
+        if self.inspector.should_pass_stdin() {
+            self.write_input_to_child(&mut child).await?;
         }
 
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|error| ProcessError::Capture {
+                bin: self.get_bin_name(),
+                error,
+            })?;
+
+        guard.disarm();
+
         self.handle_nonzero_status(&output, true)?;
 
         Ok(output)
@@ -78,11 +150,15 @@ impl<'cmd> AsyncCommand<'cmd> {
             })?;
         };
 
+        let mut guard = ProcessGroupGuard::new(&child, self.kill_process_group);
+
         let status = child.wait().await.map_err(|error| ProcessError::Stream {
             bin: self.get_bin_name(),
             error,
         })?;
 
+        guard.disarm();
+
         let output = Output {
             status,
             stderr: vec![],
@@ -97,11 +173,34 @@ impl<'cmd> AsyncCommand<'cmd> {
     pub async fn exec_stream_and_capture_output(&mut self) -> miette::Result<Output> {
         self.inspector.log_command();
 
+        self.stream_and_capture_output(None).await
+    }
+
+    /// Stream output to the parent terminal, but only once a line matches the
+    /// ready pattern. Output before that point is withheld, and is only displayed
+    /// if the process exits without ever becoming ready. All output is captured.
+    pub async fn exec_stream_output_when_ready(
+        &mut self,
+        ready_pattern: Regex,
+    ) -> miette::Result<Output> {
+        self.inspector.log_command();
+
+        self.stream_and_capture_output(Some(ReadyOutputBuffer::new(ready_pattern)))
+            .await
+    }
+
+    async fn stream_and_capture_output(
+        &mut self,
+        ready_buffer: Option<ReadyOutputBuffer>,
+    ) -> miette::Result<Output> {
         let command = &mut self.inner;
 
         let mut child = command
             .stdin(if self.inspector.should_pass_stdin() {
                 Stdio::piped()
+            } else if self.kill_process_group {
+                // A background process group is stopped when reading from the terminal
+                Stdio::null()
             } else {
                 Stdio::inherit()
             })
@@ -113,6 +212,8 @@ impl<'cmd> AsyncCommand<'cmd> {
                 error,
             })?;
 
+        let mut guard = ProcessGroupGuard::new(&child, self.kill_process_group);
+
         if self.inspector.should_pass_stdin() {
             self.write_input_to_child(&mut child).await?;
         }
@@ -125,61 +226,44 @@ impl<'cmd> AsyncCommand<'cmd> {
         // https://stackoverflow.com/a/49063262
         let stderr = BufReader::new(child.stderr.take().unwrap());
         let stdout = BufReader::new(child.stdout.take().unwrap());
-        let mut handles = vec![];
 
+        let buffer = ready_buffer.map(|buffer| Arc::new(Mutex::new(buffer)));
         let captured_stderr = Arc::new(RwLock::new(vec![]));
         let captured_stdout = Arc::new(RwLock::new(vec![]));
-        let captured_stderr_clone = Arc::clone(&captured_stderr);
-        let captured_stdout_clone = Arc::clone(&captured_stdout);
-
         let prefix = Arc::new(self.inspector.get_prefix());
-        let stderr_prefix = Arc::clone(&prefix);
-        let stdout_prefix = Arc::clone(&prefix);
-
-        handles.push(task::spawn(async move {
-            let mut lines = stderr.lines();
-            let mut captured_lines = vec![];
-
-            while let Ok(Some(line)) = lines.next_line().await {
-                if stderr_prefix.is_empty() {
-                    eprintln!("{line}");
-                } else {
-                    eprintln!("{stderr_prefix}{line}");
-                }
 
-                captured_lines.push(line);
-            }
-
-            captured_stderr_clone
-                .write()
-                .unwrap()
-                .extend(captured_lines);
-        }));
-
-        handles.push(task::spawn(async move {
-            let mut lines = stdout.lines();
-            let mut captured_lines = vec![];
-
-            while let Ok(Some(line)) = lines.next_line().await {
-                if stdout_prefix.is_empty() {
-                    println!("{line}");
-                } else {
-                    println!("{stdout_prefix}{line}");
-                }
-
-                captured_lines.push(line);
-            }
-
-            captured_stdout_clone
-                .write()
-                .unwrap()
-                .extend(captured_lines);
-        }));
+        let handles = [
+            spawn_stream_reader(
+                stderr,
+                OutputStream::Stderr,
+                Arc::clone(&prefix),
+                buffer.clone(),
+                Arc::clone(&captured_stderr),
+            ),
+            spawn_stream_reader(
+                stdout,
+                OutputStream::Stdout,
+                Arc::clone(&prefix),
+                buffer.clone(),
+                Arc::clone(&captured_stdout),
+            ),
+        ];
 
         for handle in handles {
             let _ = handle.await;
         }
 
+        // Never became ready, so display everything that was withheld
+        if let Some(buffer) = buffer {
+            let mut buffer = buffer.lock().unwrap();
+
+            if !buffer.is_ready() {
+                for (stream, line) in buffer.take_withheld() {
+                    write_line(stream, &prefix, &line);
+                }
+            }
+        }
+
         // Attempt to create the child output
         let status = child
             .wait()
@@ -189,6 +273,8 @@ impl<'cmd> AsyncCommand<'cmd> {
                 error,
             })?;
 
+        guard.disarm();
+
         let output = Output {
             status,
             stdout: captured_stdout.read().unwrap().join("\n").into_bytes(),
@@ -236,3 +322,40 @@ impl<'cmd> AsyncCommand<'cmd> {
         Ok(())
     }
 }
+
+fn write_line(stream: OutputStream, prefix: &str, line: &str) {
+    match stream {
+        OutputStream::Stderr => eprintln!("{prefix}{line}"),
+        OutputStream::Stdout => println!("{prefix}{line}"),
+    };
+}
+
+// Write each line of the stream to the parent terminal (unless withheld
+// by the ready buffer), while also capturing it.
+fn spawn_stream_reader<R: AsyncRead + Unpin + Send + 'static>(
+    reader: BufReader<R>,
+    stream: OutputStream,
+    prefix: Arc<String>,
+    ready_buffer: Option<Arc<Mutex<ReadyOutputBuffer>>>,
+    captured: Arc<RwLock<Vec<String>>>,
+) -> JoinHandle<()> {
+    task::spawn(async move {
+        let mut lines = reader.lines();
+        let mut captured_lines = vec![];
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            let show = match &ready_buffer {
+                Some(buffer) => buffer.lock().unwrap().push(stream, &line),
+                None => true,
+            };
+
+            if show {
+                write_line(stream, &prefix, &line);
+            }
+
+            captured_lines.push(line);
+        }
+
+        captured.write().unwrap().extend(captured_lines);
+    })
+}
@@ -2,11 +2,28 @@ use crate::{async_command::AsyncCommand, command_inspector::CommandInspector, sh
 use moon_common::{color, is_test_env};
 use rustc_hash::FxHashMap;
 use std::{
+    env,
     ffi::{OsStr, OsString},
     path::{Path, PathBuf},
+    process::Command as StdCommand,
 };
 use tokio::process::Command as TokioCommand;
 
+// Variables always copied from the parent environment when it's not
+// inherited, as most binaries cannot run without them.
+const BASE_ENV_VARS: [&str; 10] = [
+    "HOME",
+    "LANG",
+    "PATH",
+    "SHELL",
+    "SYSTEMROOT",
+    "TEMP",
+    "TERM",
+    "TMP",
+    "TMPDIR",
+    "USER",
+];
+
 #[derive(Debug)]
 pub struct Command {
     pub args: Vec<OsString>,
@@ -17,18 +34,31 @@ pub struct Command {
 
     pub env: FxHashMap<OsString, OsString>,
 
+    /// Variables to copy from the parent environment when not inheriting it
+    pub env_passthrough: Vec<OsString>,
+
     /// Convert non-zero exits to errors
     pub error_on_nonzero: bool,
 
+    /// Inherit the entire parent environment
+    pub inherit_env: bool,
+
     /// Values to pass to stdin
     pub input: Vec<OsString>,
 
+    /// Run in a new process group, so that the entire process tree
+    /// is killed if the command is dropped before completing (Unix only)
+    pub kill_process_group: bool,
+
     /// Prefix to prepend to all log lines
     pub prefix: Option<String>,
 
     /// Log the command to the terminal before running
     pub print_command: bool,
 
+    /// Values to mask when logging the command
+    pub secrets: Vec<String>,
+
     /// Shell to wrap executing commands in
     pub shell: Option<shell::Shell>,
 }
@@ -42,10 +72,14 @@ impl Command {
             args: vec![],
             cwd: None,
             env: FxHashMap::default(),
+            env_passthrough: vec![],
             error_on_nonzero: true,
+            inherit_env: true,
             input: vec![],
+            kill_process_group: false,
             prefix: None,
             print_command: false,
+            secrets: vec![],
             shell: None,
         };
 
@@ -94,18 +128,44 @@ impl Command {
         let inspector = self.inspect();
         let command_line = inspector.get_command_line();
 
-        let mut command = TokioCommand::new(&command_line.command[0]);
+        let mut command = StdCommand::new(&command_line.command[0]);
         command.args(&command_line.command[1..]);
+
+        // Only pass a minimal base environment, and explicitly allowed variables
+        if !self.inherit_env {
+            command.env_clear();
+
+            for name in BASE_ENV_VARS
+                .iter()
+                .map(OsStr::new)
+                .chain(self.env_passthrough.iter().map(|name| name.as_os_str()))
+            {
+                if let Some(value) = env::var_os(name) {
+                    command.env(name, value);
+                }
+            }
+        }
+
         command.envs(&self.env);
-        command.kill_on_drop(true);
 
         if let Some(cwd) = &self.cwd {
             command.current_dir(cwd);
         }
 
+        #[cfg(unix)]
+        if self.kill_process_group {
+            use std::os::unix::process::CommandExt;
+
+            command.process_group(0);
+        }
+
+        let mut command = TokioCommand::from(command);
+        command.kill_on_drop(true);
+
         AsyncCommand {
             inner: command,
             inspector,
+            kill_process_group: self.kill_process_group,
         }
     }
 
@@ -132,6 +192,18 @@ impl Command {
         self
     }
 
+    pub fn env_passthrough<I, V>(&mut self, names: I) -> &mut Command
+    where
+        I: IntoIterator<Item = V>,
+        V: AsRef<OsStr>,
+    {
+        for name in names {
+            self.env_passthrough.push(name.as_ref().to_os_string());
+        }
+
+        self
+    }
+
     pub fn inherit_colors(&mut self) -> &mut Command {
         let level = color::supports_color().to_string();
 
@@ -163,11 +235,31 @@ impl Command {
         CommandInspector::new(self)
     }
 
+    pub fn mask_secret<S: AsRef<str>>(&mut self, secret: S) -> &mut Command {
+        let secret = secret.as_ref();
+
+        if !secret.is_empty() {
+            self.secrets.push(secret.to_owned());
+        }
+
+        self
+    }
+
     pub fn set_print_command(&mut self, state: bool) -> &mut Command {
         self.print_command = state;
         self
     }
 
+    pub fn set_inherit_env(&mut self, state: bool) -> &mut Command {
+        self.inherit_env = state;
+        self
+    }
+
+    pub fn set_kill_process_group(&mut self, state: bool) -> &mut Command {
+        self.kill_process_group = state;
+        self
+    }
+
     pub fn set_error_on_nonzero(&mut self, state: bool) -> &mut Command {
         self.error_on_nonzero = state;
         self
@@ -33,6 +33,8 @@ impl CommandLine {
         // Extract the main command, without shell, for other purposes!
         push_to_line(&mut main_line);
 
+        let mut join_input = None;
+
         // If wrapped in a shell, the shell binary and arguments
         // must be placed at the start of the line.
         if let Some(shell) = &command.shell {
@@ -42,7 +44,7 @@ impl CommandLine {
             // If the main command should be passed via stdin,
             // then append the input line instead of the command line.
             if shell.pass_args_stdin {
-                // join_input = true;
+                join_input = Some(shell.quoting);
                 push_to_line(&mut input_line);
 
                 // Otherwise append as a *single* argument. This typically
@@ -51,7 +53,7 @@ impl CommandLine {
                 let mut sub_line: Vec<String> = vec![];
                 push_to_line(&mut sub_line);
 
-                command_line.push(join_args(sub_line));
+                command_line.push(shell.quoting.join_command(sub_line));
             }
 
             // Otherwise we have a normal command and arguments.
@@ -68,12 +70,11 @@ impl CommandLine {
 
         CommandLine {
             command: command_line,
-            // input: if join_input {
-            //     join_args(input_line)
-            // } else {
-            //     input_line.join("")
-            // },
-            input: input_line.join(" "),
+            // Arguments passed to a shell via stdin must be quoted for that shell
+            input: match join_input {
+                Some(quoting) => quoting.join_command(input_line),
+                None => input_line.join(" "),
+            },
             main_command: join_args(main_line),
         }
     }
@@ -132,6 +133,18 @@ impl<'cmd> CommandInspector<'cmd> {
             .get_or_init(|| CommandLine::new(self.command))
     }
 
+    /// Replace all secret values in the provided line with a mask,
+    /// so that they are not leaked into logs.
+    pub fn mask_secrets(&self, line: &str) -> String {
+        let mut line = line.to_owned();
+
+        for secret in &self.command.secrets {
+            line = line.replace(secret, "*****");
+        }
+
+        line
+    }
+
     pub fn get_prefix(&self) -> String {
         self.command.prefix.clone().unwrap_or_default()
     }
@@ -189,7 +202,11 @@ impl<'cmd> CommandInspector<'cmd> {
         if self.command.print_command {
             println!(
                 "{}",
-                self.format_command(&command_line.main_command, &workspace_root, None)
+                self.format_command(
+                    &self.mask_secrets(&command_line.main_command),
+                    &workspace_root,
+                    None
+                )
             );
         }
 
@@ -220,7 +237,7 @@ impl<'cmd> CommandInspector<'cmd> {
             env_vars = ?env_vars_field,
             working_dir = ?working_dir_field,
             "Running command {}",
-            color::shell(command_line.to_string())
+            color::shell(self.mask_secrets(&command_line.to_string()))
         );
     }
 }
@@ -3,9 +3,11 @@ mod command;
 mod command_inspector;
 mod output;
 mod process_error;
+mod ready_output;
 pub mod shell;
 
 pub use command::*;
 pub use moon_args as args;
 pub use output::*;
 pub use process_error::*;
+pub use ready_output::*;
@@ -0,0 +1,56 @@
+use regex::Regex;
+use std::mem;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputStream {
+    Stderr,
+    Stdout,
+}
+
+/// Withholds lines of output until a line matches the ready pattern,
+/// after which all lines pass through. Withheld lines are only meant
+/// to be displayed when the process never became ready.
+#[derive(Debug)]
+pub struct ReadyOutputBuffer {
+    pattern: Regex,
+    ready: bool,
+    withheld: Vec<(OutputStream, String)>,
+}
+
+impl ReadyOutputBuffer {
+    pub fn new(pattern: Regex) -> Self {
+        ReadyOutputBuffer {
+            pattern,
+            ready: false,
+            withheld: vec![],
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Push a line of output, and return true if it should be written immediately.
+    /// The line that matches the ready pattern is written, but everything before
+    /// it is discarded.
+    pub fn push(&mut self, stream: OutputStream, line: &str) -> bool {
+        if self.ready {
+            return true;
+        }
+
+        if self.pattern.is_match(line) {
+            self.ready = true;
+            self.withheld.clear();
+
+            return true;
+        }
+
+        self.withheld.push((stream, line.to_owned()));
+
+        false
+    }
+
+    pub fn take_withheld(&mut self) -> Vec<(OutputStream, String)> {
+        mem::take(&mut self.withheld)
+    }
+}
@@ -1,4 +1,5 @@
 use cached::proc_macro::cached;
+use moon_args::join_args;
 use std::{env, ffi::OsStr};
 
 #[cached]
@@ -30,42 +31,177 @@ pub fn is_windows_script<T: AsRef<OsStr>>(bin: T) -> bool {
         || bin.ends_with(".PS1")
 }
 
+/// How arguments should be quoted when joined into a single command line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShellQuoting {
+    /// Double quotes, for `cmd.exe`.
+    Cmd,
+    /// Single quotes with POSIX escaping, for `sh`, `bash`, etc.
+    Posix,
+    /// Single quotes with doubled single quotes, for `pwsh` and `powershell`.
+    PowerShell,
+}
+
+impl ShellQuoting {
+    pub fn join_args<I, S>(&self, args: I) -> String
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        match self {
+            ShellQuoting::Posix => join_args(args),
+            ShellQuoting::Cmd => args
+                .into_iter()
+                .map(|arg| {
+                    let arg = arg.as_ref();
+
+                    if arg.is_empty() || arg.contains([' ', '\t', '"']) {
+                        format!("\"{}\"", arg.replace('"', "\"\""))
+                    } else {
+                        arg.to_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            ShellQuoting::PowerShell => args
+                .into_iter()
+                .map(|arg| {
+                    let arg = arg.as_ref();
+
+                    // Variables (`$env:FOO`) must remain unquoted to be expanded
+                    if arg.is_empty() || arg.contains([' ', '\t', '\'', '"', '`']) {
+                        format!("'{}'", arg.replace('\'', "''"))
+                    } else {
+                        arg.to_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Like [`ShellQuoting::join_args`], but the first argument is the program
+    /// to execute. PowerShell treats a quoted program as a string expression,
+    /// so it must be invoked with the call operator (`&`).
+    pub fn join_command<I, S>(&self, args: I) -> String
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let line = self.join_args(args);
+
+        if *self == ShellQuoting::PowerShell && line.starts_with('\'') {
+            format!("& {line}")
+        } else {
+            line
+        }
+    }
+}
+
+/// Arguments required to execute a command through a shell program.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ShellInvocation {
+    pub args: &'static [&'static str],
+    pub pass_args_stdin: bool,
+    pub quoting: ShellQuoting,
+}
+
+const POSIX_INVOCATION: ShellInvocation = ShellInvocation {
+    args: &["-c"],
+    pass_args_stdin: false,
+    quoting: ShellQuoting::Posix,
+};
+
+const POWERSHELL_INVOCATION: ShellInvocation = ShellInvocation {
+    args: &[
+        "-NonInteractive",
+        "-NoLogo",
+        "-Command",
+        // We'll pass the command args via stdin, so that paths with special
+        // characters and spaces resolve correctly.
+        // https://learn.microsoft.com/en-us/powershell/module/microsoft.powershell.core/about/about_pwsh?view=powershell-7.2#-command---c
+        "-",
+    ],
+    pass_args_stdin: true,
+    quoting: ShellQuoting::PowerShell,
+};
+
+// Mapping of shell program (file stem) to its invocation.
+const SHELL_INVOCATIONS: &[(&str, ShellInvocation)] = &[
+    ("bash", POSIX_INVOCATION),
+    (
+        "cmd",
+        ShellInvocation {
+            args: &["/D", "/C"],
+            pass_args_stdin: false,
+            quoting: ShellQuoting::Cmd,
+        },
+    ),
+    ("dash", POSIX_INVOCATION),
+    ("elvish", POSIX_INVOCATION),
+    ("fish", POSIX_INVOCATION),
+    ("ksh", POSIX_INVOCATION),
+    ("nu", POSIX_INVOCATION),
+    ("powershell", POWERSHELL_INVOCATION),
+    ("pwsh", POWERSHELL_INVOCATION),
+    ("sh", POSIX_INVOCATION),
+    ("zsh", POSIX_INVOCATION),
+];
+
+/// Return the invocation for the provided shell program, which may be a
+/// name or a path. Unknown shells fallback to POSIX semantics (`-c`).
+pub fn get_shell_invocation<T: AsRef<str>>(bin: T) -> ShellInvocation {
+    let bin = bin.as_ref();
+    let name = bin.rsplit(['/', '\\']).next().unwrap_or(bin);
+    let name = name
+        .strip_suffix(".exe")
+        .or_else(|| name.strip_suffix(".EXE"))
+        .unwrap_or(name)
+        .to_lowercase();
+
+    SHELL_INVOCATIONS
+        .iter()
+        .find(|(shell, _)| *shell == name)
+        .map(|(_, invocation)| *invocation)
+        .unwrap_or(POSIX_INVOCATION)
+}
+
 #[derive(Debug)]
 pub struct Shell {
     pub bin: String,
     pub args: Vec<String>,
     pub pass_args_stdin: bool,
+    pub quoting: ShellQuoting,
+}
+
+impl Shell {
+    /// Create a shell for the provided program, using its known invocation.
+    pub fn new<T: AsRef<str>>(bin: T) -> Shell {
+        let bin = bin.as_ref();
+        let invocation = get_shell_invocation(bin);
+
+        Shell {
+            bin: bin.to_owned(),
+            args: invocation.args.iter().map(|arg| arg.to_string()).collect(),
+            pass_args_stdin: invocation.pass_args_stdin,
+            quoting: invocation.quoting,
+        }
+    }
 }
 
 // https://thinkpowershell.com/decision-to-switch-to-powershell-core-pwsh/
 #[cfg(windows)]
 #[inline]
 pub fn create_shell() -> Shell {
-    Shell {
-        bin: if is_program_on_path("pwsh.exe".into()) {
-            "pwsh.exe".into()
-        } else {
-            "powershell.exe".into()
-        },
-        args: vec![
-            "-NonInteractive".into(),
-            "-NoLogo".into(),
-            "-Command".into(),
-            // We'll pass the command args via stdin, so that paths with special
-            // characters and spaces resolve correctly.
-            // https://learn.microsoft.com/en-us/powershell/module/microsoft.powershell.core/about/about_pwsh?view=powershell-7.2#-command---c
-            "-".into(),
-        ],
-        pass_args_stdin: true,
-    }
+    Shell::new(if is_program_on_path("pwsh.exe".into()) {
+        "pwsh.exe"
+    } else {
+        "powershell.exe"
+    })
 }
 
 #[cfg(not(windows))]
 #[inline]
 pub fn create_shell() -> Shell {
-    Shell {
-        bin: env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into()),
-        args: vec!["-c".into()],
-        pass_args_stdin: false,
-    }
+    Shell::new(env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into()))
 }
@@ -0,0 +1,45 @@
+use moon_process::Command;
+
+mod mask_secrets {
+    use super::*;
+
+    #[test]
+    fn masks_all_secrets() {
+        let mut command = Command::new("bin");
+        command.args(["--token", "abc123", "--key=xyz"]);
+        command.mask_secret("abc123").mask_secret("xyz");
+
+        let inspector = command.inspect();
+
+        assert_eq!(
+            inspector.mask_secrets(&inspector.get_command_line().main_command),
+            "bin --token ***** '--key=*****'"
+        );
+    }
+
+    #[test]
+    fn ignores_empty_secrets() {
+        let mut command = Command::new("bin");
+        command.arg("value");
+        command.mask_secret("");
+
+        let inspector = command.inspect();
+
+        assert_eq!(
+            inspector.mask_secrets(&inspector.get_command_line().main_command),
+            "bin value"
+        );
+    }
+
+    #[test]
+    fn doesnt_mask_executed_command() {
+        let mut command = Command::new("bin");
+        command.args(["--token", "abc123"]);
+        command.mask_secret("abc123");
+
+        assert_eq!(
+            command.inspect().get_command_line().command,
+            vec!["bin", "--token", "abc123"]
+        );
+    }
+}
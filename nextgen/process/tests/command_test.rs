@@ -0,0 +1,121 @@
+#[cfg(unix)]
+mod inherit_env {
+    use moon_process::{output_to_string, Command};
+    use std::env;
+
+    fn create_env_command() -> Command {
+        let mut command = Command::new("env");
+        command.set_inherit_env(false);
+        command
+    }
+
+    #[tokio::test]
+    async fn passes_through_allowed_vars() {
+        env::set_var("MOON_TEST_PASSTHROUGH", "passed");
+
+        let mut command = create_env_command();
+        command.env_passthrough(["MOON_TEST_PASSTHROUGH"]);
+
+        let output = command.create_async().exec_capture_output().await.unwrap();
+        let stdout = output_to_string(&output.stdout);
+
+        env::remove_var("MOON_TEST_PASSTHROUGH");
+
+        assert!(stdout.contains("MOON_TEST_PASSTHROUGH=passed"));
+    }
+
+    #[tokio::test]
+    async fn doesnt_pass_through_other_vars() {
+        env::set_var("MOON_TEST_NOT_LISTED", "hidden");
+
+        let command = create_env_command();
+
+        let output = command.create_async().exec_capture_output().await.unwrap();
+        let stdout = output_to_string(&output.stdout);
+
+        env::remove_var("MOON_TEST_NOT_LISTED");
+
+        assert!(!stdout.contains("MOON_TEST_NOT_LISTED"));
+    }
+
+    #[tokio::test]
+    async fn includes_base_and_explicit_vars() {
+        let mut command = create_env_command();
+        command.env("MOON_TEST_EXPLICIT", "set");
+
+        let output = command.create_async().exec_capture_output().await.unwrap();
+        let stdout = output_to_string(&output.stdout);
+
+        assert!(stdout.contains("MOON_TEST_EXPLICIT=set"));
+        assert!(stdout.contains("PATH="));
+    }
+}
+
+#[cfg(unix)]
+mod kill_process_group {
+    use moon_process::Command;
+    use std::{env, fs, process, thread};
+    use tokio::time::{timeout, Duration};
+
+    fn is_running(pid: &str) -> bool {
+        let output = process::Command::new("ps")
+            .args(["-o", "stat=", "-p", pid])
+            .output()
+            .unwrap();
+        let stat = String::from_utf8_lossy(&output.stdout);
+
+        // Zombies have been killed, but not reaped yet
+        output.status.success() && !stat.trim().starts_with('Z')
+    }
+
+    #[tokio::test]
+    async fn kills_grandchildren_when_dropped() {
+        let pid_file = env::temp_dir().join(format!("moon-process-group-{}", process::id()));
+
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            &format!("sleep 60 & echo $! > {}; wait", pid_file.display()),
+        ]);
+        command.set_kill_process_group(true);
+
+        let result = timeout(
+            Duration::from_secs(1),
+            command.create_async().exec_capture_output(),
+        )
+        .await;
+
+        assert!(result.is_err());
+
+        let pid = fs::read_to_string(&pid_file).unwrap().trim().to_owned();
+        fs::remove_file(&pid_file).unwrap();
+
+        for _ in 0..20 {
+            if !is_running(&pid) {
+                return;
+            }
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        panic!("Grandchild process {pid} is still running");
+    }
+
+    #[tokio::test]
+    async fn doesnt_inherit_stdin_when_streaming() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "cat; echo done"]);
+        command.set_kill_process_group(true);
+
+        // Would be stopped (or block) if reading from the terminal
+        let output = timeout(
+            Duration::from_secs(5),
+            command.create_async().exec_stream_and_capture_output(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "done");
+    }
+}
@@ -0,0 +1,73 @@
+use moon_process::{OutputStream, ReadyOutputBuffer};
+use regex::Regex;
+
+fn create_buffer() -> ReadyOutputBuffer {
+    ReadyOutputBuffer::new(Regex::new("listening on \\d+").unwrap())
+}
+
+#[test]
+fn withholds_lines_until_ready() {
+    let mut buffer = create_buffer();
+
+    assert!(!buffer.push(OutputStream::Stdout, "compiling..."));
+    assert!(!buffer.push(OutputStream::Stderr, "warning: unused"));
+    assert!(!buffer.is_ready());
+
+    assert!(buffer.push(OutputStream::Stdout, "listening on 3000"));
+    assert!(buffer.is_ready());
+
+    assert!(buffer.push(OutputStream::Stdout, "GET /"));
+    assert!(buffer.push(OutputStream::Stderr, "error: not found"));
+}
+
+#[test]
+fn discards_withheld_lines_once_ready() {
+    let mut buffer = create_buffer();
+
+    buffer.push(OutputStream::Stdout, "compiling...");
+    buffer.push(OutputStream::Stdout, "listening on 3000");
+
+    assert!(buffer.take_withheld().is_empty());
+}
+
+#[test]
+fn keeps_withheld_lines_when_never_ready() {
+    let mut buffer = create_buffer();
+
+    buffer.push(OutputStream::Stdout, "compiling...");
+    buffer.push(OutputStream::Stderr, "error: port in use");
+
+    assert_eq!(
+        buffer.take_withheld(),
+        vec![
+            (OutputStream::Stdout, "compiling...".to_owned()),
+            (OutputStream::Stderr, "error: port in use".to_owned()),
+        ]
+    );
+}
+
+#[cfg(unix)]
+mod exec {
+    use super::*;
+    use moon_process::{output_to_string, Command};
+
+    #[tokio::test]
+    async fn captures_all_output() {
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            "echo starting; echo 'listening on 3000'; echo serving",
+        ]);
+
+        let output = command
+            .create_async()
+            .exec_stream_output_when_ready(Regex::new("listening on \\d+").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output_to_string(&output.stdout),
+            "starting\nlistening on 3000\nserving"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use moon_process::shell::{get_shell_invocation, Shell, ShellQuoting};
+use moon_process::Command;
+
+fn create_command_line(shell: &str) -> Vec<String> {
+    let mut command = Command::new("echo");
+    command.args(["hello world", "it's"]);
+    command.set_shell(Shell::new(shell));
+    command.inspect().get_command_line().command.clone()
+}
+
+fn create_command_input(shell: &str, bin: &str) -> String {
+    let mut command = Command::new(bin);
+    command.args(["hello world", "it's"]);
+    command.set_shell(Shell::new(shell));
+    command.inspect().get_command_line().input.clone()
+}
+
+mod shell_invocation {
+    use super::*;
+
+    #[test]
+    fn bash() {
+        let invocation = get_shell_invocation("/bin/bash");
+
+        assert_eq!(invocation.args, &["-c"]);
+        assert!(!invocation.pass_args_stdin);
+        assert_eq!(invocation.quoting, ShellQuoting::Posix);
+        assert_eq!(
+            create_command_line("bash"),
+            vec!["bash", "-c", r#"echo 'hello world' 'it'\''s'"#]
+        );
+    }
+
+    #[test]
+    fn cmd() {
+        let invocation = get_shell_invocation("C:\\Windows\\System32\\cmd.exe");
+
+        assert_eq!(invocation.args, &["/D", "/C"]);
+        assert!(!invocation.pass_args_stdin);
+        assert_eq!(invocation.quoting, ShellQuoting::Cmd);
+        assert_eq!(
+            create_command_line("cmd.exe"),
+            vec!["cmd.exe", "/D", "/C", r#"echo "hello world" it's"#]
+        );
+    }
+
+    #[test]
+    fn pwsh() {
+        let invocation = get_shell_invocation("pwsh.exe");
+
+        assert_eq!(
+            invocation.args,
+            &["-NonInteractive", "-NoLogo", "-Command", "-"]
+        );
+        assert!(invocation.pass_args_stdin);
+        assert_eq!(invocation.quoting, ShellQuoting::PowerShell);
+        assert_eq!(
+            create_command_line("pwsh.exe"),
+            vec!["pwsh.exe", "-NonInteractive", "-NoLogo", "-Command", "-"]
+        );
+        assert_eq!(
+            create_command_input("pwsh.exe", "echo"),
+            "echo 'hello world' 'it''s'"
+        );
+    }
+
+    #[test]
+    fn pwsh_invokes_quoted_program() {
+        assert_eq!(
+            create_command_input("pwsh.exe", "C:\\Program Files\\nodejs\\node.exe"),
+            "& 'C:\\Program Files\\nodejs\\node.exe' 'hello world' 'it''s'"
+        );
+    }
+
+    #[test]
+    fn pwsh_doesnt_quote_variables() {
+        let mut command = Command::new("echo");
+        command.args(["$env:FOO", "$PWD"]);
+        command.set_shell(Shell::new("pwsh.exe"));
+
+        assert_eq!(
+            command.inspect().get_command_line().input,
+            "echo $env:FOO $PWD"
+        );
+    }
+
+    #[test]
+    fn unknown_fallsback_to_posix() {
+        assert_eq!(get_shell_invocation("unknown").args, &["-c"]);
+    }
+}
@@ -2,17 +2,18 @@ use crate::project_builder_error::ProjectBuilderError;
 use moon_common::path::WorkspaceRelativePathBuf;
 use moon_common::{color, consts, Id};
 use moon_config::{
-    DependencyConfig, DependencySource, InheritedTasksManager, InheritedTasksResult, LanguageType,
-    PlatformType, ProjectConfig, ProjectDependsOn, TaskConfig, ToolchainConfig,
+    DependencyConfig, DependencySource, InheritedTasksManager, InheritedTasksResult, InputPath,
+    LanguageType, PlatformType, ProjectConfig, ProjectDependsOn, TaskConfig, TaskOptionsConfig,
+    ToolchainConfig,
 };
 use moon_file_group::FileGroup;
-use moon_project::Project;
+use moon_project::{Project, PROJECT_SCHEMA_VERSION};
 use moon_task::Task;
 use moon_task_builder::{PlatformDetector, TasksBuilder};
 use rustc_hash::FxHashMap;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use tracing::debug;
+use tracing::{debug, warn};
 
 pub type LanguageDetector = dyn Fn(&Path) -> LanguageType;
 
@@ -27,7 +28,19 @@ pub struct ProjectBuilder<'app> {
 
     // Configs to derive information from
     global_config: Option<InheritedTasksResult>,
+    global_inputs: Vec<InputPath>,
     local_config: Option<ProjectConfig>,
+    platform_options: FxHashMap<PlatformType, TaskOptionsConfig>,
+    workspace_env: FxHashMap<String, String>,
+
+    // Implicit tasks that were not injected, as a task of the same ID exists
+    skipped_tasks: Vec<Id>,
+
+    // Error instead of warn when the language and platform are incompatible
+    strict_platform: bool,
+
+    // Warn when the ID does not match the ID derived from the source
+    validate_id: bool,
 
     // Values to be continually built
     pub language: LanguageType,
@@ -59,7 +72,13 @@ impl<'app> ProjectBuilder<'app> {
             workspace_root,
             toolchain_config: None,
             global_config: None,
+            global_inputs: vec![],
             local_config: None,
+            platform_options: FxHashMap::default(),
+            workspace_env: FxHashMap::default(),
+            skipped_tasks: vec![],
+            strict_platform: false,
+            validate_id: false,
             language: LanguageType::Unknown,
             language_detector: None,
             platform: PlatformType::Unknown,
@@ -86,6 +105,110 @@ impl<'app> ProjectBuilder<'app> {
         self
     }
 
+    /// Error when loading the local config, instead of logging a warning, if
+    /// the explicitly configured platform does not support the configured language.
+    pub fn error_on_incompatible_platform(&mut self, enabled: bool) -> &mut Self {
+        self.strict_platform = enabled;
+        self
+    }
+
+    /// Validate the project ID against its source when building. This should only be
+    /// enabled for IDs that were derived from the source (globbed folder names), as
+    /// explicitly configured IDs may intentionally differ from their source.
+    pub fn validate_id_convention(&mut self, enabled: bool) -> &mut Self {
+        self.validate_id = enabled;
+        self
+    }
+
+    /// Register workspace-level inputs (root lockfiles, etc) that
+    /// will be inherited by every task within the project.
+    pub fn inherit_global_inputs<I>(&mut self, inputs: I) -> &mut Self
+    where
+        I: IntoIterator<Item = InputPath>,
+    {
+        self.global_inputs.extend(inputs);
+        self
+    }
+
+    /// Register default task options for a platform, that will be inherited
+    /// by every task of that platform with the lowest precedence.
+    pub fn inherit_platform_options(
+        &mut self,
+        platform: PlatformType,
+        options: TaskOptionsConfig,
+    ) -> &mut Self {
+        self.platform_options.insert(platform, options);
+        self
+    }
+
+    /// Register workspace-level environment variables that
+    /// will be inherited by every task within the project.
+    pub fn inherit_workspace_env<I>(&mut self, env: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        self.workspace_env.extend(env);
+        self
+    }
+
+    /// Validate that the project ID matches the ID conventionally derived from its
+    /// source (the last path segment, sanitized). A mismatch is not an error, but
+    /// usually indicates a project has been registered multiple times, so a warning
+    /// is logged. The root-level project (`.`) has no source to derive from, so is skipped.
+    pub fn check_id_convention(&self) {
+        if self.source.as_str() == "." {
+            return;
+        }
+
+        let expected = Id::clean(self.source.file_name().unwrap_or(self.source.as_str()));
+
+        if expected.as_str() == self.id {
+            return;
+        }
+
+        warn!(
+            id = self.id,
+            source = self.source.as_str(),
+            "Project {} does not match the ID {} derived from its source, is it registered multiple times?",
+            color::id(self.id),
+            color::id(&expected),
+        );
+    }
+
+    /// Validate that the project's platform has a configured toolchain, otherwise
+    /// its tasks will fail at run time. This can only be checked when a toolchain
+    /// config has been provided with [`ProjectBuilder::detect_platform`]. A missing
+    /// toolchain is not an error, so a warning is logged and the platform is returned.
+    pub fn check_platform_toolchain(&self) -> Option<PlatformType> {
+        let toolchain_config = self.toolchain_config?;
+
+        if self.platform.is_system() || self.platform.is_unknown() {
+            return None;
+        }
+
+        // Bun is also a JavaScript runtime, so can satisfy the Node.js platform
+        let toolchains = toolchain_config.enabled_toolchains();
+        let platform_id = self.platform.to_string();
+
+        if toolchains.iter().any(|toolchain| {
+            toolchain.as_str() == platform_id
+                || (self.platform == PlatformType::Node && toolchain.as_str() == "bun")
+        }) {
+            return None;
+        }
+
+        warn!(
+            id = self.id,
+            platform = ?self.platform,
+            "Project {} uses the {:?} platform, but a toolchain has not been configured for it in {}",
+            color::id(self.id),
+            self.platform,
+            color::file(format!("{}/{}", consts::CONFIG_DIRNAME, consts::CONFIG_TOOLCHAIN_FILENAME)),
+        );
+
+        Some(self.platform)
+    }
+
     /// Inherit tasks, file groups, and more from global `.moon/tasks` configs.
     pub fn inherit_global_config(
         &mut self,
@@ -96,11 +219,12 @@ impl<'app> ProjectBuilder<'app> {
             .as_ref()
             .expect("Local config must be loaded before global config!");
 
-        let global_config = tasks_manager.get_inherited_config(
+        let global_config = tasks_manager.get_inherited_config_with_files(
             &self.platform,
             &self.language,
             &local_config.type_of,
             &local_config.tags,
+            &local_config.workspace.inherited_tasks.include_files,
         )?;
 
         debug!(
@@ -129,6 +253,31 @@ impl<'app> ProjectBuilder<'app> {
 
         let config = ProjectConfig::load(self.workspace_root, config_path)?;
 
+        // Inference only occurs when the platform is not configured,
+        // so verify that an explicit platform supports the explicit language
+        if let Some(platform) = &config.platform {
+            if !config.language.is_compatible_with(platform) {
+                if self.strict_platform {
+                    return Err(ProjectBuilderError::IncompatiblePlatform {
+                        id: self.id.to_owned(),
+                        language: config.language.to_string(),
+                        platform: platform.to_string(),
+                    }
+                    .into());
+                }
+
+                warn!(
+                    id = self.id,
+                    language = ?config.language,
+                    platform = ?platform,
+                    "Project {} has a {} language, which is not supported by the {} platform",
+                    color::id(self.id),
+                    color::symbol(config.language.to_string()),
+                    color::symbol(platform.to_string()),
+                );
+            }
+        }
+
         // Use configured language or detect from environment
         self.language = if config.language == LanguageType::Unknown {
             if let Some(detector) = &self.language_detector {
@@ -201,13 +350,36 @@ impl<'app> ProjectBuilder<'app> {
             .as_mut()
             .expect("Local config must be loaded before extending tasks!");
 
-        local_config.tasks.entry(id).or_insert(config);
+        if local_config.tasks.contains_key(&id) {
+            debug!(
+                id = self.id,
+                task = id.as_str(),
+                "Skipping implicit task {}, as a task with the same ID already exists",
+                color::id(&id),
+            );
+
+            self.skipped_tasks.push(id);
+        } else {
+            local_config.tasks.insert(id, config);
+        }
 
         self
     }
 
+    /// Return the IDs of implicit tasks that were skipped by
+    /// [`ProjectBuilder::extend_with_task`], as they conflicted with an existing task.
+    pub fn get_skipped_tasks(&self) -> &[Id] {
+        &self.skipped_tasks
+    }
+
     #[tracing::instrument(name = "project", skip_all)]
     pub fn build(mut self) -> miette::Result<Project> {
+        if self.validate_id {
+            self.check_id_convention();
+        }
+
+        self.check_platform_toolchain();
+
         let mut project = Project {
             dependencies: self.build_dependencies()?,
             file_groups: self.build_file_groups()?,
@@ -216,6 +388,7 @@ impl<'app> ProjectBuilder<'app> {
             language: self.language,
             platform: self.platform,
             root: self.project_root,
+            schema_version: PROJECT_SCHEMA_VERSION,
             source: self.source,
             ..Project::default()
         };
@@ -327,6 +500,18 @@ impl<'app> ProjectBuilder<'app> {
             tasks_builder.detect_platform(detector, self.toolchain_config.as_ref().unwrap());
         }
 
+        for (platform, options) in &self.platform_options {
+            tasks_builder.inherit_platform_options(*platform, options);
+        }
+
+        if !self.global_inputs.is_empty() {
+            tasks_builder.inherit_global_inputs(&self.global_inputs);
+        }
+
+        if !self.workspace_env.is_empty() {
+            tasks_builder.inherit_workspace_env(&self.workspace_env);
+        }
+
         if let Some(global_config) = &self.global_config {
             tasks_builder.inherit_global_tasks(
                 &global_config.config,
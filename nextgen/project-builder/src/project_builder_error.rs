@@ -5,6 +5,33 @@ use thiserror::Error;
 
 #[derive(Error, Debug, Diagnostic)]
 pub enum ProjectBuilderError {
+    #[diagnostic(
+        code(project::duplicate),
+        help = "Try renaming the project folders to make them unique."
+    )]
+    #[error(
+        "Multiple projects resolved to the ID {}, from sources {}.",
+        .id.style(Style::Id),
+        .sources.iter().map(|source| source.style(Style::File)).collect::<Vec<_>>().join(", "),
+    )]
+    DuplicateID { id: Id, sources: Vec<String> },
+
+    #[diagnostic(
+        code(project::incompatible_platform),
+        help = "Remove the platform setting to infer it from the language."
+    )]
+    #[error(
+        "Project {} has a {} language, which is not supported by the {} platform.",
+        .id.style(Style::Id),
+        .language.style(Style::Symbol),
+        .platform.style(Style::Symbol),
+    )]
+    IncompatiblePlatform {
+        id: String,
+        language: String,
+        platform: String,
+    },
+
     #[diagnostic(code(project::missing_source))]
     #[error("No project exists at path {}.", .0.style(Style::File))]
     MissingAtSource(String),
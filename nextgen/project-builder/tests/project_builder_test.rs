@@ -1,8 +1,9 @@
 use moon_common::path::WorkspaceRelativePathBuf;
 use moon_common::Id;
 use moon_config::{
-    DependencyConfig, DependencyScope, DependencySource, InheritedTasksManager, LanguageType,
-    PlatformType, TaskCommandArgs, TaskConfig,
+    BunConfig, DependencyConfig, DependencyScope, DependencySource, InheritedTasksManager,
+    LanguageType, PlatformType, RustConfig, TaskCommandArgs, TaskConfig, TaskOptionsConfig,
+    ToolchainConfig,
 };
 use moon_file_group::FileGroup;
 use moon_platform_detector::detect_project_language;
@@ -10,7 +11,38 @@ use moon_project::Project;
 use moon_project_builder::ProjectBuilder;
 use rustc_hash::FxHashMap;
 use starbase_sandbox::create_sandbox;
+use std::io::{self, Write};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Run the operation while capturing all warnings that were logged.
+fn capture_warnings<T>(op: impl FnOnce() -> T) -> (T, String) {
+    let buffer = LogBuffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_max_level(tracing::Level::WARN)
+        .with_writer(move || writer.clone())
+        .finish();
+
+    let result = tracing::subscriber::with_default(subscriber, op);
+    let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+
+    (result, logs)
+}
 
 fn build_project(id: &str, root: &Path) -> Project {
     let mut builder = ProjectBuilder::new(id, id, root).unwrap();
@@ -49,6 +81,71 @@ mod project_builder {
         ProjectBuilder::new("qux", "qux", sandbox.path()).unwrap();
     }
 
+    #[test]
+    fn passes_id_convention_when_matching_source() {
+        let sandbox = create_sandbox("builder");
+        let builder = ProjectBuilder::new("baz", "baz", sandbox.path()).unwrap();
+
+        let (_, logs) = capture_warnings(|| builder.check_id_convention());
+
+        assert!(!logs.contains("derived from its source"));
+    }
+
+    #[test]
+    fn passes_id_convention_for_root_source() {
+        let sandbox = create_sandbox("builder");
+        let builder = ProjectBuilder::new("root", ".", sandbox.path()).unwrap();
+
+        let (_, logs) = capture_warnings(|| builder.check_id_convention());
+
+        assert!(!logs.contains("derived from its source"));
+    }
+
+    #[test]
+    fn warns_id_convention_when_not_matching_source() {
+        let sandbox = create_sandbox("builder");
+        let builder = ProjectBuilder::new("qux", "baz", sandbox.path()).unwrap();
+
+        let (_, logs) = capture_warnings(|| builder.check_id_convention());
+
+        assert!(logs.contains("derived from its source"));
+    }
+
+    #[test]
+    fn warns_id_convention_when_building() {
+        let sandbox = create_sandbox("builder");
+        let mut builder = ProjectBuilder::new("qux", "baz", sandbox.path()).unwrap();
+        builder.validate_id_convention(true);
+        builder.load_local_config().unwrap();
+
+        let (_, logs) = capture_warnings(|| builder.build().unwrap());
+
+        assert!(logs.contains("derived from its source"));
+    }
+
+    #[test]
+    fn doesnt_warn_id_convention_when_building_without_validation() {
+        let sandbox = create_sandbox("builder");
+        let mut builder = ProjectBuilder::new("qux", "baz", sandbox.path()).unwrap();
+        builder.load_local_config().unwrap();
+
+        let (_, logs) = capture_warnings(|| builder.build().unwrap());
+
+        assert!(!logs.contains("derived from its source"));
+    }
+
+    #[test]
+    fn doesnt_warn_id_convention_when_building_with_matching_source() {
+        let sandbox = create_sandbox("builder");
+        let mut builder = ProjectBuilder::new("baz", "baz", sandbox.path()).unwrap();
+        builder.validate_id_convention(true);
+        builder.load_local_config().unwrap();
+
+        let (_, logs) = capture_warnings(|| builder.build().unwrap());
+
+        assert!(!logs.contains("derived from its source"));
+    }
+
     #[test]
     fn sets_common_fields() {
         let sandbox = create_sandbox("builder");
@@ -214,6 +311,11 @@ mod project_builder {
 
             assert_eq!(project.language, LanguageType::Bash);
             assert_eq!(project.platform, PlatformType::System);
+
+            let project = build_lang_project("bash-config");
+
+            assert_eq!(project.language, LanguageType::Bash);
+            assert_eq!(project.platform, PlatformType::System);
         }
 
         #[test]
@@ -222,6 +324,11 @@ mod project_builder {
 
             assert_eq!(project.language, LanguageType::Batch);
             assert_eq!(project.platform, PlatformType::System);
+
+            let project = build_lang_project("batch-config");
+
+            assert_eq!(project.language, LanguageType::Batch);
+            assert_eq!(project.platform, PlatformType::System);
         }
 
         #[test]
@@ -398,6 +505,177 @@ mod project_builder {
         }
     }
 
+    mod platform_compatibility {
+        use super::*;
+
+        #[test]
+        fn warns_on_incompatible_platform() {
+            let sandbox = create_sandbox("builder");
+            sandbox.create_file("mismatch/moon.yml", "language: rust\nplatform: node");
+
+            let mut builder = ProjectBuilder::new("mismatch", "mismatch", sandbox.path()).unwrap();
+
+            let (_, logs) = capture_warnings(|| builder.load_local_config().unwrap());
+
+            assert!(logs.contains("language, which is not supported by the"));
+
+            let project = builder.build().unwrap();
+
+            assert_eq!(project.language, LanguageType::Rust);
+            assert_eq!(project.platform, PlatformType::Node);
+        }
+
+        #[test]
+        fn errors_on_incompatible_platform_when_strict() {
+            let sandbox = create_sandbox("builder");
+            sandbox.create_file("mismatch/moon.yml", "language: rust\nplatform: node");
+
+            let mut builder = ProjectBuilder::new("mismatch", "mismatch", sandbox.path()).unwrap();
+            builder.error_on_incompatible_platform(true);
+
+            let error = builder.load_local_config().err().unwrap();
+
+            assert_eq!(
+                error.to_string(),
+                "Project mismatch has a rust language, which is not supported by the node platform."
+            );
+        }
+
+        #[test]
+        fn allows_compatible_platform_when_strict() {
+            let sandbox = create_sandbox("builder");
+            sandbox.create_file("match/moon.yml", "language: typescript\nplatform: deno");
+
+            let mut builder = ProjectBuilder::new("match", "match", sandbox.path()).unwrap();
+            builder.error_on_incompatible_platform(true);
+            builder.load_local_config().unwrap();
+
+            let project = builder.build().unwrap();
+
+            assert_eq!(project.platform, PlatformType::Deno);
+        }
+
+        #[test]
+        fn allows_system_platform_when_strict() {
+            let sandbox = create_sandbox("builder");
+            sandbox.create_file("system/moon.yml", "language: rust\nplatform: system");
+
+            let mut builder = ProjectBuilder::new("system", "system", sandbox.path()).unwrap();
+            builder.error_on_incompatible_platform(true);
+
+            assert!(builder.load_local_config().is_ok());
+        }
+    }
+
+    mod platform_toolchain {
+        use super::*;
+
+        #[test]
+        fn warns_when_toolchain_not_configured() {
+            let sandbox = create_sandbox("builder");
+            let toolchain_config = ToolchainConfig::default();
+
+            let mut builder = ProjectBuilder::new("bar", "bar", sandbox.path()).unwrap();
+            builder.detect_platform(|_, _| PlatformType::Unknown, &toolchain_config);
+            builder.load_local_config().unwrap();
+
+            let (platform, logs) = capture_warnings(|| builder.check_platform_toolchain());
+
+            assert_eq!(platform, Some(PlatformType::Rust));
+            assert!(logs.contains("but a toolchain has not been configured for it"));
+        }
+
+        #[test]
+        fn warns_when_building() {
+            let sandbox = create_sandbox("builder");
+            let toolchain_config = ToolchainConfig::default();
+
+            let mut builder = ProjectBuilder::new("bar", "bar", sandbox.path()).unwrap();
+            builder.detect_platform(|_, _| PlatformType::Unknown, &toolchain_config);
+            builder.load_local_config().unwrap();
+
+            let (_, logs) = capture_warnings(|| builder.build().unwrap());
+
+            assert!(logs.contains("but a toolchain has not been configured for it"));
+        }
+
+        #[test]
+        fn doesnt_warn_when_bun_configured_for_node() {
+            let sandbox = create_sandbox("builder");
+            let toolchain_config = ToolchainConfig {
+                bun: Some(BunConfig::default()),
+                ..ToolchainConfig::default()
+            };
+
+            let mut builder = ProjectBuilder::new("baz", "baz", sandbox.path()).unwrap();
+            builder.detect_platform(|_, _| PlatformType::Unknown, &toolchain_config);
+            builder.load_local_config().unwrap();
+
+            assert_eq!(builder.platform, PlatformType::Node);
+            assert_eq!(builder.check_platform_toolchain(), None);
+        }
+
+        #[test]
+        fn doesnt_warn_when_toolchain_configured() {
+            let sandbox = create_sandbox("builder");
+            let toolchain_config = ToolchainConfig {
+                rust: Some(RustConfig::default()),
+                ..ToolchainConfig::default()
+            };
+
+            let mut builder = ProjectBuilder::new("bar", "bar", sandbox.path()).unwrap();
+            builder.detect_platform(|_, _| PlatformType::Unknown, &toolchain_config);
+            builder.load_local_config().unwrap();
+
+            assert_eq!(builder.check_platform_toolchain(), None);
+        }
+
+        #[test]
+        fn doesnt_warn_without_toolchain_config() {
+            let sandbox = create_sandbox("builder");
+
+            let mut builder = ProjectBuilder::new("bar", "bar", sandbox.path()).unwrap();
+            builder.load_local_config().unwrap();
+
+            assert_eq!(builder.check_platform_toolchain(), None);
+        }
+    }
+
+    mod platform_options {
+        use super::*;
+
+        #[test]
+        fn inherits_options_for_task_platform() {
+            let sandbox = create_sandbox("builder");
+
+            let mut builder = ProjectBuilder::new("baz", "baz", sandbox.path()).unwrap();
+            builder.load_local_config().unwrap();
+
+            builder.inherit_platform_options(
+                PlatformType::Node,
+                TaskOptionsConfig {
+                    cache: Some(false),
+                    retry_count: Some(2),
+                    ..TaskOptionsConfig::default()
+                },
+            );
+            builder.inherit_platform_options(
+                PlatformType::Rust,
+                TaskOptionsConfig {
+                    retry_count: Some(5),
+                    ..TaskOptionsConfig::default()
+                },
+            );
+
+            let project = builder.build().unwrap();
+            let task = project.tasks.get("baz").unwrap();
+
+            assert_eq!(task.platform, PlatformType::Node);
+            assert!(!task.options.cache);
+            assert_eq!(task.options.retry_count, 2);
+        }
+    }
+
     mod graph_extending {
         use super::*;
 
@@ -492,5 +770,30 @@ mod project_builder {
             assert!(project.tasks.contains_key("baz"));
             assert_eq!(project.tasks.get("baz").unwrap().command, "baz");
         }
+
+        #[test]
+        fn reports_skipped_task_of_same_id() {
+            let sandbox = create_sandbox("builder");
+
+            let mut builder = ProjectBuilder::new("baz", "baz", sandbox.path()).unwrap();
+            builder.load_local_config().unwrap();
+
+            builder.extend_with_task(Id::raw("baz"), TaskConfig::default());
+            builder.extend_with_task(Id::raw("other"), TaskConfig::default());
+
+            assert_eq!(builder.get_skipped_tasks(), &[Id::raw("baz")]);
+        }
+
+        #[test]
+        fn doesnt_report_injected_tasks() {
+            let sandbox = create_sandbox("builder");
+
+            let mut builder = ProjectBuilder::new("bar", "bar", sandbox.path()).unwrap();
+            builder.load_local_config().unwrap();
+
+            builder.extend_with_task(Id::raw("task"), TaskConfig::default());
+
+            assert!(builder.get_skipped_tasks().is_empty());
+        }
     }
 }
@@ -1,4 +1,5 @@
 use crate::project_error::ProjectError;
+use miette::IntoDiagnostic;
 use moon_common::{cacheable, path::WorkspaceRelativePathBuf, Id};
 use moon_config::{
     DependencyConfig, InheritedTasksResult, LanguageType, PlatformType, ProjectConfig, ProjectType,
@@ -8,10 +9,16 @@ use moon_query::{Condition, Criteria, Field, LogicalOperator, Queryable};
 use moon_task::Task;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Version of the serialized project format. Must be bumped whenever
+/// the shape of [`Project`] changes in a backwards incompatible way.
+///
+/// - 2: Task `options.envFile` (string) was replaced by `options.envFiles` (list).
+pub const PROJECT_SCHEMA_VERSION: u16 = 2;
 
 cacheable!(
-    #[derive(Clone, Debug, Default)]
+    #[derive(Clone, Debug)]
     pub struct Project {
         /// Unique alias of the project, alongside its official ID.
         /// This is typically for language specific semantics, like `name` from `package.json`.
@@ -41,6 +48,10 @@ cacheable!(
         /// Absolute path to the project's root folder.
         pub root: PathBuf,
 
+        /// Version of the format this project was serialized with.
+        #[serde(default)]
+        pub schema_version: u16,
+
         /// Relative path from the workspace root to the project root.
         /// Is the RHS of the `projects` setting.
         pub source: WorkspaceRelativePathBuf,
@@ -54,7 +65,87 @@ cacheable!(
     }
 );
 
+cacheable!(
+    struct ProjectSchema {
+        #[serde(default)]
+        pub schema_version: u16,
+    }
+);
+
+fn check_schema_version(actual: u16) -> miette::Result<()> {
+    if actual != PROJECT_SCHEMA_VERSION {
+        return Err(ProjectError::SchemaVersionMismatch {
+            expected: PROJECT_SCHEMA_VERSION,
+            actual,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Project {
+            alias: None,
+            config: ProjectConfig::default(),
+            dependencies: FxHashMap::default(),
+            file_groups: FxHashMap::default(),
+            id: Id::default(),
+            inherited: None,
+            language: LanguageType::default(),
+            platform: PlatformType::default(),
+            root: PathBuf::default(),
+            schema_version: PROJECT_SCHEMA_VERSION,
+            source: WorkspaceRelativePathBuf::default(),
+            tasks: BTreeMap::default(),
+            type_of: ProjectType::default(),
+        }
+    }
+}
+
 impl Project {
+    /// Deserialize a project from JSON, like a cache or graph file. Will error
+    /// if the project was serialized with a different format version.
+    pub fn from_json<T: AsRef<str>>(content: T) -> miette::Result<Project> {
+        let content = content.as_ref();
+        let schema: ProjectSchema = serde_json::from_str(content).into_diagnostic()?;
+
+        check_schema_version(schema.schema_version)?;
+
+        serde_json::from_str(content).into_diagnostic()
+    }
+
+    /// Verify that an already deserialized project (within a graph, for example)
+    /// was serialized with the current format version.
+    pub fn verify_schema_version(&self) -> miette::Result<()> {
+        check_schema_version(self.schema_version)
+    }
+
+    /// Return a file group with the defined ID, resolved to a list of absolute
+    /// file paths. Globs within the group are expanded against the workspace root.
+    pub fn file_group_paths<I: AsRef<str>>(
+        &self,
+        group_id: I,
+        workspace_root: &Path,
+    ) -> miette::Result<Vec<PathBuf>> {
+        let group_id = Id::raw(group_id.as_ref());
+
+        let group =
+            self.file_groups
+                .get(&group_id)
+                .ok_or_else(|| ProjectError::UnknownFileGroup {
+                    group_id,
+                    project_id: self.id.clone(),
+                })?;
+
+        Ok(group
+            .files(workspace_root)?
+            .into_iter()
+            .map(|file| file.to_path(workspace_root))
+            .collect())
+    }
+
     /// Return a list of project IDs this project depends on.
     pub fn get_dependency_ids(&self) -> Vec<&Id> {
         self.dependencies.keys().collect::<Vec<_>>()
@@ -4,6 +4,26 @@ use thiserror::Error;
 
 #[derive(Error, Debug, Diagnostic)]
 pub enum ProjectError {
+    #[diagnostic(
+        code(project::schema::mismatch),
+        help = "Project cache format changed, please re-run the command."
+    )]
+    #[error(
+        "Unable to load project, as it was serialized with format version {actual} (expected {expected})."
+    )]
+    SchemaVersionMismatch { expected: u16, actual: u16 },
+
+    #[diagnostic(
+        code(project::file_group::unknown),
+        help = "Has this file group been configured?"
+    )]
+    #[error(
+        "Unknown file group {} for project {}.",
+        .group_id.style(Style::Id),
+        .project_id.style(Style::Id),
+    )]
+    UnknownFileGroup { group_id: Id, project_id: Id },
+
     #[diagnostic(code(project::task::unknown), help = "Has this task been configured?")]
     #[error(
         "Unknown task {} for project {}.",
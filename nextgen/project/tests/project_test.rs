@@ -0,0 +1,116 @@
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_common::Id;
+use moon_file_group::FileGroup;
+use moon_project::{Project, PROJECT_SCHEMA_VERSION};
+use rustc_hash::FxHashMap;
+use starbase_sandbox::create_empty_sandbox;
+
+mod from_json {
+    use super::*;
+
+    #[test]
+    fn loads_current_version() {
+        let project = Project {
+            id: Id::raw("foo"),
+            schema_version: PROJECT_SCHEMA_VERSION,
+            ..Project::default()
+        };
+
+        let content = serde_json::to_string(&project).unwrap();
+
+        assert_eq!(Project::from_json(content).unwrap(), project);
+    }
+
+    #[test]
+    fn defaults_to_current_version() {
+        assert_eq!(Project::default().schema_version, PROJECT_SCHEMA_VERSION);
+
+        let content = serde_json::to_string(&Project::default()).unwrap();
+
+        assert!(Project::from_json(content).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "serialized with format version 0")]
+    fn errors_when_verifying_old_version() {
+        let project = Project {
+            schema_version: 0,
+            ..Project::default()
+        };
+
+        project.verify_schema_version().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "serialized with format version 0")]
+    fn errors_for_old_version() {
+        let project = Project {
+            id: Id::raw("foo"),
+            schema_version: 0,
+            ..Project::default()
+        };
+
+        Project::from_json(serde_json::to_string(&project).unwrap()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "serialized with format version 0")]
+    fn errors_when_version_missing() {
+        Project::from_json(r#"{ "id": "foo", "tasks": [] }"#).unwrap();
+    }
+}
+
+mod file_group_paths {
+    use super::*;
+
+    fn create_project() -> Project {
+        Project {
+            id: Id::raw("project"),
+            file_groups: FxHashMap::from_iter([(
+                Id::raw("sources"),
+                FileGroup::new_with_source(
+                    "sources",
+                    [
+                        WorkspaceRelativePathBuf::from("project/README.md"),
+                        WorkspaceRelativePathBuf::from("project/src/*.ts"),
+                    ],
+                )
+                .unwrap(),
+            )]),
+            ..Project::default()
+        }
+    }
+
+    #[test]
+    fn resolves_to_absolute_paths() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("project/README.md", "");
+        sandbox.create_file("project/src/a.ts", "");
+        sandbox.create_file("project/src/b.ts", "");
+        sandbox.create_file("project/src/c.js", "");
+
+        let mut paths = create_project()
+            .file_group_paths("sources", sandbox.path())
+            .unwrap();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                sandbox.path().join("project/README.md"),
+                sandbox.path().join("project/src/a.ts"),
+                sandbox.path().join("project/src/b.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown file group unknown for project project.")]
+    fn errors_for_unknown_group() {
+        let sandbox = create_empty_sandbox();
+
+        create_project()
+            .file_group_paths("unknown", sandbox.path())
+            .unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+use crate::target_error::TargetError;
+use crate::target_scope::TargetScope;
+use moon_common::{Id, ID_CHARS};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schematic::{SchemaType, Schematic};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+};
+
+// The @ is to support npm package scopes!
+pub static TARGET_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^(?P<scope>(?:[A-Za-z@#]{{1}}{chars}|\^|~))?:(?P<task>{chars})$",
+        chars = ID_CHARS
+    ))
+    .unwrap()
+});
+
+static TASK_ID_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!("^{chars}$", chars = ID_CHARS)).unwrap());
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Target {
+    pub id: String,
+    pub scope: TargetScope,
+    pub scope_id: Option<Id>,
+    pub task_id: Id,
+}
+
+impl Target {
+    pub fn new<S, T>(scope_id: S, task_id: T) -> miette::Result<Target>
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let scope_id = scope_id.as_ref();
+        let task_id = task_id.as_ref();
+
+        let handle_error = |_| TargetError::InvalidFormat(format!("{scope_id}:{task_id}"));
+        let scope = TargetScope::Project(Id::new(scope_id).map_err(handle_error)?);
+
+        Ok(Target {
+            id: Target::format(&scope, task_id)?,
+            scope,
+            scope_id: Some(Id::raw(scope_id)),
+            task_id: Id::new(task_id).map_err(handle_error)?,
+        })
+    }
+
+    pub fn new_self<T>(task_id: T) -> miette::Result<Target>
+    where
+        T: AsRef<str>,
+    {
+        let task_id = task_id.as_ref();
+
+        Ok(Target {
+            id: Target::format(TargetScope::OwnSelf, task_id)?,
+            scope: TargetScope::OwnSelf,
+            scope_id: None,
+            task_id: Id::new(task_id)
+                .map_err(|_| TargetError::InvalidFormat(format!("~:{task_id}")))?,
+        })
+    }
+
+    pub fn format<S, T>(scope: S, task: T) -> miette::Result<String>
+    where
+        S: AsRef<TargetScope>,
+        T: AsRef<str>,
+    {
+        Ok(format!("{}:{}", scope.as_ref(), task.as_ref()))
+    }
+
+    pub fn parse(target_id: &str) -> miette::Result<Target> {
+        if target_id == ":" {
+            return Err(TargetError::TooWild.into());
+        }
+
+        let Some((scope_part, task_part)) = target_id.split_once(':') else {
+            return Target::new_self(target_id);
+        };
+
+        if let Some(index) = task_part.find(':') {
+            return Err(TargetError::TooManyColons {
+                target: target_id.to_owned(),
+                span: (scope_part.len() + 1 + index, 1).into(),
+            }
+            .into());
+        }
+
+        if task_part.is_empty() {
+            return Err(TargetError::EmptyTask {
+                target: target_id.to_owned(),
+                span: (scope_part.len(), 1).into(),
+            }
+            .into());
+        }
+
+        if scope_part == "#" {
+            return Err(TargetError::EmptyProject {
+                target: target_id.to_owned(),
+                span: (0, 1).into(),
+            }
+            .into());
+        }
+
+        let Some(matches) = TARGET_PATTERN.captures(target_id) else {
+            return Err(Target::find_invalid_id(target_id, scope_part, task_part).into());
+        };
+
+        let handle_error = |_| Target::find_invalid_id(target_id, scope_part, task_part);
+
+        let mut scope_id = None;
+        let scope = match matches.name("scope") {
+            Some(value) => match value.as_str() {
+                "" => TargetScope::All,
+                "^" => TargetScope::Deps,
+                "~" => TargetScope::OwnSelf,
+                id => {
+                    if let Some(tag) = id.strip_prefix('#') {
+                        scope_id = Some(Id::new(tag).map_err(handle_error)?);
+                        TargetScope::Tag(Id::raw(tag))
+                    } else {
+                        scope_id = Some(Id::new(id).map_err(handle_error)?);
+                        TargetScope::Project(Id::raw(id))
+                    }
+                }
+            },
+            None => TargetScope::All,
+        };
+
+        let task_id = Id::new(matches.name("task").unwrap().as_str()).map_err(handle_error)?;
+
+        Ok(Target {
+            id: target_id.to_owned(),
+            scope,
+            scope_id,
+            task_id,
+        })
+    }
+
+    // Determine whether the scope or the task is the offending identifier,
+    // so that the error can point to it, otherwise fallback to the format.
+    fn find_invalid_id(target_id: &str, scope_part: &str, task_part: &str) -> TargetError {
+        let scope_id = scope_part.strip_prefix('#').unwrap_or(scope_part);
+
+        let (id, offset) = if !matches!(scope_part, "" | "^" | "~") && Id::new(scope_id).is_err() {
+            (scope_id, scope_part.len() - scope_id.len())
+        } else if !TASK_ID_PATTERN.is_match(task_part) || Id::new(task_part).is_err() {
+            (task_part, scope_part.len() + 1)
+        } else {
+            return TargetError::InvalidFormat(target_id.to_owned());
+        };
+
+        TargetError::InvalidId {
+            target: target_id.to_owned(),
+            id: id.to_owned(),
+            span: (offset, id.len()).into(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_all_task(&self, task_id: &str) -> bool {
+        if matches!(&self.scope, TargetScope::All) {
+            return if let Some(id) = task_id.strip_prefix(':') {
+                self.task_id == id
+            } else {
+                self.task_id == task_id
+            };
+        }
+
+        false
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Target {
+            id: "~:unknown".into(),
+            scope: TargetScope::OwnSelf,
+            scope_id: None,
+            task_id: Id::raw("unknown"),
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl AsRef<Target> for Target {
+    fn as_ref(&self) -> &Target {
+        self
+    }
+}
+
+impl AsRef<str> for Target {
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl PartialOrd for Target {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Targets are ordered by their scope kind (all, deps, self, project, tag),
+/// then by the project or tag identifier, and finally by the task identifier.
+impl Ord for Target {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.scope
+            .cmp(&other.scope)
+            .then_with(|| self.task_id.cmp(&other.task_id))
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl<'de> Deserialize<'de> for Target {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Target::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Target {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+impl Schematic for Target {
+    fn generate_schema() -> SchemaType {
+        SchemaType::string()
+    }
+}
+
+// This is only used by tests!
+
+impl From<&str> for Target {
+    fn from(value: &str) -> Self {
+        Target::parse(value).unwrap()
+    }
+}
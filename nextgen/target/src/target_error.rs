@@ -0,0 +1,75 @@
+use miette::{Diagnostic, SourceSpan};
+use starbase_styles::{Style, Stylize};
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum TargetError {
+    #[diagnostic(code(target::invalid_format))]
+    #[error(
+        "Invalid target {}, must be in the format of \"scope:task\".",
+        .0.style(Style::Label),
+    )]
+    InvalidFormat(String),
+
+    #[diagnostic(code(target::empty_project))]
+    #[error(
+        "Invalid target {}, the project or tag scope is empty.",
+        .target.style(Style::Label),
+    )]
+    EmptyProject {
+        #[source_code]
+        target: String,
+        #[label("missing scope")]
+        span: SourceSpan,
+    },
+
+    #[diagnostic(code(target::empty_task))]
+    #[error(
+        "Invalid target {}, the task is empty.",
+        .target.style(Style::Label),
+    )]
+    EmptyTask {
+        #[source_code]
+        target: String,
+        #[label("missing task")]
+        span: SourceSpan,
+    },
+
+    #[diagnostic(code(target::invalid_id))]
+    #[error(
+        "Invalid target {}, {} is not a valid identifier.",
+        .target.style(Style::Label),
+        .id.style(Style::Id),
+    )]
+    InvalidId {
+        #[source_code]
+        target: String,
+        id: String,
+        #[label("invalid identifier")]
+        span: SourceSpan,
+    },
+
+    #[diagnostic(code(target::too_many_colons))]
+    #[error(
+        "Invalid target {}, only a single colon may separate the scope and task.",
+        .target.style(Style::Label),
+    )]
+    TooManyColons {
+        #[source_code]
+        target: String,
+        #[label("unexpected colon")]
+        span: SourceSpan,
+    },
+
+    #[diagnostic(code(target::scope::no_deps))]
+    #[error("Dependencies scope (^:) is not supported in run contexts.")]
+    NoDepsInRunContext,
+
+    #[diagnostic(code(target::scope::no_self))]
+    #[error("Self scope (~:) is not supported in run contexts.")]
+    NoSelfInRunContext,
+
+    #[diagnostic(code(target::too_wild))]
+    #[error("Target \":\" encountered. Wildcard scope and task not supported.")]
+    TooWild,
+}
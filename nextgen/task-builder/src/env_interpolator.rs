@@ -0,0 +1,121 @@
+use rustc_hash::FxHashMap;
+use std::env;
+
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Interpolate `$VAR` and `${VAR}` references within a value, by resolving
+/// each variable name with the provided lookup. A literal `$$` is an escape
+/// for a single `$`, and a `$` that does not start a reference is kept as-is.
+pub fn interpolate_value<F>(value: &str, mut lookup: F) -> Result<String, String>
+where
+    F: FnMut(&str) -> Result<String, String>,
+{
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+
+        let after = &rest[index + 1..];
+
+        if let Some(remaining) = after.strip_prefix('$') {
+            result.push('$');
+            rest = remaining;
+            continue;
+        }
+
+        let (name, remaining) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(after.len());
+
+                (&after[..end], &after[end..])
+            }
+        };
+
+        if is_valid_name(name) {
+            result.push_str(&lookup(name)?);
+            rest = remaining;
+        } else {
+            result.push('$');
+            rest = after;
+        }
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Interpolate variable references within each value of the provided env map.
+/// References are resolved against other keys in the same map first (which may
+/// be nested), then the inherited env (whose values are used as-is), and then
+/// the process environment. A key referencing itself (or a cycle) will resolve
+/// from the inherited or process environment, so `PATH=$PATH:./bin` works as expected.
+///
+/// Undefined variables are replaced with an empty string, unless `strict` is
+/// enabled, in which case the name of the undefined variable is returned as an error.
+pub fn interpolate_env(
+    env_map: &FxHashMap<String, String>,
+    inherited_env: &FxHashMap<String, String>,
+    strict: bool,
+) -> Result<FxHashMap<String, String>, String> {
+    let mut resolved = FxHashMap::default();
+
+    for key in env_map.keys() {
+        resolve_var(
+            key,
+            env_map,
+            inherited_env,
+            strict,
+            &mut resolved,
+            &mut vec![],
+        )?;
+    }
+
+    Ok(resolved)
+}
+
+fn resolve_var(
+    key: &str,
+    env_map: &FxHashMap<String, String>,
+    inherited_env: &FxHashMap<String, String>,
+    strict: bool,
+    resolved: &mut FxHashMap<String, String>,
+    stack: &mut Vec<String>,
+) -> Result<String, String> {
+    if let Some(value) = resolved.get(key) {
+        return Ok(value.to_owned());
+    }
+
+    stack.push(key.to_owned());
+
+    let value = interpolate_value(&env_map[key], |name| {
+        if env_map.contains_key(name) && !stack.iter().any(|k| k == name) {
+            return resolve_var(name, env_map, inherited_env, strict, resolved, stack);
+        }
+
+        if let Some(value) = inherited_env.get(name) {
+            return Ok(value.to_owned());
+        }
+
+        match env::var(name) {
+            Ok(value) => Ok(value),
+            Err(_) if strict => Err(name.to_owned()),
+            Err(_) => Ok(String::new()),
+        }
+    })?;
+
+    stack.pop();
+    resolved.insert(key.to_owned(), value.clone());
+
+    Ok(value)
+}
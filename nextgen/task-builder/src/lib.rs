@@ -1,3 +1,4 @@
+pub mod env_interpolator;
 mod tasks_builder;
 mod tasks_builder_error;
 
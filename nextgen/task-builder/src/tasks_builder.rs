@@ -1,21 +1,27 @@
 #![allow(dead_code)]
 
+use crate::env_interpolator::interpolate_env;
 use crate::tasks_builder_error::TasksBuilderError;
+use flate2::read::GzDecoder;
 use moon_args::split_args;
 use moon_common::{color, Id};
 use moon_config::{
     InheritedTasksConfig, InputPath, PlatformType, ProjectConfig,
-    ProjectWorkspaceInheritedTasksConfig, TaskCommandArgs, TaskConfig, TaskMergeStrategy,
-    TaskOutputStyle, TaskType, ToolchainConfig,
+    ProjectWorkspaceInheritedTasksConfig, TaskCommandArgs, TaskConfig, TaskDependency,
+    TaskMergeStrategy, TaskOptionsConfig, TaskOutputStyle, TaskType, ToolchainConfig,
 };
 use moon_target::Target;
 use moon_task::{Task, TaskOptions};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
+use std::env;
 use std::hash::Hash;
+use std::io::Read;
 use std::path::Path;
 use tracing::{debug, trace, warn};
 
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
 pub type PlatformDetector = dyn Fn(&str, &ToolchainConfig) -> PlatformType;
 
 pub struct TasksBuilder<'proj> {
@@ -25,11 +31,16 @@ pub struct TasksBuilder<'proj> {
     project_source: &'proj str,
 
     // Workspace information
+    environment: Option<String>,
     workspace_root: &'proj Path,
     platform_detector: Option<Box<PlatformDetector>>,
     toolchain_config: Option<&'proj ToolchainConfig>,
 
     // Global settings for tasks to inherit
+    workspace_env: FxHashMap<&'proj str, &'proj str>,
+    platform_options: FxHashMap<PlatformType, &'proj TaskOptionsConfig>,
+    options_presets: FxHashMap<&'proj Id, &'proj TaskOptionsConfig>,
+    global_inputs: Vec<&'proj InputPath>,
     implicit_deps: Vec<&'proj Target>,
     implicit_inputs: Vec<&'proj InputPath>,
 
@@ -51,9 +62,14 @@ impl<'proj> TasksBuilder<'proj> {
             project_env: FxHashMap::default(),
             project_platform,
             project_source,
+            environment: env::var("MOON_ENV").ok().filter(|env| !env.is_empty()),
             workspace_root,
             platform_detector: None,
             toolchain_config: None,
+            workspace_env: FxHashMap::default(),
+            platform_options: FxHashMap::default(),
+            options_presets: FxHashMap::default(),
+            global_inputs: vec![],
             implicit_deps: vec![],
             implicit_inputs: vec![],
             task_ids: FxHashSet::default(),
@@ -62,6 +78,13 @@ impl<'proj> TasksBuilder<'proj> {
         }
     }
 
+    /// Set the active environment, used to select environment specific commands.
+    /// Defaults to the `MOON_ENV` environment variable.
+    pub fn set_environment<T: AsRef<str>>(&mut self, environment: T) -> &mut Self {
+        self.environment = Some(environment.as_ref().to_owned());
+        self
+    }
+
     /// Register a function to detect a task's platform when unknown.
     pub fn detect_platform<F>(&mut self, detector: F, config: &'proj ToolchainConfig) -> &mut Self
     where
@@ -72,6 +95,34 @@ impl<'proj> TasksBuilder<'proj> {
         self
     }
 
+    /// Register default task options for a platform. These are applied with
+    /// the lowest precedence, before global and local task options.
+    pub fn inherit_platform_options(
+        &mut self,
+        platform: PlatformType,
+        options: &'proj TaskOptionsConfig,
+    ) -> &mut Self {
+        self.platform_options.insert(platform, options);
+        self
+    }
+
+    /// Register workspace-level environment variables that will be inherited by
+    /// every task, with the lowest precedence (project and task `env` override).
+    pub fn inherit_workspace_env(&mut self, env: &'proj FxHashMap<String, String>) -> &mut Self {
+        for (key, value) in env {
+            self.workspace_env.insert(key, value);
+        }
+
+        self
+    }
+
+    /// Register workspace-level inputs that will be inherited by every task,
+    /// regardless of merge strategy, so that changes to them invalidate all hashes.
+    pub fn inherit_global_inputs(&mut self, inputs: &'proj [InputPath]) -> &mut Self {
+        self.global_inputs.extend(inputs);
+        self
+    }
+
     pub fn inherit_global_tasks(
         &mut self,
         global_config: &'proj InheritedTasksConfig,
@@ -154,6 +205,7 @@ impl<'proj> TasksBuilder<'proj> {
             self.task_ids.insert(task_key);
         }
 
+        self.options_presets.extend(&global_config.options_presets);
         self.implicit_deps.extend(&global_config.implicit_deps);
         self.implicit_inputs.extend(&global_config.implicit_inputs);
         self
@@ -164,6 +216,8 @@ impl<'proj> TasksBuilder<'proj> {
             self.project_env.insert(key, value);
         }
 
+        // Local presets take precedence over global presets of the same name
+        self.options_presets.extend(&local_config.options_presets);
         self.local_tasks.extend(&local_config.tasks);
 
         for id in local_config.tasks.keys() {
@@ -190,19 +244,12 @@ impl<'proj> TasksBuilder<'proj> {
         debug!(target = target.as_str(), "Building task");
 
         let mut task = Task::default();
-        let mut configs = vec![];
-
-        if let Some(config) = self.global_tasks.get(id) {
-            configs.push(*config);
-        }
-
-        if let Some(config) = self.local_tasks.get(id) {
-            configs.push(*config);
-        }
+        let configs = self.get_task_configs(id, &mut vec![])?;
 
         // Determine command and args before building options and the task,
         // as we need to figure out if we're running in local mode or not.
         let mut is_local = id == "dev" || id == "serve" || id == "start";
+        let is_serve = is_serve_type(&configs);
         let mut args_sets = vec![];
 
         for config in &configs {
@@ -222,7 +269,10 @@ impl<'proj> TasksBuilder<'proj> {
 
         trace!(target = target.as_str(), "Marking task as local");
 
-        task.options = self.build_task_options(id, is_local)?;
+        // Resolve the platform before options, as platforms may provide default options
+        task.platform = self.build_platform(&configs, &task.command);
+        task.toolchain = self.build_toolchain(&target, &configs, &task.platform)?;
+        task.options = self.build_task_options(&target, &configs, is_local, &task.platform)?;
         task.flags.local = is_local;
 
         // Aggregate all values that are inherited from the global task configs,
@@ -232,9 +282,41 @@ impl<'proj> TasksBuilder<'proj> {
 
         // Aggregate all values that that are inherited from the project,
         // and should be set on the task first, so that merge strategies can be applied.
+        let mut named_args: Option<BTreeMap<String, String>> = None;
+
         for args in args_sets {
-            if !args.is_empty() {
-                task.args = self.merge_vec(task.args, args, task.options.merge_args, false);
+            match args {
+                TaskArgsSet::List(args) => {
+                    if !args.is_empty() {
+                        task.args = self.merge_vec(task.args, args, task.options.merge_args, false);
+                        named_args = None;
+                    }
+                }
+                // Named args are merged by key when all previous args are also named,
+                // otherwise the same flag would be passed multiple times
+                TaskArgsSet::Named(next) => {
+                    let merged = match named_args.take() {
+                        Some(base) => self.merge_args_map(base, next, task.options.merge_args),
+                        None if task.args.is_empty()
+                            || matches!(task.options.merge_args, TaskMergeStrategy::Replace) =>
+                        {
+                            next
+                        }
+                        None => {
+                            task.args = self.merge_vec(
+                                task.args,
+                                flatten_args_map(&next),
+                                task.options.merge_args,
+                                false,
+                            );
+
+                            continue;
+                        }
+                    };
+
+                    task.args = flatten_args_map(&merged);
+                    named_args = Some(merged);
+                }
             }
         }
 
@@ -245,17 +327,31 @@ impl<'proj> TasksBuilder<'proj> {
         let mut has_configured_inputs = false;
 
         for config in configs {
+            // Artifacts are metadata only, so the last configured list wins
+            if !config.artifacts.is_empty() {
+                task.artifacts = config.artifacts.to_owned();
+            }
+
             if !config.deps.is_empty() {
-                task.deps = self.merge_vec(
-                    task.deps,
-                    config.deps.to_owned(),
-                    task.options.merge_deps,
-                    true,
-                );
+                let mut deps = vec![];
+                let mut file_deps = vec![];
+
+                for dep in &config.deps {
+                    match dep {
+                        TaskDependency::Target(target) => deps.push(target.to_owned()),
+                        TaskDependency::File(file) => file_deps.push(file.to_owned()),
+                    };
+                }
+
+                task.deps = self.merge_vec(task.deps, deps, task.options.merge_deps, true);
+                task.file_deps =
+                    self.merge_vec(task.file_deps, file_deps, task.options.merge_deps, true);
             }
 
             if !config.env.is_empty() {
-                task.env = self.merge_map(task.env, config.env.to_owned(), task.options.merge_env);
+                let env = self.interpolate_env(&target, &config.env, &task.env, &task.options)?;
+
+                task.env = self.merge_map(task.env, env, task.options.merge_env);
             }
 
             // Inherit global inputs as normal inputs, but do not consider them a configured input
@@ -284,10 +380,6 @@ impl<'proj> TasksBuilder<'proj> {
                     true,
                 );
             }
-
-            if !config.platform.is_unknown() {
-                task.platform = config.platform;
-            }
         }
 
         // Inputs are tricky, as they come from many sources. We need to ensure that user configured
@@ -328,13 +420,37 @@ impl<'proj> TasksBuilder<'proj> {
                 self.merge_vec(task.inputs, global_inputs, TaskMergeStrategy::Append, true);
         }
 
-        if task.platform.is_unknown() {
+        task.target = target;
+
+        task.type_of = if is_serve {
+            TaskType::Serve
+        } else if !task.outputs.is_empty() {
+            TaskType::Build
+        } else if is_local {
+            TaskType::Run
+        } else {
+            TaskType::Test
+        };
+
+        Ok(task)
+    }
+
+    fn build_platform(&self, configs: &[&TaskConfig], command: &str) -> PlatformType {
+        let mut platform = PlatformType::Unknown;
+
+        for config in configs {
+            if !config.platform.is_unknown() {
+                platform = config.platform;
+            }
+        }
+
+        if platform.is_unknown() {
             if let Some(detector) = &self.platform_detector {
-                task.platform = detector(&task.command, self.toolchain_config.as_ref().unwrap());
+                platform = detector(command, self.toolchain_config.as_ref().unwrap());
             }
 
-            if task.platform.is_unknown() {
-                task.platform = if self.project_platform.is_unknown() {
+            if platform.is_unknown() {
+                platform = if self.project_platform.is_unknown() {
                     PlatformType::System
                 } else {
                     self.project_platform.to_owned()
@@ -342,36 +458,94 @@ impl<'proj> TasksBuilder<'proj> {
             }
         }
 
-        task.target = target;
+        platform
+    }
 
-        task.type_of = if !task.outputs.is_empty() {
-            TaskType::Build
-        } else if is_local {
-            TaskType::Run
-        } else {
-            TaskType::Test
+    fn build_toolchain(
+        &self,
+        target: &Target,
+        configs: &[&TaskConfig],
+        platform: &PlatformType,
+    ) -> miette::Result<Option<Id>> {
+        let Some(toolchain) = configs
+            .iter()
+            .rev()
+            .find_map(|config| config.toolchain.as_ref())
+        else {
+            // Fallback to the toolchain of the platform, if it has one
+            return Ok(match platform {
+                PlatformType::Deno | PlatformType::Node | PlatformType::Rust => {
+                    Some(Id::raw(platform.to_string()))
+                }
+                PlatformType::System | PlatformType::Unknown => None,
+            });
         };
 
-        Ok(task)
+        let is_configured = self
+            .toolchain_config
+            .is_some_and(|config| config.enabled_toolchains().contains(toolchain));
+
+        if !is_configured {
+            return Err(TasksBuilderError::UnknownToolchain {
+                target: target.id.to_owned(),
+                toolchain: toolchain.to_owned(),
+            }
+            .into());
+        }
+
+        trace!(
+            target = target.as_str(),
+            toolchain = toolchain.as_str(),
+            "Using explicitly configured toolchain",
+        );
+
+        Ok(Some(toolchain.to_owned()))
     }
 
-    fn build_task_options(&self, id: &Id, is_local: bool) -> miette::Result<TaskOptions> {
-        let mut options = TaskOptions {
-            cache: !is_local,
+    fn build_task_options(
+        &self,
+        target: &Target,
+        task_configs: &[&TaskConfig],
+        is_local: bool,
+        platform: &PlatformType,
+    ) -> miette::Result<TaskOptions> {
+        // Dev servers never exit, so should never be cached
+        let is_serve = is_serve_type(task_configs);
+
+        let implied_options = TaskOptionsConfig {
+            cache: (is_local || is_serve).then_some(false),
             output_style: is_local.then_some(TaskOutputStyle::Stream),
-            persistent: is_local,
-            run_in_ci: !is_local,
-            ..TaskOptions::default()
+            persistent: (is_local || is_serve).then_some(true),
+            run_in_ci: is_local.then_some(false),
+            ..TaskOptionsConfig::default()
         };
 
+        let mut options = TaskOptions::default();
         let mut configs = vec![];
 
-        if let Some(config) = self.global_tasks.get(id) {
-            configs.push(&config.options);
+        // Platform defaults have the lowest precedence, and must not
+        // override the options implied by local and serve tasks
+        if let Some(config) = self.platform_options.get(platform) {
+            configs.push(*config);
         }
 
-        if let Some(config) = self.local_tasks.get(id) {
-            configs.push(&config.options);
+        configs.push(&implied_options);
+
+        for task_config in task_configs {
+            // Presets are applied before the task's own options
+            if let Some(preset) = &task_config.options_preset {
+                let Some(preset_config) = self.options_presets.get(preset) else {
+                    return Err(TasksBuilderError::UnknownOptionsPreset {
+                        target: target.id.to_owned(),
+                        preset: preset.to_owned(),
+                    }
+                    .into());
+                };
+
+                configs.push(*preset_config);
+            }
+
+            configs.push(&task_config.options);
         }
 
         for config in configs {
@@ -383,8 +557,28 @@ impl<'proj> TasksBuilder<'proj> {
                 options.cache = *cache;
             }
 
+            if let Some(cache_key_extra) = &config.cache_key_extra {
+                options.cache_key_extra = Some(cache_key_extra.to_owned());
+            }
+
+            if let Some(cache_undeclared_outputs) = &config.cache_undeclared_outputs {
+                options.cache_undeclared_outputs = *cache_undeclared_outputs;
+            }
+
             if let Some(env_file) = &config.env_file {
-                options.env_file = env_file.to_input_path();
+                options.env_files = env_file.to_input_paths();
+            }
+
+            if let Some(env_passthrough) = &config.env_passthrough {
+                options.env_passthrough = env_passthrough.to_owned();
+            }
+
+            if let Some(inherit_env) = &config.inherit_env {
+                options.inherit_env = *inherit_env;
+            }
+
+            if let Some(interactive) = &config.interactive {
+                options.interactive = *interactive;
             }
 
             if let Some(merge_args) = &config.merge_args {
@@ -415,6 +609,10 @@ impl<'proj> TasksBuilder<'proj> {
                 options.persistent = *persistent;
             }
 
+            if let Some(ready_pattern) = &config.ready_pattern {
+                options.ready_pattern = Some(ready_pattern.to_owned());
+            }
+
             if let Some(retry_count) = &config.retry_count {
                 options.retry_count = *retry_count;
             }
@@ -434,6 +632,14 @@ impl<'proj> TasksBuilder<'proj> {
             if let Some(shell) = &config.shell {
                 options.shell = *shell;
             }
+
+            if let Some(strict_env) = &config.strict_env {
+                options.strict_env = *strict_env;
+            }
+
+            if let Some(timeout) = &config.timeout {
+                options.timeout = (*timeout > 0).then_some(*timeout);
+            }
         }
 
         Ok(options)
@@ -463,15 +669,16 @@ impl<'proj> TasksBuilder<'proj> {
         options: &TaskOptions,
     ) -> miette::Result<Vec<InputPath>> {
         let mut global_inputs = self
-            .implicit_inputs
+            .global_inputs
             .iter()
+            .chain(self.implicit_inputs.iter())
             .map(|d| (*d).to_owned())
             .collect::<Vec<_>>();
 
         global_inputs.push(InputPath::WorkspaceGlob(".moon/*.yml".into()));
 
-        if let Some(env_file) = &options.env_file {
-            global_inputs.push(env_file.to_owned());
+        if let Some(env_files) = &options.env_files {
+            global_inputs.extend(env_files.to_owned());
         }
 
         if !global_inputs.is_empty() {
@@ -486,74 +693,205 @@ impl<'proj> TasksBuilder<'proj> {
     }
 
     /// Build environment variables for the task. The precedence is as follows.
-    ///     - 1st - project-level `env`
-    ///     - 2nd - task `env_file` (when enabled)
-    ///     - 3rd - task-level `env`
+    ///     - 1st - workspace-level `env`
+    ///     - 2nd - project-level `env`
+    ///     - 3rd - task `env_file` files (when enabled), in order
+    ///     - 4th - task-level `env`
     fn build_env(
         &self,
         target: &Target,
         options: &TaskOptions,
     ) -> miette::Result<FxHashMap<String, String>> {
-        let mut env = self
-            .project_env
-            .iter()
-            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
-            .collect::<FxHashMap<_, _>>();
+        let mut env = self.interpolate_env(
+            target,
+            &self
+                .workspace_env
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect(),
+            &FxHashMap::default(),
+            options,
+        )?;
 
         if !env.is_empty() {
             trace!(
                 target = target.as_str(),
-                env_vars = ?self.project_env,
-                "Inheriting project env vars",
+                env_vars = ?self.workspace_env,
+                "Inheriting workspace env vars",
             );
         }
 
-        if let Some(env_file) = &options.env_file {
-            let env_path = env_file
-                .to_workspace_relative(self.project_source)
-                .to_path(self.workspace_root);
+        let project_env = self.interpolate_env(
+            target,
+            &self
+                .project_env
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect(),
+            &env,
+            options,
+        )?;
+
+        env.extend(project_env);
 
+        if !self.project_env.is_empty() {
             trace!(
                 target = target.as_str(),
-                env_file = ?env_path,
-                "Loading env vars from dotfile",
+                env_vars = ?self.project_env,
+                "Inheriting project env vars",
             );
+        }
 
-            // The `.env` file may not have been committed, so avoid crashing
-            if env_path.exists() {
-                let env_file_vars = dotenvy::from_path_iter(&env_path)
-                    .map_err(|error| TasksBuilderError::InvalidEnvFile {
-                        path: env_path.to_path_buf(),
-                        error,
-                    })?
-                    .flatten()
-                    .collect::<FxHashMap<_, _>>();
-
-                env = self.merge_map(env, env_file_vars, options.merge_env);
-            } else {
-                warn!(
+        if let Some(env_files) = &options.env_files {
+            let mut env_files_vars = FxHashMap::default();
+            let mut loaded = false;
+
+            // Later files take precedence over earlier files
+            for env_file in env_files {
+                let env_path = env_file
+                    .to_workspace_relative(self.project_source)
+                    .to_path(self.workspace_root);
+
+                trace!(
                     target = target.as_str(),
                     env_file = ?env_path,
-                    "The {} option is enabled but file doesn't exist, skipping as this may be intentional",
-                    color::id("envFile"),
+                    "Loading env vars from dotfile",
                 );
+
+                // The `.env` file may not have been committed, so avoid crashing
+                if env_path.exists() {
+                    let env_file_vars = read_env_file(&env_path).map_err(|error| {
+                        TasksBuilderError::InvalidEnvFile {
+                            path: env_path.to_path_buf(),
+                            error,
+                        }
+                    })?;
+
+                    env_files_vars.extend(env_file_vars);
+                    loaded = true;
+                } else {
+                    warn!(
+                        target = target.as_str(),
+                        env_file = ?env_path,
+                        "The {} option is enabled but file doesn't exist, skipping as this may be intentional",
+                        color::id("envFile"),
+                    );
+                }
+            }
+
+            if loaded {
+                env = self.merge_map(env, env_files_vars, options.merge_env);
             }
         }
 
         Ok(env)
     }
 
+    /// Interpolate variable references within configured `env` values, against the
+    /// inherited env. Values loaded from env files are not interpolated, as dotenv
+    /// substitution has already been applied when the file was parsed.
+    fn interpolate_env(
+        &self,
+        target: &Target,
+        env: &FxHashMap<String, String>,
+        inherited_env: &FxHashMap<String, String>,
+        options: &TaskOptions,
+    ) -> miette::Result<FxHashMap<String, String>> {
+        interpolate_env(env, inherited_env, options.strict_env).map_err(|var| {
+            TasksBuilderError::UndefinedEnvVar {
+                target: target.to_string(),
+                var,
+            }
+            .into()
+        })
+    }
+
+    /// Gather the global and local configs for a task. If the task extends another
+    /// task, that task's configs are gathered first, so that the task's own configs
+    /// take precedence when merged.
+    fn get_task_configs(
+        &self,
+        id: &Id,
+        chain: &mut Vec<Id>,
+    ) -> miette::Result<Vec<&'proj TaskConfig>> {
+        let mut own_configs = vec![];
+
+        if let Some(config) = self.global_tasks.get(id) {
+            own_configs.push(*config);
+        }
+
+        if let Some(config) = self.local_tasks.get(id) {
+            own_configs.push(*config);
+        }
+
+        chain.push(id.to_owned());
+
+        let mut configs = vec![];
+
+        // A local extends takes precedence over a global extends
+        if let Some(extends) = own_configs
+            .iter()
+            .rev()
+            .find_map(|config| config.extends.as_ref())
+        {
+            let extends_id = &extends.task_id;
+            let target = Target::new(self.project_id, id)?;
+
+            if chain.contains(extends_id) {
+                chain.push(extends_id.to_owned());
+
+                return Err(TasksBuilderError::CircularExtends {
+                    target: target.id,
+                    chain: chain
+                        .iter()
+                        .map(|id| id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" → "),
+                }
+                .into());
+            }
+
+            if !self.task_ids.contains(extends_id) {
+                return Err(TasksBuilderError::UnknownExtendsTask {
+                    target: target.id,
+                    extends: extends_id.to_owned(),
+                }
+                .into());
+            }
+
+            trace!(
+                target = target.as_str(),
+                extends = extends_id.as_str(),
+                "Extending task",
+            );
+
+            configs.extend(self.get_task_configs(extends_id, chain)?);
+        }
+
+        configs.extend(own_configs);
+
+        Ok(configs)
+    }
+
     fn get_command_and_args(
         &self,
         config: &TaskConfig,
-    ) -> miette::Result<(Option<String>, Vec<String>)> {
+    ) -> miette::Result<(Option<String>, TaskArgsSet)> {
         let mut command = None;
         let mut args = vec![];
 
-        let mut cmd_list = match &config.command {
+        // An environment specific command takes precedence over the base command
+        let base_command = self
+            .environment
+            .as_ref()
+            .and_then(|env| config.command_by_env.get(env))
+            .unwrap_or(&config.command);
+
+        let mut cmd_list = match base_command {
             TaskCommandArgs::None => vec![],
             TaskCommandArgs::String(cmd_string) => split_args(cmd_string)?,
             TaskCommandArgs::List(cmd_args) => cmd_args.to_owned(),
+            TaskCommandArgs::Map(cmd_map) => flatten_args_map(cmd_map),
         };
 
         if !cmd_list.is_empty() {
@@ -565,9 +903,38 @@ impl<'proj> TasksBuilder<'proj> {
             TaskCommandArgs::None => {}
             TaskCommandArgs::String(args_string) => args.extend(split_args(args_string)?),
             TaskCommandArgs::List(args_list) => args.extend(args_list.to_owned()),
+            TaskCommandArgs::Map(args_map) => {
+                // Keep named args as a map, so they can be merged by key
+                if args.is_empty() {
+                    return Ok((command, TaskArgsSet::Named(args_map.to_owned())));
+                }
+
+                args.extend(flatten_args_map(args_map));
+            }
         };
 
-        Ok((command, args))
+        Ok((command, TaskArgsSet::List(args)))
+    }
+
+    fn merge_args_map(
+        &self,
+        base: BTreeMap<String, String>,
+        next: BTreeMap<String, String>,
+        strategy: TaskMergeStrategy,
+    ) -> BTreeMap<String, String> {
+        match strategy {
+            TaskMergeStrategy::Append => {
+                let mut map = base;
+                map.extend(next);
+                map
+            }
+            TaskMergeStrategy::Prepend => {
+                let mut map = next;
+                map.extend(base);
+                map
+            }
+            TaskMergeStrategy::Replace => next,
+        }
     }
 
     fn merge_map<K, V>(
@@ -633,3 +1000,62 @@ impl<'proj> TasksBuilder<'proj> {
         list
     }
 }
+
+// Args of a single task config, before they are merged
+enum TaskArgsSet {
+    List(Vec<String>),
+    Named(BTreeMap<String, String>),
+}
+
+// Only the serve type is configured explicitly, other types are inferred
+fn is_serve_type(configs: &[&TaskConfig]) -> bool {
+    configs
+        .iter()
+        .rev()
+        .find_map(|config| config.type_of)
+        .is_some_and(|type_of| matches!(type_of, TaskType::Serve))
+}
+
+// Flatten a map of named args into `--key value` pairs. Keys that are already
+// prefixed (`-v`) are kept as-is, and empty values only pass the flag.
+fn flatten_args_map(map: &BTreeMap<String, String>) -> Vec<String> {
+    let mut args = vec![];
+
+    for (key, value) in map {
+        if key.starts_with('-') {
+            args.push(key.to_owned());
+        } else {
+            args.push(format!("--{key}"));
+        }
+
+        if !value.is_empty() {
+            args.push(value.to_owned());
+        }
+    }
+
+    args
+}
+
+/// Read and parse an env file, transparently decompressing it when it has
+/// a `.gz` extension, or its contents start with the gzip magic bytes.
+fn read_env_file(path: &Path) -> Result<FxHashMap<String, String>, dotenvy::Error> {
+    let bytes = std::fs::read(path).map_err(dotenvy::Error::Io)?;
+
+    let contents = if path.extension().is_some_and(|ext| ext == "gz")
+        || bytes.starts_with(&GZIP_MAGIC_BYTES)
+    {
+        let mut decoded = vec![];
+
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decoded)
+            .map_err(dotenvy::Error::Io)?;
+
+        decoded
+    } else {
+        bytes
+    };
+
+    Ok(dotenvy::from_read_iter(contents.as_slice())
+        .flatten()
+        .collect())
+}
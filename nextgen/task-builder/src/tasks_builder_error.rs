@@ -1,10 +1,18 @@
 use miette::Diagnostic;
-use moon_common::{Style, Stylize};
+use moon_common::{Id, Style, Stylize};
 use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug, Diagnostic)]
 pub enum TasksBuilderError {
+    #[diagnostic(code(task_builder::circular_extends))]
+    #[error(
+        "Task {} has a circular extends chain: {}",
+        .target.style(Style::Label),
+        .chain,
+    )]
+    CircularExtends { target: String, chain: String },
+
     #[diagnostic(code(task_builder::invalid_env_file))]
     #[error("Failed to parse env file {}.", .path.style(Style::Path))]
     InvalidEnvFile {
@@ -12,4 +20,38 @@ pub enum TasksBuilderError {
         #[source]
         error: dotenvy::Error,
     },
+
+    #[diagnostic(code(task_builder::undefined_env_var))]
+    #[error(
+        "Task {} references the undefined environment variable {} in its {} setting. Define the variable, or disable the {} option.",
+        .target.style(Style::Label),
+        .var.style(Style::Symbol),
+        "env".style(Style::Symbol),
+        "options.strictEnv".style(Style::Symbol),
+    )]
+    UndefinedEnvVar { target: String, var: String },
+
+    #[diagnostic(code(task_builder::unknown_extends))]
+    #[error(
+        "Task {} extends the task {}, but it does not exist in this project.",
+        .target.style(Style::Label),
+        .extends.style(Style::Id),
+    )]
+    UnknownExtendsTask { target: String, extends: Id },
+
+    #[diagnostic(code(task_builder::unknown_options_preset))]
+    #[error(
+        "Task {} references the options preset {}, but it has not been defined.",
+        .target.style(Style::Label),
+        .preset.style(Style::Id),
+    )]
+    UnknownOptionsPreset { target: String, preset: Id },
+
+    #[diagnostic(code(task_builder::unknown_toolchain))]
+    #[error(
+        "Task {} requires the {} toolchain, but it has not been configured.",
+        .target.style(Style::Label),
+        .toolchain.style(Style::Id),
+    )]
+    UnknownToolchain { target: String, toolchain: Id },
 }
@@ -0,0 +1,200 @@
+use moon_task_builder::env_interpolator::{interpolate_env, interpolate_value};
+use rustc_hash::FxHashMap;
+use std::env;
+
+fn create_env(pairs: &[(&str, &str)]) -> FxHashMap<String, String> {
+    FxHashMap::from_iter(
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned())),
+    )
+}
+
+fn lookup(name: &str) -> Result<String, String> {
+    Ok(format!("<{name}>"))
+}
+
+mod interpolate_value {
+    use super::*;
+
+    #[test]
+    fn returns_value_as_is() {
+        assert_eq!(interpolate_value("foo bar", lookup).unwrap(), "foo bar");
+    }
+
+    #[test]
+    fn substitutes_bare_and_braced() {
+        assert_eq!(
+            interpolate_value("$FOO:${BAR}/bin", lookup).unwrap(),
+            "<FOO>:<BAR>/bin"
+        );
+    }
+
+    #[test]
+    fn supports_escapes() {
+        assert_eq!(
+            interpolate_value("$$FOO $${BAR} $$$BAZ", lookup).unwrap(),
+            "$FOO ${BAR} $<BAZ>"
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_references() {
+        assert_eq!(
+            interpolate_value("$ $1 ${} ${FOO $-", lookup).unwrap(),
+            "$ $1 ${} ${FOO $-"
+        );
+    }
+
+    #[test]
+    fn returns_lookup_errors() {
+        assert_eq!(
+            interpolate_value("$FOO", |name| Err(name.to_owned())).unwrap_err(),
+            "FOO"
+        );
+    }
+}
+
+mod interpolate_env {
+    use super::*;
+
+    #[test]
+    fn resolves_other_keys() {
+        let result = interpolate_env(
+            &create_env(&[("BASE", "base"), ("SUB", "$BASE-sub")]),
+            &FxHashMap::default(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result, create_env(&[("BASE", "base"), ("SUB", "base-sub")]));
+    }
+
+    #[test]
+    fn resolves_nested_keys() {
+        let result = interpolate_env(
+            &create_env(&[("A", "${B}/a"), ("B", "${C}/b"), ("C", "c")]),
+            &FxHashMap::default(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            create_env(&[("A", "c/b/a"), ("B", "c/b"), ("C", "c")])
+        );
+    }
+
+    #[test]
+    fn resolves_self_from_process_env() {
+        env::set_var("MOON_TEST_INTERPOLATE_SELF", "/usr/bin");
+
+        let result = interpolate_env(
+            &create_env(&[(
+                "MOON_TEST_INTERPOLATE_SELF",
+                "$MOON_TEST_INTERPOLATE_SELF:./bin",
+            )]),
+            &FxHashMap::default(),
+            false,
+        )
+        .unwrap();
+
+        env::remove_var("MOON_TEST_INTERPOLATE_SELF");
+
+        assert_eq!(
+            result,
+            create_env(&[("MOON_TEST_INTERPOLATE_SELF", "/usr/bin:./bin")])
+        );
+    }
+
+    #[test]
+    fn resolves_cycles_from_process_env() {
+        let result = interpolate_env(
+            &create_env(&[("A", "${B}a"), ("B", "${A}b")]),
+            &FxHashMap::default(),
+            false,
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn prefers_map_over_process_env() {
+        env::set_var("MOON_TEST_INTERPOLATE_MAP", "process");
+
+        let result = interpolate_env(
+            &create_env(&[
+                ("MOON_TEST_INTERPOLATE_MAP", "map"),
+                ("SUB", "$MOON_TEST_INTERPOLATE_MAP"),
+            ]),
+            &FxHashMap::default(),
+            false,
+        )
+        .unwrap();
+
+        env::remove_var("MOON_TEST_INTERPOLATE_MAP");
+
+        assert_eq!(result.get("SUB").unwrap(), "map");
+    }
+
+    #[test]
+    fn replaces_missing_with_empty() {
+        let result = interpolate_env(
+            &create_env(&[("SUB", "a${MOON_TEST_INTERPOLATE_MISSING}b")]),
+            &FxHashMap::default(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result, create_env(&[("SUB", "ab")]));
+    }
+
+    #[test]
+    fn errors_for_missing_when_strict() {
+        let result = interpolate_env(
+            &create_env(&[
+                ("BASE", "base"),
+                ("SUB", "${BASE}${MOON_TEST_INTERPOLATE_MISSING}"),
+            ]),
+            &FxHashMap::default(),
+            true,
+        );
+
+        assert_eq!(result.unwrap_err(), "MOON_TEST_INTERPOLATE_MISSING");
+    }
+
+    #[test]
+    fn errors_for_nested_missing_when_strict() {
+        let result = interpolate_env(
+            &create_env(&[("A", "$B"), ("B", "$MOON_TEST_INTERPOLATE_MISSING")]),
+            &FxHashMap::default(),
+            true,
+        );
+
+        assert_eq!(result.unwrap_err(), "MOON_TEST_INTERPOLATE_MISSING");
+    }
+
+    #[test]
+    fn resolves_inherited_keys_as_is() {
+        let result = interpolate_env(
+            &create_env(&[("SUB", "$BASE-sub")]),
+            &create_env(&[("BASE", "${OTHER}base"), ("OTHER", "other")]),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result, create_env(&[("SUB", "${OTHER}base-sub")]));
+    }
+
+    #[test]
+    fn resolves_self_from_inherited() {
+        let result = interpolate_env(
+            &create_env(&[("PATH", "$PATH:./bin")]),
+            &create_env(&[("PATH", "/usr/bin")]),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result, create_env(&[("PATH", "/usr/bin:./bin")]));
+    }
+}
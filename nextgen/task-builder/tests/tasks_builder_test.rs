@@ -1,24 +1,29 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use moon_common::Id;
 use moon_config::{
-    DenoConfig, InheritedTasksManager, InputPath, NodeConfig, OutputPath, PlatformType,
+    BunConfig, DenoConfig, InheritedTasksManager, InputPath, NodeConfig, OutputPath, PlatformType,
     ProjectConfig, ProjectWorkspaceConfig, ProjectWorkspaceInheritedTasksConfig, RustConfig,
-    TaskOptionAffectedFiles, TaskOutputStyle, TaskType, ToolchainConfig,
+    TaskArtifactConfig, TaskArtifactKind, TaskOptionAffectedFiles, TaskOptionsConfig,
+    TaskOutputStyle, TaskType, ToolchainConfig, TypeScriptConfig,
 };
 use moon_platform_detector::detect_task_platform;
 use moon_target::Target;
 use moon_task::Task;
-use moon_task_builder::TasksBuilder;
+use moon_task_builder::{TasksBuilder, TasksBuilderError};
 use rustc_hash::FxHashMap;
 use starbase_sandbox::create_sandbox;
 use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
 use std::path::Path;
 
-fn build_tasks_with_config(
+fn try_build_tasks_with_config(
     root: &Path,
     source: &str,
     local_config: ProjectConfig,
     toolchain_config: ToolchainConfig,
-) -> BTreeMap<Id, Task> {
+) -> miette::Result<BTreeMap<Id, Task>> {
     let platform = local_config.platform.unwrap_or_default();
 
     let mut builder = TasksBuilder::new("project", source, &platform, root);
@@ -43,7 +48,16 @@ fn build_tasks_with_config(
 
     builder.detect_platform(detect_task_platform, &toolchain_config);
 
-    builder.build().unwrap()
+    builder.build()
+}
+
+fn build_tasks_with_config(
+    root: &Path,
+    source: &str,
+    local_config: ProjectConfig,
+    toolchain_config: ToolchainConfig,
+) -> BTreeMap<Id, Task> {
+    try_build_tasks_with_config(root, source, local_config, toolchain_config).unwrap()
 }
 
 fn build_tasks(root: &Path, config_path: &str) -> BTreeMap<Id, Task> {
@@ -222,6 +236,94 @@ mod tasks_builder {
         }
     }
 
+    mod artifacts {
+        use super::*;
+
+        #[test]
+        fn stores_artifacts_without_affecting_execution() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "artifacts/moon.yml");
+            let task = tasks.get("release").unwrap();
+
+            assert_eq!(
+                task.artifacts,
+                vec![TaskArtifactConfig {
+                    name: "app".into(),
+                    kind: TaskArtifactKind::Binary,
+                    path: "target/release/app".into(),
+                }]
+            );
+            assert_eq!(
+                task.outputs,
+                vec![OutputPath::ProjectFile("target/release/app".into())]
+            );
+        }
+
+        #[test]
+        fn replaces_when_extending() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "artifacts/moon.yml");
+            let task = tasks.get("release-override").unwrap();
+
+            assert_eq!(
+                task.artifacts,
+                vec![TaskArtifactConfig {
+                    name: "bundle".into(),
+                    kind: TaskArtifactKind::Archive,
+                    path: "dist/*.tar.gz".into(),
+                }]
+            );
+        }
+
+        #[test]
+        fn defaults_to_empty() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "artifacts/moon.yml");
+
+            assert!(tasks.get("no-artifacts").unwrap().artifacts.is_empty());
+        }
+    }
+
+    mod serve_type {
+        use super::*;
+
+        #[test]
+        fn implies_persistent_and_no_cache() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "serve/moon.yml");
+            let task = tasks.get("server").unwrap();
+
+            assert_eq!(task.type_of, TaskType::Serve);
+            assert!(task.is_serve_type());
+            assert!(!task.is_run_type());
+            assert!(task.options.persistent);
+            assert!(!task.options.cache);
+            assert!(!task.should_run_in_ci());
+        }
+
+        #[test]
+        fn can_override_implied_options() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "serve/moon.yml");
+            let task = tasks.get("server-with-options").unwrap();
+
+            assert_eq!(task.type_of, TaskType::Serve);
+            assert!(!task.options.persistent);
+            assert!(task.options.cache);
+        }
+
+        #[test]
+        fn doesnt_affect_other_types() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "serve/moon.yml");
+            let task = tasks.get("runner").unwrap();
+
+            assert_eq!(task.type_of, TaskType::Test);
+            assert!(!task.options.persistent);
+            assert!(task.options.cache);
+        }
+    }
+
     mod command_args {
         use super::*;
 
@@ -315,6 +417,16 @@ mod tasks_builder {
             assert_eq!(task.args, vec!["-qux", "--foo", "bar"]);
         }
 
+        #[test]
+        fn both_map() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "commands/moon.yml");
+            let task = tasks.get("both-map").unwrap();
+
+            assert_eq!(task.command, "bin");
+            assert_eq!(task.args, vec!["-q", "--baz", "qux", "--foo", "bar"]);
+        }
+
         #[test]
         fn override_global_command() {
             let sandbox = create_sandbox("builder");
@@ -334,6 +446,52 @@ mod tasks_builder {
             assert_eq!(task.command, "global-test");
             assert_eq!(task.args, vec!["--with", "args", "extra", "args"]);
         }
+
+        fn build_tasks_with_environment(root: &Path, environment: &str) -> BTreeMap<Id, Task> {
+            let local_config = ProjectConfig::load(root, root.join("commands/moon.yml")).unwrap();
+            let platform = PlatformType::default();
+
+            let mut builder = TasksBuilder::new("project", "commands", &platform, root);
+            builder.load_local_tasks(&local_config);
+            builder.set_environment(environment);
+            builder.build().unwrap()
+        }
+
+        #[test]
+        fn selects_command_by_environment() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks_with_environment(sandbox.path(), "production");
+            let task = tasks.get("command-env").unwrap();
+
+            assert_eq!(task.command, "bin");
+            assert_eq!(task.args, vec!["--optimize", "--foo"]);
+
+            let tasks = build_tasks_with_environment(sandbox.path(), "staging");
+            let task = tasks.get("command-env").unwrap();
+
+            assert_eq!(task.command, "staging-bin");
+            assert_eq!(task.args, vec!["--fast", "--foo"]);
+        }
+
+        #[test]
+        fn fallsback_to_base_command_for_unknown_environment() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks_with_environment(sandbox.path(), "development");
+            let task = tasks.get("command-env").unwrap();
+
+            assert_eq!(task.command, "bin");
+            assert_eq!(task.args, vec!["--debug", "--foo"]);
+        }
+
+        #[test]
+        fn uses_base_command_without_environment() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "commands/moon.yml");
+            let task = tasks.get("command-env").unwrap();
+
+            assert_eq!(task.command, "bin");
+            assert_eq!(task.args, vec!["--debug", "--foo"]);
+        }
     }
 
     mod detect_platforms {
@@ -414,6 +572,214 @@ mod tasks_builder {
         }
     }
 
+    mod platform_options {
+        use super::*;
+
+        #[test]
+        fn applies_platform_defaults_with_lowest_precedence() {
+            let sandbox = create_sandbox("builder");
+            let root = sandbox.path();
+            let local_config = ProjectConfig::load(root, root.join("platforms/moon.yml")).unwrap();
+            let platform = local_config.platform.unwrap_or_default();
+            let system_options = TaskOptionsConfig {
+                cache: Some(false),
+                ..TaskOptionsConfig::default()
+            };
+            let node_options = TaskOptionsConfig {
+                retry_count: Some(2),
+                ..TaskOptionsConfig::default()
+            };
+
+            let mut builder = TasksBuilder::new("project", "platforms", &platform, root);
+            builder.inherit_platform_options(PlatformType::System, &system_options);
+            builder.inherit_platform_options(PlatformType::Node, &node_options);
+            builder.load_local_tasks(&local_config);
+
+            let tasks = builder.build().unwrap();
+
+            let task = tasks.get("system").unwrap();
+
+            assert_eq!(task.platform, PlatformType::System);
+            assert!(!task.options.cache);
+
+            let task = tasks.get("node").unwrap();
+
+            assert_eq!(task.platform, PlatformType::Node);
+            assert!(task.options.cache);
+            assert_eq!(task.options.retry_count, 2);
+
+            let task = tasks.get("unknown").unwrap();
+
+            assert_eq!(task.platform, PlatformType::Rust);
+            assert!(task.options.cache);
+        }
+
+        #[test]
+        fn doesnt_override_implied_local_options() {
+            let sandbox = create_sandbox("builder");
+            let root = sandbox.path();
+            let local_config = ProjectConfig::load(root, root.join("platforms/moon.yml")).unwrap();
+            let platform = local_config.platform.unwrap_or_default();
+            let rust_options = TaskOptionsConfig {
+                cache: Some(true),
+                ..TaskOptionsConfig::default()
+            };
+
+            let mut builder = TasksBuilder::new("project", "platforms", &platform, root);
+            builder.inherit_platform_options(PlatformType::Rust, &rust_options);
+            builder.load_local_tasks(&local_config);
+
+            let tasks = builder.build().unwrap();
+
+            let task = tasks.get("rust-local").unwrap();
+
+            assert_eq!(task.platform, PlatformType::Rust);
+            assert!(!task.options.cache);
+            assert!(task.options.persistent);
+
+            let task = tasks.get("rust-via-cmd").unwrap();
+
+            assert_eq!(task.platform, PlatformType::Rust);
+            assert!(task.options.cache);
+        }
+    }
+
+    mod timeout {
+        use super::*;
+
+        #[test]
+        fn sets_timeout_and_disables_with_zero() {
+            let sandbox = create_sandbox("builder");
+            let root = sandbox.path();
+            let local_config = ProjectConfig::load(root, root.join("platforms/moon.yml")).unwrap();
+            let platform = local_config.platform.unwrap_or_default();
+            let system_options = TaskOptionsConfig {
+                timeout: Some(0),
+                ..TaskOptionsConfig::default()
+            };
+            let node_options = TaskOptionsConfig {
+                timeout: Some(300),
+                ..TaskOptionsConfig::default()
+            };
+
+            let mut builder = TasksBuilder::new("project", "platforms", &platform, root);
+            builder.inherit_platform_options(PlatformType::System, &system_options);
+            builder.inherit_platform_options(PlatformType::Node, &node_options);
+            builder.load_local_tasks(&local_config);
+
+            let tasks = builder.build().unwrap();
+
+            assert_eq!(tasks.get("system").unwrap().options.timeout, None);
+            assert_eq!(tasks.get("node").unwrap().options.timeout, Some(300));
+            assert_eq!(tasks.get("unknown").unwrap().options.timeout, None);
+        }
+    }
+
+    mod toolchains {
+        use super::*;
+
+        #[test]
+        fn uses_explicitly_configured() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks_with_toolchain(sandbox.path(), "toolchains/moon.yml");
+
+            let task = tasks.get("explicit").unwrap();
+
+            assert_eq!(task.platform, PlatformType::System);
+            assert_eq!(task.toolchain, Some(Id::raw("node")));
+        }
+
+        #[test]
+        fn derives_from_platform() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks_with_toolchain(sandbox.path(), "toolchains/moon.yml");
+
+            assert_eq!(
+                tasks.get("derived").unwrap().toolchain,
+                Some(Id::raw("rust"))
+            );
+            assert_eq!(tasks.get("system").unwrap().toolchain, None);
+        }
+
+        #[test]
+        fn supports_toolchains_without_a_platform() {
+            let sandbox = create_sandbox("builder");
+            let tasks = try_build_tasks_with_config(
+                sandbox.path(),
+                "toolchains-other",
+                ProjectConfig::load(
+                    sandbox.path(),
+                    sandbox.path().join("toolchains-other/moon.yml"),
+                )
+                .unwrap(),
+                ToolchainConfig {
+                    bun: Some(BunConfig::default()),
+                    typescript: Some(TypeScriptConfig::default()),
+                    ..ToolchainConfig::default()
+                },
+            )
+            .unwrap();
+
+            let task = tasks.get("bun").unwrap();
+
+            assert_eq!(task.toolchain, Some(Id::raw("bun")));
+            assert_eq!(task.get_runtime_platform(), PlatformType::System);
+
+            let task = tasks.get("typescript").unwrap();
+
+            assert_eq!(task.toolchain, Some(Id::raw("typescript")));
+            assert_eq!(task.get_runtime_platform(), PlatformType::Node);
+        }
+
+        #[test]
+        fn errors_if_toolchain_not_enabled() {
+            let sandbox = create_sandbox("builder");
+            let error = try_build_tasks_with_config(
+                sandbox.path(),
+                "toolchains",
+                ProjectConfig::load(sandbox.path(), sandbox.path().join("toolchains/moon.yml"))
+                    .unwrap(),
+                ToolchainConfig::default(),
+            )
+            .unwrap_err();
+
+            match error.downcast_ref::<TasksBuilderError>() {
+                Some(TasksBuilderError::UnknownToolchain { target, toolchain }) => {
+                    assert_eq!(target, "project:explicit");
+                    assert_eq!(toolchain, &Id::raw("node"));
+                }
+                _ => panic!("expected an unknown toolchain error, got {error}"),
+            };
+        }
+
+        #[test]
+        fn errors_for_unknown_toolchain() {
+            let sandbox = create_sandbox("builder");
+            let error = try_build_tasks_with_config(
+                sandbox.path(),
+                "toolchains-unknown",
+                ProjectConfig::load(
+                    sandbox.path(),
+                    sandbox.path().join("toolchains-unknown/moon.yml"),
+                )
+                .unwrap(),
+                ToolchainConfig {
+                    node: Some(NodeConfig::default()),
+                    ..ToolchainConfig::default()
+                },
+            )
+            .unwrap_err();
+
+            match error.downcast_ref::<TasksBuilderError>() {
+                Some(TasksBuilderError::UnknownToolchain { target, toolchain }) => {
+                    assert_eq!(target, "project:unknown");
+                    assert_eq!(toolchain, &Id::raw("python"));
+                }
+                _ => panic!("expected an unknown toolchain error, got {error}"),
+            };
+        }
+    }
+
     mod special_options {
         use super::*;
 
@@ -451,6 +817,15 @@ mod tasks_builder {
             );
         }
 
+        #[test]
+        fn strict_env() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "options/moon.yml");
+
+            assert!(tasks.get("strict-env").unwrap().options.strict_env);
+            assert!(!tasks.get("env-file").unwrap().options.strict_env);
+        }
+
         #[test]
         fn env_file() {
             let sandbox = create_sandbox("builder");
@@ -459,26 +834,36 @@ mod tasks_builder {
             let task = tasks.get("env-file").unwrap();
 
             assert_eq!(
-                task.options.env_file,
-                Some(InputPath::ProjectFile(".env".into()))
+                task.options.env_files,
+                Some(vec![InputPath::ProjectFile(".env".into())])
             );
 
             let task = tasks.get("no-env-file").unwrap();
 
-            assert_eq!(task.options.env_file, None);
+            assert_eq!(task.options.env_files, None);
 
             let task = tasks.get("env-file-project").unwrap();
 
             assert_eq!(
-                task.options.env_file,
-                Some(InputPath::ProjectFile(".env.test".into()))
+                task.options.env_files,
+                Some(vec![InputPath::ProjectFile(".env.test".into())])
             );
 
             let task = tasks.get("env-file-workspace").unwrap();
 
             assert_eq!(
-                task.options.env_file,
-                Some(InputPath::WorkspaceFile(".env.shared".into()))
+                task.options.env_files,
+                Some(vec![InputPath::WorkspaceFile(".env.shared".into())])
+            );
+
+            let task = tasks.get("env-files").unwrap();
+
+            assert_eq!(
+                task.options.env_files,
+                Some(vec![
+                    InputPath::ProjectFile(".env".into()),
+                    InputPath::WorkspaceFile(".env.shared".into()),
+                ])
             );
         }
 
@@ -506,6 +891,13 @@ mod tasks_builder {
             assert!(task
                 .inputs
                 .contains(&InputPath::WorkspaceFile(".env.shared".into())));
+
+            let task = tasks.get("env-files").unwrap();
+
+            assert!(task.inputs.contains(&InputPath::ProjectFile(".env".into())));
+            assert!(task
+                .inputs
+                .contains(&InputPath::WorkspaceFile(".env.shared".into())));
         }
     }
 
@@ -660,6 +1052,10 @@ mod tasks_builder {
 
             assert_eq!(task.args, vec!["a", "b", "c", "x", "y", "z"]);
 
+            let task = tasks.get("named-args").unwrap();
+
+            assert_eq!(task.args, vec!["--bar", "b", "--baz", "--foo", "c"]);
+
             let task = tasks.get("deps").unwrap();
 
             assert_eq!(
@@ -713,6 +1109,10 @@ mod tasks_builder {
 
             assert_eq!(task.args, vec!["x", "y", "z", "a", "b", "c"]);
 
+            let task = tasks.get("named-args").unwrap();
+
+            assert_eq!(task.args, vec!["--bar", "b", "--baz", "--foo", "a"]);
+
             let task = tasks.get("deps").unwrap();
 
             assert_eq!(
@@ -766,6 +1166,10 @@ mod tasks_builder {
 
             assert_eq!(task.args, vec!["x", "y", "z"]);
 
+            let task = tasks.get("named-args").unwrap();
+
+            assert_eq!(task.args, vec!["--baz", "--foo", "c"]);
+
             let task = tasks.get("deps").unwrap();
 
             assert_eq!(task.deps, vec![Target::parse("local:build").unwrap(),]);
@@ -838,6 +1242,154 @@ mod tasks_builder {
         }
     }
 
+    mod extends {
+        use super::*;
+
+        #[test]
+        fn inherits_command_when_only_args_set() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "extends/moon.yml");
+
+            let task = tasks.get("child-args").unwrap();
+
+            assert_eq!(task.command, "lint");
+            assert_eq!(task.args, vec!["--cache", "--fix"]);
+        }
+
+        #[test]
+        fn inherits_inputs_env_and_options() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "extends/moon.yml");
+
+            let task = tasks.get("child-args").unwrap();
+
+            assert_eq!(
+                task.inputs,
+                vec![
+                    InputPath::ProjectGlob("src/**/*".into()),
+                    InputPath::WorkspaceGlob(".moon/*.yml".into()),
+                ]
+            );
+            assert_eq!(
+                task.env,
+                FxHashMap::from_iter([("KEY".into(), "value".into())])
+            );
+            assert!(!task.options.run_in_ci);
+            assert_eq!(task.options.retry_count, 2);
+        }
+
+        #[test]
+        fn can_override_with_merge_strategies() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "extends/moon.yml");
+
+            let task = tasks.get("child-override").unwrap();
+
+            assert_eq!(task.command, "lint-other");
+            assert_eq!(
+                task.inputs,
+                vec![
+                    InputPath::ProjectGlob("tests/**/*".into()),
+                    InputPath::WorkspaceGlob(".moon/*.yml".into()),
+                ]
+            );
+            assert_eq!(task.options.retry_count, 5);
+        }
+
+        #[test]
+        fn inherits_through_chains() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "extends/moon.yml");
+
+            let task = tasks.get("grandchild").unwrap();
+
+            assert_eq!(task.command, "lint");
+            assert_eq!(task.args, vec!["--cache", "--fix"]);
+            assert_eq!(
+                task.env,
+                FxHashMap::from_iter([
+                    ("KEY".into(), "value".into()),
+                    ("OTHER".into(), "value".into())
+                ])
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "has a circular extends chain")]
+        fn errors_for_cycles() {
+            let sandbox = create_sandbox("builder");
+
+            build_tasks(sandbox.path(), "extends-circular/moon.yml");
+        }
+
+        #[test]
+        #[should_panic(expected = "but it does not exist in this project")]
+        fn errors_for_unknown_task() {
+            let sandbox = create_sandbox("builder");
+
+            build_tasks(sandbox.path(), "extends-unknown/moon.yml");
+        }
+    }
+
+    mod options_presets {
+        use super::*;
+
+        #[test]
+        fn applies_preset_to_tasks() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "options-presets/moon.yml");
+
+            let task = tasks.get("dev").unwrap();
+
+            assert!(!task.options.cache);
+            assert!(task.options.persistent);
+            assert_eq!(task.options.output_style, Some(TaskOutputStyle::Stream));
+        }
+
+        #[test]
+        fn task_options_override_preset() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "options-presets/moon.yml");
+
+            let task = tasks.get("serve").unwrap();
+
+            assert!(!task.options.cache);
+            assert!(task.options.persistent);
+            assert_eq!(task.options.output_style, Some(TaskOutputStyle::Buffer));
+        }
+
+        #[test]
+        fn local_preset_overrides_global_preset() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "options-presets/moon.yml");
+
+            let task = tasks.get("lint").unwrap();
+
+            assert_eq!(task.options.retry_count, 3);
+            assert!(task.options.run_in_ci);
+        }
+
+        #[test]
+        fn doesnt_apply_without_preset() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "options-presets/moon.yml");
+
+            let task = tasks.get("build").unwrap();
+
+            assert!(task.options.cache);
+            assert!(!task.options.persistent);
+            assert_eq!(task.options.output_style, None);
+        }
+
+        #[test]
+        #[should_panic(expected = "references the options preset")]
+        fn errors_for_unknown_preset() {
+            let sandbox = create_sandbox("builder");
+
+            build_tasks(sandbox.path(), "options-presets-unknown/moon.yml");
+        }
+    }
+
     mod workspace_overrides {
         use super::*;
 
@@ -1020,6 +1572,49 @@ mod tasks_builder {
             assert!(!task.flags.empty_inputs);
         }
 
+        #[test]
+        fn inherits_global_inputs_for_every_task() {
+            let sandbox = create_sandbox("builder");
+            let root = sandbox.path();
+            let local_config = ProjectConfig::load(root, root.join("implicits/moon.yml")).unwrap();
+            let platform = local_config.platform.unwrap_or_default();
+            let global_inputs = vec![
+                InputPath::WorkspaceFile(".moon/toolchain.yml".into()),
+                InputPath::WorkspaceFile("yarn.lock".into()),
+            ];
+
+            let mut builder = TasksBuilder::new("project", "implicits", &platform, root);
+            builder.inherit_global_inputs(&global_inputs);
+            builder.load_local_tasks(&local_config);
+
+            let tasks = builder.build().unwrap();
+
+            assert!(!tasks.is_empty());
+
+            for task in tasks.values() {
+                for input in &global_inputs {
+                    assert!(
+                        task.inputs.contains(input),
+                        "{} is missing input {}",
+                        task.target,
+                        input.as_str()
+                    );
+                }
+            }
+
+            // Explicitly empty inputs still inherit global inputs
+            let task = tasks.get("empty-inputs").unwrap();
+
+            assert_eq!(
+                task.inputs,
+                vec![
+                    InputPath::WorkspaceFile(".moon/toolchain.yml".into()),
+                    InputPath::WorkspaceFile("yarn.lock".into()),
+                    InputPath::WorkspaceGlob(".moon/*.yml".into()),
+                ]
+            );
+        }
+
         #[test]
         fn no_deps() {
             let sandbox = create_sandbox("builder");
@@ -1057,6 +1652,47 @@ mod tasks_builder {
     mod env_var_merging {
         use super::*;
 
+        fn build_tasks_with_workspace_env(root: &Path) -> BTreeMap<Id, Task> {
+            let local_config = ProjectConfig::load(root, root.join("env/moon.yml")).unwrap();
+            let platform = local_config.platform.unwrap_or_default();
+            let workspace_env = FxHashMap::from_iter([
+                ("TZ".to_owned(), "UTC".to_owned()),
+                ("SCOPE".to_owned(), "workspace".to_owned()),
+            ]);
+
+            let mut builder = TasksBuilder::new("project", "env", &platform, root);
+            builder.inherit_workspace_env(&workspace_env);
+            builder.load_local_tasks(&local_config);
+            builder.build().unwrap()
+        }
+
+        #[test]
+        fn inherits_workspace_env() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks_with_workspace_env(sandbox.path());
+            let task = tasks.get("no-env").unwrap();
+
+            assert_eq!(
+                task.env,
+                FxHashMap::from_iter([
+                    ("TZ".into(), "UTC".into()),
+                    ("SCOPE".into(), "project".into()),
+                    ("KEY1".into(), "value1".into()),
+                    ("KEY2".into(), "value2".into()),
+                ])
+            );
+        }
+
+        #[test]
+        fn task_env_overrides_workspace_env() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks_with_workspace_env(sandbox.path());
+            let task = tasks.get("with-env").unwrap();
+
+            assert_eq!(task.env.get("TZ").unwrap(), "UTC");
+            assert_eq!(task.env.get("SCOPE").unwrap(), "task");
+        }
+
         #[test]
         fn no_env() {
             let sandbox = create_sandbox("builder");
@@ -1107,6 +1743,93 @@ mod tasks_builder {
             );
         }
 
+        fn write_gzipped_env_file(root: &Path, name: &str) {
+            let mut encoder = GzEncoder::new(vec![], Compression::default());
+            encoder
+                .write_all(b"SCOPE=env-file-gz\nKEY1=gz-value1\nEXTRA=xyz")
+                .unwrap();
+
+            fs::write(root.join("env").join(name), encoder.finish().unwrap()).unwrap();
+        }
+
+        #[test]
+        fn env_files_in_order() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "env/moon.yml");
+            let task = tasks.get("env-files").unwrap();
+
+            assert_eq!(
+                task.env,
+                FxHashMap::from_iter([
+                    ("SCOPE".into(), "env-file-local".into()),
+                    ("KEY1".into(), "file-value1".into()),
+                    ("KEY2".into(), "value2".into()),
+                    ("EXTRA".into(), "abc".into()),
+                    ("LOCAL".into(), "true".into()),
+                ])
+            );
+        }
+
+        #[test]
+        fn env_files_skips_missing() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "env/moon.yml");
+            let task = tasks.get("env-files-missing").unwrap();
+
+            assert_eq!(task.env.get("SCOPE").unwrap(), "env-file");
+        }
+
+        #[test]
+        fn env_file_gzipped() {
+            let sandbox = create_sandbox("builder");
+
+            write_gzipped_env_file(sandbox.path(), ".env.gz");
+
+            let tasks = build_tasks(sandbox.path(), "env/moon.yml");
+            let task = tasks.get("env-file-gz").unwrap();
+
+            assert_eq!(
+                task.env,
+                FxHashMap::from_iter([
+                    ("SCOPE".into(), "env-file-gz".into()),
+                    ("KEY1".into(), "gz-value1".into()),
+                    ("KEY2".into(), "value2".into()),
+                    ("EXTRA".into(), "xyz".into()),
+                ])
+            );
+        }
+
+        #[test]
+        fn env_file_gzipped_without_extension() {
+            let sandbox = create_sandbox("builder");
+
+            write_gzipped_env_file(sandbox.path(), ".env.packed");
+
+            let tasks = build_tasks(sandbox.path(), "env/moon.yml");
+            let task = tasks.get("env-file-gz-magic").unwrap();
+
+            assert_eq!(task.env.get("KEY1").unwrap(), "gz-value1");
+        }
+
+        #[test]
+        fn interpolates_task_env() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "env/moon.yml");
+            let task = tasks.get("interpolated").unwrap();
+
+            assert_eq!(task.env.get("SUB").unwrap(), "value1-sub");
+            assert_eq!(task.env.get("NESTED").unwrap(), "value1-sub-nested");
+        }
+
+        #[test]
+        fn doesnt_interpolate_env_file_values() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "env/moon.yml");
+            let task = tasks.get("env-file-literal").unwrap();
+
+            assert_eq!(task.env.get("LITERAL").unwrap(), "$KEY1");
+        }
+
         #[test]
         fn all_patterns() {
             let sandbox = create_sandbox("builder");
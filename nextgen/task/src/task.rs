@@ -4,11 +4,15 @@ use moon_common::{
     path::{ProjectRelativePathBuf, WorkspaceRelativePathBuf},
     Id,
 };
-use moon_config::{InputPath, OutputPath, PlatformType, TaskType};
+use moon_config::{
+    InputPath, OutputPath, PlatformType, TaskArtifactConfig, TaskFileDependency, TaskType,
+};
 use moon_target::Target;
 use rustc_hash::{FxHashMap, FxHashSet};
 use starbase_utils::glob;
 use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tracing::debug;
 
 cacheable!(
@@ -27,12 +31,16 @@ cacheable!(
     pub struct Task {
         pub args: Vec<String>,
 
+        pub artifacts: Vec<TaskArtifactConfig>,
+
         pub command: String,
 
         pub deps: Vec<Target>,
 
         pub env: FxHashMap<String, String>,
 
+        pub file_deps: Vec<TaskFileDependency>,
+
         pub flags: TaskFlags,
 
         pub id: Id,
@@ -57,12 +65,37 @@ cacheable!(
 
         pub target: Target,
 
+        pub toolchain: Option<Id>,
+
         #[serde(rename = "type")]
         pub type_of: TaskType,
     }
 );
 
 impl Task {
+    /// Return the platform of the configured toolchain, which takes precedence over
+    /// the task's platform when selecting a runtime. TypeScript runs on Node.js,
+    /// while toolchains without a platform (Bun) return `None`.
+    pub fn get_toolchain_platform(&self) -> Option<PlatformType> {
+        let toolchain = self.toolchain.as_ref()?;
+
+        let platform = match toolchain.as_str() {
+            "typescript" => PlatformType::Node,
+            id => PlatformType::from_str(id).ok()?,
+        };
+
+        if platform.is_system() || platform.is_unknown() {
+            None
+        } else {
+            Some(platform)
+        }
+    }
+
+    /// Return the platform used to run the task, preferring the configured toolchain.
+    pub fn get_runtime_platform(&self) -> PlatformType {
+        self.get_toolchain_platform().unwrap_or(self.platform)
+    }
+
     /// Create a globset of all input globs to match with.
     pub fn create_globset(&self) -> miette::Result<glob::GlobSet> {
         Ok(glob::GlobSet::new_split(
@@ -71,6 +104,20 @@ impl Task {
         )?)
     }
 
+    /// Return the number of files that are inputs to this task. Literal paths were
+    /// resolved when the task was built (and are counted whether they exist or not),
+    /// so only globs require a file system walk.
+    pub fn get_input_files_count(&self, workspace_root: &Path) -> miette::Result<usize> {
+        count_files(workspace_root, &self.input_paths, &self.input_globs)
+    }
+
+    /// Return the number of files that are outputs of this task. Like inputs,
+    /// only globs require a file system walk, which will only match outputs
+    /// that currently exist.
+    pub fn get_output_files_count(&self, workspace_root: &Path) -> miette::Result<usize> {
+        count_files(workspace_root, &self.output_paths, &self.output_globs)
+    }
+
     /// Return a list of project-relative affected files filtered down from
     /// the provided touched files list.
     pub fn get_affected_files<S: AsRef<str>>(
@@ -166,6 +213,11 @@ impl Task {
         matches!(self.type_of, TaskType::Run) || self.flags.local
     }
 
+    /// Return true if the task is a "serve" type (a long-running dev server).
+    pub fn is_serve_type(&self) -> bool {
+        matches!(self.type_of, TaskType::Serve)
+    }
+
     /// Return true if the task is a "test" type.
     pub fn is_test_type(&self) -> bool {
         matches!(self.type_of, TaskType::Test)
@@ -185,3 +237,20 @@ impl Task {
         self.is_build_type() || self.is_test_type()
     }
 }
+
+fn count_files(
+    workspace_root: &Path,
+    paths: &FxHashSet<WorkspaceRelativePathBuf>,
+    globs: &FxHashSet<WorkspaceRelativePathBuf>,
+) -> miette::Result<usize> {
+    let mut files = paths
+        .iter()
+        .map(|path| path.to_path(workspace_root))
+        .collect::<FxHashSet<PathBuf>>();
+
+    if !globs.is_empty() {
+        files.extend(glob::walk_files(workspace_root, globs)?);
+    }
+
+    Ok(files.len())
+}
@@ -8,7 +8,17 @@ cacheable!(
 
         pub cache: bool,
 
-        pub env_file: Option<InputPath>,
+        pub cache_key_extra: Option<String>,
+
+        pub cache_undeclared_outputs: bool,
+
+        pub env_files: Option<Vec<InputPath>>,
+
+        pub env_passthrough: Vec<String>,
+
+        pub inherit_env: bool,
+
+        pub interactive: bool,
 
         pub merge_args: TaskMergeStrategy,
 
@@ -24,6 +34,8 @@ cacheable!(
 
         pub persistent: bool,
 
+        pub ready_pattern: Option<String>,
+
         pub retry_count: u8,
 
         pub run_deps_in_parallel: bool,
@@ -34,6 +46,10 @@ cacheable!(
         pub run_from_workspace_root: bool,
 
         pub shell: bool,
+
+        pub strict_env: bool,
+
+        pub timeout: Option<u32>,
     }
 );
 
@@ -42,7 +58,12 @@ impl Default for TaskOptions {
         TaskOptions {
             affected_files: None,
             cache: true,
-            env_file: None,
+            cache_key_extra: None,
+            cache_undeclared_outputs: false,
+            env_files: None,
+            env_passthrough: vec![],
+            inherit_env: true,
+            interactive: false,
             merge_args: TaskMergeStrategy::Append,
             merge_deps: TaskMergeStrategy::Append,
             merge_env: TaskMergeStrategy::Append,
@@ -50,11 +71,14 @@ impl Default for TaskOptions {
             merge_outputs: TaskMergeStrategy::Append,
             output_style: None,
             persistent: false,
+            ready_pattern: None,
             retry_count: 0,
             run_deps_in_parallel: true,
             run_in_ci: true,
             run_from_workspace_root: false,
             shell: true,
+            strict_env: false,
+            timeout: None,
         }
     }
 }
@@ -0,0 +1,60 @@
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_task::Task;
+use rustc_hash::FxHashSet;
+use starbase_sandbox::create_empty_sandbox;
+
+fn create_paths(paths: &[&str]) -> FxHashSet<WorkspaceRelativePathBuf> {
+    paths
+        .iter()
+        .map(|path| WorkspaceRelativePathBuf::from(*path))
+        .collect()
+}
+
+mod files_count {
+    use super::*;
+
+    #[test]
+    fn counts_paths_and_globs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("project/src/a.ts", "");
+        sandbox.create_file("project/src/b.ts", "");
+        sandbox.create_file("project/src/c.css", "");
+        sandbox.create_file("project/package.json", "{}");
+        sandbox.create_file("project/dist/a.js", "");
+        sandbox.create_file("project/dist/b.js", "");
+
+        let task = Task {
+            input_globs: create_paths(&["project/src/**/*.ts"]),
+            input_paths: create_paths(&["project/package.json", "project/tsconfig.json"]),
+            output_globs: create_paths(&["project/dist/*.js"]),
+            output_paths: create_paths(&["project/types"]),
+            ..Task::default()
+        };
+
+        assert_eq!(task.get_input_files_count(sandbox.path()).unwrap(), 4);
+        assert_eq!(task.get_output_files_count(sandbox.path()).unwrap(), 3);
+    }
+
+    #[test]
+    fn doesnt_double_count_paths_matched_by_globs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("project/src/a.ts", "");
+
+        let task = Task {
+            input_globs: create_paths(&["project/src/*.ts"]),
+            input_paths: create_paths(&["project/src/a.ts"]),
+            ..Task::default()
+        };
+
+        assert_eq!(task.get_input_files_count(sandbox.path()).unwrap(), 1);
+    }
+
+    #[test]
+    fn returns_zero_when_empty() {
+        let sandbox = create_empty_sandbox();
+        let task = Task::default();
+
+        assert_eq!(task.get_input_files_count(sandbox.path()).unwrap(), 0);
+        assert_eq!(task.get_output_files_count(sandbox.path()).unwrap(), 0);
+    }
+}
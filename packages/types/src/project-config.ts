@@ -2,7 +2,14 @@
 
 /* eslint-disable */
 
-import type { PartialTaskConfig, PlatformType, TaskConfig } from './tasks-config';
+import type {
+	PartialTaskConfig,
+	PartialTaskOptionsConfig,
+	PlatformType,
+	TaskConfig,
+	TaskOptionsConfig,
+} from './tasks-config';
+import type { HasherOptimization, HasherWalkStrategy } from './workspace-config';
 
 export type DependencyScope = 'development' | 'peer' | 'production';
 
@@ -17,6 +24,11 @@ export interface PartialDependencyConfig {
 
 export type PartialProjectDependsOn = string | PartialDependencyConfig;
 
+export interface PartialProjectHasherConfig {
+	optimization?: HasherOptimization | null;
+	walkStrategy?: HasherWalkStrategy | null;
+}
+
 export type LanguageType =
 	| 'bash'
 	| 'batch'
@@ -70,6 +82,7 @@ export type ProjectType = 'application' | 'library' | 'tool' | 'unknown';
 export interface PartialProjectWorkspaceInheritedTasksConfig {
 	exclude?: string[] | null;
 	include?: string[] | null;
+	includeFiles?: string[] | null;
 	rename?: Record<string, string> | null;
 }
 
@@ -83,7 +96,9 @@ export interface PartialProjectConfig {
 	dependsOn?: PartialProjectDependsOn[] | null;
 	env?: Record<string, string> | null;
 	fileGroups?: Record<string, string[]> | null;
+	hasher?: PartialProjectHasherConfig | null;
 	language?: LanguageType | null;
+	optionsPresets?: Record<string, PartialTaskOptionsConfig> | null;
 	owners?: PartialOwnersConfig | null;
 	platform?: PlatformType | null;
 	project?: PartialProjectMetadataConfig | null;
@@ -103,6 +118,11 @@ export interface DependencyConfig {
 
 export type ProjectDependsOn = string | DependencyConfig;
 
+export interface ProjectHasherConfig {
+	optimization: HasherOptimization | null;
+	walkStrategy: HasherWalkStrategy | null;
+}
+
 export type OwnersPaths = string[] | Record<string, string[]>;
 
 export interface OwnersConfig {
@@ -142,6 +162,7 @@ export interface ProjectToolchainConfig {
 export interface ProjectWorkspaceInheritedTasksConfig {
 	exclude: string[];
 	include: string[] | null;
+	includeFiles: string[];
 	rename: Record<string, string>;
 }
 
@@ -155,7 +176,9 @@ export interface ProjectConfig {
 	dependsOn: ProjectDependsOn[];
 	env: Record<string, string>;
 	fileGroups: Record<string, string[]>;
+	hasher: ProjectHasherConfig;
 	language: LanguageType;
+	optionsPresets: Record<string, TaskOptionsConfig>;
 	owners: OwnersConfig;
 	platform: PlatformType | null;
 	project: ProjectMetadataConfig | null;
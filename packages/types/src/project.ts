@@ -2,6 +2,8 @@ import type { DependencyConfig, LanguageType, ProjectConfig, ProjectType } from
 import type {
 	InheritedTasksConfig,
 	PlatformType,
+	TaskArtifactConfig,
+	TaskFileDependency,
 	TaskMergeStrategy,
 	TaskOutputStyle,
 	TaskType,
@@ -15,25 +17,35 @@ export interface FileGroup {
 export interface TaskOptions {
 	affectedFiles: 'args' | 'both' | 'env';
 	cache: boolean;
-	envFile: string | null;
+	cacheKeyExtra: string | null;
+	cacheUndeclaredOutputs: boolean;
+	envFiles: string[] | null;
+	envPassthrough: string[];
+	inheritEnv: boolean;
+	interactive: boolean;
 	mergeArgs: TaskMergeStrategy;
 	mergeDeps: TaskMergeStrategy;
 	mergeEnv: TaskMergeStrategy;
 	mergeInputs: TaskMergeStrategy;
 	mergeOutputs: TaskMergeStrategy;
 	outputStyle: TaskOutputStyle | null;
+	readyPattern: string | null;
 	retryCount: number;
 	runDepsInParallel: boolean;
 	runInCI: boolean;
 	runFromWorkspaceRoot: boolean;
 	shell: boolean;
+	strictEnv: boolean;
+	timeout: number | null;
 }
 
 export interface Task {
 	args: string[];
+	artifacts: TaskArtifactConfig[];
 	command: string;
 	deps: string[];
 	env: Record<string, string>;
+	fileDeps: TaskFileDependency[];
 	id: string;
 	inputs: string[];
 	inputGlobs: string[];
@@ -57,6 +69,7 @@ export interface Project {
 	inheritedConfig: InheritedTasksConfig;
 	language: LanguageType;
 	root: string;
+	schemaVersion: number;
 	source: string;
 	tasks: Record<string, Task>;
 	type: ProjectType;
@@ -2,20 +2,33 @@
 
 /* eslint-disable */
 
-export type PartialTaskCommandArgs = null | string | string[];
+export type PartialTaskCommandArgs = null | string | string[] | Record<string, string>;
 
 export type TaskOptionAffectedFiles = boolean | 'args' | 'env';
 
-export type TaskOptionEnvFile = boolean | string;
+export type TaskOptionEnvFile = boolean | string | string[];
 
 export type TaskMergeStrategy = 'append' | 'prepend' | 'replace';
 
-export type TaskOutputStyle = 'buffer' | 'buffer-only-failure' | 'hash' | 'none' | 'stream';
+export type TaskOutputStyle =
+	| 'buffer'
+	| 'buffer-only-failure'
+	| 'buffer-until-ready'
+	| 'hash'
+	| 'json'
+	| 'none'
+	| 'stream';
 
 export interface PartialTaskOptionsConfig {
 	affectedFiles?: TaskOptionAffectedFiles | null;
+	allowOutputOverlap?: boolean | null;
 	cache?: boolean | null;
+	cacheKeyExtra?: string | null;
+	cacheUndeclaredOutputs?: boolean | null;
 	envFile?: TaskOptionEnvFile | null;
+	envPassthrough?: string[] | null;
+	inheritEnv?: boolean | null;
+	interactive?: boolean | null;
 	mergeArgs?: TaskMergeStrategy | null;
 	mergeDeps?: TaskMergeStrategy | null;
 	mergeEnv?: TaskMergeStrategy | null;
@@ -23,27 +36,56 @@ export interface PartialTaskOptionsConfig {
 	mergeOutputs?: TaskMergeStrategy | null;
 	outputStyle?: TaskOutputStyle | null;
 	persistent?: boolean | null;
+	readyPattern?: string | null;
 	retryCount?: number | null;
 	runDepsInParallel?: boolean | null;
 	runFromWorkspaceRoot?: boolean | null;
 	runInCI?: boolean | null;
 	shell?: boolean | null;
+	strictEnv?: boolean | null;
+	timeout?: number | null;
 }
 
+export interface TaskFileDependency {
+	file: string;
+	/** @default 30 */
+	timeout?: number;
+}
+
+export type TaskDependency = string | TaskFileDependency;
+
 export type PlatformType = 'deno' | 'node' | 'rust' | 'system' | 'unknown';
 
-export type TaskType = 'build' | 'run' | 'test';
+export type TaskType = 'build' | 'run' | 'serve' | 'test';
+
+export type TaskArtifactKind = 'archive' | 'binary' | 'container' | 'file' | 'package';
+
+/**
+ * Metadata about an artifact produced by a task, for consumption by
+ * external tooling (release automation, etc). Does not affect execution.
+ */
+export interface PartialTaskArtifactConfig {
+	kind?: TaskArtifactKind | null;
+	name?: string | null;
+	path?: string | null;
+}
 
 export interface PartialTaskConfig {
 	args?: PartialTaskCommandArgs | null;
+	artifacts?: PartialTaskArtifactConfig[] | null;
 	command?: PartialTaskCommandArgs | null;
-	deps?: string[] | null;
+	commandByEnv?: Record<string, PartialTaskCommandArgs> | null;
+	deps?: TaskDependency[] | null;
 	env?: Record<string, string> | null;
+	extends?: string | null;
+	globalInputs?: string[] | null;
 	inputs?: string[] | null;
 	local?: boolean | null;
 	options?: PartialTaskOptionsConfig | null;
+	optionsPreset?: string | null;
 	outputs?: string[] | null;
 	platform?: PlatformType | null;
+	toolchain?: string | null;
 	type?: TaskType | null;
 }
 
@@ -54,15 +96,22 @@ export interface PartialInheritedTasksConfig {
 	fileGroups?: Record<string, string[]> | null;
 	implicitDeps?: string[] | null;
 	implicitInputs?: string[] | null;
+	optionsPresets?: Record<string, PartialTaskOptionsConfig> | null;
 	tasks?: Record<string, PartialTaskConfig> | null;
 }
 
-export type TaskCommandArgs = null | string | string[];
+export type TaskCommandArgs = null | string | string[] | Record<string, string>;
 
 export interface TaskOptionsConfig {
 	affectedFiles: TaskOptionAffectedFiles | null;
+	allowOutputOverlap: boolean | null;
 	cache: boolean | null;
+	cacheKeyExtra: string | null;
+	cacheUndeclaredOutputs: boolean | null;
 	envFile: TaskOptionEnvFile | null;
+	envPassthrough: string[] | null;
+	inheritEnv: boolean | null;
+	interactive: boolean | null;
 	mergeArgs: TaskMergeStrategy | null;
 	mergeDeps: TaskMergeStrategy | null;
 	mergeEnv: TaskMergeStrategy | null;
@@ -70,23 +119,42 @@ export interface TaskOptionsConfig {
 	mergeOutputs: TaskMergeStrategy | null;
 	outputStyle: TaskOutputStyle | null;
 	persistent: boolean | null;
+	readyPattern: string | null;
 	retryCount: number | null;
 	runDepsInParallel: boolean | null;
 	runFromWorkspaceRoot: boolean | null;
 	runInCI: boolean | null;
 	shell: boolean | null;
+	strictEnv: boolean | null;
+	timeout: number | null;
+}
+
+/**
+ * Metadata about an artifact produced by a task, for consumption by
+ * external tooling (release automation, etc). Does not affect execution.
+ */
+export interface TaskArtifactConfig {
+	kind: TaskArtifactKind;
+	name: string;
+	path: string;
 }
 
 export interface TaskConfig {
 	args: TaskCommandArgs;
+	artifacts: TaskArtifactConfig[];
 	command: TaskCommandArgs;
-	deps: string[];
+	commandByEnv: Record<string, TaskCommandArgs>;
+	deps: TaskDependency[];
 	env: Record<string, string>;
+	extends: string | null;
+	globalInputs: string[];
 	inputs: string[] | null;
 	local: boolean | null;
 	options: TaskOptionsConfig;
+	optionsPreset: string | null;
 	outputs: string[] | null;
 	platform: PlatformType;
+	toolchain: string | null;
 	type: TaskType | null;
 }
 
@@ -97,5 +165,6 @@ export interface InheritedTasksConfig {
 	fileGroups: Record<string, string[]>;
 	implicitDeps: string[];
 	implicitInputs: string[];
+	optionsPresets: Record<string, TaskOptionsConfig>;
 	tasks: Record<string, TaskConfig>;
 }
@@ -11,10 +11,16 @@ export interface PartialBinConfig {
 
 export type PartialBinEntry = string | PartialBinConfig;
 
+export interface PartialBunConfig {
+	bins?: PartialBinEntry[] | null;
+	version?: string | null;
+}
+
 export interface PartialDenoConfig {
 	bins?: PartialBinEntry[] | null;
 	/** @default 'deps.ts' */
 	depsFile?: string | null;
+	importMap?: string | null;
 	lockfile?: boolean | null;
 }
 
@@ -32,6 +38,9 @@ export type NodeVersionFormat =
 	| 'workspace-tilde';
 
 export interface PartialNpmConfig {
+	audit?: boolean | null;
+	fund?: boolean | null;
+	registry?: string | null;
 	version?: string | null;
 }
 
@@ -51,12 +60,18 @@ export interface PartialYarnConfig {
 export interface PartialNodeConfig {
 	/** @default true */
 	addEnginesConstraint?: boolean | null;
+	allowVersionAliasInCi?: boolean | null;
 	aliasPackageNames?: NodeProjectAliasFormat | null;
 	binExecArgs?: string[] | null;
 	/** @default true */
+	dedupeAlways?: boolean | null;
 	dedupeOnLockfileChange?: boolean | null;
 	dependencyVersionFormat?: NodeVersionFormat | null;
 	inferTasksFromScripts?: boolean | null;
+	/** @default 1000 */
+	installRetryBackoff?: number | null;
+	/** @default 2 */
+	installRetryCount?: number | null;
 	npm?: PartialNpmConfig | null;
 	packageManager?: NodePackageManager | null;
 	pnpm?: PartialPnpmConfig | null;
@@ -91,8 +106,10 @@ export interface PartialTypeScriptConfig {
 export interface PartialToolchainConfig {
 	/** @default 'https://moonrepo.dev/schemas/toolchain.json' */
 	$schema?: string | null;
+	bun?: PartialBunConfig | null;
 	deno?: PartialDenoConfig | null;
 	extends?: string | null;
+	inheritToolVersions?: boolean | null;
 	node?: PartialNodeConfig | null;
 	rust?: PartialRustConfig | null;
 	typescript?: PartialTypeScriptConfig | null;
@@ -107,14 +124,23 @@ export interface BinConfig {
 
 export type BinEntry = string | BinConfig;
 
+export interface BunConfig {
+	bins: BinEntry[];
+	version: string | null;
+}
+
 export interface DenoConfig {
 	bins: BinEntry[];
 	/** @default 'deps.ts' */
 	depsFile: string;
+	importMap: string | null;
 	lockfile: boolean;
 }
 
 export interface NpmConfig {
+	audit: boolean | null;
+	fund: boolean;
+	registry: string | null;
 	version: string | null;
 }
 
@@ -130,12 +156,18 @@ export interface YarnConfig {
 export interface NodeConfig {
 	/** @default true */
 	addEnginesConstraint: boolean;
+	allowVersionAliasInCi: boolean;
 	aliasPackageNames: NodeProjectAliasFormat;
 	binExecArgs: string[];
 	/** @default true */
+	dedupeAlways: boolean;
 	dedupeOnLockfileChange: boolean;
 	dependencyVersionFormat: NodeVersionFormat;
 	inferTasksFromScripts: boolean;
+	/** @default 1000 */
+	installRetryBackoff: number;
+	/** @default 2 */
+	installRetryCount: number;
 	npm: NpmConfig;
 	packageManager: NodePackageManager;
 	pnpm: PnpmConfig | null;
@@ -170,8 +202,10 @@ export interface TypeScriptConfig {
 export interface ToolchainConfig {
 	/** @default 'https://moonrepo.dev/schemas/toolchain.json' */
 	$schema: string;
+	bun: BunConfig | null;
 	deno: DenoConfig | null;
 	extends: string | null;
+	inheritToolVersions: boolean;
 	node: NodeConfig | null;
 	rust: RustConfig | null;
 	typescript: TypeScriptConfig | null;
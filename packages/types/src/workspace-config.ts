@@ -11,6 +11,7 @@ export interface PartialCodeownersConfig {
 }
 
 export interface PartialConstraintsConfig {
+	enforceLanguagePlatformCompatibility?: boolean | null;
 	/** @default true */
 	enforceProjectTypeRelationships?: boolean | null;
 	tagRelationships?: Record<string, string[]> | null;
@@ -22,11 +23,13 @@ export interface PartialGeneratorConfig {
 
 export type HasherOptimization = 'accuracy' | 'performance';
 
-export type HasherWalkStrategy = 'glob' | 'vcs';
+export type HasherWalkStrategy = 'auto' | 'glob' | 'vcs';
 
 export interface PartialHasherConfig {
 	/** @default 2500 */
 	batchSize?: number | null;
+	/** @default true */
+	followSymlinks?: boolean | null;
 	ignoreMissingPatterns?: string[] | null;
 	ignorePatterns?: string[] | null;
 	optimization?: HasherOptimization | null;
@@ -53,6 +56,7 @@ export interface PartialRunnerConfig {
 	archivableTargets?: string[] | null;
 	/** @default '7 days' */
 	cacheLifetime?: string | null;
+	expandTagDeps?: boolean | null;
 	/** @default true */
 	inheritColorsForPipedTasks?: boolean | null;
 	logRunningCommand?: boolean | null;
@@ -77,8 +81,10 @@ export interface PartialWorkspaceConfig {
 	$schema?: string | null;
 	codeowners?: PartialCodeownersConfig | null;
 	constraints?: PartialConstraintsConfig | null;
+	env?: Record<string, string> | null;
 	extends?: string | null;
 	generator?: PartialGeneratorConfig | null;
+	globalInputs?: string[] | null;
 	hasher?: PartialHasherConfig | null;
 	notifier?: PartialNotifierConfig | null;
 	projects?: PartialWorkspaceProjects | null;
@@ -96,6 +102,7 @@ export interface CodeownersConfig {
 }
 
 export interface ConstraintsConfig {
+	enforceLanguagePlatformCompatibility: boolean;
 	/** @default true */
 	enforceProjectTypeRelationships: boolean;
 	tagRelationships: Record<string, string[]>;
@@ -108,6 +115,8 @@ export interface GeneratorConfig {
 export interface HasherConfig {
 	/** @default 2500 */
 	batchSize: number;
+	/** @default true */
+	followSymlinks: boolean;
 	ignoreMissingPatterns: string[];
 	ignorePatterns: string[];
 	optimization: HasherOptimization;
@@ -131,6 +140,7 @@ export interface RunnerConfig {
 	archivableTargets: string[];
 	/** @default '7 days' */
 	cacheLifetime: string;
+	expandTagDeps: boolean;
 	/** @default true */
 	inheritColorsForPipedTasks: boolean;
 	logRunningCommand: boolean;
@@ -151,8 +161,10 @@ export interface WorkspaceConfig {
 	$schema: string;
 	codeowners: CodeownersConfig;
 	constraints: ConstraintsConfig;
+	env: Record<string, string>;
 	extends: string | null;
 	generator: GeneratorConfig;
+	globalInputs: string[];
 	hasher: HasherConfig;
 	notifier: NotifierConfig;
 	projects: WorkspaceProjects;
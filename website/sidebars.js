@@ -154,13 +154,18 @@ const sidebars = {
 				{
 					type: 'category',
 					label: 'sync',
-					items: ['commands/sync/codeowners', 'commands/sync/hooks', 'commands/sync/projects'],
+					items: [
+						'commands/sync/codeowners',
+						'commands/sync/hooks',
+						'commands/sync/projects',
+						'commands/sync/prototools',
+					],
 					link: {
 						type: 'generated-index',
 						title: 'sync',
 						description: 'Operations for syncing the workspace to a healthy state.',
 						slug: '/commands/sync',
-						keywords: ['sync', 'codeowners', 'projects'],
+						keywords: ['sync', 'codeowners', 'projects', 'prototools'],
 					},
 				},
 				'commands/task',